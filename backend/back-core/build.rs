@@ -0,0 +1,5 @@
+// `sqlx::migrate!` 在编译期嵌入迁移文件，新增或修改迁移后需要重新编译
+fn main() {
+    println!("cargo:rerun-if-changed=../migrations");
+    println!("cargo:rerun-if-changed=../sqlite-migrations");
+}
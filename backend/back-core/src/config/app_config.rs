@@ -0,0 +1,361 @@
+use crate::config::error_handling::ConfigError;
+use crate::context::error_handling::InitError;
+use crate::context::secret::redact_secret;
+use log::{LogFormat, LogOptions, LogRotation};
+use serde::{Deserialize, Serialize};
+use sqlx::postgres::PgConnectOptions;
+use std::fmt;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+
+/// 应用的全部配置
+///
+/// 由 `ConfigLoader` 按 默认值 → TOML 文件 → 环境变量 → 命令行参数 的顺序逐层覆盖得到。
+/// 每个配置项都有一个以点分隔的键，例如 `database.pool_size`，
+/// 对应的环境变量为键的大写形式，点换成下划线，例如 `DATABASE_POOL_SIZE`。
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AppConfig {
+    pub server: ServerConfig,
+    pub database: DatabaseConfig,
+    pub redis: RedisConfig,
+    pub storage: StorageConfig,
+    pub log: LogConfig,
+    pub simulation: SimulationConfig,
+}
+
+/// HTTP 服务
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ServerConfig {
+    /// 监听地址
+    pub listen_addr: SocketAddr,
+}
+
+/// Postgres 数据库
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DatabaseConfig {
+    pub host: String,
+    pub port: u16,
+    /// 用户名，为空时视为未配置数据库
+    pub user: String,
+    pub password: String,
+    /// 数据库名，为空时视为未配置数据库
+    pub name: String,
+    /// 连接池的最大连接数
+    pub pool_size: u32,
+    /// 空闲连接的回收时间（秒）
+    pub idle_timeout_secs: u64,
+    /// 从连接池获取连接的超时时间（秒）
+    pub acquire_timeout_secs: u64,
+    /// 连接成功后是否执行内嵌的数据库迁移
+    pub run_migrations: bool,
+}
+
+/// Redis
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RedisConfig {
+    pub host: String,
+    pub port: u16,
+    /// 密码，为空时不认证
+    pub password: String,
+}
+
+/// 持久化存储的后端
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StorageBackend {
+    /// 使用 `database` 中配置的 Postgres
+    #[default]
+    Postgres,
+    /// 使用本地的 SQLite 文件，无需数据库服务
+    Sqlite,
+    /// 仅保存在内存中，进程退出后丢失，适合测试与临时实验
+    Memory,
+}
+
+/// 个体、资源、偏好与世界检查点的持久化存储
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct StorageConfig {
+    pub backend: StorageBackend,
+    /// SQLite 数据库文件的路径，不存在时自动创建；仅在 `backend = "sqlite"` 时使用
+    pub sqlite_path: PathBuf,
+}
+
+/// 日志
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LogConfig {
+    /// 日志文件所在的目录，不存在时自动创建
+    pub dir: PathBuf,
+    /// `tracing` 的过滤指令，例如 `info,sqlx=warn`
+    pub filter: String,
+    /// 当前日志文件的名称，轮转出的文件在其后追加时间
+    pub file_name: String,
+    /// 日志文件的格式
+    pub format: LogFormat,
+    /// 日志文件按时间轮转的周期
+    pub rotation: LogRotation,
+    /// 日志文件超过该大小（MiB）后轮转，为 0 时不按大小轮转
+    pub max_file_size_mb: u64,
+    /// 最多保留的旧日志文件数，为 0 时全部保留
+    pub max_files: usize,
+    /// 控制台输出的过滤指令，为空时不输出到控制台
+    pub console_filter: String,
+    /// 控制台输出的格式
+    pub console_format: LogFormat,
+    /// OTLP/HTTP 采集器的地址，例如 `http://127.0.0.1:4318`，为空时不导出
+    pub otlp_endpoint: String,
+    /// 导出时 `service.name` 资源属性的值
+    pub service_name: String,
+}
+
+/// 模拟的默认参数
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SimulationConfig {
+    /// 地图高度（格）
+    pub map_height: usize,
+    /// 地图宽度（格）
+    pub map_width: usize,
+    /// 重力常数
+    pub gravity: f64,
+    /// 逗号分隔的指标名称，例如 `mean:1/2,gini:potential`，为空时不记录指标
+    pub metrics: String,
+    /// 指标的采样间隔步数
+    pub metrics_interval: usize,
+    /// 由其他实例模拟、通过 Redis 缓存跟随并记录指标的世界的文明 ID，为空时不跟随
+    pub metrics_world: String,
+    /// 属性目录文件（`.toml` 或 `.json`），为空时只使用内置属性
+    pub property_catalogue: PathBuf,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self {
+            listen_addr: SocketAddr::from(([0, 0, 0, 0], 8000)),
+        }
+    }
+}
+
+impl Default for DatabaseConfig {
+    fn default() -> Self {
+        Self {
+            host: "localhost".to_string(),
+            port: 5432,
+            user: String::new(),
+            password: String::new(),
+            name: String::new(),
+            pool_size: 10,
+            idle_timeout_secs: 30,
+            acquire_timeout_secs: 5,
+            run_migrations: true,
+        }
+    }
+}
+
+impl Default for RedisConfig {
+    fn default() -> Self {
+        Self {
+            host: "localhost".to_string(),
+            port: 6379,
+            password: String::new(),
+        }
+    }
+}
+
+impl Default for StorageConfig {
+    fn default() -> Self {
+        Self {
+            backend: StorageBackend::default(),
+            sqlite_path: PathBuf::from("re-hive.sqlite"),
+        }
+    }
+}
+
+impl Default for LogConfig {
+    fn default() -> Self {
+        Self {
+            dir: PathBuf::from("log"),
+            filter: "debug".to_string(),
+            file_name: "app.json".to_string(),
+            format: LogFormat::Json,
+            rotation: LogRotation::Daily,
+            max_file_size_mb: 0,
+            max_files: 0,
+            console_filter: "info".to_string(),
+            console_format: LogFormat::Compact,
+            otlp_endpoint: String::new(),
+            service_name: "re-hive".to_string(),
+        }
+    }
+}
+
+impl Default for SimulationConfig {
+    fn default() -> Self {
+        Self {
+            map_height: 255,
+            map_width: 255,
+            gravity: 10.0,
+            metrics: String::new(),
+            metrics_interval: 1,
+            metrics_world: String::new(),
+            property_catalogue: PathBuf::new(),
+        }
+    }
+}
+
+/// 隐藏密码，避免配置被打印到日志时泄露
+impl fmt::Debug for DatabaseConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DatabaseConfig")
+            .field("host", &self.host)
+            .field("port", &self.port)
+            .field("user", &self.user)
+            .field("password", &redact_secret(&self.password, &self.password))
+            .field("name", &self.name)
+            .field("pool_size", &self.pool_size)
+            .field("idle_timeout_secs", &self.idle_timeout_secs)
+            .field("acquire_timeout_secs", &self.acquire_timeout_secs)
+            .field("run_migrations", &self.run_migrations)
+            .finish()
+    }
+}
+
+impl fmt::Debug for RedisConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RedisConfig")
+            .field("host", &self.host)
+            .field("port", &self.port)
+            .field("password", &redact_secret(&self.password, &self.password))
+            .finish()
+    }
+}
+
+impl DatabaseConfig {
+    /// 构造连接参数，用户名或数据库名为空时返回 `MissingConfig`
+    ///
+    /// 密码单独传给连接参数，不经过 URL，因此不需要转义，也不会出现在日志中。
+    ///
+    /// ```
+    /// use back_core::config::app_config::DatabaseConfig;
+    ///
+    /// let config = DatabaseConfig {
+    ///     user: "rehive".to_string(),
+    ///     password: "p@ss/word:1".to_string(),
+    ///     name: "rehive".to_string(),
+    ///     ..Default::default()
+    /// };
+    /// let options = config.connect_options().unwrap();
+    /// assert_eq!(options.get_host(), "localhost");
+    /// assert_eq!(options.get_database(), Some("rehive"));
+    ///
+    /// let config = DatabaseConfig { user: String::new(), ..config };
+    /// assert!(config.connect_options().is_err());
+    /// ```
+    pub fn connect_options(&self) -> Result<PgConnectOptions, InitError> {
+        if self.user.is_empty() {
+            return Err(InitError::MissingConfig("database.user"));
+        }
+        if self.name.is_empty() {
+            return Err(InitError::MissingConfig("database.name"));
+        }
+        Ok(PgConnectOptions::new_without_pgpass()
+            .host(&self.host)
+            .port(self.port)
+            .username(&self.user)
+            .password(&self.password)
+            .database(&self.name))
+    }
+}
+
+impl SimulationConfig {
+    /// 配置的属性目录文件，未配置时返回 `None`
+    pub fn property_catalogue(&self) -> Option<&Path> {
+        (!self.property_catalogue.as_os_str().is_empty())
+            .then_some(self.property_catalogue.as_path())
+    }
+}
+
+impl LogConfig {
+    /// 转换为 `log::init_logging` 的参数
+    pub fn to_options(&self) -> LogOptions {
+        let console_filter = (!self.console_filter.is_empty()).then(|| self.console_filter.clone());
+        let mut options = LogOptions::new(Some(self.dir.clone()), Some(self.filter.clone()))
+            .with_file_name(&self.file_name)
+            .with_file_format(self.format)
+            .with_rotation(self.rotation)
+            .with_console(console_filter, self.console_format)
+            .with_service_name(&self.service_name);
+        if self.max_file_size_mb > 0 {
+            options = options.with_max_file_size(self.max_file_size_mb.saturating_mul(1024 * 1024));
+        }
+        if self.max_files > 0 {
+            options = options.with_max_files(self.max_files);
+        }
+        if !self.otlp_endpoint.is_empty() {
+            options = options.with_otlp_endpoint(&self.otlp_endpoint);
+        }
+        options
+    }
+}
+
+impl AppConfig {
+    /// 检查配置项的取值范围，一次列出全部问题
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        let mut problems = Vec::new();
+        let mut check = |ok: bool, problem: &str| {
+            if !ok {
+                problems.push(problem.to_string());
+            }
+        };
+
+        check(self.database.port != 0, "database.port 不能为 0");
+        check(self.database.pool_size > 0, "database.pool_size 至少为 1");
+        check(
+            self.database.acquire_timeout_secs > 0,
+            "database.acquire_timeout_secs 至少为 1",
+        );
+        check(self.redis.port != 0, "redis.port 不能为 0");
+        check(
+            self.storage.backend != StorageBackend::Sqlite
+                || !self.storage.sqlite_path.as_os_str().is_empty(),
+            "storage.sqlite_path 不能为空",
+        );
+        check(!self.log.dir.as_os_str().is_empty(), "log.dir 不能为空");
+        check(
+            !self.log.file_name.is_empty() && !self.log.file_name.contains(['/', '\\']),
+            "log.file_name 不能为空，也不能包含路径分隔符",
+        );
+        check(
+            self.log.otlp_endpoint.is_empty() || self.log.otlp_endpoint.starts_with("http://"),
+            "log.otlp_endpoint 只支持 http:// 开头的地址",
+        );
+        check(
+            self.simulation.map_height > 0 && self.simulation.map_width > 0,
+            "simulation.map_height 与 simulation.map_width 至少为 1",
+        );
+        check(
+            self.simulation.gravity.is_finite() && self.simulation.gravity > 0.0,
+            "simulation.gravity 必须是正数",
+        );
+        check(
+            self.simulation.metrics_interval > 0,
+            "simulation.metrics_interval 至少为 1",
+        );
+        check(
+            self.simulation.metrics_world.is_empty()
+                || uuid::Uuid::parse_str(&self.simulation.metrics_world).is_ok(),
+            "simulation.metrics_world 必须是 UUID",
+        );
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(ConfigError::Invalid(problems))
+        }
+    }
+}
@@ -0,0 +1,312 @@
+use crate::config::app_config::AppConfig;
+use crate::config::error_handling::ConfigError;
+use dotenvy::dotenv;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use toml::{Table, Value};
+
+/// 未指定配置文件时尝试读取的文件，不存在时跳过
+const DEFAULT_CONFIG_FILE: &str = "config.toml";
+/// 指定配置文件路径的环境变量
+const CONFIG_FILE_ENV: &str = "RE_HIVE_CONFIG";
+/// 指定配置文件路径的命令行参数 `--config`
+const CONFIG_FILE_KEY: &str = "config";
+/// 旧版环境变量：其下的 `log` 目录作为日志目录，`LOG_DIR` 优先
+const LEGACY_PROJECT_ROOT_ENV: &str = "PROJECT_ROOT";
+/// 旧版环境变量与配置项的对应关系，新名称优先
+const LEGACY_ENV: [(&str, &str); 2] = [
+    ("RUST_LOG", "log.filter"),
+    ("PROPERTY_CATALOGUE_PATH", "simulation.property_catalogue"),
+];
+
+/// 分层加载 `AppConfig`
+///
+/// 优先级从低到高：
+/// 1. `AppConfig::default()`。
+/// 2. TOML 配置文件：`with_file`、`--config <路径>` 或环境变量 `RE_HIVE_CONFIG` 指定的文件必须存在；
+///    都未指定时读取当前目录下的 `config.toml`，不存在则跳过。
+/// 3. 环境变量：配置项 `database.pool_size` 对应 `DATABASE_POOL_SIZE`，依此类推。
+/// 4. 命令行参数：`--database.pool_size 20` 或 `--database.pool_size=20`。
+///
+/// 环境变量与命令行参数的值按默认配置中该项的类型解析，加载完成后再统一校验取值范围。
+///
+/// ```
+/// use back_core::config::loader::ConfigLoader;
+///
+/// let args = ["--redis.port=6380", "--simulation.gravity", "9.8", "--verbose"].map(String::from);
+/// let config = ConfigLoader::new()
+///     .with_env([
+///         ("REDIS_PORT".to_string(), "6390".to_string()),
+///         ("DATABASE_POOL_SIZE".to_string(), "20".to_string()),
+///     ])
+///     .with_args(&args)
+///     .unwrap()
+///     .load()
+///     .unwrap();
+///
+/// assert_eq!(config.redis.port, 6380);
+/// assert_eq!(config.database.pool_size, 20);
+/// assert_eq!(config.simulation.gravity, 9.8);
+///
+/// let invalid = ConfigLoader::new()
+///     .with_env([("DATABASE_POOL_SIZE".to_string(), "many".to_string())])
+///     .load();
+/// assert!(invalid.is_err());
+///
+/// let invalid = ConfigLoader::new()
+///     .with_env([("SIMULATION_METRICS_WORLD".to_string(), "world-1".to_string())])
+///     .load();
+/// assert!(invalid.is_err());
+/// ```
+#[derive(Debug, Clone)]
+pub struct ConfigLoader {
+    /// 显式指定的配置文件
+    file: Option<PathBuf>,
+    /// 环境变量的快照
+    env: Vec<(String, String)>,
+    /// 命令行参数中的 `(键, 值)`
+    overrides: Vec<(String, String)>,
+}
+
+impl ConfigLoader {
+    /// 创建加载器，读取当前进程的环境变量（包括 `.env` 文件中的变量）
+    pub fn new() -> Self {
+        dotenv().ok();
+        Self {
+            file: None,
+            env: std::env::vars().collect(),
+            overrides: Vec::new(),
+        }
+    }
+
+    /// 指定配置文件，文件必须存在（可链式调用）
+    pub fn with_file(mut self, path: impl AsRef<Path>) -> Self {
+        self.file = Some(path.as_ref().to_path_buf());
+        self
+    }
+
+    /// 用给定的变量代替进程的环境变量（可链式调用）
+    pub fn with_env(mut self, vars: impl IntoIterator<Item = (String, String)>) -> Self {
+        self.env = vars.into_iter().collect();
+        self
+    }
+
+    /// 添加一个最高优先级的覆盖项（可链式调用）
+    pub fn with_override(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.overrides.push((key.into(), value.into()));
+        self
+    }
+
+    /// 从命令行参数中读取 `--config` 与 `--<键>` 形式的覆盖项（可链式调用）
+    ///
+    /// 键中不含 `.` 的其他参数（例如 `--verbose`）与位置参数不属于配置，会被忽略，留给调用方处理。
+    ///
+    /// ### 返回值
+    /// 覆盖项缺少取值时返回 `MissingValue`。
+    pub fn with_args(mut self, args: &[String]) -> Result<Self, ConfigError> {
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            let Some(flag) = arg.strip_prefix("--") else {
+                continue;
+            };
+            let (key, inline_value) = match flag.split_once('=') {
+                Some((key, value)) => (key, Some(value.to_string())),
+                None => (flag, None),
+            };
+            if key != CONFIG_FILE_KEY && !key.contains('.') {
+                continue;
+            }
+
+            let value = match inline_value {
+                Some(value) => value,
+                None => args
+                    .next()
+                    .cloned()
+                    .ok_or_else(|| ConfigError::MissingValue(arg.clone()))?,
+            };
+            if key == CONFIG_FILE_KEY {
+                self.file = Some(PathBuf::from(value));
+            } else {
+                self.overrides.push((key.to_string(), value));
+            }
+        }
+        Ok(self)
+    }
+
+    /// 全部可以覆盖的配置项的键，例如 `database.pool_size`
+    ///
+    /// ### 示例
+    /// ```
+    /// use back_core::config::loader::ConfigLoader;
+    ///
+    /// let keys = ConfigLoader::keys();
+    /// assert!(keys.contains(&"database.pool_size".to_string()));
+    /// assert!(!keys.contains(&"database".to_string()));
+    /// ```
+    pub fn keys() -> Vec<String> {
+        match Value::try_from(AppConfig::default()) {
+            Ok(Value::Table(tree)) => leaf_keys(&tree, ""),
+            _ => unreachable!("AppConfig 总是序列化为表"),
+        }
+    }
+
+    /// 逐层合并并校验配置
+    pub fn load(self) -> Result<AppConfig, ConfigError> {
+        let defaults = Value::try_from(AppConfig::default())
+            .map_err(|e| ConfigError::Deserialize(e.to_string()))?;
+        let Value::Table(mut tree) = defaults else {
+            unreachable!("AppConfig 总是序列化为表");
+        };
+
+        if let Some(file) = self.read_file()? {
+            merge(&mut tree, file);
+        }
+        self.apply_env(&mut tree)?;
+        for (key, value) in &self.overrides {
+            if lookup(&tree, key).is_none() {
+                return Err(ConfigError::UnknownKey(key.clone()));
+            }
+            set(&mut tree, key, value, "命令行参数")?;
+        }
+
+        let config = AppConfig::deserialize(Value::Table(tree))
+            .map_err(|e| ConfigError::Deserialize(e.to_string()))?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    fn env(&self, name: &str) -> Option<&str> {
+        self.env
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    fn read_file(&self) -> Result<Option<Table>, ConfigError> {
+        let explicit = self
+            .file
+            .clone()
+            .or_else(|| self.env(CONFIG_FILE_ENV).map(PathBuf::from));
+        let (path, required) = match explicit {
+            Some(path) => (path, true),
+            None => (PathBuf::from(DEFAULT_CONFIG_FILE), false),
+        };
+        if !required && !path.exists() {
+            return Ok(None);
+        }
+
+        let content = std::fs::read_to_string(&path).map_err(|e| ConfigError::Io {
+            path: path.clone(),
+            message: e.to_string(),
+        })?;
+        content
+            .parse::<Table>()
+            .map(Some)
+            .map_err(|e| ConfigError::Parse {
+                path,
+                message: e.to_string(),
+            })
+    }
+
+    fn apply_env(&self, tree: &mut Table) -> Result<(), ConfigError> {
+        let env = |name: &str| self.env(name);
+
+        if let (None, Some(root)) = (env(&env_name("log.dir")), env(LEGACY_PROJECT_ROOT_ENV)) {
+            let dir = Path::new(root).join("log");
+            let origin = format!("环境变量 {}", LEGACY_PROJECT_ROOT_ENV);
+            set(tree, "log.dir", &dir.to_string_lossy(), &origin)?;
+        }
+        for (legacy, key) in LEGACY_ENV {
+            if let (None, Some(value)) = (env(&env_name(key)), env(legacy)) {
+                set(tree, key, value, &format!("环境变量 {}", legacy))?;
+            }
+        }
+
+        for key in leaf_keys(tree, "") {
+            let name = env_name(&key);
+            if let Some(value) = env(&name) {
+                set(tree, &key, value, &format!("环境变量 {}", name))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Default for ConfigLoader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 配置项对应的环境变量名，例如 `database.pool_size` → `DATABASE_POOL_SIZE`
+fn env_name(key: &str) -> String {
+    key.replace('.', "_").to_uppercase()
+}
+
+/// 以 `overlay` 中的值覆盖 `base`，两边都是表的项递归合并
+fn merge(base: &mut Table, overlay: Table) {
+    for (key, value) in overlay {
+        match (base.get_mut(&key), value) {
+            (Some(Value::Table(base)), Value::Table(overlay)) => merge(base, overlay),
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+/// 全部叶子配置项的键
+fn leaf_keys(table: &Table, prefix: &str) -> Vec<String> {
+    table
+        .iter()
+        .flat_map(|(key, value)| {
+            let key = format!("{}{}", prefix, key);
+            match value {
+                Value::Table(table) => leaf_keys(table, &format!("{}.", key)),
+                _ => vec![key],
+            }
+        })
+        .collect()
+}
+
+fn lookup<'a>(table: &'a Table, key: &str) -> Option<&'a Value> {
+    let (head, rest) = key
+        .split_once('.')
+        .map_or((key, None), |(h, r)| (h, Some(r)));
+    match (table.get(head)?, rest) {
+        (Value::Table(table), Some(rest)) => lookup(table, rest),
+        (value, None) => Some(value),
+        _ => None,
+    }
+}
+
+/// 按当前值的类型解析 `raw` 并写入配置项
+fn set(table: &mut Table, key: &str, raw: &str, origin: &str) -> Result<(), ConfigError> {
+    let invalid = || ConfigError::InvalidValue {
+        key: key.to_string(),
+        value: raw.to_string(),
+        origin: origin.to_string(),
+    };
+    let value = match lookup(table, key) {
+        Some(Value::Integer(_)) => Value::Integer(raw.trim().parse().map_err(|_| invalid())?),
+        Some(Value::Float(_)) => Value::Float(raw.trim().parse().map_err(|_| invalid())?),
+        Some(Value::Boolean(_)) => Value::Boolean(raw.trim().parse().map_err(|_| invalid())?),
+        Some(Value::Table(_)) => return Err(invalid()),
+        _ => Value::String(raw.to_string()),
+    };
+
+    let mut segments: Vec<&str> = key.split('.').collect();
+    let leaf = segments.pop().unwrap_or(key);
+    let mut current = table;
+    for segment in segments {
+        current = match current
+            .entry(segment)
+            .or_insert_with(|| Value::Table(Table::new()))
+        {
+            Value::Table(table) => table,
+            _ => return Err(invalid()),
+        };
+    }
+    current.insert(leaf.to_string(), value);
+    Ok(())
+}
@@ -0,0 +1,43 @@
+pub mod app_config;
+pub mod loader;
+
+pub mod error_handling {
+    use std::path::PathBuf;
+    use thiserror::Error;
+
+    /// 加载配置失败的原因
+    #[derive(Debug, Clone, PartialEq, Eq, Error)]
+    pub enum ConfigError {
+        /// 配置文件无法读取
+        #[error("无法读取配置文件 {}: {message}", path.display())]
+        Io { path: PathBuf, message: String },
+
+        /// 配置文件不是合法的 TOML
+        #[error("配置文件 {} 格式错误: {message}", path.display())]
+        Parse { path: PathBuf, message: String },
+
+        /// 环境变量或命令行参数的值与配置项的类型不符
+        #[error("{origin} 为 {key} 提供的值无效: {value}")]
+        InvalidValue {
+            key: String,
+            value: String,
+            origin: String,
+        },
+
+        /// 命令行参数指定了不存在的配置项
+        #[error("未知的配置项: {0}")]
+        UnknownKey(String),
+
+        /// 命令行参数缺少取值
+        #[error("命令行参数 {0} 缺少取值")]
+        MissingValue(String),
+
+        /// 合并后的配置无法转换为 `AppConfig`，例如配置文件中有未知的字段
+        #[error("配置无法解析: {0}")]
+        Deserialize(String),
+
+        /// 配置项的取值超出允许范围
+        #[error("配置无效: {}", .0.join("; "))]
+        Invalid(Vec<String>),
+    }
+}
@@ -1,10 +1,13 @@
+use crate::config::app_config::{DatabaseConfig, RedisConfig, StorageConfig};
 use crate::context::db::connection::create_pool;
+use crate::context::error_handling::InitError;
+use crate::context::health::{HealthReport, ServiceStatus};
 use crate::context::redis::connection::init_redis_connection;
+use crate::context::redis::store::RedisStore;
+use crate::context::retry::RetryPolicy;
+use crate::storage::{open_storage, Storage};
 use my_proc_macro::Literal;
 use once_cell::sync::Lazy;
-use redis::aio::Connection as RedisConnection;
-use redis::AsyncCommands;
-use redis::Client as RedisClient;
 use share_and_commute::errors::context_error::ContextError;
 use sqlx::{Pool, Postgres};
 use std::sync::{Arc, RwLock};
@@ -21,8 +24,18 @@ pub struct AppContext {
     /// 数据库连接池，可选字段，如果尚未初始化则为 None
     db_pool: Option<Arc<Pool<Postgres>>>,
 
-    /// Redis 连接客户端，可选字段
-    redis_client: Option<Arc<RedisClient>>,
+    /// Redis 连接，可选字段
+    redis: Option<RedisStore>,
+
+    /// 个体、资源、偏好与世界检查点的存储，可选字段
+    storage: Option<Arc<dyn Storage>>,
+
+    /// 连接外部服务时的重试策略
+    #[notLiteral]
+    retry_policy: RetryPolicy,
+
+    /// 各外部服务的初始化结果
+    health: HealthReport,
 }
 
 impl AppContext {
@@ -30,29 +43,103 @@ impl AppContext {
     pub fn new() -> Self {
         Self {
             db_pool: None,
-            redis_client: None,
+            redis: None,
+            storage: None,
+            retry_policy: RetryPolicy::default(),
+            health: HealthReport::default(),
         }
     }
+
+    /// 用本上下文替换全局上下文
+    pub fn install(self) {
+        Self::update_global(|context| *context = self);
+    }
 }
 
 /// with
 impl AppContext {
-    /// 使用数据库连接池
-    pub async fn with_db_pool(
-        mut self,
-        idle_timeout: Option<usize>,
-        pool_size: Option<usize>,
-        acquire_timeout: Option<usize>,
-    ) -> Self {
-        self.db_pool = Some(create_pool(idle_timeout, pool_size, acquire_timeout).await);
+    /// 设置连接外部服务时的重试策略（可链式调用）
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// 使用数据库连接池（可链式调用）
+    ///
+    /// 连接失败时不会中断：错误记录在 `health` 中，应用以降级模式继续运行。
+    /// 需要在失败时立即停止的调用方请使用 `try_with_db_pool`。
+    pub async fn with_db_pool(mut self, config: &DatabaseConfig) -> Self {
+        match create_pool(config, &self.retry_policy).await {
+            Ok(pool) => {
+                self.db_pool = Some(pool);
+                self.health.database = ServiceStatus::Up;
+            }
+            Err(e) => {
+                tracing::error!("数据库不可用，以降级模式运行: {}", e);
+                self.health.database = ServiceStatus::Down(e.to_string());
+            }
+        }
         self
     }
 
-    /// 使用 Redis 连接客户端
-    pub async fn with_redis_client(mut self) -> Self {
-        self.redis_client = Some(init_redis_connection(None, None, None).await);
+    /// 使用数据库连接池，连接失败时返回错误
+    pub async fn try_with_db_pool(mut self, config: &DatabaseConfig) -> Result<Self, InitError> {
+        let pool = create_pool(config, &self.retry_policy).await?;
+        self.db_pool = Some(pool);
+        self.health.database = ServiceStatus::Up;
+        Ok(self)
+    }
+
+    /// 按配置使用存储（可链式调用）
+    ///
+    /// Postgres 后端使用已设置的数据库连接池，因此须在 `with_db_pool` 之后调用。
+    /// 与 `with_db_pool` 相同，失败时记录在 `health` 中，应用以降级模式继续运行。
+    pub async fn with_storage(mut self, config: &StorageConfig) -> Self {
+        match open_storage(config, self.db_pool.clone()).await {
+            Ok(storage) => {
+                self.storage = Some(storage);
+                self.health.storage = ServiceStatus::Up;
+            }
+            Err(e) => {
+                tracing::error!("存储不可用，以降级模式运行: {}", e);
+                self.health.storage = ServiceStatus::Down(e.to_string());
+            }
+        }
         self
     }
+
+    /// 按配置使用存储，失败时返回错误
+    pub async fn try_with_storage(mut self, config: &StorageConfig) -> Result<Self, InitError> {
+        let storage = open_storage(config, self.db_pool.clone()).await?;
+        self.storage = Some(storage);
+        self.health.storage = ServiceStatus::Up;
+        Ok(self)
+    }
+
+    /// 使用 Redis 连接（可链式调用）
+    ///
+    /// 与 `with_db_pool` 相同，连接失败时记录在 `health` 中，应用以降级模式继续运行。
+    pub async fn with_redis(mut self, config: &RedisConfig) -> Self {
+        match init_redis_connection(config, &self.retry_policy).await {
+            Ok(store) => {
+                self.redis = Some(store);
+                self.health.redis = ServiceStatus::Up;
+            }
+            Err(e) => {
+                tracing::error!("Redis 不可用，以降级模式运行: {}", e);
+                self.health.redis = ServiceStatus::Down(e.to_string());
+            }
+        }
+        self
+    }
+
+    /// 使用 Redis 连接，连接失败时返回错误
+    pub async fn try_with_redis(mut self, config: &RedisConfig) -> Result<Self, InitError> {
+        let store = init_redis_connection(config, &self.retry_policy).await?;
+        self.redis = Some(store);
+        self.health.redis = ServiceStatus::Up;
+        Ok(self)
+    }
 }
 
 /// update
@@ -69,28 +156,59 @@ impl AppContext {
         update_fn(&mut context);
     }
 
-    /// 初始化（或更新）全局数据库连接池。
-    pub async fn update_global_db_pool(
-        idle_timeout: Option<usize>,
-        pool_size: Option<usize>,
-        acquire_timeout: Option<usize>,
-    ) {
-        let pool = create_pool(idle_timeout, pool_size, acquire_timeout).await;
+    /// 更新全局的重试策略，之后的全局初始化都按此策略重试。
+    pub fn update_global_retry_policy(retry_policy: RetryPolicy) {
         Self::update_global(|context| {
-            context.db_pool = Some(pool);
+            context.retry_policy = retry_policy;
         });
     }
 
-    /// 初始化（或更新）全局 Redis 连接客户端。
-    pub async fn update_global_redis_client(
-        host: Option<&str>,
-        port: Option<&str>,
-        password: Option<&str>,
-    ) {
-        let client = init_redis_connection(host, port, password).await;
-        Self::update_global(|context| {
-            context.redis_client = Some(client);
+    /// 初始化（或更新）全局数据库连接池。
+    ///
+    /// 无论成功与否，结果都会记录到全局的 `health` 中；失败时原有的连接池保持不变。
+    pub async fn update_global_db_pool(config: &DatabaseConfig) -> Result<(), InitError> {
+        let retry_policy = Self::access_app_context(|context| context.retry_policy.clone());
+        let result = create_pool(config, &retry_policy).await;
+        Self::update_global(|context| match &result {
+            Ok(pool) => {
+                context.db_pool = Some(pool.clone());
+                context.health.database = ServiceStatus::Up;
+            }
+            Err(e) => context.health.database = ServiceStatus::Down(e.to_string()),
         });
+        result.map(|_| ())
+    }
+
+    /// 按配置初始化（或更新）全局存储，Postgres 后端使用全局的数据库连接池。
+    ///
+    /// 与 `update_global_db_pool` 相同，结果记录到全局的 `health` 中；失败时原有的存储保持不变。
+    pub async fn update_global_storage(config: &StorageConfig) -> Result<(), InitError> {
+        let db_pool = Self::access_app_context(|context| context.db_pool.clone());
+        let result = open_storage(config, db_pool).await;
+        Self::update_global(|context| match &result {
+            Ok(storage) => {
+                context.storage = Some(storage.clone());
+                context.health.storage = ServiceStatus::Up;
+            }
+            Err(e) => context.health.storage = ServiceStatus::Down(e.to_string()),
+        });
+        result.map(|_| ())
+    }
+
+    /// 初始化（或更新）全局 Redis 连接。
+    ///
+    /// 与 `update_global_db_pool` 相同，结果记录到全局的 `health` 中；失败时原有的连接保持不变。
+    pub async fn update_global_redis(config: &RedisConfig) -> Result<(), InitError> {
+        let retry_policy = Self::access_app_context(|context| context.retry_policy.clone());
+        let result = init_redis_connection(config, &retry_policy).await;
+        Self::update_global(|context| match &result {
+            Ok(store) => {
+                context.redis = Some(store.clone());
+                context.health.redis = ServiceStatus::Up;
+            }
+            Err(e) => context.health.redis = ServiceStatus::Down(e.to_string()),
+        });
+        result.map(|_| ())
     }
 }
 
@@ -107,17 +225,44 @@ impl AppContext {
         accessor(&context)
     }
 
-    /// 获取全局可选字段，若未初始化则触发 panic。
+    /// 获取全局可选字段，读锁失败或字段未初始化时返回错误。
     ///
-    /// 可以复用此方法来获取类似 `db_pool` 等可选字段的值，若为空则报错。
+    /// 可以复用此方法来获取类似 `db_pool` 等可选字段的值。
+    fn try_get_global_optional_field<T, F>(
+        field_accessor: F,
+        field_name: &'static str,
+    ) -> Result<T, AppContextError>
+    where
+        F: FnOnce(&AppContext) -> Option<T>,
+    {
+        let context = APP_CONTEXT
+            .read()
+            .map_err(AppContextError::ReadLockFailed)?;
+        field_accessor(&context).ok_or(AppContextError::ContextFieldNotSet(field_name))
+    }
+
+    /// 获取全局可选字段，若未初始化则触发 panic。
     fn get_global_optional_field<T, F>(field_accessor: F, field_name: &'static str) -> T
     where
         F: FnOnce(&AppContext) -> Option<T>,
     {
-        Self::access_app_context(|context| {
-            field_accessor(context)
-                .unwrap_or_else(|| panic!("{}", AppContextError::ContextFieldNotSet(field_name)))
-        })
+        Self::try_get_global_optional_field(field_accessor, field_name)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// 获取全局数据库连接池，尚未初始化时返回错误。
+    pub fn try_get_db_pool() -> Result<Arc<Pool<Postgres>>, AppContextError> {
+        Self::try_get_global_optional_field(|ctx| ctx.db_pool.clone(), AppContext::DB_POOL)
+    }
+
+    /// 获取全局 Redis 连接，尚未初始化时返回错误。
+    pub fn try_get_redis() -> Result<RedisStore, AppContextError> {
+        Self::try_get_global_optional_field(|ctx| ctx.redis.clone(), AppContext::REDIS)
+    }
+
+    /// 获取全局存储，尚未初始化时返回错误。
+    pub fn try_get_storage() -> Result<Arc<dyn Storage>, AppContextError> {
+        Self::try_get_global_optional_field(|ctx| ctx.storage.clone(), AppContext::STORAGE)
     }
 
     /// 获取全局数据库连接池，如果尚未初始化则会 panic。
@@ -125,8 +270,18 @@ impl AppContext {
         Self::get_global_optional_field(|ctx| ctx.db_pool.clone(), AppContext::DB_POOL)
     }
 
-    /// 获取全局 Redis 连接客户端，如果尚未初始化则会 panic。
-    pub fn get_redis_client() -> Arc<RedisClient> {
-        Self::get_global_optional_field(|ctx| ctx.redis_client.clone(), AppContext::REDIS_CLIENT)
+    /// 获取全局 Redis 连接，如果尚未初始化则会 panic。
+    pub fn get_redis() -> RedisStore {
+        Self::get_global_optional_field(|ctx| ctx.redis.clone(), AppContext::REDIS)
+    }
+
+    /// 获取全局存储，如果尚未初始化则会 panic。
+    pub fn get_storage() -> Arc<dyn Storage> {
+        Self::get_global_optional_field(|ctx| ctx.storage.clone(), AppContext::STORAGE)
+    }
+
+    /// 获取各外部服务的健康状况
+    pub fn health() -> HealthReport {
+        Self::access_app_context(|context| context.health.clone())
     }
 }
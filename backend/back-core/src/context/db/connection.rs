@@ -1,44 +1,80 @@
-use dotenvy::dotenv;
+use crate::config::app_config::DatabaseConfig;
+use crate::context::db::migration::run_migrations;
+use crate::context::error_handling::InitError;
+use crate::context::retry::RetryPolicy;
+use crate::context::secret::redact_secret;
 use sqlx::postgres::PgPoolOptions;
 use sqlx::{Pool, Postgres};
-use std::env;
+use std::io::ErrorKind;
 use std::sync::Arc;
 use std::time::Duration;
 
-// 数据库设置和初始化
-pub(crate) async fn create_pool(
-    idle_timeout: Option<usize>,
-    pool_size: Option<usize>,
-    acquire_timeout: Option<usize>,
-) -> Arc<Pool<Postgres>> {
-    dotenv().ok();
-
-    let idle_timeout = idle_timeout.unwrap_or(30);
-    let pool_size = pool_size.unwrap_or(10);
-    let acquire_timeout = acquire_timeout.unwrap_or(5);
+/// 日志与错误信息中的服务名称
+const SERVICE: &str = "数据库";
+/// Postgres 认证失败的错误码：invalid_authorization_specification 与 invalid_password
+const AUTH_FAILED_CODES: [&str; 2] = ["28000", "28P01"];
 
-    // 从环境变量获取数据库配置信息
-    let db_user = env::var("DATABASE_USER").expect("DATABASE_USER must be set");
-    let db_password = env::var("DATABASE_PASSWORD").expect("DATABASE_PASSWORD must be set");
-    let db_host = env::var("DATABASE_HOST").expect("DATABASE_HOST must be set");
-    let db_port = env::var("DATABASE_PORT").expect("DATABASE_PORT must be set");
-    let db_name = env::var("DATABASE_NAME").expect("DATABASE_NAME must be set");
+// 数据库设置和初始化，按配置执行迁移
+pub(crate) async fn create_pool(
+    config: &DatabaseConfig,
+    retry: &RetryPolicy,
+) -> Result<Arc<Pool<Postgres>>, InitError> {
+    let options = config.connect_options()?;
 
-    // 动态生成 DATABASE_URL
-    let database_url = format!(
-        "postgresql://{}:{}@{}:{}/{}",
-        db_user, db_password, db_host, db_port, db_name
+    tracing::info!(
+        "连接数据库: {}@{}:{}/{}",
+        config.user,
+        config.host,
+        config.port,
+        config.name
     );
 
-    println!("Database URL: {}", database_url);
+    let pool = retry
+        .run(SERVICE, || async {
+            PgPoolOptions::new()
+                .max_connections(config.pool_size)
+                .acquire_timeout(Duration::from_secs(config.acquire_timeout_secs))
+                .idle_timeout(Duration::from_secs(config.idle_timeout_secs))
+                .connect_with(options.clone())
+                .await
+                .map_err(|e| classify(e, &config.password))
+        })
+        .await?;
+
+    if config.run_migrations {
+        run_migrations(&pool).await?;
+    }
 
-    let pool = PgPoolOptions::new()
-        .max_connections(pool_size as u32)
-        .acquire_timeout(Duration::from_secs(acquire_timeout as u64))
-        .idle_timeout(Duration::from_secs(idle_timeout as u64))
-        .connect(&database_url)
-        .await
-        .expect("无法构建数据库连接池");
+    Ok(Arc::new(pool))
+}
 
-    Arc::new(pool)
-}
\ No newline at end of file
+/// 将 sqlx 的错误归类为 `InitError`，并隐藏其中可能出现的密码
+fn classify(error: sqlx::Error, password: &str) -> InitError {
+    let message = redact_secret(&error.to_string(), password);
+    match &error {
+        sqlx::Error::PoolTimedOut => InitError::Timeout { service: SERVICE },
+        sqlx::Error::Io(io_error) if io_error.kind() == ErrorKind::TimedOut => {
+            InitError::Timeout { service: SERVICE }
+        }
+        sqlx::Error::Io(_) | sqlx::Error::Tls(_) | sqlx::Error::PoolClosed => {
+            InitError::ConnectionRefused {
+                service: SERVICE,
+                message,
+            }
+        }
+        sqlx::Error::Database(db_error)
+            if db_error
+                .code()
+                .is_some_and(|code| AUTH_FAILED_CODES.contains(&code.as_ref())) =>
+        {
+            InitError::AuthFailed {
+                service: SERVICE,
+                message,
+            }
+        }
+        _ => InitError::Other {
+            service: SERVICE,
+            message,
+        },
+    }
+}
@@ -0,0 +1,42 @@
+use crate::context::error_handling::InitError;
+use sqlx::migrate::{Migrate, Migrator};
+use sqlx::{Pool, Postgres};
+
+/// 编译期嵌入的 `backend/migrations` 目录，部署时无需携带迁移文件
+static MIGRATOR: Migrator = sqlx::migrate!("../migrations");
+
+/// 执行尚未应用的数据库迁移
+///
+/// 已应用的迁移会被跳过；已应用的迁移文件被修改时返回错误，而不是重复执行。
+///
+/// ### 参数
+/// - `pool`: 数据库连接池。
+///
+/// ### 返回值
+/// 返回本次新应用的迁移数。
+pub async fn run_migrations(pool: &Pool<Postgres>) -> Result<usize, InitError> {
+    let failed = |e: sqlx::migrate::MigrateError| InitError::Migration(e.to_string());
+
+    let applied_before = {
+        let mut connection = pool
+            .acquire()
+            .await
+            .map_err(|e| InitError::Migration(e.to_string()))?;
+        connection.ensure_migrations_table().await.map_err(failed)?;
+        connection
+            .list_applied_migrations()
+            .await
+            .map_err(failed)?
+            .len()
+    };
+
+    MIGRATOR.run(pool).await.map_err(failed)?;
+
+    let applied = MIGRATOR.iter().count().saturating_sub(applied_before);
+    tracing::info!(
+        "数据库迁移完成：新应用 {} 个，共 {} 个",
+        applied,
+        MIGRATOR.iter().count()
+    );
+    Ok(applied)
+}
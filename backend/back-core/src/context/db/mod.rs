@@ -1 +1,2 @@
 pub(crate) mod connection;
+pub mod migration;
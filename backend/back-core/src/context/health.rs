@@ -0,0 +1,31 @@
+use serde::Serialize;
+
+/// 单个外部服务的状态
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(tag = "status", content = "detail", rename_all = "snake_case")]
+pub enum ServiceStatus {
+    /// 尚未尝试初始化
+    #[default]
+    NotConfigured,
+    /// 已连接
+    Up,
+    /// 初始化失败，附带（已隐藏敏感信息的）原因
+    Down(String),
+}
+
+/// 应用依赖的外部服务的健康状况
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct HealthReport {
+    pub database: ServiceStatus,
+    pub redis: ServiceStatus,
+    pub storage: ServiceStatus,
+}
+
+impl HealthReport {
+    /// 是否有服务初始化失败，此时应用以降级模式运行
+    pub fn is_degraded(&self) -> bool {
+        [&self.database, &self.redis, &self.storage]
+            .iter()
+            .any(|status| matches!(status, ServiceStatus::Down(_)))
+    }
+}
@@ -1,3 +1,57 @@
 pub mod core_context;
-mod db;
-mod redis;
+pub mod db;
+pub mod health;
+pub mod redis;
+pub mod retry;
+pub(crate) mod secret;
+
+pub mod error_handling {
+    use thiserror::Error;
+
+    /// 外部服务初始化失败的原因
+    #[derive(Debug, Clone, PartialEq, Eq, Error)]
+    pub enum InitError {
+        /// 缺少必需的配置项，例如 `database.user`
+        #[error("缺少配置项: {0}")]
+        MissingConfig(&'static str),
+
+        /// 服务不可达或拒绝连接
+        #[error("无法连接到 {service}: {message}")]
+        ConnectionRefused {
+            service: &'static str,
+            message: String,
+        },
+
+        /// 用户名或密码错误
+        #[error("{service} 认证失败: {message}")]
+        AuthFailed {
+            service: &'static str,
+            message: String,
+        },
+
+        /// 建立连接超时
+        #[error("连接 {service} 超时")]
+        Timeout { service: &'static str },
+
+        /// 数据库迁移执行失败，或已应用的迁移文件被修改
+        #[error("数据库迁移失败: {0}")]
+        Migration(String),
+
+        /// 其他无法归类的错误
+        #[error("{service} 初始化失败: {message}")]
+        Other {
+            service: &'static str,
+            message: String,
+        },
+    }
+
+    impl InitError {
+        /// 是否值得重试：连接被拒绝与超时通常是暂时的，缺少配置与认证失败则不是
+        pub fn is_retryable(&self) -> bool {
+            matches!(
+                self,
+                InitError::ConnectionRefused { .. } | InitError::Timeout { .. }
+            )
+        }
+    }
+}
@@ -1,41 +1,62 @@
-use redis::AsyncCommands;
-use tokio;
+use crate::config::app_config::RedisConfig;
+use crate::context::error_handling::InitError;
+use crate::context::redis::store::RedisStore;
+use crate::context::retry::RetryPolicy;
+use crate::context::secret::redact_secret;
+use redis::{ConnectionAddr, ConnectionInfo, ErrorKind, RedisConnectionInfo, RedisError};
 
-use redis::AsyncCommands;
-use tokio;
+/// 日志与错误信息中的服务名称
+const SERVICE: &str = "Redis";
 
+/// 建立 Redis 连接，失败时按 `retry` 重试
 pub(crate) async fn init_redis_connection(
-    host: Option<&str>,
-    port: Option<&str>,
-    password: Option<&str>,
-) -> redis::RedisResult<redis::aio::Connection> {
-    // 使用外部传入的参数，如果未传入则从环境变量获取，最终使用默认值
-    let redis_host = host
-        .map(|h| h.to_string())
-        .or_else(|| std::env::var("REDIS_HOST").ok())
-        .unwrap_or_else(|| "localhost".to_string());
+    config: &RedisConfig,
+    retry: &RetryPolicy,
+) -> Result<RedisStore, InitError> {
+    // 直接构造连接信息，密码不会出现在 URL 与日志中
+    let connection_info = ConnectionInfo {
+        addr: ConnectionAddr::Tcp(config.host.clone(), config.port),
+        redis: RedisConnectionInfo {
+            db: 0,
+            username: None,
+            password: Some(config.password.clone()).filter(|password| !password.is_empty()),
+        },
+    };
 
-    let redis_port = port
-        .map(|p| p.to_string())
-        .or_else(|| std::env::var("REDIS_PORT").ok())
-        .unwrap_or_else(|| "6379".to_string());
+    tracing::info!("连接 Redis: redis://{}:{}", config.host, config.port);
 
-    let redis_password = password
-        .map(|pwd| pwd.to_string())
-        .or_else(|| std::env::var("REDIS_PASSWORD").ok())
-        .unwrap_or_else(|| "".to_string());
+    retry
+        .run(SERVICE, || async {
+            RedisStore::open(connection_info.clone())
+                .await
+                .map_err(|e| classify(e, &config.password))
+        })
+        .await
+}
 
-    // 动态组合 Redis URL，包含密码
-    let redis_url = if redis_password.is_empty() {
-        // 如果密码为空，则不添加密码部分
-        format!("redis://{}:{}", redis_host, redis_port)
+/// 将 Redis 的错误归类为 `InitError`，并隐藏其中可能出现的密码
+fn classify(error: RedisError, password: &str) -> InitError {
+    let message = redact_secret(&error.to_string(), password);
+    if error.is_timeout() {
+        InitError::Timeout { service: SERVICE }
+    } else if error.is_io_error() {
+        InitError::ConnectionRefused {
+            service: SERVICE,
+            message,
+        }
+    } else if error.kind() == ErrorKind::AuthenticationFailed
+        || error
+            .code()
+            .is_some_and(|code| code == "NOAUTH" || code == "WRONGPASS")
+    {
+        InitError::AuthFailed {
+            service: SERVICE,
+            message,
+        }
     } else {
-        // 如果密码不为空，则添加密码部分
-        format!("redis://:{}@{}:{}", redis_password, redis_host, redis_port)
-    };
-
-    // 建立异步连接
-    let client = redis::Client::open(redis_url)?;
-    let conn = client.get_async_connection().await?;
-    Ok(conn)
+        InitError::Other {
+            service: SERVICE,
+            message,
+        }
+    }
 }
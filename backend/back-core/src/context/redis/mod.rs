@@ -1 +1,18 @@
-pub(crate) mod connection;
\ No newline at end of file
+pub(crate) mod connection;
+pub mod store;
+
+pub mod error_handling {
+    use thiserror::Error;
+
+    /// Redis 读写失败的原因
+    #[derive(Debug, Error)]
+    pub enum RedisStoreError {
+        /// Redis 命令执行失败，或连接已断开且重连失败
+        #[error("Redis 命令执行失败: {0}")]
+        Redis(#[from] redis::RedisError),
+
+        /// 值无法序列化为 JSON，或读出的内容不是预期的 JSON
+        #[error("Redis 中的值无法（反）序列化: {0}")]
+        Serialization(#[from] serde_json::Error),
+    }
+}
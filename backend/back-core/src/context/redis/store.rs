@@ -0,0 +1,207 @@
+use crate::context::redis::error_handling::RedisStoreError;
+use futures::stream::{BoxStream, StreamExt};
+use redis::aio::ConnectionManager;
+use redis::{Client, FromRedisValue, IntoConnectionInfo, RedisResult, ToRedisArgs};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fmt;
+use std::future::Future;
+use std::time::Duration;
+
+/// Redis 连接
+///
+/// 普通命令通过 `ConnectionManager` 复用同一条多路复用连接，可以廉价地克隆并在任务间共享。
+/// 连接断开后，`ConnectionManager` 会在后台按指数退避重连；
+/// 因断线而失败的命令会在重连后再执行一次。
+///
+/// 订阅需要独占一条连接，每次调用 `subscribe` 都会新建一条。
+#[derive(Clone)]
+pub struct RedisStore {
+    client: Client,
+    manager: ConnectionManager,
+}
+
+/// 从订阅的频道收到的一条消息
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RedisMessage {
+    pub channel: String,
+    pub payload: Vec<u8>,
+}
+
+impl RedisMessage {
+    /// 按 UTF-8 解读消息内容
+    pub fn payload_str(&self) -> Option<&str> {
+        std::str::from_utf8(&self.payload).ok()
+    }
+
+    /// 按 JSON 解读消息内容
+    pub fn payload_json<T: DeserializeOwned>(&self) -> Result<T, RedisStoreError> {
+        Ok(serde_json::from_slice(&self.payload)?)
+    }
+}
+
+impl fmt::Debug for RedisStore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RedisStore")
+            .field("client", &self.client)
+            .finish_non_exhaustive()
+    }
+}
+
+impl RedisStore {
+    /// 连接到 Redis，不做重试
+    ///
+    /// ### 参数
+    /// - `info`: 连接信息，例如 `redis://127.0.0.1:6379`。
+    pub async fn open(info: impl IntoConnectionInfo) -> RedisResult<Self> {
+        let client = Client::open(info)?;
+        // 先单独连接并 PING 一次，服务不可达或需要认证时立即失败，
+        // 而不是等待 ConnectionManager 的重连退避
+        let mut probe = client.get_multiplexed_async_connection().await?;
+        redis::cmd("PING").query_async::<_, ()>(&mut probe).await?;
+        let manager = ConnectionManager::new(client.clone()).await?;
+        Ok(Self { client, manager })
+    }
+
+    /// 执行命令；若因连接断开而失败，等待重连后再执行一次
+    async fn execute<T, F, Fut>(&self, command: F) -> Result<T, RedisStoreError>
+    where
+        F: Fn(ConnectionManager) -> Fut,
+        Fut: Future<Output = RedisResult<T>>,
+    {
+        match command(self.manager.clone()).await {
+            Err(e) if e.is_connection_dropped() || e.is_io_error() => {
+                tracing::warn!("Redis 连接已断开，重连后重试: {}", e);
+                Ok(command(self.manager.clone()).await?)
+            }
+            result => Ok(result?),
+        }
+    }
+
+    /// 检查连接是否可用
+    pub async fn ping(&self) -> Result<(), RedisStoreError> {
+        self.execute(|mut conn| async move { redis::cmd("PING").query_async(&mut conn).await })
+            .await
+    }
+}
+
+/// 键值读写
+impl RedisStore {
+    /// 读取键的值，键不存在时返回 `None`
+    pub async fn get<T: FromRedisValue>(&self, key: &str) -> Result<Option<T>, RedisStoreError> {
+        self.execute(
+            |mut conn| async move { redis::cmd("GET").arg(key).query_async(&mut conn).await },
+        )
+        .await
+    }
+
+    /// 写入键的值
+    ///
+    /// ### 参数
+    /// - `key`: 键。
+    /// - `value`: 值。
+    /// - `ttl`: 过期时间，为 `None` 时永不过期；精度为毫秒。
+    pub async fn set<V: ToRedisArgs>(
+        &self,
+        key: &str,
+        value: V,
+        ttl: Option<Duration>,
+    ) -> Result<(), RedisStoreError> {
+        let mut command = redis::cmd("SET");
+        command.arg(key).arg(value);
+        if let Some(ttl) = ttl {
+            command.arg("PX").arg(ttl.as_millis().max(1) as u64);
+        }
+        let command = &command;
+        self.execute(|mut conn| async move { command.query_async(&mut conn).await })
+            .await
+    }
+
+    /// 删除键
+    ///
+    /// ### 返回值
+    /// 返回键删除前是否存在。
+    pub async fn delete(&self, key: &str) -> Result<bool, RedisStoreError> {
+        let removed: usize = self
+            .execute(
+                |mut conn| async move { redis::cmd("DEL").arg(key).query_async(&mut conn).await },
+            )
+            .await?;
+        Ok(removed > 0)
+    }
+
+    /// 读取以 JSON 存放的值，键不存在时返回 `None`
+    pub async fn get_json<T: DeserializeOwned>(
+        &self,
+        key: &str,
+    ) -> Result<Option<T>, RedisStoreError> {
+        let raw: Option<Vec<u8>> = self.get(key).await?;
+        raw.map(|raw| serde_json::from_slice(&raw))
+            .transpose()
+            .map_err(RedisStoreError::from)
+    }
+
+    /// 以 JSON 写入值，`ttl` 的含义与 `set` 相同
+    pub async fn set_json<T: Serialize>(
+        &self,
+        key: &str,
+        value: &T,
+        ttl: Option<Duration>,
+    ) -> Result<(), RedisStoreError> {
+        let raw = serde_json::to_vec(value)?;
+        self.set(key, raw, ttl).await
+    }
+}
+
+/// 发布与订阅
+impl RedisStore {
+    /// 向频道发布消息
+    ///
+    /// ### 返回值
+    /// 返回收到消息的订阅者数量。
+    pub async fn publish<V: ToRedisArgs>(
+        &self,
+        channel: &str,
+        message: V,
+    ) -> Result<usize, RedisStoreError> {
+        let mut command = redis::cmd("PUBLISH");
+        command.arg(channel).arg(message);
+        let command = &command;
+        self.execute(|mut conn| async move { command.query_async(&mut conn).await })
+            .await
+    }
+
+    /// 以 JSON 向频道发布消息
+    pub async fn publish_json<T: Serialize>(
+        &self,
+        channel: &str,
+        message: &T,
+    ) -> Result<usize, RedisStoreError> {
+        let raw = serde_json::to_vec(message)?;
+        self.publish(channel, raw).await
+    }
+
+    /// 订阅若干频道
+    ///
+    /// 订阅使用一条独立的连接。连接断开时返回的流随之结束，需要时可再次调用本方法重新订阅。
+    ///
+    /// ### 返回值
+    /// 返回收到的消息流；订阅确认后才返回，之后发布的消息都不会遗漏。
+    pub async fn subscribe(
+        &self,
+        channels: &[&str],
+    ) -> Result<BoxStream<'static, RedisMessage>, RedisStoreError> {
+        let mut pubsub = self.client.get_async_connection().await?.into_pubsub();
+        for channel in channels {
+            pubsub.subscribe(*channel).await?;
+        }
+
+        Ok(pubsub
+            .into_on_message()
+            .map(|message| RedisMessage {
+                channel: message.get_channel_name().to_string(),
+                payload: message.get_payload_bytes().to_vec(),
+            })
+            .boxed())
+    }
+}
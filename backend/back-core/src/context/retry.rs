@@ -0,0 +1,103 @@
+use crate::context::error_handling::InitError;
+use std::future::Future;
+use std::time::Duration;
+
+const DEFAULT_MAX_ATTEMPTS: usize = 5;
+const DEFAULT_INITIAL_DELAY: Duration = Duration::from_millis(500);
+const DEFAULT_MAX_DELAY: Duration = Duration::from_secs(10);
+const DEFAULT_MULTIPLIER: f64 = 2.0;
+
+/// 连接外部服务时的重试策略：指数退避
+///
+/// - `max_attempts`: 最多尝试的次数（含第一次），至少为 1。
+/// - `initial_delay`: 第一次失败后的等待时间。
+/// - `max_delay`: 等待时间的上限。
+/// - `multiplier`: 每次失败后等待时间的倍数，至少为 1。
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetryPolicy {
+    max_attempts: usize,
+    initial_delay: Duration,
+    max_delay: Duration,
+    multiplier: f64,
+}
+
+impl RetryPolicy {
+    /// 使用可选的参数创建重试策略，未提供时使用默认值
+    pub fn new(
+        max_attempts: Option<usize>,
+        initial_delay: Option<Duration>,
+        max_delay: Option<Duration>,
+    ) -> Self {
+        Self {
+            max_attempts: max_attempts.unwrap_or(DEFAULT_MAX_ATTEMPTS).max(1),
+            initial_delay: initial_delay.unwrap_or(DEFAULT_INITIAL_DELAY),
+            max_delay: max_delay.unwrap_or(DEFAULT_MAX_DELAY),
+            multiplier: DEFAULT_MULTIPLIER,
+        }
+    }
+
+    /// 只尝试一次、不重试的策略
+    pub fn no_retry() -> Self {
+        Self::new(Some(1), None, None)
+    }
+
+    /// 设置每次失败后等待时间的倍数（可链式调用）
+    pub fn with_multiplier(mut self, multiplier: f64) -> Self {
+        self.multiplier = multiplier.max(1.0);
+        self
+    }
+
+    pub fn max_attempts(&self) -> usize {
+        self.max_attempts
+    }
+
+    /// 第 `attempt` 次（从 1 开始）失败后的等待时间
+    pub fn delay_after(&self, attempt: usize) -> Duration {
+        let factor = self.multiplier.powi(attempt.saturating_sub(1) as i32);
+        self.initial_delay.mul_f64(factor).min(self.max_delay)
+    }
+
+    /// 按策略执行 `operation`，仅在错误可重试时重试
+    ///
+    /// ### 参数
+    /// - `service`: 服务名称，用于日志。
+    /// - `operation`: 每次尝试都会重新调用以得到新的 future。
+    ///
+    /// ### 返回值
+    /// 返回第一次成功的结果；不可重试的错误立即返回，否则返回最后一次的错误。
+    pub async fn run<T, F, Fut>(&self, service: &str, mut operation: F) -> Result<T, InitError>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, InitError>>,
+    {
+        let mut attempt = 1;
+        loop {
+            match operation().await {
+                Ok(value) => return Ok(value),
+                Err(e) if e.is_retryable() && attempt < self.max_attempts => {
+                    let delay = self.delay_after(attempt);
+                    tracing::warn!(
+                        "第 {}/{} 次连接 {} 失败: {}，{:?} 后重试",
+                        attempt,
+                        self.max_attempts,
+                        service,
+                        e,
+                        delay
+                    );
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                Err(e) => {
+                    tracing::error!("连接 {} 失败（共尝试 {} 次）: {}", service, attempt, e);
+                    return Err(e);
+                }
+            }
+        }
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::new(None, None, None)
+    }
+}
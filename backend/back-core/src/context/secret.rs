@@ -0,0 +1,11 @@
+/// 日志中代替敏感信息的占位符
+const REDACTED: &str = "***";
+
+/// 将文本中出现的秘密替换为占位符，用于清理可能携带密码的错误信息
+pub(crate) fn redact_secret(text: &str, secret: &str) -> String {
+    if secret.is_empty() {
+        text.to_string()
+    } else {
+        text.replace(secret, REDACTED)
+    }
+}
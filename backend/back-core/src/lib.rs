@@ -1 +1,4 @@
+pub mod config;
 pub mod context;
+pub mod repository;
+pub mod storage;
@@ -0,0 +1,113 @@
+use crate::repository::error_handling::RepositoryError;
+use crate::repository::{check_unique_keys, BATCH_SIZE};
+use sqlx::{FromRow, Pool, Postgres, QueryBuilder};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// `agents` 表中的一行：世界中的一个个体
+#[derive(Debug, Clone, Copy, PartialEq, Eq, FromRow)]
+pub struct AgentRecord {
+    pub agent_id: Uuid,
+    /// 个体所属的世界
+    pub world_id: Uuid,
+    /// 个体诞生时世界的步数
+    pub born_tick: i64,
+}
+
+impl AgentRecord {
+    pub(crate) fn validate(&self) -> Result<(), RepositoryError> {
+        if self.born_tick < 0 {
+            return Err(RepositoryError::InvalidRecord(format!(
+                "个体 {} 的诞生步数不能为负: {}",
+                self.agent_id, self.born_tick
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// `agents` 表的读写
+///
+/// 删除世界时，其个体会被级联删除；个体的资源与偏好由 `delete` 一并删除。
+#[derive(Debug, Clone)]
+pub struct AgentRepository {
+    pool: Arc<Pool<Postgres>>,
+}
+
+impl AgentRepository {
+    pub fn new(pool: Arc<Pool<Postgres>>) -> Self {
+        Self { pool }
+    }
+}
+
+/// 写入
+impl AgentRepository {
+    /// 批量写入个体，已存在的个体更新其所属世界与诞生步数
+    ///
+    /// 全部记录在同一事务中写入，任一批失败时整体回滚。
+    ///
+    /// ### 返回值
+    /// 返回写入的记录数；有记录不满足表约束或 `agent_id` 重复时返回 `InvalidRecord`，不写入任何记录。
+    pub async fn upsert_many(&self, records: &[AgentRecord]) -> Result<usize, RepositoryError> {
+        records.iter().try_for_each(AgentRecord::validate)?;
+        check_unique_keys(records.iter().map(|record| record.agent_id), "个体")?;
+
+        let mut transaction = self.pool.begin().await?;
+        for batch in records.chunks(BATCH_SIZE) {
+            QueryBuilder::<Postgres>::new("INSERT INTO agents (agent_id, world_id, born_tick) ")
+                .push_values(batch, |mut row, record| {
+                    row.push_bind(record.agent_id)
+                        .push_bind(record.world_id)
+                        .push_bind(record.born_tick);
+                })
+                .push(
+                    " ON CONFLICT (agent_id) \
+                     DO UPDATE SET world_id = EXCLUDED.world_id, born_tick = EXCLUDED.born_tick",
+                )
+                .build()
+                .execute(&mut *transaction)
+                .await?;
+        }
+        transaction.commit().await?;
+
+        Ok(records.len())
+    }
+
+    /// 删除个体及其全部资源与偏好
+    ///
+    /// ### 返回值
+    /// 返回个体原本是否存在。
+    pub async fn delete(&self, agent_id: Uuid) -> Result<bool, RepositoryError> {
+        let mut transaction = self.pool.begin().await?;
+        for statement in [
+            "DELETE FROM resources WHERE agent_id = $1",
+            "DELETE FROM preferences WHERE agent_id = $1",
+        ] {
+            sqlx::query(statement)
+                .bind(agent_id)
+                .execute(&mut *transaction)
+                .await?;
+        }
+        let result = sqlx::query("DELETE FROM agents WHERE agent_id = $1")
+            .bind(agent_id)
+            .execute(&mut *transaction)
+            .await?;
+        transaction.commit().await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+/// 读取
+impl AgentRepository {
+    /// 读取世界的全部个体，按 `agent_id` 排序
+    pub async fn list_by_world(&self, world_id: Uuid) -> Result<Vec<AgentRecord>, RepositoryError> {
+        Ok(sqlx::query_as(
+            "SELECT agent_id, world_id, born_tick FROM agents \
+             WHERE world_id = $1 ORDER BY agent_id",
+        )
+        .bind(world_id)
+        .fetch_all(&*self.pool)
+        .await?)
+    }
+}
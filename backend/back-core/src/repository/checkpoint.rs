@@ -0,0 +1,290 @@
+use crate::repository::error_handling::RepositoryError;
+use chrono::{DateTime, Utc};
+use sqlx::{FromRow, Pool, Postgres};
+use std::sync::Arc;
+use std::time::Duration;
+use uuid::Uuid;
+
+/// `checkpoints` 表中的一行：某次运行在某一步的世界快照
+#[derive(Debug, Clone, PartialEq, Eq, FromRow)]
+pub struct CheckpointRecord {
+    pub checkpoint_id: i64,
+    pub run_id: Uuid,
+    pub world_id: Uuid,
+    pub tick: i64,
+    /// 压缩后的快照，编码由调用方决定
+    pub snapshot: Vec<u8>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// 不含快照内容的检查点信息，用于列表
+#[derive(Debug, Clone, PartialEq, Eq, FromRow)]
+pub struct CheckpointSummary {
+    pub checkpoint_id: i64,
+    pub run_id: Uuid,
+    pub world_id: Uuid,
+    pub tick: i64,
+    /// 快照的字节数
+    pub size: i32,
+    pub created_at: DateTime<Utc>,
+}
+
+/// 检查点的保留策略
+///
+/// 每个世界最新的 `keep_latest` 个检查点总是保留；其余的检查点只有同时满足以下条件才保留：
+/// - 设置了 `keep_every`，且步数是它的整数倍（里程碑）；
+/// - 未设置 `max_age`，或创建时间在 `max_age` 之内。
+///
+/// 例如 `RetentionPolicy::new(Some(3)).with_keep_every(1).with_max_age(一周)`
+/// 保留最新的 3 个以及一周内的全部检查点。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetentionPolicy {
+    keep_latest: usize,
+    keep_every: Option<u64>,
+    max_age: Option<Duration>,
+}
+
+impl RetentionPolicy {
+    /// 创建保留策略，只保留最新的 `keep_latest` 个检查点
+    ///
+    /// ### 参数
+    /// - `keep_latest`: 默认为 1，至少为 1，保证总能从最新的检查点恢复。
+    pub fn new(keep_latest: Option<usize>) -> Self {
+        Self {
+            keep_latest: keep_latest.unwrap_or(1).max(1),
+            keep_every: None,
+            max_age: None,
+        }
+    }
+
+    /// 额外保留步数为 `interval` 整数倍的检查点（可链式调用）
+    pub fn with_keep_every(mut self, interval: u64) -> Self {
+        self.keep_every = Some(interval.max(1));
+        self
+    }
+
+    /// 额外保留的检查点超过 `max_age` 后也会被删除（可链式调用）
+    pub fn with_max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = Some(max_age);
+        self
+    }
+
+    pub fn keep_latest(&self) -> usize {
+        self.keep_latest
+    }
+
+    pub fn keep_every(&self) -> Option<u64> {
+        self.keep_every
+    }
+
+    pub fn max_age(&self) -> Option<Duration> {
+        self.max_age
+    }
+
+    /// 额外保留的检查点的最早创建时间，未设置 `max_age` 时为 `None`
+    pub(crate) fn cutoff(&self) -> Option<DateTime<Utc>> {
+        self.max_age.map(|max_age| {
+            chrono::Duration::from_std(max_age)
+                .ok()
+                .and_then(|max_age| Utc::now().checked_sub_signed(max_age))
+                .unwrap_or(DateTime::<Utc>::MIN_UTC)
+        })
+    }
+
+    /// 不在最新的 `keep_latest` 个之列的检查点是否仍应保留
+    pub(crate) fn retains_extra(
+        &self,
+        tick: i64,
+        created_at: DateTime<Utc>,
+        cutoff: Option<DateTime<Utc>>,
+    ) -> bool {
+        let milestone = self.keep_every.is_some_and(|interval| {
+            u64::try_from(tick).is_ok_and(|tick| tick.is_multiple_of(interval))
+        });
+        milestone && cutoff.is_none_or(|cutoff| created_at >= cutoff)
+    }
+}
+
+impl Default for RetentionPolicy {
+    fn default() -> Self {
+        Self::new(None)
+    }
+}
+
+/// `checkpoints` 表的读写
+///
+/// ```no_run
+/// use back_core::context::core_context::AppContext;
+/// use back_core::repository::checkpoint::{CheckpointRepository, RetentionPolicy};
+/// use back_core::repository::run::RunRepository;
+/// use std::time::Duration;
+/// use uuid::Uuid;
+///
+/// # async fn run(world_id: Uuid) -> Result<(), back_core::repository::error_handling::RepositoryError> {
+/// let runs = RunRepository::new(AppContext::get_db_pool());
+/// let checkpoints = CheckpointRepository::new(AppContext::get_db_pool());
+///
+/// let run = runs.start(world_id, 0, 10).await?;
+/// checkpoints.save(run.run_id, 10, b"snapshot").await?;
+///
+/// let latest = checkpoints.latest(world_id).await?.unwrap();
+/// assert_eq!(latest.tick, 10);
+///
+/// let policy = RetentionPolicy::new(Some(5))
+///     .with_keep_every(100)
+///     .with_max_age(Duration::from_secs(7 * 24 * 3600));
+/// checkpoints.prune(world_id, &policy).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct CheckpointRepository {
+    pool: Arc<Pool<Postgres>>,
+}
+
+impl CheckpointRepository {
+    pub fn new(pool: Arc<Pool<Postgres>>) -> Self {
+        Self { pool }
+    }
+}
+
+/// 写入
+impl CheckpointRepository {
+    /// 保存检查点，并把运行的 `last_tick` 推进到该步
+    ///
+    /// 两项修改在同一事务中完成；同一运行在同一步重复保存时覆盖原有的快照。
+    ///
+    /// ### 返回值
+    /// 返回检查点的 `checkpoint_id`；运行不存在时返回 `InvalidRecord`。
+    pub async fn save(
+        &self,
+        run_id: Uuid,
+        tick: i64,
+        snapshot: &[u8],
+    ) -> Result<i64, RepositoryError> {
+        let mut transaction = self.pool.begin().await?;
+
+        let world_id: Option<Uuid> = sqlx::query_scalar(
+            "UPDATE runs SET last_tick = GREATEST(last_tick, $2) \
+             WHERE run_id = $1 AND start_tick <= $2 \
+             RETURNING world_id",
+        )
+        .bind(run_id)
+        .bind(tick)
+        .fetch_optional(&mut *transaction)
+        .await?;
+        let Some(world_id) = world_id else {
+            return Err(RepositoryError::InvalidRecord(format!(
+                "运行 {} 不存在，或第 {} 步早于运行的起始步数",
+                run_id, tick
+            )));
+        };
+
+        let checkpoint_id = sqlx::query_scalar(
+            "INSERT INTO checkpoints (run_id, world_id, tick, snapshot) \
+             VALUES ($1, $2, $3, $4) \
+             ON CONFLICT (run_id, tick) DO UPDATE SET snapshot = EXCLUDED.snapshot, created_at = now() \
+             RETURNING checkpoint_id",
+        )
+        .bind(run_id)
+        .bind(world_id)
+        .bind(tick)
+        .bind(snapshot)
+        .fetch_one(&mut *transaction)
+        .await?;
+
+        transaction.commit().await?;
+        Ok(checkpoint_id)
+    }
+
+    /// 按保留策略删除世界的旧检查点
+    ///
+    /// ### 返回值
+    /// 返回删除的检查点数。
+    pub async fn prune(
+        &self,
+        world_id: Uuid,
+        policy: &RetentionPolicy,
+    ) -> Result<u64, RepositoryError> {
+        let keep_latest = i64::try_from(policy.keep_latest).unwrap_or(i64::MAX);
+        let keep_every = policy
+            .keep_every
+            .map(|interval| i64::try_from(interval).unwrap_or(i64::MAX));
+        let cutoff = policy.cutoff();
+
+        let result = sqlx::query(
+            "DELETE FROM checkpoints \
+             WHERE world_id = $1 \
+             AND checkpoint_id NOT IN ( \
+                 SELECT checkpoint_id FROM checkpoints WHERE world_id = $1 \
+                 ORDER BY tick DESC, created_at DESC LIMIT $2 \
+             ) \
+             AND NOT ( \
+                 $3::BIGINT IS NOT NULL AND tick % $3 = 0 \
+                 AND ($4::TIMESTAMPTZ IS NULL OR created_at >= $4) \
+             )",
+        )
+        .bind(world_id)
+        .bind(keep_latest)
+        .bind(keep_every)
+        .bind(cutoff)
+        .execute(&*self.pool)
+        .await?;
+
+        if result.rows_affected() > 0 {
+            tracing::info!(
+                "已按保留策略删除世界 {} 的 {} 个检查点",
+                world_id,
+                result.rows_affected()
+            );
+        }
+        Ok(result.rows_affected())
+    }
+}
+
+/// 读取
+impl CheckpointRepository {
+    /// 读取世界最新的检查点（步数最大者），没有检查点时返回 `None`
+    pub async fn latest(
+        &self,
+        world_id: Uuid,
+    ) -> Result<Option<CheckpointRecord>, RepositoryError> {
+        Ok(sqlx::query_as(
+            "SELECT checkpoint_id, run_id, world_id, tick, snapshot, created_at \
+             FROM checkpoints WHERE world_id = $1 \
+             ORDER BY tick DESC, created_at DESC LIMIT 1",
+        )
+        .bind(world_id)
+        .fetch_optional(&*self.pool)
+        .await?)
+    }
+
+    /// 读取指定的检查点，不存在时返回 `None`
+    pub async fn find(
+        &self,
+        checkpoint_id: i64,
+    ) -> Result<Option<CheckpointRecord>, RepositoryError> {
+        Ok(sqlx::query_as(
+            "SELECT checkpoint_id, run_id, world_id, tick, snapshot, created_at \
+             FROM checkpoints WHERE checkpoint_id = $1",
+        )
+        .bind(checkpoint_id)
+        .fetch_optional(&*self.pool)
+        .await?)
+    }
+
+    /// 列出世界的全部检查点（不含快照内容），步数大的在前
+    pub async fn list_by_world(
+        &self,
+        world_id: Uuid,
+    ) -> Result<Vec<CheckpointSummary>, RepositoryError> {
+        Ok(sqlx::query_as(
+            "SELECT checkpoint_id, run_id, world_id, tick, octet_length(snapshot) AS size, created_at \
+             FROM checkpoints WHERE world_id = $1 \
+             ORDER BY tick DESC, created_at DESC",
+        )
+        .bind(world_id)
+        .fetch_all(&*self.pool)
+        .await?)
+    }
+}
@@ -0,0 +1,104 @@
+pub mod agent;
+pub mod checkpoint;
+pub mod preference;
+pub mod property;
+pub mod resource;
+pub mod run;
+pub mod world;
+
+/// 批量写入时每条语句包含的行数，远低于 Postgres 单条语句 65535 个绑定参数的上限
+pub(crate) const BATCH_SIZE: usize = 1000;
+
+pub mod error_handling {
+    use thiserror::Error;
+
+    /// 仓储读写失败的原因
+    #[derive(Debug, Error)]
+    pub enum RepositoryError {
+        /// 查询或事务执行失败
+        #[error("数据库操作失败: {0}")]
+        Database(sqlx::Error),
+
+        /// 记录不满足表约束，整批记录都未写入
+        #[error("记录无效: {0}")]
+        InvalidRecord(String),
+    }
+
+    /// 违反外键约束（例如引用了不存在的世界）视为记录无效，与内存存储的行为一致
+    impl From<sqlx::Error> for RepositoryError {
+        fn from(e: sqlx::Error) -> Self {
+            match e.as_database_error() {
+                Some(database_error) if database_error.is_foreign_key_violation() => {
+                    RepositoryError::InvalidRecord(database_error.message().to_string())
+                }
+                _ => RepositoryError::Database(e),
+            }
+        }
+    }
+}
+
+/// 校验物质类型 `numerator/denominator` 满足表约束：分子与分母都必须为正
+fn check_substance(
+    numerator: i32,
+    denominator: i32,
+) -> Result<(), error_handling::RepositoryError> {
+    if numerator > 0 && denominator > 0 {
+        Ok(())
+    } else {
+        Err(error_handling::RepositoryError::InvalidRecord(format!(
+            "物质类型 {}/{} 的分子与分母必须为正",
+            numerator, denominator
+        )))
+    }
+}
+
+/// 校验取值位于 [0, 1]
+fn check_unit_interval(name: &str, value: f64) -> Result<(), error_handling::RepositoryError> {
+    if (0.0..=1.0).contains(&value) {
+        Ok(())
+    } else {
+        Err(error_handling::RepositoryError::InvalidRecord(format!(
+            "{} 必须位于 [0, 1]，实际为 {}",
+            name, value
+        )))
+    }
+}
+
+/// 校验替换个体的记录时，全部记录都属于该个体
+pub(crate) fn check_owner(
+    agent_id: uuid::Uuid,
+    owners: impl IntoIterator<Item = uuid::Uuid>,
+    kind: &str,
+) -> Result<(), error_handling::RepositoryError> {
+    match owners.into_iter().find(|owner| *owner != agent_id) {
+        Some(other) => Err(error_handling::RepositoryError::InvalidRecord(format!(
+            "替换个体 {} 的{}时出现了个体 {} 的记录",
+            agent_id, kind, other
+        ))),
+        None => Ok(()),
+    }
+}
+
+/// 校验同一批记录的键互不重复
+///
+/// 一条 `INSERT ... ON CONFLICT DO UPDATE` 语句不能两次更新同一行，
+/// 重复的键会使整批写入失败，因此在写入前以 `InvalidRecord` 拒绝。
+pub(crate) fn check_unique_keys<K>(
+    keys: impl IntoIterator<Item = K>,
+    kind: &str,
+) -> Result<(), error_handling::RepositoryError>
+where
+    K: Eq + std::hash::Hash + std::fmt::Debug,
+{
+    let mut seen = std::collections::HashSet::new();
+    for key in keys {
+        if seen.contains(&key) {
+            return Err(error_handling::RepositoryError::InvalidRecord(format!(
+                "同一批{}中出现了重复的键 {:?}",
+                kind, key
+            )));
+        }
+        seen.insert(key);
+    }
+    Ok(())
+}
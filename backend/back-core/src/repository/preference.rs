@@ -0,0 +1,180 @@
+use crate::repository::error_handling::RepositoryError;
+use crate::repository::{
+    check_owner, check_substance, check_unique_keys, check_unit_interval, BATCH_SIZE,
+};
+use sqlx::{FromRow, Pool, Postgres, QueryBuilder, Transaction};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// `preferences` 表中的一行：个体对某种物质的偏好
+#[derive(Debug, Clone, Copy, PartialEq, FromRow)]
+pub struct PreferenceRecord {
+    pub agent_id: Uuid,
+    #[sqlx(rename = "subtance_numerator")]
+    pub substance_numerator: i32,
+    #[sqlx(rename = "subtance_denominator")]
+    pub substance_denominator: i32,
+    /// 偏好程度，位于 [0, 1]
+    pub preference: f64,
+}
+
+impl PreferenceRecord {
+    /// 表的主键：（个体，物质类型）
+    pub(crate) fn key(&self) -> (Uuid, i32, i32) {
+        (
+            self.agent_id,
+            self.substance_numerator,
+            self.substance_denominator,
+        )
+    }
+
+    pub(crate) fn validate(&self) -> Result<(), RepositoryError> {
+        check_substance(self.substance_numerator, self.substance_denominator)?;
+        check_unit_interval("preference", self.preference)
+    }
+}
+
+/// `preferences` 表的读写
+///
+/// 用法与 `ResourceRepository` 相同。
+#[derive(Debug, Clone)]
+pub struct PreferenceRepository {
+    pool: Arc<Pool<Postgres>>,
+}
+
+impl PreferenceRepository {
+    pub fn new(pool: Arc<Pool<Postgres>>) -> Self {
+        Self { pool }
+    }
+
+    /// 在事务中分批 upsert，调用方负责提交
+    async fn upsert_in(
+        transaction: &mut Transaction<'_, Postgres>,
+        records: &[PreferenceRecord],
+    ) -> Result<(), RepositoryError> {
+        for batch in records.chunks(BATCH_SIZE) {
+            QueryBuilder::<Postgres>::new(
+                "INSERT INTO preferences \
+                 (agent_id, subtance_numerator, subtance_denominator, preference) ",
+            )
+            .push_values(batch, |mut row, record| {
+                row.push_bind(record.agent_id)
+                    .push_bind(record.substance_numerator)
+                    .push_bind(record.substance_denominator)
+                    .push_bind(record.preference);
+            })
+            .push(
+                " ON CONFLICT (agent_id, subtance_numerator, subtance_denominator) \
+                 DO UPDATE SET preference = EXCLUDED.preference",
+            )
+            .build()
+            .execute(&mut **transaction)
+            .await?;
+        }
+        Ok(())
+    }
+}
+
+/// 写入
+impl PreferenceRepository {
+    /// 批量写入偏好，已存在的（个体，物质类型）组合更新其偏好
+    ///
+    /// 全部记录在同一事务中写入，任一批失败时整体回滚。
+    ///
+    /// ### 返回值
+    /// 返回写入的记录数；有记录不满足表约束或（个体，物质类型）重复时返回 `InvalidRecord`，
+    /// 不写入任何记录。
+    pub async fn upsert_many(
+        &self,
+        records: &[PreferenceRecord],
+    ) -> Result<usize, RepositoryError> {
+        records.iter().try_for_each(PreferenceRecord::validate)?;
+        check_unique_keys(records.iter().map(PreferenceRecord::key), "偏好")?;
+
+        let mut transaction = self.pool.begin().await?;
+        Self::upsert_in(&mut transaction, records).await?;
+        transaction.commit().await?;
+
+        Ok(records.len())
+    }
+
+    /// 用 `records` 替换个体的全部偏好，不在其中的物质类型会被删除
+    ///
+    /// 删除与写入在同一事务中完成。
+    ///
+    /// ### 返回值
+    /// 返回写入的记录数；有记录属于其他个体、不满足表约束或物质类型重复时返回 `InvalidRecord`。
+    pub async fn replace_for_agent(
+        &self,
+        agent_id: Uuid,
+        records: &[PreferenceRecord],
+    ) -> Result<usize, RepositoryError> {
+        check_owner(
+            agent_id,
+            records.iter().map(|record| record.agent_id),
+            "偏好",
+        )?;
+        records.iter().try_for_each(PreferenceRecord::validate)?;
+        check_unique_keys(records.iter().map(PreferenceRecord::key), "偏好")?;
+
+        let mut transaction = self.pool.begin().await?;
+        sqlx::query("DELETE FROM preferences WHERE agent_id = $1")
+            .bind(agent_id)
+            .execute(&mut *transaction)
+            .await?;
+        Self::upsert_in(&mut transaction, records).await?;
+        transaction.commit().await?;
+
+        Ok(records.len())
+    }
+
+    /// 删除个体的全部偏好
+    ///
+    /// ### 返回值
+    /// 返回删除的记录数。
+    pub async fn delete_by_agent(&self, agent_id: Uuid) -> Result<u64, RepositoryError> {
+        let result = sqlx::query("DELETE FROM preferences WHERE agent_id = $1")
+            .bind(agent_id)
+            .execute(&*self.pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
+
+/// 读取
+impl PreferenceRepository {
+    /// 读取个体的全部偏好，按物质类型排序
+    pub async fn list_by_agent(
+        &self,
+        agent_id: Uuid,
+    ) -> Result<Vec<PreferenceRecord>, RepositoryError> {
+        Ok(sqlx::query_as(
+            "SELECT agent_id, subtance_numerator, subtance_denominator, preference \
+             FROM preferences \
+             WHERE agent_id = $1 \
+             ORDER BY subtance_numerator, subtance_denominator",
+        )
+        .bind(agent_id)
+        .fetch_all(&*self.pool)
+        .await?)
+    }
+
+    /// 读取个体对某种物质的偏好，不存在时返回 `None`
+    pub async fn find(
+        &self,
+        agent_id: Uuid,
+        numerator: i32,
+        denominator: i32,
+    ) -> Result<Option<PreferenceRecord>, RepositoryError> {
+        Ok(sqlx::query_as(
+            "SELECT agent_id, subtance_numerator, subtance_denominator, preference \
+             FROM preferences \
+             WHERE agent_id = $1 AND subtance_numerator = $2 AND subtance_denominator = $3",
+        )
+        .bind(agent_id)
+        .bind(numerator)
+        .bind(denominator)
+        .fetch_optional(&*self.pool)
+        .await?)
+    }
+}
@@ -0,0 +1,178 @@
+use crate::repository::error_handling::RepositoryError;
+use crate::repository::{check_substance, check_unique_keys, check_unit_interval, BATCH_SIZE};
+use sqlx::{FromRow, Pool, Postgres, QueryBuilder};
+use std::sync::Arc;
+
+/// `properties` 表中的一行：物质类型在一组有效常量下的属性值
+#[derive(Debug, Clone, Copy, PartialEq, FromRow)]
+pub struct PropertyRecord {
+    #[sqlx(rename = "subtance_numerator")]
+    pub substance_numerator: i32,
+    #[sqlx(rename = "subtance_denominator")]
+    pub substance_denominator: i32,
+    pub frequency_constant: i32,
+    pub phase_constant: i32,
+    /// 属性值，位于 [0, 1]
+    pub property_value: f64,
+}
+
+impl PropertyRecord {
+    /// 表的主键：（物质类型，有效常量）
+    fn key(&self) -> (i32, i32, i32, i32) {
+        (
+            self.substance_numerator,
+            self.substance_denominator,
+            self.frequency_constant,
+            self.phase_constant,
+        )
+    }
+
+    fn validate(&self) -> Result<(), RepositoryError> {
+        check_substance(self.substance_numerator, self.substance_denominator)?;
+        check_unit_interval("property_value", self.property_value)
+    }
+}
+
+/// `properties` 表的读写
+///
+/// ```no_run
+/// use back_core::context::core_context::AppContext;
+/// use back_core::repository::property::{PropertyRecord, PropertyRepository};
+///
+/// # async fn run() -> Result<(), back_core::repository::error_handling::RepositoryError> {
+/// let repository = PropertyRepository::new(AppContext::get_db_pool());
+/// let record = PropertyRecord {
+///     substance_numerator: 3,
+///     substance_denominator: 4,
+///     frequency_constant: 5,
+///     phase_constant: 0,
+///     property_value: 0.5,
+/// };
+/// repository.upsert_many(&[record]).await?;
+/// assert_eq!(repository.find(3, 4, 5, 0).await?, Some(record));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct PropertyRepository {
+    pool: Arc<Pool<Postgres>>,
+}
+
+impl PropertyRepository {
+    pub fn new(pool: Arc<Pool<Postgres>>) -> Self {
+        Self { pool }
+    }
+}
+
+/// 写入
+impl PropertyRepository {
+    /// 批量写入属性值，已存在的（物质类型，有效常量）组合更新其属性值
+    ///
+    /// 全部记录在同一事务中分批写入，任一批失败时整体回滚。
+    ///
+    /// ### 返回值
+    /// 返回写入的记录数；有记录不满足表约束或（物质类型，有效常量）重复时返回 `InvalidRecord`，
+    /// 不写入任何记录。
+    pub async fn upsert_many(&self, records: &[PropertyRecord]) -> Result<usize, RepositoryError> {
+        records.iter().try_for_each(PropertyRecord::validate)?;
+        check_unique_keys(records.iter().map(PropertyRecord::key), "属性值")?;
+
+        let mut transaction = self.pool.begin().await?;
+        for batch in records.chunks(BATCH_SIZE) {
+            QueryBuilder::<Postgres>::new(
+                "INSERT INTO properties \
+                 (subtance_numerator, subtance_denominator, frequency_constant, phase_constant, property_value) ",
+            )
+            .push_values(batch, |mut row, record| {
+                row.push_bind(record.substance_numerator)
+                    .push_bind(record.substance_denominator)
+                    .push_bind(record.frequency_constant)
+                    .push_bind(record.phase_constant)
+                    .push_bind(record.property_value);
+            })
+            .push(
+                " ON CONFLICT (subtance_numerator, subtance_denominator, frequency_constant, phase_constant) \
+                 DO UPDATE SET property_value = EXCLUDED.property_value",
+            )
+            .build()
+            .execute(&mut *transaction)
+            .await?;
+        }
+        transaction.commit().await?;
+
+        Ok(records.len())
+    }
+
+    /// 删除一个物质类型的全部属性值
+    ///
+    /// ### 返回值
+    /// 返回删除的记录数。
+    pub async fn delete_by_substance(
+        &self,
+        numerator: i32,
+        denominator: i32,
+    ) -> Result<u64, RepositoryError> {
+        let result = sqlx::query(
+            "DELETE FROM properties WHERE subtance_numerator = $1 AND subtance_denominator = $2",
+        )
+        .bind(numerator)
+        .bind(denominator)
+        .execute(&*self.pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
+
+/// 读取
+impl PropertyRepository {
+    /// 读取全部属性值
+    pub async fn list(&self) -> Result<Vec<PropertyRecord>, RepositoryError> {
+        Ok(sqlx::query_as(
+            "SELECT subtance_numerator, subtance_denominator, frequency_constant, phase_constant, property_value \
+             FROM properties \
+             ORDER BY subtance_numerator, subtance_denominator, frequency_constant, phase_constant",
+        )
+        .fetch_all(&*self.pool)
+        .await?)
+    }
+
+    /// 读取一个物质类型在各组有效常量下的属性值
+    pub async fn list_by_substance(
+        &self,
+        numerator: i32,
+        denominator: i32,
+    ) -> Result<Vec<PropertyRecord>, RepositoryError> {
+        Ok(sqlx::query_as(
+            "SELECT subtance_numerator, subtance_denominator, frequency_constant, phase_constant, property_value \
+             FROM properties \
+             WHERE subtance_numerator = $1 AND subtance_denominator = $2 \
+             ORDER BY frequency_constant, phase_constant",
+        )
+        .bind(numerator)
+        .bind(denominator)
+        .fetch_all(&*self.pool)
+        .await?)
+    }
+
+    /// 读取一个（物质类型，有效常量）组合的属性值，不存在时返回 `None`
+    pub async fn find(
+        &self,
+        numerator: i32,
+        denominator: i32,
+        frequency_constant: i32,
+        phase_constant: i32,
+    ) -> Result<Option<PropertyRecord>, RepositoryError> {
+        Ok(sqlx::query_as(
+            "SELECT subtance_numerator, subtance_denominator, frequency_constant, phase_constant, property_value \
+             FROM properties \
+             WHERE subtance_numerator = $1 AND subtance_denominator = $2 \
+             AND frequency_constant = $3 AND phase_constant = $4",
+        )
+        .bind(numerator)
+        .bind(denominator)
+        .bind(frequency_constant)
+        .bind(phase_constant)
+        .fetch_optional(&*self.pool)
+        .await?)
+    }
+}
@@ -0,0 +1,208 @@
+use crate::repository::error_handling::RepositoryError;
+use crate::repository::{check_owner, check_substance, check_unique_keys, BATCH_SIZE};
+use sqlx::{FromRow, Pool, Postgres, QueryBuilder, Transaction};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// `resources` 表中的一行：个体持有的某种物质
+#[derive(Debug, Clone, Copy, PartialEq, Eq, FromRow)]
+pub struct ResourceRecord {
+    pub agent_id: Uuid,
+    #[sqlx(rename = "subtance_numerator")]
+    pub substance_numerator: i32,
+    #[sqlx(rename = "subtance_denominator")]
+    pub substance_denominator: i32,
+    /// 可自由支配的数量
+    pub allocatable: i32,
+    /// 已投入的数量
+    pub investment: i32,
+    /// 欠下的数量
+    pub debt: i32,
+}
+
+impl ResourceRecord {
+    /// 表的主键：（个体，物质类型）
+    pub(crate) fn key(&self) -> (Uuid, i32, i32) {
+        (
+            self.agent_id,
+            self.substance_numerator,
+            self.substance_denominator,
+        )
+    }
+
+    pub(crate) fn validate(&self) -> Result<(), RepositoryError> {
+        check_substance(self.substance_numerator, self.substance_denominator)?;
+        if self.allocatable < 0 || self.investment < 0 || self.debt < 0 {
+            return Err(RepositoryError::InvalidRecord(format!(
+                "个体 {} 的资源数量不能为负: {:?}",
+                self.agent_id, self
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// `resources` 表的读写
+///
+/// ```no_run
+/// use back_core::context::core_context::AppContext;
+/// use back_core::repository::resource::{ResourceRecord, ResourceRepository};
+/// use uuid::Uuid;
+///
+/// # async fn run() -> Result<(), back_core::repository::error_handling::RepositoryError> {
+/// let repository = ResourceRepository::new(AppContext::get_db_pool());
+/// let agent_id = Uuid::from_u128(1);
+/// let record = ResourceRecord {
+///     agent_id,
+///     substance_numerator: 1,
+///     substance_denominator: 2,
+///     allocatable: 10,
+///     investment: 0,
+///     debt: 0,
+/// };
+/// repository.replace_for_agent(agent_id, &[record]).await?;
+/// assert_eq!(repository.list_by_agent(agent_id).await?, vec![record]);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct ResourceRepository {
+    pool: Arc<Pool<Postgres>>,
+}
+
+impl ResourceRepository {
+    pub fn new(pool: Arc<Pool<Postgres>>) -> Self {
+        Self { pool }
+    }
+
+    /// 在事务中分批 upsert，调用方负责提交
+    async fn upsert_in(
+        transaction: &mut Transaction<'_, Postgres>,
+        records: &[ResourceRecord],
+    ) -> Result<(), RepositoryError> {
+        for batch in records.chunks(BATCH_SIZE) {
+            QueryBuilder::<Postgres>::new(
+                "INSERT INTO resources \
+                 (agent_id, subtance_numerator, subtance_denominator, allocatable, investment, debt) ",
+            )
+            .push_values(batch, |mut row, record| {
+                row.push_bind(record.agent_id)
+                    .push_bind(record.substance_numerator)
+                    .push_bind(record.substance_denominator)
+                    .push_bind(record.allocatable)
+                    .push_bind(record.investment)
+                    .push_bind(record.debt);
+            })
+            .push(
+                " ON CONFLICT (agent_id, subtance_numerator, subtance_denominator) \
+                 DO UPDATE SET allocatable = EXCLUDED.allocatable, \
+                 investment = EXCLUDED.investment, debt = EXCLUDED.debt",
+            )
+            .build()
+            .execute(&mut **transaction)
+            .await?;
+        }
+        Ok(())
+    }
+}
+
+/// 写入
+impl ResourceRepository {
+    /// 批量写入资源，已存在的（个体，物质类型）组合更新其数量
+    ///
+    /// 全部记录在同一事务中写入，任一批失败时整体回滚。
+    ///
+    /// ### 返回值
+    /// 返回写入的记录数；有记录不满足表约束或（个体，物质类型）重复时返回 `InvalidRecord`，
+    /// 不写入任何记录。
+    pub async fn upsert_many(&self, records: &[ResourceRecord]) -> Result<usize, RepositoryError> {
+        records.iter().try_for_each(ResourceRecord::validate)?;
+        check_unique_keys(records.iter().map(ResourceRecord::key), "资源")?;
+
+        let mut transaction = self.pool.begin().await?;
+        Self::upsert_in(&mut transaction, records).await?;
+        transaction.commit().await?;
+
+        Ok(records.len())
+    }
+
+    /// 用 `records` 替换个体的全部资源，不在其中的物质类型会被删除
+    ///
+    /// 删除与写入在同一事务中完成，其他连接不会读到只替换了一半的资源。
+    ///
+    /// ### 返回值
+    /// 返回写入的记录数；有记录属于其他个体、不满足表约束或物质类型重复时返回 `InvalidRecord`。
+    pub async fn replace_for_agent(
+        &self,
+        agent_id: Uuid,
+        records: &[ResourceRecord],
+    ) -> Result<usize, RepositoryError> {
+        check_owner(
+            agent_id,
+            records.iter().map(|record| record.agent_id),
+            "资源",
+        )?;
+        records.iter().try_for_each(ResourceRecord::validate)?;
+        check_unique_keys(records.iter().map(ResourceRecord::key), "资源")?;
+
+        let mut transaction = self.pool.begin().await?;
+        sqlx::query("DELETE FROM resources WHERE agent_id = $1")
+            .bind(agent_id)
+            .execute(&mut *transaction)
+            .await?;
+        Self::upsert_in(&mut transaction, records).await?;
+        transaction.commit().await?;
+
+        Ok(records.len())
+    }
+
+    /// 删除个体的全部资源
+    ///
+    /// ### 返回值
+    /// 返回删除的记录数。
+    pub async fn delete_by_agent(&self, agent_id: Uuid) -> Result<u64, RepositoryError> {
+        let result = sqlx::query("DELETE FROM resources WHERE agent_id = $1")
+            .bind(agent_id)
+            .execute(&*self.pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
+
+/// 读取
+impl ResourceRepository {
+    /// 读取个体持有的全部资源，按物质类型排序
+    pub async fn list_by_agent(
+        &self,
+        agent_id: Uuid,
+    ) -> Result<Vec<ResourceRecord>, RepositoryError> {
+        Ok(sqlx::query_as(
+            "SELECT agent_id, subtance_numerator, subtance_denominator, allocatable, investment, debt \
+             FROM resources \
+             WHERE agent_id = $1 \
+             ORDER BY subtance_numerator, subtance_denominator",
+        )
+        .bind(agent_id)
+        .fetch_all(&*self.pool)
+        .await?)
+    }
+
+    /// 读取个体持有的某种物质，不存在时返回 `None`
+    pub async fn find(
+        &self,
+        agent_id: Uuid,
+        numerator: i32,
+        denominator: i32,
+    ) -> Result<Option<ResourceRecord>, RepositoryError> {
+        Ok(sqlx::query_as(
+            "SELECT agent_id, subtance_numerator, subtance_denominator, allocatable, investment, debt \
+             FROM resources \
+             WHERE agent_id = $1 AND subtance_numerator = $2 AND subtance_denominator = $3",
+        )
+        .bind(agent_id)
+        .bind(numerator)
+        .bind(denominator)
+        .fetch_optional(&*self.pool)
+        .await?)
+    }
+}
@@ -0,0 +1,177 @@
+use crate::repository::error_handling::RepositoryError;
+use chrono::{DateTime, Utc};
+use sqlx::{FromRow, Pool, Postgres};
+use std::fmt;
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// 一次运行的状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RunStatus {
+    /// 正在运行，或进程意外退出而未能更新状态
+    Running,
+    /// 正常结束
+    Completed,
+    /// 因错误中止
+    Failed,
+}
+
+impl RunStatus {
+    /// 在 `runs.status` 列中的取值
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RunStatus::Running => "running",
+            RunStatus::Completed => "completed",
+            RunStatus::Failed => "failed",
+        }
+    }
+}
+
+impl fmt::Display for RunStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl TryFrom<String> for RunStatus {
+    type Error = RepositoryError;
+
+    fn try_from(status: String) -> Result<Self, Self::Error> {
+        match status.as_str() {
+            "running" => Ok(RunStatus::Running),
+            "completed" => Ok(RunStatus::Completed),
+            "failed" => Ok(RunStatus::Failed),
+            _ => Err(RepositoryError::InvalidRecord(format!(
+                "未知的运行状态: {}",
+                status
+            ))),
+        }
+    }
+}
+
+/// `runs` 表中的一行：世界的一次运行
+///
+/// 一个世界可以从检查点恢复多次，每次恢复都是一次新的运行。
+#[derive(Debug, Clone, PartialEq, FromRow)]
+pub struct RunRecord {
+    pub run_id: Uuid,
+    pub world_id: Uuid,
+    #[sqlx(try_from = "String")]
+    pub status: RunStatus,
+    /// 运行开始时世界的步数
+    pub start_tick: i64,
+    /// 已保存检查点中最新的步数
+    pub last_tick: i64,
+    /// 每隔多少步保存一次检查点
+    pub checkpoint_interval: i32,
+    pub started_at: DateTime<Utc>,
+    /// 运行结束的时间，仍在运行时为 `None`
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+/// 校验运行的参数满足表约束
+pub(crate) fn check_run(start_tick: i64, checkpoint_interval: i32) -> Result<(), RepositoryError> {
+    if start_tick < 0 || checkpoint_interval <= 0 {
+        return Err(RepositoryError::InvalidRecord(format!(
+            "运行的起始步数 {} 或检查点间隔 {} 无效",
+            start_tick, checkpoint_interval
+        )));
+    }
+    Ok(())
+}
+
+/// `runs` 表的读写
+#[derive(Debug, Clone)]
+pub struct RunRepository {
+    pool: Arc<Pool<Postgres>>,
+}
+
+impl RunRepository {
+    pub fn new(pool: Arc<Pool<Postgres>>) -> Self {
+        Self { pool }
+    }
+}
+
+/// 写入
+impl RunRepository {
+    /// 登记一次新的运行，状态为 `Running`
+    ///
+    /// ### 参数
+    /// - `world_id`: 运行所属的世界，必须已登记。
+    /// - `start_tick`: 运行开始时世界的步数，从检查点恢复时为检查点的步数。
+    /// - `checkpoint_interval`: 每隔多少步保存一次检查点，至少为 1。
+    pub async fn start(
+        &self,
+        world_id: Uuid,
+        start_tick: i64,
+        checkpoint_interval: i32,
+    ) -> Result<RunRecord, RepositoryError> {
+        check_run(start_tick, checkpoint_interval)?;
+
+        Ok(sqlx::query_as(
+            "INSERT INTO runs (run_id, world_id, status, start_tick, last_tick, checkpoint_interval) \
+             VALUES ($1, $2, $3, $4, $4, $5) \
+             RETURNING run_id, world_id, status, start_tick, last_tick, checkpoint_interval, started_at, finished_at",
+        )
+        .bind(Uuid::new_v4())
+        .bind(world_id)
+        .bind(RunStatus::Running.as_str())
+        .bind(start_tick)
+        .bind(checkpoint_interval)
+        .fetch_one(&*self.pool)
+        .await?)
+    }
+
+    /// 结束运行，记录最终状态与结束时间
+    ///
+    /// ### 返回值
+    /// 返回运行原本是否存在。
+    pub async fn finish(&self, run_id: Uuid, status: RunStatus) -> Result<bool, RepositoryError> {
+        let result = sqlx::query(
+            "UPDATE runs SET status = $2, \
+             finished_at = CASE WHEN $2 = 'running' THEN NULL ELSE now() END \
+             WHERE run_id = $1",
+        )
+        .bind(run_id)
+        .bind(status.as_str())
+        .execute(&*self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+/// 读取
+impl RunRepository {
+    /// 读取运行，不存在时返回 `None`
+    pub async fn find(&self, run_id: Uuid) -> Result<Option<RunRecord>, RepositoryError> {
+        Ok(sqlx::query_as(
+            "SELECT run_id, world_id, status, start_tick, last_tick, checkpoint_interval, started_at, finished_at \
+             FROM runs WHERE run_id = $1",
+        )
+        .bind(run_id)
+        .fetch_optional(&*self.pool)
+        .await?)
+    }
+
+    /// 读取世界的全部运行，最新开始的在前
+    pub async fn list_by_world(&self, world_id: Uuid) -> Result<Vec<RunRecord>, RepositoryError> {
+        Ok(sqlx::query_as(
+            "SELECT run_id, world_id, status, start_tick, last_tick, checkpoint_interval, started_at, finished_at \
+             FROM runs WHERE world_id = $1 ORDER BY started_at DESC",
+        )
+        .bind(world_id)
+        .fetch_all(&*self.pool)
+        .await?)
+    }
+
+    /// 读取最近开始的 `limit` 次运行，不区分世界
+    pub async fn list_recent(&self, limit: i64) -> Result<Vec<RunRecord>, RepositoryError> {
+        Ok(sqlx::query_as(
+            "SELECT run_id, world_id, status, start_tick, last_tick, checkpoint_interval, started_at, finished_at \
+             FROM runs ORDER BY started_at DESC LIMIT $1",
+        )
+        .bind(limit)
+        .fetch_all(&*self.pool)
+        .await?)
+    }
+}
@@ -0,0 +1,116 @@
+use crate::repository::error_handling::RepositoryError;
+use chrono::{DateTime, Utc};
+use sqlx::{FromRow, Pool, Postgres};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// `worlds` 表中的一行：一个世界创建时的参数
+#[derive(Debug, Clone, PartialEq, FromRow)]
+pub struct WorldRecord {
+    /// 世界的标识，与 `civilization_id` 一致
+    pub world_id: Uuid,
+    /// 生成地形与噪声的随机种子
+    pub seed: i64,
+    pub map_width: i32,
+    pub map_height: i32,
+    /// 重力常数
+    pub gravity: f64,
+    pub created_at: DateTime<Utc>,
+}
+
+/// 校验世界的参数满足表约束
+pub(crate) fn check_world(
+    world_id: Uuid,
+    map_width: i32,
+    map_height: i32,
+    gravity: f64,
+) -> Result<(), RepositoryError> {
+    if map_width <= 0 || map_height <= 0 || !(gravity.is_finite() && gravity > 0.0) {
+        return Err(RepositoryError::InvalidRecord(format!(
+            "世界 {} 的地图尺寸 {}×{} 或重力常数 {} 无效",
+            world_id, map_width, map_height, gravity
+        )));
+    }
+    Ok(())
+}
+
+/// `worlds` 表的读写
+///
+/// 删除世界时，其运行记录与检查点会被级联删除。
+#[derive(Debug, Clone)]
+pub struct WorldRepository {
+    pool: Arc<Pool<Postgres>>,
+}
+
+impl WorldRepository {
+    pub fn new(pool: Arc<Pool<Postgres>>) -> Self {
+        Self { pool }
+    }
+}
+
+/// 写入
+impl WorldRepository {
+    /// 登记一个新世界
+    ///
+    /// ### 返回值
+    /// 返回写入的记录，其中包含数据库生成的 `created_at`；世界已存在时返回数据库错误。
+    pub async fn create(
+        &self,
+        world_id: Uuid,
+        seed: i64,
+        map_width: i32,
+        map_height: i32,
+        gravity: f64,
+    ) -> Result<WorldRecord, RepositoryError> {
+        check_world(world_id, map_width, map_height, gravity)?;
+
+        Ok(sqlx::query_as(
+            "INSERT INTO worlds (world_id, seed, map_width, map_height, gravity) \
+             VALUES ($1, $2, $3, $4, $5) \
+             RETURNING world_id, seed, map_width, map_height, gravity, created_at",
+        )
+        .bind(world_id)
+        .bind(seed)
+        .bind(map_width)
+        .bind(map_height)
+        .bind(gravity)
+        .fetch_one(&*self.pool)
+        .await?)
+    }
+
+    /// 删除世界及其全部运行记录与检查点
+    ///
+    /// ### 返回值
+    /// 返回世界原本是否存在。
+    pub async fn delete(&self, world_id: Uuid) -> Result<bool, RepositoryError> {
+        let result = sqlx::query("DELETE FROM worlds WHERE world_id = $1")
+            .bind(world_id)
+            .execute(&*self.pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+/// 读取
+impl WorldRepository {
+    /// 读取世界，不存在时返回 `None`
+    pub async fn find(&self, world_id: Uuid) -> Result<Option<WorldRecord>, RepositoryError> {
+        Ok(sqlx::query_as(
+            "SELECT world_id, seed, map_width, map_height, gravity, created_at \
+             FROM worlds WHERE world_id = $1",
+        )
+        .bind(world_id)
+        .fetch_optional(&*self.pool)
+        .await?)
+    }
+
+    /// 读取全部世界，最新创建的在前
+    pub async fn list(&self) -> Result<Vec<WorldRecord>, RepositoryError> {
+        Ok(sqlx::query_as(
+            "SELECT world_id, seed, map_width, map_height, gravity, created_at \
+             FROM worlds ORDER BY created_at DESC",
+        )
+        .fetch_all(&*self.pool)
+        .await?)
+    }
+}
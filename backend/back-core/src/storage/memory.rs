@@ -0,0 +1,413 @@
+use crate::repository::agent::AgentRecord;
+use crate::repository::checkpoint::{CheckpointRecord, CheckpointSummary, RetentionPolicy};
+use crate::repository::error_handling::RepositoryError;
+use crate::repository::preference::PreferenceRecord;
+use crate::repository::resource::ResourceRecord;
+use crate::repository::run::{check_run, RunRecord, RunStatus};
+use crate::repository::world::{check_world, WorldRecord};
+use crate::repository::{check_owner, check_unique_keys};
+use crate::storage::Storage;
+use async_trait::async_trait;
+use chrono::Utc;
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+use uuid::Uuid;
+
+/// 资源与偏好的键：（个体，物质类型的分子，分母）
+type SubstanceKey = (Uuid, i32, i32);
+
+/// 内存中的全部表
+#[derive(Debug, Default)]
+struct Tables {
+    agents: BTreeMap<Uuid, AgentRecord>,
+    resources: BTreeMap<SubstanceKey, ResourceRecord>,
+    preferences: BTreeMap<SubstanceKey, PreferenceRecord>,
+    worlds: HashMap<Uuid, WorldRecord>,
+    runs: HashMap<Uuid, RunRecord>,
+    checkpoints: BTreeMap<i64, CheckpointRecord>,
+    /// 下一个检查点的 `checkpoint_id`，与 Postgres 的序列一样从 1 开始
+    next_checkpoint_id: i64,
+}
+
+impl Tables {
+    fn check_world_exists(&self, world_id: Uuid) -> Result<(), RepositoryError> {
+        if self.worlds.contains_key(&world_id) {
+            Ok(())
+        } else {
+            Err(RepositoryError::InvalidRecord(format!(
+                "世界 {} 不存在",
+                world_id
+            )))
+        }
+    }
+
+    /// 世界的全部检查点，步数大的在前，同一步数时新保存的在前
+    fn checkpoints_of(&self, world_id: Uuid) -> Vec<&CheckpointRecord> {
+        let mut checkpoints: Vec<_> = self
+            .checkpoints
+            .values()
+            .filter(|checkpoint| checkpoint.world_id == world_id)
+            .collect();
+        checkpoints.sort_by_key(|checkpoint| Reverse((checkpoint.tick, checkpoint.created_at)));
+        checkpoints
+    }
+}
+
+/// 仅保存在内存中的存储，进程退出后丢失
+///
+/// 行为与 Postgres 后端一致，外键约束不满足时返回 `InvalidRecord`。
+/// 适合单元测试与无需保留结果的临时实验。
+#[derive(Debug)]
+pub struct MemoryStorage {
+    tables: RwLock<Tables>,
+}
+
+impl MemoryStorage {
+    pub fn new() -> Self {
+        Self {
+            tables: RwLock::new(Tables {
+                next_checkpoint_id: 1,
+                ..Tables::default()
+            }),
+        }
+    }
+
+    fn read(&self) -> RwLockReadGuard<'_, Tables> {
+        self.tables.read().expect("未能获取读锁，读取内存存储失败")
+    }
+
+    fn write(&self) -> RwLockWriteGuard<'_, Tables> {
+        self.tables.write().expect("未能获取写锁，写入内存存储失败")
+    }
+}
+
+impl Default for MemoryStorage {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl Storage for MemoryStorage {
+    async fn upsert_agents(&self, records: &[AgentRecord]) -> Result<usize, RepositoryError> {
+        records.iter().try_for_each(AgentRecord::validate)?;
+        check_unique_keys(records.iter().map(|record| record.agent_id), "个体")?;
+
+        let mut tables = self.write();
+        for record in records {
+            tables.check_world_exists(record.world_id)?;
+        }
+        for record in records {
+            tables.agents.insert(record.agent_id, *record);
+        }
+        Ok(records.len())
+    }
+
+    async fn list_agents(&self, world_id: Uuid) -> Result<Vec<AgentRecord>, RepositoryError> {
+        Ok(self
+            .read()
+            .agents
+            .values()
+            .filter(|agent| agent.world_id == world_id)
+            .copied()
+            .collect())
+    }
+
+    async fn delete_agent(&self, agent_id: Uuid) -> Result<bool, RepositoryError> {
+        let mut tables = self.write();
+        tables
+            .resources
+            .retain(|(owner, _, _), _| *owner != agent_id);
+        tables
+            .preferences
+            .retain(|(owner, _, _), _| *owner != agent_id);
+        Ok(tables.agents.remove(&agent_id).is_some())
+    }
+
+    async fn upsert_resources(&self, records: &[ResourceRecord]) -> Result<usize, RepositoryError> {
+        records.iter().try_for_each(ResourceRecord::validate)?;
+        check_unique_keys(records.iter().map(ResourceRecord::key), "资源")?;
+
+        let mut tables = self.write();
+        for record in records {
+            tables.resources.insert(record.key(), *record);
+        }
+        Ok(records.len())
+    }
+
+    async fn replace_resources(
+        &self,
+        agent_id: Uuid,
+        records: &[ResourceRecord],
+    ) -> Result<usize, RepositoryError> {
+        check_owner(
+            agent_id,
+            records.iter().map(|record| record.agent_id),
+            "资源",
+        )?;
+        records.iter().try_for_each(ResourceRecord::validate)?;
+        check_unique_keys(records.iter().map(ResourceRecord::key), "资源")?;
+
+        let mut tables = self.write();
+        tables
+            .resources
+            .retain(|(owner, _, _), _| *owner != agent_id);
+        for record in records {
+            tables.resources.insert(record.key(), *record);
+        }
+        Ok(records.len())
+    }
+
+    async fn list_resources(&self, agent_id: Uuid) -> Result<Vec<ResourceRecord>, RepositoryError> {
+        Ok(self
+            .read()
+            .resources
+            .range((agent_id, i32::MIN, i32::MIN)..=(agent_id, i32::MAX, i32::MAX))
+            .map(|(_, record)| *record)
+            .collect())
+    }
+
+    async fn upsert_preferences(
+        &self,
+        records: &[PreferenceRecord],
+    ) -> Result<usize, RepositoryError> {
+        records.iter().try_for_each(PreferenceRecord::validate)?;
+        check_unique_keys(records.iter().map(PreferenceRecord::key), "偏好")?;
+
+        let mut tables = self.write();
+        for record in records {
+            tables.preferences.insert(record.key(), *record);
+        }
+        Ok(records.len())
+    }
+
+    async fn replace_preferences(
+        &self,
+        agent_id: Uuid,
+        records: &[PreferenceRecord],
+    ) -> Result<usize, RepositoryError> {
+        check_owner(
+            agent_id,
+            records.iter().map(|record| record.agent_id),
+            "偏好",
+        )?;
+        records.iter().try_for_each(PreferenceRecord::validate)?;
+        check_unique_keys(records.iter().map(PreferenceRecord::key), "偏好")?;
+
+        let mut tables = self.write();
+        tables
+            .preferences
+            .retain(|(owner, _, _), _| *owner != agent_id);
+        for record in records {
+            tables.preferences.insert(record.key(), *record);
+        }
+        Ok(records.len())
+    }
+
+    async fn list_preferences(
+        &self,
+        agent_id: Uuid,
+    ) -> Result<Vec<PreferenceRecord>, RepositoryError> {
+        Ok(self
+            .read()
+            .preferences
+            .range((agent_id, i32::MIN, i32::MIN)..=(agent_id, i32::MAX, i32::MAX))
+            .map(|(_, record)| *record)
+            .collect())
+    }
+
+    async fn create_world(
+        &self,
+        world_id: Uuid,
+        seed: i64,
+        map_width: i32,
+        map_height: i32,
+        gravity: f64,
+    ) -> Result<WorldRecord, RepositoryError> {
+        check_world(world_id, map_width, map_height, gravity)?;
+
+        let mut tables = self.write();
+        if tables.worlds.contains_key(&world_id) {
+            return Err(RepositoryError::InvalidRecord(format!(
+                "世界 {} 已存在",
+                world_id
+            )));
+        }
+        let world = WorldRecord {
+            world_id,
+            seed,
+            map_width,
+            map_height,
+            gravity,
+            created_at: Utc::now(),
+        };
+        tables.worlds.insert(world_id, world.clone());
+        Ok(world)
+    }
+
+    async fn find_world(&self, world_id: Uuid) -> Result<Option<WorldRecord>, RepositoryError> {
+        Ok(self.read().worlds.get(&world_id).cloned())
+    }
+
+    async fn list_worlds(&self) -> Result<Vec<WorldRecord>, RepositoryError> {
+        let mut worlds: Vec<_> = self.read().worlds.values().cloned().collect();
+        worlds.sort_by_key(|world| Reverse(world.created_at));
+        Ok(worlds)
+    }
+
+    async fn start_run(
+        &self,
+        world_id: Uuid,
+        start_tick: i64,
+        checkpoint_interval: i32,
+    ) -> Result<RunRecord, RepositoryError> {
+        check_run(start_tick, checkpoint_interval)?;
+
+        let mut tables = self.write();
+        tables.check_world_exists(world_id)?;
+        let run = RunRecord {
+            run_id: Uuid::new_v4(),
+            world_id,
+            status: RunStatus::Running,
+            start_tick,
+            last_tick: start_tick,
+            checkpoint_interval,
+            started_at: Utc::now(),
+            finished_at: None,
+        };
+        tables.runs.insert(run.run_id, run.clone());
+        Ok(run)
+    }
+
+    async fn finish_run(&self, run_id: Uuid, status: RunStatus) -> Result<bool, RepositoryError> {
+        let mut tables = self.write();
+        let Some(run) = tables.runs.get_mut(&run_id) else {
+            return Ok(false);
+        };
+        run.status = status;
+        run.finished_at = (status != RunStatus::Running).then(Utc::now);
+        Ok(true)
+    }
+
+    async fn list_runs(&self, world_id: Uuid) -> Result<Vec<RunRecord>, RepositoryError> {
+        let mut runs: Vec<_> = self
+            .read()
+            .runs
+            .values()
+            .filter(|run| run.world_id == world_id)
+            .cloned()
+            .collect();
+        runs.sort_by_key(|run| Reverse(run.started_at));
+        Ok(runs)
+    }
+
+    async fn save_checkpoint(
+        &self,
+        run_id: Uuid,
+        tick: i64,
+        snapshot: &[u8],
+    ) -> Result<i64, RepositoryError> {
+        let mut tables = self.write();
+
+        let Some(run) = tables
+            .runs
+            .get_mut(&run_id)
+            .filter(|run| run.start_tick <= tick)
+        else {
+            return Err(RepositoryError::InvalidRecord(format!(
+                "运行 {} 不存在，或第 {} 步早于运行的起始步数",
+                run_id, tick
+            )));
+        };
+        run.last_tick = run.last_tick.max(tick);
+        let world_id = run.world_id;
+
+        let created_at = Utc::now();
+        let existing = tables
+            .checkpoints
+            .values_mut()
+            .find(|checkpoint| checkpoint.run_id == run_id && checkpoint.tick == tick);
+        if let Some(checkpoint) = existing {
+            checkpoint.snapshot = snapshot.to_vec();
+            checkpoint.created_at = created_at;
+            return Ok(checkpoint.checkpoint_id);
+        }
+
+        let checkpoint_id = tables.next_checkpoint_id;
+        tables.next_checkpoint_id += 1;
+        tables.checkpoints.insert(
+            checkpoint_id,
+            CheckpointRecord {
+                checkpoint_id,
+                run_id,
+                world_id,
+                tick,
+                snapshot: snapshot.to_vec(),
+                created_at,
+            },
+        );
+        Ok(checkpoint_id)
+    }
+
+    async fn latest_checkpoint(
+        &self,
+        world_id: Uuid,
+    ) -> Result<Option<CheckpointRecord>, RepositoryError> {
+        Ok(self
+            .read()
+            .checkpoints_of(world_id)
+            .first()
+            .map(|checkpoint| (*checkpoint).clone()))
+    }
+
+    async fn list_checkpoints(
+        &self,
+        world_id: Uuid,
+    ) -> Result<Vec<CheckpointSummary>, RepositoryError> {
+        Ok(self
+            .read()
+            .checkpoints_of(world_id)
+            .into_iter()
+            .map(|checkpoint| CheckpointSummary {
+                checkpoint_id: checkpoint.checkpoint_id,
+                run_id: checkpoint.run_id,
+                world_id: checkpoint.world_id,
+                tick: checkpoint.tick,
+                size: i32::try_from(checkpoint.snapshot.len()).unwrap_or(i32::MAX),
+                created_at: checkpoint.created_at,
+            })
+            .collect())
+    }
+
+    async fn prune_checkpoints(
+        &self,
+        world_id: Uuid,
+        policy: &RetentionPolicy,
+    ) -> Result<u64, RepositoryError> {
+        let cutoff = policy.cutoff();
+        let mut tables = self.write();
+
+        let expired: Vec<i64> = tables
+            .checkpoints_of(world_id)
+            .into_iter()
+            .skip(policy.keep_latest())
+            .filter(|checkpoint| {
+                !policy.retains_extra(checkpoint.tick, checkpoint.created_at, cutoff)
+            })
+            .map(|checkpoint| checkpoint.checkpoint_id)
+            .collect();
+        for checkpoint_id in &expired {
+            tables.checkpoints.remove(checkpoint_id);
+        }
+
+        if !expired.is_empty() {
+            tracing::info!(
+                "已按保留策略删除世界 {} 的 {} 个检查点",
+                world_id,
+                expired.len()
+            );
+        }
+        Ok(expired.len() as u64)
+    }
+}
@@ -0,0 +1,168 @@
+pub mod memory;
+pub mod postgres;
+pub mod sqlite;
+
+use crate::config::app_config::{StorageBackend, StorageConfig};
+use crate::context::error_handling::InitError;
+use crate::repository::agent::AgentRecord;
+use crate::repository::checkpoint::{CheckpointRecord, CheckpointSummary, RetentionPolicy};
+use crate::repository::error_handling::RepositoryError;
+use crate::repository::preference::PreferenceRecord;
+use crate::repository::resource::ResourceRecord;
+use crate::repository::run::{RunRecord, RunStatus};
+use crate::repository::world::WorldRecord;
+use async_trait::async_trait;
+use memory::MemoryStorage;
+use postgres::PostgresStorage;
+use sqlite::SqliteStorage;
+use sqlx::{Pool, Postgres};
+use std::fmt;
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// 个体、资源、偏好与世界检查点的持久化存储
+///
+/// 各后端的行为一致：相同的校验、相同的排序与相同的返回值，
+/// 因此模拟可以在 Postgres、SQLite 与内存之间切换而无需修改。
+/// 各方法的语义与 `repository` 中对应的方法相同。
+///
+/// ```
+/// use back_core::storage::memory::MemoryStorage;
+/// use back_core::storage::Storage;
+/// use uuid::Uuid;
+///
+/// # async fn run() -> Result<(), back_core::repository::error_handling::RepositoryError> {
+/// let storage = MemoryStorage::new();
+/// let world_id = Uuid::from_u128(1);
+/// storage.create_world(world_id, 42, 8, 8, 10.0).await?;
+///
+/// let run = storage.start_run(world_id, 0, 10).await?;
+/// storage.save_checkpoint(run.run_id, 0, b"snapshot").await?;
+/// assert_eq!(storage.latest_checkpoint(world_id).await?.unwrap().tick, 0);
+/// # Ok(())
+/// # }
+/// ```
+#[async_trait]
+pub trait Storage: fmt::Debug + Send + Sync {
+    /// 批量写入个体，已存在的个体更新其所属世界与诞生步数
+    async fn upsert_agents(&self, records: &[AgentRecord]) -> Result<usize, RepositoryError>;
+
+    /// 读取世界的全部个体，按 `agent_id` 排序
+    async fn list_agents(&self, world_id: Uuid) -> Result<Vec<AgentRecord>, RepositoryError>;
+
+    /// 删除个体及其全部资源与偏好，返回个体原本是否存在
+    async fn delete_agent(&self, agent_id: Uuid) -> Result<bool, RepositoryError>;
+
+    /// 批量写入资源，已存在的（个体，物质类型）组合更新其数量
+    async fn upsert_resources(&self, records: &[ResourceRecord]) -> Result<usize, RepositoryError>;
+
+    /// 用 `records` 替换个体的全部资源
+    async fn replace_resources(
+        &self,
+        agent_id: Uuid,
+        records: &[ResourceRecord],
+    ) -> Result<usize, RepositoryError>;
+
+    /// 读取个体的全部资源，按物质类型排序
+    async fn list_resources(&self, agent_id: Uuid) -> Result<Vec<ResourceRecord>, RepositoryError>;
+
+    /// 批量写入偏好，已存在的（个体，物质类型）组合更新其偏好
+    async fn upsert_preferences(
+        &self,
+        records: &[PreferenceRecord],
+    ) -> Result<usize, RepositoryError>;
+
+    /// 用 `records` 替换个体的全部偏好
+    async fn replace_preferences(
+        &self,
+        agent_id: Uuid,
+        records: &[PreferenceRecord],
+    ) -> Result<usize, RepositoryError>;
+
+    /// 读取个体的全部偏好，按物质类型排序
+    async fn list_preferences(
+        &self,
+        agent_id: Uuid,
+    ) -> Result<Vec<PreferenceRecord>, RepositoryError>;
+
+    /// 登记一个新世界
+    async fn create_world(
+        &self,
+        world_id: Uuid,
+        seed: i64,
+        map_width: i32,
+        map_height: i32,
+        gravity: f64,
+    ) -> Result<WorldRecord, RepositoryError>;
+
+    /// 读取世界，不存在时返回 `None`
+    async fn find_world(&self, world_id: Uuid) -> Result<Option<WorldRecord>, RepositoryError>;
+
+    /// 读取全部世界，最新创建的在前
+    async fn list_worlds(&self) -> Result<Vec<WorldRecord>, RepositoryError>;
+
+    /// 登记一次新的运行，状态为 `Running`
+    async fn start_run(
+        &self,
+        world_id: Uuid,
+        start_tick: i64,
+        checkpoint_interval: i32,
+    ) -> Result<RunRecord, RepositoryError>;
+
+    /// 结束运行，返回运行原本是否存在
+    async fn finish_run(&self, run_id: Uuid, status: RunStatus) -> Result<bool, RepositoryError>;
+
+    /// 读取世界的全部运行，最新开始的在前
+    async fn list_runs(&self, world_id: Uuid) -> Result<Vec<RunRecord>, RepositoryError>;
+
+    /// 保存检查点，并把运行的 `last_tick` 推进到该步
+    async fn save_checkpoint(
+        &self,
+        run_id: Uuid,
+        tick: i64,
+        snapshot: &[u8],
+    ) -> Result<i64, RepositoryError>;
+
+    /// 读取世界最新的检查点（步数最大者），没有检查点时返回 `None`
+    async fn latest_checkpoint(
+        &self,
+        world_id: Uuid,
+    ) -> Result<Option<CheckpointRecord>, RepositoryError>;
+
+    /// 列出世界的全部检查点（不含快照内容），步数大的在前
+    async fn list_checkpoints(
+        &self,
+        world_id: Uuid,
+    ) -> Result<Vec<CheckpointSummary>, RepositoryError>;
+
+    /// 按保留策略删除世界的旧检查点，返回删除的检查点数
+    async fn prune_checkpoints(
+        &self,
+        world_id: Uuid,
+        policy: &RetentionPolicy,
+    ) -> Result<u64, RepositoryError>;
+}
+
+/// 按配置打开存储
+///
+/// ### 参数
+/// - `config`: 存储配置。
+/// - `db_pool`: 已建立的 Postgres 连接池，仅在 `backend = "postgres"` 时使用且必须提供。
+pub(crate) async fn open_storage(
+    config: &StorageConfig,
+    db_pool: Option<Arc<Pool<Postgres>>>,
+) -> Result<Arc<dyn Storage>, InitError> {
+    let storage: Arc<dyn Storage> = match config.backend {
+        StorageBackend::Postgres => {
+            let pool = db_pool.ok_or(InitError::Other {
+                service: "存储",
+                message: "Postgres 后端需要可用的数据库连接池".to_string(),
+            })?;
+            Arc::new(PostgresStorage::new(pool))
+        }
+        StorageBackend::Sqlite => Arc::new(SqliteStorage::open(&config.sqlite_path).await?),
+        StorageBackend::Memory => Arc::new(MemoryStorage::new()),
+    };
+    tracing::info!("使用 {:?} 存储", config.backend);
+    Ok(storage)
+}
@@ -0,0 +1,162 @@
+use crate::repository::agent::{AgentRecord, AgentRepository};
+use crate::repository::checkpoint::{
+    CheckpointRecord, CheckpointRepository, CheckpointSummary, RetentionPolicy,
+};
+use crate::repository::error_handling::RepositoryError;
+use crate::repository::preference::{PreferenceRecord, PreferenceRepository};
+use crate::repository::resource::{ResourceRecord, ResourceRepository};
+use crate::repository::run::{RunRecord, RunRepository, RunStatus};
+use crate::repository::world::{WorldRecord, WorldRepository};
+use crate::storage::Storage;
+use async_trait::async_trait;
+use sqlx::{Pool, Postgres};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// 基于 Postgres 的存储，委托给 `repository` 中的各仓储
+#[derive(Debug, Clone)]
+pub struct PostgresStorage {
+    agents: AgentRepository,
+    resources: ResourceRepository,
+    preferences: PreferenceRepository,
+    worlds: WorldRepository,
+    runs: RunRepository,
+    checkpoints: CheckpointRepository,
+}
+
+impl PostgresStorage {
+    pub fn new(pool: Arc<Pool<Postgres>>) -> Self {
+        Self {
+            agents: AgentRepository::new(pool.clone()),
+            resources: ResourceRepository::new(pool.clone()),
+            preferences: PreferenceRepository::new(pool.clone()),
+            worlds: WorldRepository::new(pool.clone()),
+            runs: RunRepository::new(pool.clone()),
+            checkpoints: CheckpointRepository::new(pool),
+        }
+    }
+}
+
+#[async_trait]
+impl Storage for PostgresStorage {
+    async fn upsert_agents(&self, records: &[AgentRecord]) -> Result<usize, RepositoryError> {
+        self.agents.upsert_many(records).await
+    }
+
+    async fn list_agents(&self, world_id: Uuid) -> Result<Vec<AgentRecord>, RepositoryError> {
+        self.agents.list_by_world(world_id).await
+    }
+
+    async fn delete_agent(&self, agent_id: Uuid) -> Result<bool, RepositoryError> {
+        self.agents.delete(agent_id).await
+    }
+
+    async fn upsert_resources(&self, records: &[ResourceRecord]) -> Result<usize, RepositoryError> {
+        self.resources.upsert_many(records).await
+    }
+
+    async fn replace_resources(
+        &self,
+        agent_id: Uuid,
+        records: &[ResourceRecord],
+    ) -> Result<usize, RepositoryError> {
+        self.resources.replace_for_agent(agent_id, records).await
+    }
+
+    async fn list_resources(&self, agent_id: Uuid) -> Result<Vec<ResourceRecord>, RepositoryError> {
+        self.resources.list_by_agent(agent_id).await
+    }
+
+    async fn upsert_preferences(
+        &self,
+        records: &[PreferenceRecord],
+    ) -> Result<usize, RepositoryError> {
+        self.preferences.upsert_many(records).await
+    }
+
+    async fn replace_preferences(
+        &self,
+        agent_id: Uuid,
+        records: &[PreferenceRecord],
+    ) -> Result<usize, RepositoryError> {
+        self.preferences.replace_for_agent(agent_id, records).await
+    }
+
+    async fn list_preferences(
+        &self,
+        agent_id: Uuid,
+    ) -> Result<Vec<PreferenceRecord>, RepositoryError> {
+        self.preferences.list_by_agent(agent_id).await
+    }
+
+    async fn create_world(
+        &self,
+        world_id: Uuid,
+        seed: i64,
+        map_width: i32,
+        map_height: i32,
+        gravity: f64,
+    ) -> Result<WorldRecord, RepositoryError> {
+        self.worlds
+            .create(world_id, seed, map_width, map_height, gravity)
+            .await
+    }
+
+    async fn find_world(&self, world_id: Uuid) -> Result<Option<WorldRecord>, RepositoryError> {
+        self.worlds.find(world_id).await
+    }
+
+    async fn list_worlds(&self) -> Result<Vec<WorldRecord>, RepositoryError> {
+        self.worlds.list().await
+    }
+
+    async fn start_run(
+        &self,
+        world_id: Uuid,
+        start_tick: i64,
+        checkpoint_interval: i32,
+    ) -> Result<RunRecord, RepositoryError> {
+        self.runs
+            .start(world_id, start_tick, checkpoint_interval)
+            .await
+    }
+
+    async fn finish_run(&self, run_id: Uuid, status: RunStatus) -> Result<bool, RepositoryError> {
+        self.runs.finish(run_id, status).await
+    }
+
+    async fn list_runs(&self, world_id: Uuid) -> Result<Vec<RunRecord>, RepositoryError> {
+        self.runs.list_by_world(world_id).await
+    }
+
+    async fn save_checkpoint(
+        &self,
+        run_id: Uuid,
+        tick: i64,
+        snapshot: &[u8],
+    ) -> Result<i64, RepositoryError> {
+        self.checkpoints.save(run_id, tick, snapshot).await
+    }
+
+    async fn latest_checkpoint(
+        &self,
+        world_id: Uuid,
+    ) -> Result<Option<CheckpointRecord>, RepositoryError> {
+        self.checkpoints.latest(world_id).await
+    }
+
+    async fn list_checkpoints(
+        &self,
+        world_id: Uuid,
+    ) -> Result<Vec<CheckpointSummary>, RepositoryError> {
+        self.checkpoints.list_by_world(world_id).await
+    }
+
+    async fn prune_checkpoints(
+        &self,
+        world_id: Uuid,
+        policy: &RetentionPolicy,
+    ) -> Result<u64, RepositoryError> {
+        self.checkpoints.prune(world_id, policy).await
+    }
+}
@@ -0,0 +1,486 @@
+use crate::context::error_handling::InitError;
+use crate::repository::agent::AgentRecord;
+use crate::repository::checkpoint::{CheckpointRecord, CheckpointSummary, RetentionPolicy};
+use crate::repository::error_handling::RepositoryError;
+use crate::repository::preference::PreferenceRecord;
+use crate::repository::resource::ResourceRecord;
+use crate::repository::run::{check_run, RunRecord, RunStatus};
+use crate::repository::world::{check_world, WorldRecord};
+use crate::repository::{check_owner, check_unique_keys, BATCH_SIZE};
+use crate::storage::Storage;
+use async_trait::async_trait;
+use chrono::{DateTime, SecondsFormat, Utc};
+use sqlx::migrate::Migrator;
+use sqlx::sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePoolOptions};
+use sqlx::{Pool, QueryBuilder, Sqlite, Transaction};
+use std::path::Path;
+use std::time::Duration;
+use uuid::Uuid;
+
+/// 日志与错误信息中的服务名称
+const SERVICE: &str = "SQLite";
+/// 连接池的最大连接数；SQLite 同一时刻只允许一个写入者，更多连接只会增加等待
+const POOL_SIZE: u32 = 4;
+/// 数据库被其他连接锁定时的等待时间
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// 编译期嵌入的 `backend/sqlite-migrations` 目录
+static MIGRATOR: Migrator = sqlx::migrate!("../sqlite-migrations");
+
+/// 时间以固定精度的 RFC 3339 文本保存，使文本的字典序与时间顺序一致
+fn timestamp(time: DateTime<Utc>) -> String {
+    time.to_rfc3339_opts(SecondsFormat::Micros, true)
+}
+
+/// 基于本地 SQLite 文件的存储，无需数据库服务
+///
+/// 表结构与 Postgres 一致，UUID 以 16 字节的 BLOB 保存。
+#[derive(Debug, Clone)]
+pub struct SqliteStorage {
+    pool: Pool<Sqlite>,
+}
+
+impl SqliteStorage {
+    /// 打开（不存在时创建）SQLite 数据库文件，并执行内嵌的迁移
+    pub async fn open(path: &Path) -> Result<Self, InitError> {
+        tracing::info!("打开 SQLite 数据库: {}", path.display());
+
+        let options = SqliteConnectOptions::new()
+            .filename(path)
+            .create_if_missing(true)
+            .foreign_keys(true)
+            .journal_mode(SqliteJournalMode::Wal)
+            .busy_timeout(BUSY_TIMEOUT);
+        let pool = SqlitePoolOptions::new()
+            .max_connections(POOL_SIZE)
+            .connect_with(options)
+            .await
+            .map_err(|e| InitError::Other {
+                service: SERVICE,
+                message: e.to_string(),
+            })?;
+
+        MIGRATOR
+            .run(&pool)
+            .await
+            .map_err(|e| InitError::Migration(e.to_string()))?;
+
+        Ok(Self { pool })
+    }
+
+    async fn upsert_resources_in(
+        transaction: &mut Transaction<'_, Sqlite>,
+        records: &[ResourceRecord],
+    ) -> Result<(), RepositoryError> {
+        for batch in records.chunks(BATCH_SIZE) {
+            QueryBuilder::<Sqlite>::new(
+                "INSERT INTO resources \
+                 (agent_id, subtance_numerator, subtance_denominator, allocatable, investment, debt) ",
+            )
+            .push_values(batch, |mut row, record| {
+                row.push_bind(record.agent_id)
+                    .push_bind(record.substance_numerator)
+                    .push_bind(record.substance_denominator)
+                    .push_bind(record.allocatable)
+                    .push_bind(record.investment)
+                    .push_bind(record.debt);
+            })
+            .push(
+                " ON CONFLICT (agent_id, subtance_numerator, subtance_denominator) \
+                 DO UPDATE SET allocatable = excluded.allocatable, \
+                 investment = excluded.investment, debt = excluded.debt",
+            )
+            .build()
+            .execute(&mut **transaction)
+            .await?;
+        }
+        Ok(())
+    }
+
+    async fn upsert_preferences_in(
+        transaction: &mut Transaction<'_, Sqlite>,
+        records: &[PreferenceRecord],
+    ) -> Result<(), RepositoryError> {
+        for batch in records.chunks(BATCH_SIZE) {
+            QueryBuilder::<Sqlite>::new(
+                "INSERT INTO preferences \
+                 (agent_id, subtance_numerator, subtance_denominator, preference) ",
+            )
+            .push_values(batch, |mut row, record| {
+                row.push_bind(record.agent_id)
+                    .push_bind(record.substance_numerator)
+                    .push_bind(record.substance_denominator)
+                    .push_bind(record.preference);
+            })
+            .push(
+                " ON CONFLICT (agent_id, subtance_numerator, subtance_denominator) \
+                 DO UPDATE SET preference = excluded.preference",
+            )
+            .build()
+            .execute(&mut **transaction)
+            .await?;
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Storage for SqliteStorage {
+    async fn upsert_agents(&self, records: &[AgentRecord]) -> Result<usize, RepositoryError> {
+        records.iter().try_for_each(AgentRecord::validate)?;
+        check_unique_keys(records.iter().map(|record| record.agent_id), "个体")?;
+
+        let mut transaction = self.pool.begin().await?;
+        for batch in records.chunks(BATCH_SIZE) {
+            QueryBuilder::<Sqlite>::new("INSERT INTO agents (agent_id, world_id, born_tick) ")
+                .push_values(batch, |mut row, record| {
+                    row.push_bind(record.agent_id)
+                        .push_bind(record.world_id)
+                        .push_bind(record.born_tick);
+                })
+                .push(
+                    " ON CONFLICT (agent_id) \
+                     DO UPDATE SET world_id = excluded.world_id, born_tick = excluded.born_tick",
+                )
+                .build()
+                .execute(&mut *transaction)
+                .await?;
+        }
+        transaction.commit().await?;
+
+        Ok(records.len())
+    }
+
+    async fn list_agents(&self, world_id: Uuid) -> Result<Vec<AgentRecord>, RepositoryError> {
+        Ok(sqlx::query_as(
+            "SELECT agent_id, world_id, born_tick FROM agents \
+             WHERE world_id = ?1 ORDER BY agent_id",
+        )
+        .bind(world_id)
+        .fetch_all(&self.pool)
+        .await?)
+    }
+
+    async fn delete_agent(&self, agent_id: Uuid) -> Result<bool, RepositoryError> {
+        let mut transaction = self.pool.begin().await?;
+        for statement in [
+            "DELETE FROM resources WHERE agent_id = ?1",
+            "DELETE FROM preferences WHERE agent_id = ?1",
+        ] {
+            sqlx::query(statement)
+                .bind(agent_id)
+                .execute(&mut *transaction)
+                .await?;
+        }
+        let result = sqlx::query("DELETE FROM agents WHERE agent_id = ?1")
+            .bind(agent_id)
+            .execute(&mut *transaction)
+            .await?;
+        transaction.commit().await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn upsert_resources(&self, records: &[ResourceRecord]) -> Result<usize, RepositoryError> {
+        records.iter().try_for_each(ResourceRecord::validate)?;
+        check_unique_keys(records.iter().map(ResourceRecord::key), "资源")?;
+
+        let mut transaction = self.pool.begin().await?;
+        Self::upsert_resources_in(&mut transaction, records).await?;
+        transaction.commit().await?;
+
+        Ok(records.len())
+    }
+
+    async fn replace_resources(
+        &self,
+        agent_id: Uuid,
+        records: &[ResourceRecord],
+    ) -> Result<usize, RepositoryError> {
+        check_owner(
+            agent_id,
+            records.iter().map(|record| record.agent_id),
+            "资源",
+        )?;
+        records.iter().try_for_each(ResourceRecord::validate)?;
+        check_unique_keys(records.iter().map(ResourceRecord::key), "资源")?;
+
+        let mut transaction = self.pool.begin().await?;
+        sqlx::query("DELETE FROM resources WHERE agent_id = ?1")
+            .bind(agent_id)
+            .execute(&mut *transaction)
+            .await?;
+        Self::upsert_resources_in(&mut transaction, records).await?;
+        transaction.commit().await?;
+
+        Ok(records.len())
+    }
+
+    async fn list_resources(&self, agent_id: Uuid) -> Result<Vec<ResourceRecord>, RepositoryError> {
+        Ok(sqlx::query_as(
+            "SELECT agent_id, subtance_numerator, subtance_denominator, allocatable, investment, debt \
+             FROM resources \
+             WHERE agent_id = ?1 \
+             ORDER BY subtance_numerator, subtance_denominator",
+        )
+        .bind(agent_id)
+        .fetch_all(&self.pool)
+        .await?)
+    }
+
+    async fn upsert_preferences(
+        &self,
+        records: &[PreferenceRecord],
+    ) -> Result<usize, RepositoryError> {
+        records.iter().try_for_each(PreferenceRecord::validate)?;
+        check_unique_keys(records.iter().map(PreferenceRecord::key), "偏好")?;
+
+        let mut transaction = self.pool.begin().await?;
+        Self::upsert_preferences_in(&mut transaction, records).await?;
+        transaction.commit().await?;
+
+        Ok(records.len())
+    }
+
+    async fn replace_preferences(
+        &self,
+        agent_id: Uuid,
+        records: &[PreferenceRecord],
+    ) -> Result<usize, RepositoryError> {
+        check_owner(
+            agent_id,
+            records.iter().map(|record| record.agent_id),
+            "偏好",
+        )?;
+        records.iter().try_for_each(PreferenceRecord::validate)?;
+        check_unique_keys(records.iter().map(PreferenceRecord::key), "偏好")?;
+
+        let mut transaction = self.pool.begin().await?;
+        sqlx::query("DELETE FROM preferences WHERE agent_id = ?1")
+            .bind(agent_id)
+            .execute(&mut *transaction)
+            .await?;
+        Self::upsert_preferences_in(&mut transaction, records).await?;
+        transaction.commit().await?;
+
+        Ok(records.len())
+    }
+
+    async fn list_preferences(
+        &self,
+        agent_id: Uuid,
+    ) -> Result<Vec<PreferenceRecord>, RepositoryError> {
+        Ok(sqlx::query_as(
+            "SELECT agent_id, subtance_numerator, subtance_denominator, preference \
+             FROM preferences \
+             WHERE agent_id = ?1 \
+             ORDER BY subtance_numerator, subtance_denominator",
+        )
+        .bind(agent_id)
+        .fetch_all(&self.pool)
+        .await?)
+    }
+
+    async fn create_world(
+        &self,
+        world_id: Uuid,
+        seed: i64,
+        map_width: i32,
+        map_height: i32,
+        gravity: f64,
+    ) -> Result<WorldRecord, RepositoryError> {
+        check_world(world_id, map_width, map_height, gravity)?;
+
+        Ok(sqlx::query_as(
+            "INSERT INTO worlds (world_id, seed, map_width, map_height, gravity, created_at) \
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6) \
+             RETURNING world_id, seed, map_width, map_height, gravity, created_at",
+        )
+        .bind(world_id)
+        .bind(seed)
+        .bind(map_width)
+        .bind(map_height)
+        .bind(gravity)
+        .bind(timestamp(Utc::now()))
+        .fetch_one(&self.pool)
+        .await?)
+    }
+
+    async fn find_world(&self, world_id: Uuid) -> Result<Option<WorldRecord>, RepositoryError> {
+        Ok(sqlx::query_as(
+            "SELECT world_id, seed, map_width, map_height, gravity, created_at \
+             FROM worlds WHERE world_id = ?1",
+        )
+        .bind(world_id)
+        .fetch_optional(&self.pool)
+        .await?)
+    }
+
+    async fn list_worlds(&self) -> Result<Vec<WorldRecord>, RepositoryError> {
+        Ok(sqlx::query_as(
+            "SELECT world_id, seed, map_width, map_height, gravity, created_at \
+             FROM worlds ORDER BY created_at DESC",
+        )
+        .fetch_all(&self.pool)
+        .await?)
+    }
+
+    async fn start_run(
+        &self,
+        world_id: Uuid,
+        start_tick: i64,
+        checkpoint_interval: i32,
+    ) -> Result<RunRecord, RepositoryError> {
+        check_run(start_tick, checkpoint_interval)?;
+
+        // 带 RETURNING 的语句只读取一行时，外键检查的失败不会撤销已插入的行，
+        // 因此先确认世界存在，不存在时不插入
+        let run: Option<RunRecord> = sqlx::query_as(
+            "INSERT INTO runs \
+             (run_id, world_id, status, start_tick, last_tick, checkpoint_interval, started_at) \
+             SELECT ?1, ?2, ?3, ?4, ?4, ?5, ?6 \
+             WHERE EXISTS (SELECT 1 FROM worlds WHERE world_id = ?2) \
+             RETURNING run_id, world_id, status, start_tick, last_tick, checkpoint_interval, started_at, finished_at",
+        )
+        .bind(Uuid::new_v4())
+        .bind(world_id)
+        .bind(RunStatus::Running.as_str())
+        .bind(start_tick)
+        .bind(checkpoint_interval)
+        .bind(timestamp(Utc::now()))
+        .fetch_optional(&self.pool)
+        .await?;
+        run.ok_or_else(|| RepositoryError::InvalidRecord(format!("世界 {} 不存在", world_id)))
+    }
+
+    async fn finish_run(&self, run_id: Uuid, status: RunStatus) -> Result<bool, RepositoryError> {
+        let finished_at = (status != RunStatus::Running).then(|| timestamp(Utc::now()));
+        let result = sqlx::query("UPDATE runs SET status = ?2, finished_at = ?3 WHERE run_id = ?1")
+            .bind(run_id)
+            .bind(status.as_str())
+            .bind(finished_at)
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn list_runs(&self, world_id: Uuid) -> Result<Vec<RunRecord>, RepositoryError> {
+        Ok(sqlx::query_as(
+            "SELECT run_id, world_id, status, start_tick, last_tick, checkpoint_interval, started_at, finished_at \
+             FROM runs WHERE world_id = ?1 ORDER BY started_at DESC",
+        )
+        .bind(world_id)
+        .fetch_all(&self.pool)
+        .await?)
+    }
+
+    async fn save_checkpoint(
+        &self,
+        run_id: Uuid,
+        tick: i64,
+        snapshot: &[u8],
+    ) -> Result<i64, RepositoryError> {
+        let mut transaction = self.pool.begin().await?;
+
+        let world_id: Option<Uuid> = sqlx::query_scalar(
+            "UPDATE runs SET last_tick = MAX(last_tick, ?2) \
+             WHERE run_id = ?1 AND start_tick <= ?2 \
+             RETURNING world_id",
+        )
+        .bind(run_id)
+        .bind(tick)
+        .fetch_optional(&mut *transaction)
+        .await?;
+        let Some(world_id) = world_id else {
+            return Err(RepositoryError::InvalidRecord(format!(
+                "运行 {} 不存在，或第 {} 步早于运行的起始步数",
+                run_id, tick
+            )));
+        };
+
+        let checkpoint_id = sqlx::query_scalar(
+            "INSERT INTO checkpoints (run_id, world_id, tick, snapshot, created_at) \
+             VALUES (?1, ?2, ?3, ?4, ?5) \
+             ON CONFLICT (run_id, tick) DO UPDATE SET snapshot = excluded.snapshot, created_at = excluded.created_at \
+             RETURNING checkpoint_id",
+        )
+        .bind(run_id)
+        .bind(world_id)
+        .bind(tick)
+        .bind(snapshot)
+        .bind(timestamp(Utc::now()))
+        .fetch_one(&mut *transaction)
+        .await?;
+
+        transaction.commit().await?;
+        Ok(checkpoint_id)
+    }
+
+    async fn latest_checkpoint(
+        &self,
+        world_id: Uuid,
+    ) -> Result<Option<CheckpointRecord>, RepositoryError> {
+        Ok(sqlx::query_as(
+            "SELECT checkpoint_id, run_id, world_id, tick, snapshot, created_at \
+             FROM checkpoints WHERE world_id = ?1 \
+             ORDER BY tick DESC, created_at DESC LIMIT 1",
+        )
+        .bind(world_id)
+        .fetch_optional(&self.pool)
+        .await?)
+    }
+
+    async fn list_checkpoints(
+        &self,
+        world_id: Uuid,
+    ) -> Result<Vec<CheckpointSummary>, RepositoryError> {
+        Ok(sqlx::query_as(
+            "SELECT checkpoint_id, run_id, world_id, tick, length(snapshot) AS size, created_at \
+             FROM checkpoints WHERE world_id = ?1 \
+             ORDER BY tick DESC, created_at DESC",
+        )
+        .bind(world_id)
+        .fetch_all(&self.pool)
+        .await?)
+    }
+
+    async fn prune_checkpoints(
+        &self,
+        world_id: Uuid,
+        policy: &RetentionPolicy,
+    ) -> Result<u64, RepositoryError> {
+        let keep_latest = i64::try_from(policy.keep_latest()).unwrap_or(i64::MAX);
+        let keep_every = policy
+            .keep_every()
+            .map(|interval| i64::try_from(interval).unwrap_or(i64::MAX));
+        let cutoff = policy.cutoff().map(timestamp);
+
+        let result = sqlx::query(
+            "DELETE FROM checkpoints \
+             WHERE world_id = ?1 \
+             AND checkpoint_id NOT IN ( \
+                 SELECT checkpoint_id FROM checkpoints WHERE world_id = ?1 \
+                 ORDER BY tick DESC, created_at DESC LIMIT ?2 \
+             ) \
+             AND NOT ( \
+                 ?3 IS NOT NULL AND tick % ?3 = 0 \
+                 AND (?4 IS NULL OR created_at >= ?4) \
+             )",
+        )
+        .bind(world_id)
+        .bind(keep_latest)
+        .bind(keep_every)
+        .bind(cutoff)
+        .execute(&self.pool)
+        .await?;
+
+        if result.rows_affected() > 0 {
+            tracing::info!(
+                "已按保留策略删除世界 {} 的 {} 个检查点",
+                world_id,
+                result.rows_affected()
+            );
+        }
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,154 @@
+mod support;
+
+use back_core::config::app_config::RedisConfig;
+use back_core::context::core_context::AppContext;
+use back_core::context::error_handling::InitError;
+use back_core::context::health::ServiceStatus;
+use back_core::context::redis::store::RedisStore;
+use back_core::context::retry::RetryPolicy;
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use support::RedisStandIn;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Snapshot {
+    tick: u64,
+    population: Vec<u32>,
+}
+
+#[tokio::test]
+async fn set_and_get_with_ttl() {
+    let server = RedisStandIn::start(None).await;
+    let store = RedisStore::open(server.url()).await.unwrap();
+
+    store.set("answer", 42, None).await.unwrap();
+    assert_eq!(store.get::<i64>("answer").await.unwrap(), Some(42));
+    assert_eq!(store.get::<i64>("missing").await.unwrap(), None);
+
+    store
+        .set("short-lived", "soon gone", Some(Duration::from_millis(50)))
+        .await
+        .unwrap();
+    assert_eq!(
+        store.get::<String>("short-lived").await.unwrap().as_deref(),
+        Some("soon gone")
+    );
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert_eq!(store.get::<String>("short-lived").await.unwrap(), None);
+
+    assert!(store.delete("answer").await.unwrap());
+    assert!(!store.delete("answer").await.unwrap());
+}
+
+#[tokio::test]
+async fn json_values_round_trip() {
+    let server = RedisStandIn::start(None).await;
+    let store = RedisStore::open(server.url()).await.unwrap();
+
+    let snapshot = Snapshot {
+        tick: 7,
+        population: vec![3, 1, 4],
+    };
+    store.set_json("world:1", &snapshot, None).await.unwrap();
+    assert_eq!(
+        store.get_json::<Snapshot>("world:1").await.unwrap(),
+        Some(snapshot)
+    );
+
+    store.set("world:2", "not json", None).await.unwrap();
+    assert!(store.get_json::<Snapshot>("world:2").await.is_err());
+}
+
+#[tokio::test]
+async fn published_messages_reach_subscribers() {
+    let server = RedisStandIn::start(None).await;
+    let store = RedisStore::open(server.url()).await.unwrap();
+
+    let mut messages = store.subscribe(&["ticks", "events"]).await.unwrap();
+    assert_eq!(store.publish("ticks", "1").await.unwrap(), 1);
+    assert_eq!(store.publish("nobody", "ignored").await.unwrap(), 0);
+    assert_eq!(
+        store
+            .publish_json("events", &vec!["founded"])
+            .await
+            .unwrap(),
+        1
+    );
+
+    let tick = messages.next().await.unwrap();
+    assert_eq!(tick.channel, "ticks");
+    assert_eq!(tick.payload_str(), Some("1"));
+
+    let event = messages.next().await.unwrap();
+    assert_eq!(event.channel, "events");
+    assert_eq!(
+        event.payload_json::<Vec<String>>().unwrap(),
+        vec!["founded"]
+    );
+}
+
+#[tokio::test]
+async fn commands_survive_a_dropped_connection() {
+    let server = RedisStandIn::start(None).await;
+    let store = RedisStore::open(server.url()).await.unwrap();
+    store.set("key", "value", None).await.unwrap();
+
+    server.drop_connections();
+    tokio::time::sleep(Duration::from_millis(20)).await;
+
+    assert_eq!(
+        store.get::<String>("key").await.unwrap().as_deref(),
+        Some("value")
+    );
+    store.ping().await.unwrap();
+}
+
+#[tokio::test]
+async fn init_errors_are_classified() {
+    let server = RedisStandIn::start(Some("s3cret")).await;
+    let redis = |port: u16, password: &str| RedisConfig {
+        host: "127.0.0.1".to_string(),
+        port,
+        password: password.to_string(),
+    };
+    let port = server.port();
+    let context = || AppContext::new().with_retry_policy(RetryPolicy::no_retry());
+
+    context()
+        .try_with_redis(&redis(port, "s3cret"))
+        .await
+        .unwrap();
+
+    let wrong_password = context()
+        .try_with_redis(&redis(port, "guess"))
+        .await
+        .unwrap_err();
+    assert!(matches!(wrong_password, InitError::AuthFailed { .. }));
+    assert!(!wrong_password.to_string().contains("guess"));
+
+    let no_password = context()
+        .try_with_redis(&redis(port, ""))
+        .await
+        .unwrap_err();
+    assert!(matches!(no_password, InitError::AuthFailed { .. }));
+
+    let closed_port = {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        listener.local_addr().unwrap().port()
+    };
+    let refused = context()
+        .try_with_redis(&redis(closed_port, ""))
+        .await
+        .unwrap_err();
+    assert!(matches!(refused, InitError::ConnectionRefused { .. }));
+    assert!(refused.is_retryable());
+
+    // 非 try 版本不会失败，而是把应用标记为降级
+    context()
+        .with_redis(&redis(closed_port, ""))
+        .await
+        .install();
+    assert!(matches!(AppContext::health().redis, ServiceStatus::Down(_)));
+    assert!(AppContext::try_get_redis().is_err());
+}
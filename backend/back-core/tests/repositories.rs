@@ -0,0 +1,223 @@
+//! Postgres 仓储的测试
+//!
+//! 需要一个可写的 Postgres，通过 `TEST_DATABASE_URL` 指定，例如
+//! `TEST_DATABASE_URL=postgres://postgres@127.0.0.1:5432/rehive cargo test`；
+//! 未设置时跳过。每个测试使用随机的个体与物质类型，结束时清理自己写入的记录。
+
+use back_core::context::db::migration::run_migrations;
+use back_core::repository::agent::{AgentRecord, AgentRepository};
+use back_core::repository::error_handling::RepositoryError;
+use back_core::repository::preference::{PreferenceRecord, PreferenceRepository};
+use back_core::repository::property::{PropertyRecord, PropertyRepository};
+use back_core::repository::resource::{ResourceRecord, ResourceRepository};
+use back_core::repository::world::WorldRepository;
+use sqlx::postgres::PgPoolOptions;
+use sqlx::{Pool, Postgres};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// 连接测试数据库并执行迁移，未设置 `TEST_DATABASE_URL` 时返回 `None`
+async fn pool() -> Option<Arc<Pool<Postgres>>> {
+    let Ok(url) = std::env::var("TEST_DATABASE_URL") else {
+        eprintln!("未设置 TEST_DATABASE_URL，跳过");
+        return None;
+    };
+    let pool = PgPoolOptions::new()
+        .max_connections(4)
+        .connect(&url)
+        .await
+        .unwrap();
+    run_migrations(&pool).await.unwrap();
+    Some(Arc::new(pool))
+}
+
+fn resource(agent_id: Uuid, numerator: i32, allocatable: i32) -> ResourceRecord {
+    ResourceRecord {
+        agent_id,
+        substance_numerator: numerator,
+        substance_denominator: 7,
+        allocatable,
+        investment: 0,
+        debt: 0,
+    }
+}
+
+fn preference(agent_id: Uuid, numerator: i32, preference: f64) -> PreferenceRecord {
+    PreferenceRecord {
+        agent_id,
+        substance_numerator: numerator,
+        substance_denominator: 7,
+        preference,
+    }
+}
+
+#[tokio::test]
+async fn resources_upsert_and_reject_duplicate_keys() {
+    let Some(pool) = pool().await else { return };
+    let repository = ResourceRepository::new(pool);
+    let agent_id = Uuid::new_v4();
+
+    let records = [resource(agent_id, 1, 10), resource(agent_id, 2, 20)];
+    assert_eq!(repository.upsert_many(&records).await.unwrap(), 2);
+    // 已存在的（个体，物质类型）更新数量
+    repository
+        .upsert_many(&[resource(agent_id, 1, 15)])
+        .await
+        .unwrap();
+    assert_eq!(
+        repository.list_by_agent(agent_id).await.unwrap(),
+        vec![resource(agent_id, 1, 15), resource(agent_id, 2, 20)]
+    );
+
+    // 同一批中的重复键被拒绝，整批都不写入
+    let duplicated = [
+        resource(agent_id, 3, 30),
+        resource(agent_id, 1, 1),
+        resource(agent_id, 1, 2),
+    ];
+    assert!(matches!(
+        repository.upsert_many(&duplicated).await,
+        Err(RepositoryError::InvalidRecord(_))
+    ));
+    assert!(matches!(
+        repository.replace_for_agent(agent_id, &duplicated).await,
+        Err(RepositoryError::InvalidRecord(_))
+    ));
+    assert_eq!(
+        repository.list_by_agent(agent_id).await.unwrap(),
+        vec![resource(agent_id, 1, 15), resource(agent_id, 2, 20)]
+    );
+
+    // 替换后只剩新的记录
+    repository
+        .replace_for_agent(agent_id, &[resource(agent_id, 3, 30)])
+        .await
+        .unwrap();
+    assert_eq!(
+        repository.find(agent_id, 3, 7).await.unwrap(),
+        Some(resource(agent_id, 3, 30))
+    );
+    assert_eq!(repository.find(agent_id, 1, 7).await.unwrap(), None);
+
+    assert_eq!(repository.delete_by_agent(agent_id).await.unwrap(), 1);
+}
+
+#[tokio::test]
+async fn preferences_upsert_and_reject_duplicate_keys() {
+    let Some(pool) = pool().await else { return };
+    let repository = PreferenceRepository::new(pool);
+    let agent_id = Uuid::new_v4();
+
+    repository
+        .upsert_many(&[preference(agent_id, 1, 0.25), preference(agent_id, 2, 0.5)])
+        .await
+        .unwrap();
+    repository
+        .upsert_many(&[preference(agent_id, 2, 0.75)])
+        .await
+        .unwrap();
+    assert_eq!(
+        repository.list_by_agent(agent_id).await.unwrap(),
+        vec![preference(agent_id, 1, 0.25), preference(agent_id, 2, 0.75)]
+    );
+
+    let duplicated = [preference(agent_id, 2, 0.1), preference(agent_id, 2, 0.2)];
+    assert!(matches!(
+        repository.upsert_many(&duplicated).await,
+        Err(RepositoryError::InvalidRecord(_))
+    ));
+    assert!(matches!(
+        repository.replace_for_agent(agent_id, &duplicated).await,
+        Err(RepositoryError::InvalidRecord(_))
+    ));
+    assert_eq!(
+        repository.find(agent_id, 2, 7).await.unwrap(),
+        Some(preference(agent_id, 2, 0.75))
+    );
+
+    repository
+        .replace_for_agent(agent_id, &[preference(agent_id, 3, 1.0)])
+        .await
+        .unwrap();
+    assert_eq!(
+        repository.list_by_agent(agent_id).await.unwrap(),
+        vec![preference(agent_id, 3, 1.0)]
+    );
+
+    assert_eq!(repository.delete_by_agent(agent_id).await.unwrap(), 1);
+}
+
+#[tokio::test]
+async fn properties_upsert_and_reject_duplicate_keys() {
+    let Some(pool) = pool().await else { return };
+    let repository = PropertyRepository::new(pool);
+    // properties 表不区分个体，用随机的分子避免与其他数据冲突
+    let numerator = 1_000_000 + (Uuid::new_v4().as_u128() % 1_000_000) as i32;
+    let property = |frequency: i32, value: f64| PropertyRecord {
+        substance_numerator: numerator,
+        substance_denominator: 3,
+        frequency_constant: frequency,
+        phase_constant: 0,
+        property_value: value,
+    };
+
+    repository
+        .upsert_many(&[property(1, 0.1), property(2, 0.2)])
+        .await
+        .unwrap();
+    repository.upsert_many(&[property(1, 0.3)]).await.unwrap();
+    assert_eq!(
+        repository.find(numerator, 3, 1, 0).await.unwrap(),
+        Some(property(1, 0.3))
+    );
+
+    assert!(matches!(
+        repository
+            .upsert_many(&[property(3, 0.4), property(2, 0.5), property(2, 0.6)])
+            .await,
+        Err(RepositoryError::InvalidRecord(_))
+    ));
+    assert_eq!(repository.find(numerator, 3, 3, 0).await.unwrap(), None);
+    assert_eq!(
+        repository.find(numerator, 3, 2, 0).await.unwrap(),
+        Some(property(2, 0.2))
+    );
+
+    assert_eq!(
+        repository.delete_by_substance(numerator, 3).await.unwrap(),
+        2
+    );
+}
+
+#[tokio::test]
+async fn agents_reject_duplicate_ids() {
+    let Some(pool) = pool().await else { return };
+    let worlds = WorldRepository::new(pool.clone());
+    let repository = AgentRepository::new(pool);
+    let world_id = Uuid::new_v4();
+    worlds.create(world_id, 1, 8, 8, 9.8).await.unwrap();
+
+    let agent = |agent_id: Uuid, born_tick: i64| AgentRecord {
+        agent_id,
+        world_id,
+        born_tick,
+    };
+    let (first, second) = (Uuid::new_v4(), Uuid::new_v4());
+    assert!(matches!(
+        repository
+            .upsert_many(&[agent(first, 0), agent(second, 0), agent(first, 5)])
+            .await,
+        Err(RepositoryError::InvalidRecord(_))
+    ));
+    assert!(repository.list_by_world(world_id).await.unwrap().is_empty());
+
+    repository
+        .upsert_many(&[agent(first, 0), agent(second, 5)])
+        .await
+        .unwrap();
+    assert_eq!(repository.list_by_world(world_id).await.unwrap().len(), 2);
+
+    // 删除世界时个体被级联删除
+    assert!(worlds.delete(world_id).await.unwrap());
+    assert!(repository.list_by_world(world_id).await.unwrap().is_empty());
+}
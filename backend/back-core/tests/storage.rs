@@ -0,0 +1,442 @@
+//! 各存储后端共用的测试
+//!
+//! 同一组用例分别在内存、SQLite（临时文件）与 Postgres 上运行，保证后端之间行为一致。
+//! Postgres 需要通过 `TEST_DATABASE_URL` 指定，未设置时跳过。
+//! 用例只读写自己创建的世界与个体，因此可以在非空的数据库上运行。
+
+use back_core::context::db::migration::run_migrations;
+use back_core::repository::agent::AgentRecord;
+use back_core::repository::checkpoint::RetentionPolicy;
+use back_core::repository::error_handling::RepositoryError;
+use back_core::repository::preference::PreferenceRecord;
+use back_core::repository::resource::ResourceRecord;
+use back_core::repository::run::RunStatus;
+use back_core::storage::memory::MemoryStorage;
+use back_core::storage::postgres::PostgresStorage;
+use back_core::storage::sqlite::SqliteStorage;
+use back_core::storage::Storage;
+use sqlx::postgres::PgPoolOptions;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use uuid::Uuid;
+
+/// 两次写入之间的间隔，保证按创建时间排序的结果确定
+const GAP: Duration = Duration::from_millis(5);
+
+fn resource(agent_id: Uuid, numerator: i32, allocatable: i32) -> ResourceRecord {
+    ResourceRecord {
+        agent_id,
+        substance_numerator: numerator,
+        substance_denominator: 7,
+        allocatable,
+        investment: 0,
+        debt: 0,
+    }
+}
+
+fn preference(agent_id: Uuid, numerator: i32, preference: f64) -> PreferenceRecord {
+    PreferenceRecord {
+        agent_id,
+        substance_numerator: numerator,
+        substance_denominator: 7,
+        preference,
+    }
+}
+
+/// 依次运行全部用例
+async fn exercise(storage: &dyn Storage) {
+    worlds_are_listed_newest_first(storage).await;
+    agents_require_a_world_and_are_sorted(storage).await;
+    resources_upsert_and_replace(storage).await;
+    preferences_upsert_and_replace(storage).await;
+    runs_are_listed_newest_first(storage).await;
+    checkpoints_advance_last_tick(storage).await;
+    checkpoints_are_pruned_by_policy(storage).await;
+}
+
+async fn worlds_are_listed_newest_first(storage: &dyn Storage) {
+    let (older, newer) = (Uuid::new_v4(), Uuid::new_v4());
+    storage.create_world(older, 1, 8, 4, 9.8).await.unwrap();
+    tokio::time::sleep(GAP).await;
+    let created = storage.create_world(newer, 2, 16, 8, 9.8).await.unwrap();
+    assert_eq!((created.map_width, created.map_height), (16, 8));
+
+    let listed: Vec<Uuid> = storage
+        .list_worlds()
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|world| world.world_id)
+        .filter(|world_id| [older, newer].contains(world_id))
+        .collect();
+    assert_eq!(listed, vec![newer, older]);
+
+    assert_eq!(storage.find_world(older).await.unwrap().unwrap().seed, 1);
+    assert!(storage.find_world(Uuid::new_v4()).await.unwrap().is_none());
+}
+
+async fn agents_require_a_world_and_are_sorted(storage: &dyn Storage) {
+    let world_id = Uuid::new_v4();
+    let agent = |agent_id: Uuid, born_tick: i64| AgentRecord {
+        agent_id,
+        world_id,
+        born_tick,
+    };
+    let mut agent_ids = [Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4()];
+
+    // 世界尚不存在
+    assert!(matches!(
+        storage.upsert_agents(&[agent(agent_ids[0], 0)]).await,
+        Err(RepositoryError::InvalidRecord(_))
+    ));
+    storage.create_world(world_id, 3, 8, 8, 9.8).await.unwrap();
+
+    // 同一批中的重复个体被拒绝，整批都不写入
+    assert!(matches!(
+        storage
+            .upsert_agents(&[agent(agent_ids[0], 0), agent(agent_ids[0], 1)])
+            .await,
+        Err(RepositoryError::InvalidRecord(_))
+    ));
+    assert!(storage.list_agents(world_id).await.unwrap().is_empty());
+
+    let records: Vec<_> = agent_ids.iter().map(|id| agent(*id, 0)).collect();
+    assert_eq!(storage.upsert_agents(&records).await.unwrap(), 3);
+    // 已存在的个体更新诞生步数
+    storage
+        .upsert_agents(&[agent(agent_ids[1], 7)])
+        .await
+        .unwrap();
+
+    agent_ids.sort();
+    let listed = storage.list_agents(world_id).await.unwrap();
+    assert_eq!(
+        listed
+            .iter()
+            .map(|agent| agent.agent_id)
+            .collect::<Vec<_>>(),
+        agent_ids
+    );
+    assert_eq!(listed.iter().map(|agent| agent.born_tick).sum::<i64>(), 7);
+
+    // 删除个体时一并删除其资源与偏好
+    let removed = agent_ids[0];
+    storage
+        .upsert_resources(&[resource(removed, 1, 10)])
+        .await
+        .unwrap();
+    storage
+        .upsert_preferences(&[preference(removed, 1, 0.5)])
+        .await
+        .unwrap();
+    assert!(storage.delete_agent(removed).await.unwrap());
+    assert!(!storage.delete_agent(removed).await.unwrap());
+    assert!(storage.list_resources(removed).await.unwrap().is_empty());
+    assert!(storage.list_preferences(removed).await.unwrap().is_empty());
+    assert_eq!(storage.list_agents(world_id).await.unwrap().len(), 2);
+}
+
+async fn resources_upsert_and_replace(storage: &dyn Storage) {
+    let agent_id = Uuid::new_v4();
+
+    // 按物质类型排序，而不是按写入顺序
+    let records = [resource(agent_id, 3, 30), resource(agent_id, 1, 10)];
+    assert_eq!(storage.upsert_resources(&records).await.unwrap(), 2);
+    storage
+        .upsert_resources(&[resource(agent_id, 1, 15), resource(agent_id, 2, 20)])
+        .await
+        .unwrap();
+    assert_eq!(
+        storage.list_resources(agent_id).await.unwrap(),
+        vec![
+            resource(agent_id, 1, 15),
+            resource(agent_id, 2, 20),
+            resource(agent_id, 3, 30)
+        ]
+    );
+
+    let duplicated = [resource(agent_id, 4, 40), resource(agent_id, 4, 41)];
+    assert!(matches!(
+        storage.upsert_resources(&duplicated).await,
+        Err(RepositoryError::InvalidRecord(_))
+    ));
+    assert!(matches!(
+        storage.replace_resources(agent_id, &duplicated).await,
+        Err(RepositoryError::InvalidRecord(_))
+    ));
+    // 替换时不能混入其他个体的记录
+    assert!(matches!(
+        storage
+            .replace_resources(agent_id, &[resource(Uuid::new_v4(), 5, 50)])
+            .await,
+        Err(RepositoryError::InvalidRecord(_))
+    ));
+    assert_eq!(storage.list_resources(agent_id).await.unwrap().len(), 3);
+
+    assert_eq!(
+        storage
+            .replace_resources(agent_id, &[resource(agent_id, 4, 40)])
+            .await
+            .unwrap(),
+        1
+    );
+    assert_eq!(
+        storage.list_resources(agent_id).await.unwrap(),
+        vec![resource(agent_id, 4, 40)]
+    );
+    storage.replace_resources(agent_id, &[]).await.unwrap();
+    assert!(storage.list_resources(agent_id).await.unwrap().is_empty());
+}
+
+async fn preferences_upsert_and_replace(storage: &dyn Storage) {
+    let agent_id = Uuid::new_v4();
+
+    storage
+        .upsert_preferences(&[preference(agent_id, 2, 0.5), preference(agent_id, 1, 0.25)])
+        .await
+        .unwrap();
+    storage
+        .upsert_preferences(&[preference(agent_id, 2, 0.75)])
+        .await
+        .unwrap();
+    assert_eq!(
+        storage.list_preferences(agent_id).await.unwrap(),
+        vec![preference(agent_id, 1, 0.25), preference(agent_id, 2, 0.75)]
+    );
+
+    // 偏好须在 [0, 1] 之内
+    assert!(matches!(
+        storage
+            .upsert_preferences(&[preference(agent_id, 3, 1.5)])
+            .await,
+        Err(RepositoryError::InvalidRecord(_))
+    ));
+    let duplicated = [preference(agent_id, 3, 0.1), preference(agent_id, 3, 0.2)];
+    assert!(matches!(
+        storage.replace_preferences(agent_id, &duplicated).await,
+        Err(RepositoryError::InvalidRecord(_))
+    ));
+
+    storage
+        .replace_preferences(agent_id, &[preference(agent_id, 3, 1.0)])
+        .await
+        .unwrap();
+    assert_eq!(
+        storage.list_preferences(agent_id).await.unwrap(),
+        vec![preference(agent_id, 3, 1.0)]
+    );
+}
+
+async fn runs_are_listed_newest_first(storage: &dyn Storage) {
+    let world_id = Uuid::new_v4();
+    assert!(matches!(
+        storage.start_run(world_id, 0, 10).await,
+        Err(RepositoryError::InvalidRecord(_))
+    ));
+    storage.create_world(world_id, 4, 8, 8, 9.8).await.unwrap();
+
+    let first = storage.start_run(world_id, 0, 10).await.unwrap();
+    assert_eq!(first.status, RunStatus::Running);
+    assert_eq!(first.last_tick, 0);
+    tokio::time::sleep(GAP).await;
+    let second = storage.start_run(world_id, 50, 10).await.unwrap();
+
+    assert!(storage
+        .finish_run(first.run_id, RunStatus::Completed)
+        .await
+        .unwrap());
+    assert!(!storage
+        .finish_run(Uuid::new_v4(), RunStatus::Failed)
+        .await
+        .unwrap());
+
+    let runs = storage.list_runs(world_id).await.unwrap();
+    assert_eq!(
+        runs.iter().map(|run| run.run_id).collect::<Vec<_>>(),
+        vec![second.run_id, first.run_id]
+    );
+    assert_eq!(runs[1].status, RunStatus::Completed);
+    assert!(runs[1].finished_at.is_some());
+    assert_eq!(runs[0].status, RunStatus::Running);
+    assert!(runs[0].finished_at.is_none());
+}
+
+async fn checkpoints_advance_last_tick(storage: &dyn Storage) {
+    let world_id = Uuid::new_v4();
+    storage.create_world(world_id, 5, 8, 8, 9.8).await.unwrap();
+    assert!(storage.latest_checkpoint(world_id).await.unwrap().is_none());
+    let run = storage.start_run(world_id, 10, 10).await.unwrap();
+    let last_tick = || async { storage.list_runs(world_id).await.unwrap()[0].last_tick };
+
+    // 早于运行起始步数的检查点被拒绝
+    assert!(matches!(
+        storage.save_checkpoint(run.run_id, 5, b"early").await,
+        Err(RepositoryError::InvalidRecord(_))
+    ));
+    assert!(matches!(
+        storage.save_checkpoint(Uuid::new_v4(), 20, b"orphan").await,
+        Err(RepositoryError::InvalidRecord(_))
+    ));
+
+    storage
+        .save_checkpoint(run.run_id, 20, b"tick 20")
+        .await
+        .unwrap();
+    assert_eq!(last_tick().await, 20);
+    let id = storage
+        .save_checkpoint(run.run_id, 30, b"tick 30")
+        .await
+        .unwrap();
+    assert_eq!(last_tick().await, 30);
+    // 补存较早的检查点不会让 last_tick 倒退
+    storage
+        .save_checkpoint(run.run_id, 15, b"tick 15")
+        .await
+        .unwrap();
+    assert_eq!(last_tick().await, 30);
+
+    // 同一运行的同一步覆盖原有的检查点
+    assert_eq!(
+        storage
+            .save_checkpoint(run.run_id, 30, b"tick 30 again")
+            .await
+            .unwrap(),
+        id
+    );
+    let latest = storage.latest_checkpoint(world_id).await.unwrap().unwrap();
+    assert_eq!((latest.checkpoint_id, latest.tick), (id, 30));
+    assert_eq!(latest.snapshot, b"tick 30 again");
+
+    let listed = storage.list_checkpoints(world_id).await.unwrap();
+    assert_eq!(
+        listed
+            .iter()
+            .map(|checkpoint| checkpoint.tick)
+            .collect::<Vec<_>>(),
+        vec![30, 20, 15]
+    );
+    assert_eq!(listed[0].size, b"tick 30 again".len() as i32);
+}
+
+async fn checkpoints_are_pruned_by_policy(storage: &dyn Storage) {
+    let world_id = Uuid::new_v4();
+    storage.create_world(world_id, 6, 8, 8, 9.8).await.unwrap();
+    let run = storage.start_run(world_id, 0, 10).await.unwrap();
+    for tick in (0..=50).step_by(10) {
+        storage
+            .save_checkpoint(run.run_id, tick, &tick.to_le_bytes())
+            .await
+            .unwrap();
+    }
+    let ticks = || async {
+        storage
+            .list_checkpoints(world_id)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|checkpoint| checkpoint.tick)
+            .collect::<Vec<_>>()
+    };
+
+    // 其他世界的检查点不受影响
+    let other = Uuid::new_v4();
+    storage.create_world(other, 7, 8, 8, 9.8).await.unwrap();
+    let other_run = storage.start_run(other, 0, 10).await.unwrap();
+    storage
+        .save_checkpoint(other_run.run_id, 10, b"other")
+        .await
+        .unwrap();
+
+    // 保留最新的 2 个，以及步数为 20 的整数倍的里程碑
+    let milestones = RetentionPolicy::new(Some(2)).with_keep_every(20);
+    assert_eq!(
+        storage
+            .prune_checkpoints(world_id, &milestones)
+            .await
+            .unwrap(),
+        2
+    );
+    assert_eq!(ticks().await, vec![50, 40, 20, 0]);
+    assert_eq!(
+        storage
+            .prune_checkpoints(world_id, &milestones)
+            .await
+            .unwrap(),
+        0
+    );
+
+    // 里程碑超过保留时长后也被删除
+    let expired = milestones.with_max_age(Duration::ZERO);
+    tokio::time::sleep(GAP).await;
+    assert_eq!(
+        storage.prune_checkpoints(world_id, &expired).await.unwrap(),
+        2
+    );
+    assert_eq!(ticks().await, vec![50, 40]);
+
+    // 至少保留最新的一个
+    assert_eq!(
+        storage
+            .prune_checkpoints(world_id, &RetentionPolicy::new(Some(0)))
+            .await
+            .unwrap(),
+        1
+    );
+    assert_eq!(ticks().await, vec![50]);
+    assert_eq!(
+        storage
+            .latest_checkpoint(world_id)
+            .await
+            .unwrap()
+            .unwrap()
+            .snapshot,
+        50i64.to_le_bytes()
+    );
+    assert_eq!(storage.list_checkpoints(other).await.unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn memory_storage() {
+    exercise(&MemoryStorage::new()).await;
+}
+
+/// 临时的 SQLite 数据库文件，测试结束（包括失败）时连同 WAL 文件一起删除
+struct TempDatabase(PathBuf);
+
+impl TempDatabase {
+    fn new() -> Self {
+        Self(std::env::temp_dir().join(format!("re-hive-storage-{}.db", Uuid::new_v4())))
+    }
+}
+
+impl Drop for TempDatabase {
+    fn drop(&mut self) {
+        for suffix in ["", "-wal", "-shm"] {
+            let mut file = self.0.clone().into_os_string();
+            file.push(suffix);
+            let _ = std::fs::remove_file(file);
+        }
+    }
+}
+
+#[tokio::test]
+async fn sqlite_storage() {
+    let database = TempDatabase::new();
+    exercise(&SqliteStorage::open(&database.0).await.unwrap()).await;
+}
+
+#[tokio::test]
+async fn postgres_storage() {
+    let Ok(url) = std::env::var("TEST_DATABASE_URL") else {
+        eprintln!("未设置 TEST_DATABASE_URL，跳过");
+        return;
+    };
+    let pool = PgPoolOptions::new()
+        .max_connections(4)
+        .connect(&url)
+        .await
+        .unwrap();
+    run_migrations(&pool).await.unwrap();
+    exercise(&PostgresStorage::new(Arc::new(pool))).await;
+}
@@ -0,0 +1,252 @@
+//! 进程内的 Redis 替身：实现 RESP2 协议中测试用到的少量命令
+//!
+//! 支持 `AUTH`、`PING`、`GET`、`SET`（含 `PX`/`EX`）、`DEL`、`PUBLISH` 与 `SUBSCRIBE`。
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::tcp::OwnedReadHalf;
+use tokio::net::TcpListener;
+use tokio::sync::{mpsc, watch};
+
+type Message = (Vec<u8>, Vec<u8>);
+
+#[derive(Default)]
+struct State {
+    values: HashMap<Vec<u8>, (Vec<u8>, Option<Instant>)>,
+    subscribers: HashMap<Vec<u8>, Vec<mpsc::UnboundedSender<Message>>>,
+}
+
+/// 正在运行的替身服务器，丢弃时停止接受新连接
+pub struct RedisStandIn {
+    port: u16,
+    disconnect: watch::Sender<u64>,
+    accept_task: tokio::task::JoinHandle<()>,
+}
+
+impl RedisStandIn {
+    /// 在随机端口上启动，`password` 不为空时要求先认证
+    pub async fn start(password: Option<&str>) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let state = Arc::new(Mutex::new(State::default()));
+        let password = password.map(|password| password.as_bytes().to_vec());
+        let (disconnect, _) = watch::channel(0);
+
+        let disconnect_rx = disconnect.subscribe();
+        let accept_task = tokio::spawn(async move {
+            while let Ok((socket, _)) = listener.accept().await {
+                tokio::spawn(serve(
+                    socket,
+                    state.clone(),
+                    password.clone(),
+                    disconnect_rx.clone(),
+                ));
+            }
+        });
+
+        Self {
+            port,
+            disconnect,
+            accept_task,
+        }
+    }
+
+    pub fn port(&self) -> u16 {
+        self.port
+    }
+
+    pub fn url(&self) -> String {
+        format!("redis://127.0.0.1:{}", self.port)
+    }
+
+    /// 断开当前所有连接，模拟网络闪断；之后的新连接不受影响
+    pub fn drop_connections(&self) {
+        self.disconnect.send_modify(|generation| *generation += 1);
+    }
+}
+
+impl Drop for RedisStandIn {
+    fn drop(&mut self) {
+        self.accept_task.abort();
+    }
+}
+
+async fn serve(
+    socket: tokio::net::TcpStream,
+    state: Arc<Mutex<State>>,
+    password: Option<Vec<u8>>,
+    mut disconnect: watch::Receiver<u64>,
+) {
+    disconnect.borrow_and_update();
+    let (reader, mut writer) = socket.into_split();
+
+    // 读取放在单独的任务里，避免 select 取消读到一半的命令
+    let (command_tx, mut command_rx) = mpsc::unbounded_channel();
+    let reader_task = tokio::spawn(async move {
+        let mut reader = BufReader::new(reader);
+        while let Some(command) = read_command(&mut reader).await {
+            if command_tx.send(command).is_err() {
+                break;
+            }
+        }
+    });
+
+    let (message_tx, mut message_rx) = mpsc::unbounded_channel::<Message>();
+    let mut authenticated = password.is_none();
+    let mut subscriptions = 0;
+
+    loop {
+        let reply = tokio::select! {
+            command = command_rx.recv() => match command {
+                Some(command) => execute(
+                    &command,
+                    &state,
+                    password.as_deref(),
+                    &mut authenticated,
+                    &message_tx,
+                    &mut subscriptions,
+                ),
+                None => break,
+            },
+            Some((channel, payload)) = message_rx.recv() => {
+                array(&[bulk(b"message"), bulk(&channel), bulk(&payload)])
+            }
+            _ = disconnect.changed() => break,
+        };
+        if writer.write_all(&reply).await.is_err() {
+            break;
+        }
+    }
+
+    reader_task.abort();
+}
+
+fn execute(
+    command: &[Vec<u8>],
+    state: &Mutex<State>,
+    password: Option<&[u8]>,
+    authenticated: &mut bool,
+    message_tx: &mpsc::UnboundedSender<Message>,
+    subscriptions: &mut i64,
+) -> Vec<u8> {
+    let name = command[0].to_ascii_uppercase();
+    let args = &command[1..];
+
+    if name == b"AUTH" {
+        return if password == args.last().map(Vec::as_slice) {
+            *authenticated = true;
+            b"+OK\r\n".to_vec()
+        } else {
+            b"-WRONGPASS invalid username-password pair\r\n".to_vec()
+        };
+    }
+    if !*authenticated {
+        return b"-NOAUTH Authentication required.\r\n".to_vec();
+    }
+
+    let mut state = state.lock().unwrap();
+    match name.as_slice() {
+        b"PING" => b"+PONG\r\n".to_vec(),
+        b"GET" => {
+            let now = Instant::now();
+            match state.values.get(&args[0]) {
+                Some((value, expiry)) if expiry.is_none_or(|expiry| expiry > now) => bulk(value),
+                _ => b"$-1\r\n".to_vec(),
+            }
+        }
+        b"SET" => {
+            let ttl = match args.get(2).map(|option| option.to_ascii_uppercase()) {
+                Some(option) if option == b"PX" => Some(Duration::from_millis(integer(&args[3]))),
+                Some(option) if option == b"EX" => Some(Duration::from_secs(integer(&args[3]))),
+                _ => None,
+            };
+            let expiry = ttl.map(|ttl| Instant::now() + ttl);
+            state
+                .values
+                .insert(args[0].clone(), (args[1].clone(), expiry));
+            b"+OK\r\n".to_vec()
+        }
+        b"DEL" => {
+            let removed = args
+                .iter()
+                .filter(|key| state.values.remove(*key).is_some())
+                .count();
+            format!(":{}\r\n", removed).into_bytes()
+        }
+        b"PUBLISH" => {
+            let subscribers = state.subscribers.entry(args[0].clone()).or_default();
+            subscribers
+                .retain(|subscriber| subscriber.send((args[0].clone(), args[1].clone())).is_ok());
+            format!(":{}\r\n", subscribers.len()).into_bytes()
+        }
+        b"SUBSCRIBE" => {
+            let mut reply = Vec::new();
+            for channel in args {
+                state
+                    .subscribers
+                    .entry(channel.clone())
+                    .or_default()
+                    .push(message_tx.clone());
+                *subscriptions += 1;
+                reply.extend(array(&[
+                    bulk(b"subscribe"),
+                    bulk(channel),
+                    format!(":{}\r\n", subscriptions).into_bytes(),
+                ]));
+            }
+            reply
+        }
+        _ => format!(
+            "-ERR unknown command '{}'\r\n",
+            String::from_utf8_lossy(&command[0])
+        )
+        .into_bytes(),
+    }
+}
+
+/// 读取一条以 RESP 数组编码的命令，连接关闭时返回 `None`
+async fn read_command(reader: &mut BufReader<OwnedReadHalf>) -> Option<Vec<Vec<u8>>> {
+    let count = read_header(reader, b'*').await?;
+    let mut command = Vec::with_capacity(count);
+    for _ in 0..count {
+        let len = read_header(reader, b'$').await?;
+        let mut argument = vec![0; len + 2];
+        reader.read_exact(&mut argument).await.ok()?;
+        argument.truncate(len);
+        command.push(argument);
+    }
+    Some(command)
+}
+
+async fn read_header(reader: &mut BufReader<OwnedReadHalf>, prefix: u8) -> Option<usize> {
+    let mut line = String::new();
+    if reader.read_line(&mut line).await.ok()? == 0 {
+        return None;
+    }
+    let line = line.trim_end();
+    if line.as_bytes().first() != Some(&prefix) {
+        return None;
+    }
+    line[1..].parse().ok()
+}
+
+fn integer(argument: &[u8]) -> u64 {
+    std::str::from_utf8(argument).unwrap().parse().unwrap()
+}
+
+fn bulk(value: &[u8]) -> Vec<u8> {
+    let mut encoded = format!("${}\r\n", value.len()).into_bytes();
+    encoded.extend_from_slice(value);
+    encoded.extend_from_slice(b"\r\n");
+    encoded
+}
+
+fn array(items: &[Vec<u8>]) -> Vec<u8> {
+    let mut encoded = format!("*{}\r\n", items.len()).into_bytes();
+    for item in items {
+        encoded.extend_from_slice(item);
+    }
+    encoded
+}
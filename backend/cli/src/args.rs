@@ -0,0 +1,241 @@
+//! 命令行参数的定义
+//!
+//! 参数有误时由 clap 打印错误与用法，并以退出码 2 退出；各子命令只处理已校验的参数。
+
+use clap::builder::RangedU64ValueParser;
+use clap::{Args, Parser, Subcommand};
+use game::{ColorMap, HeatmapTarget, Metric, SubstanceType};
+use std::path::PathBuf;
+
+/// 不依赖数据库与 HTTP 服务的命令行工具，直接使用 `game` crate 批量运行和检查模拟
+///
+/// 启动时先加载 `--property-catalogue` 指定的属性目录。
+#[derive(Debug, Parser)]
+#[command(name = "re-hive-cli", version)]
+pub(crate) struct Cli {
+    /// 属性目录文件（`.toml` 或 `.json`），未指定时只使用内置属性
+    #[arg(
+        long,
+        global = true,
+        env = "PROPERTY_CATALOGUE_PATH",
+        value_name = "目录.toml"
+    )]
+    pub(crate) property_catalogue: Option<PathBuf>,
+    #[command(subcommand)]
+    pub(crate) command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+pub(crate) enum Command {
+    /// 按配置文件创建世界，写出第 0 步的快照
+    Create(CreateArgs),
+    /// 推进若干步，默认覆盖输入的快照；可按间隔另存中间快照，或按间隔记录指标
+    Run(RunArgs),
+    /// 打印每种物质与势能场强的统计摘要
+    Stats(StatsArgs),
+    /// 将物质分布导出为 CSV 数值矩阵或 16 位灰度 PNG
+    Export(ExportArgs),
+    /// 渲染热力图
+    Heatmap(HeatmapArgs),
+    /// 逐单元格比较两个快照，存在差异时退出码为 1
+    Diff(DiffArgs),
+    /// 从事件日志重建世界并校验状态哈希，不一致时退出码为 1；可同时记录指标
+    Replay(ReplayArgs),
+}
+
+#[derive(Debug, Args)]
+pub(crate) struct CreateArgs {
+    /// 世界配置文件，示例见 `cli/world.example.toml`
+    #[arg(value_name = "配置.toml")]
+    pub(crate) config: PathBuf,
+    /// 写出的快照
+    #[arg(short, long, value_name = "快照.json")]
+    pub(crate) out: PathBuf,
+}
+
+#[derive(Debug, Args)]
+pub(crate) struct RunArgs {
+    #[arg(value_name = "快照.json")]
+    pub(crate) snapshot: PathBuf,
+    /// 推进的步数
+    #[arg(long, value_name = "步数")]
+    pub(crate) ticks: u64,
+    /// 写出的快照，默认覆盖输入的快照
+    #[arg(short, long, value_name = "快照.json")]
+    pub(crate) out: Option<PathBuf>,
+    /// 每隔多少步另存一次中间快照
+    #[arg(
+        long,
+        value_name = "步数",
+        requires = "snapshot_dir",
+        value_parser = RangedU64ValueParser::<u64>::new().range(1..)
+    )]
+    pub(crate) snapshot_every: Option<u64>,
+    /// 中间快照所在的目录，文件名为 `tick-<步数>.json`
+    #[arg(long, value_name = "目录", requires = "snapshot_every")]
+    pub(crate) snapshot_dir: Option<PathBuf>,
+    #[command(flatten)]
+    pub(crate) metrics: MetricsArgs,
+}
+
+#[derive(Debug, Args)]
+pub(crate) struct StatsArgs {
+    #[arg(value_name = "快照.json")]
+    pub(crate) snapshot: PathBuf,
+    /// 只统计这一种物质
+    #[arg(long, value_name = "m/n")]
+    pub(crate) substance: Option<SubstanceType>,
+    /// 以 JSON 输出
+    #[arg(long)]
+    pub(crate) json: bool,
+}
+
+#[derive(Debug, Args)]
+pub(crate) struct ExportArgs {
+    #[arg(value_name = "快照.json")]
+    pub(crate) snapshot: PathBuf,
+    #[arg(long, value_name = "m/n")]
+    pub(crate) substance: SubstanceType,
+    /// 导出的文件，按扩展名选择格式
+    #[arg(short, long, value_name = "文件.csv|文件.png", value_parser = csv_or_png)]
+    pub(crate) out: PathBuf,
+    /// 摩尔数的缩放倍数：CSV 默认为 1，PNG 默认以最大摩尔数为纯白
+    #[arg(long, value_name = "倍数", value_parser = positive)]
+    pub(crate) mole_scale: Option<f64>,
+}
+
+#[derive(Debug, Args)]
+pub(crate) struct HeatmapArgs {
+    #[arg(value_name = "快照.json")]
+    pub(crate) snapshot: PathBuf,
+    /// 物质类型 `m/n`，或势能场强 `potential`
+    #[arg(long, value_name = "m/n|potential", value_parser = heatmap_target)]
+    pub(crate) target: HeatmapTarget,
+    #[arg(short, long, value_name = "文件.png")]
+    pub(crate) out: PathBuf,
+    #[arg(long, value_name = "名称")]
+    pub(crate) color_map: Option<ColorMap>,
+    #[arg(long, value_name = "像素")]
+    pub(crate) pixels_per_unit: Option<f64>,
+    /// 每隔多少个单元格绘制一个运动方向箭头
+    #[arg(long, value_name = "间隔")]
+    pub(crate) arrows: Option<usize>,
+    /// 固定色图两端对应的取值，默认取图层的最小值与最大值
+    #[arg(long, value_name = "最小值,最大值", value_parser = value_range)]
+    pub(crate) range: Option<(f64, f64)>,
+}
+
+#[derive(Debug, Args)]
+pub(crate) struct DiffArgs {
+    #[arg(value_name = "快照A.json")]
+    pub(crate) a: PathBuf,
+    #[arg(value_name = "快照B.json")]
+    pub(crate) b: PathBuf,
+    /// 只比较这一种物质
+    #[arg(long, value_name = "m/n")]
+    pub(crate) substance: Option<SubstanceType>,
+    /// 每种物质最多列出的差异单元格数
+    #[arg(long, value_name = "条数", default_value_t = 10)]
+    pub(crate) limit: usize,
+}
+
+#[derive(Debug, Args)]
+pub(crate) struct ReplayArgs {
+    #[arg(value_name = "事件日志.jsonl")]
+    pub(crate) log: PathBuf,
+    /// 只重放到这一步
+    #[arg(long, value_name = "步数")]
+    pub(crate) until: Option<u64>,
+    /// 写出重放结束（或出现不一致）时的快照
+    #[arg(short, long, value_name = "快照.json")]
+    pub(crate) out: Option<PathBuf>,
+    #[command(flatten)]
+    pub(crate) metrics: MetricsArgs,
+}
+
+/// `run` 与 `replay` 共用的指标选项
+#[derive(Debug, Args)]
+pub(crate) struct MetricsArgs {
+    /// 记录的指标，以逗号分隔，例如 `mean:1/2,gini:potential,substance_count`
+    #[arg(
+        long,
+        value_name = "列表",
+        requires = "metrics_out",
+        value_parser = metric_list
+    )]
+    pub(crate) metrics: Option<MetricList>,
+    /// 每隔多少步采样一次，默认每步采样
+    #[arg(
+        long,
+        value_name = "步数",
+        requires = "metrics",
+        value_parser = RangedU64ValueParser::<usize>::new().range(1..)
+    )]
+    pub(crate) metrics_interval: Option<usize>,
+    /// 导出的文件，按扩展名选择格式
+    #[arg(
+        long,
+        value_name = "文件.csv|文件.parquet",
+        requires = "metrics",
+        value_parser = csv_or_parquet
+    )]
+    pub(crate) metrics_out: Option<PathBuf>,
+}
+
+/// `--metrics` 的值：至少一个指标
+#[derive(Debug, Clone)]
+pub(crate) struct MetricList(pub(crate) Vec<Metric>);
+
+/// 扩展名是 `extensions` 之一的路径
+fn with_extension(value: &str, extensions: &[&str]) -> Result<PathBuf, String> {
+    let path = PathBuf::from(value);
+    match path.extension().and_then(|extension| extension.to_str()) {
+        Some(extension) if extensions.contains(&extension) => Ok(path),
+        _ => Err(format!("扩展名须为 .{}", extensions.join(" 或 ."))),
+    }
+}
+
+fn csv_or_png(value: &str) -> Result<PathBuf, String> {
+    with_extension(value, &["csv", "png"])
+}
+
+fn csv_or_parquet(value: &str) -> Result<PathBuf, String> {
+    with_extension(value, &["csv", "parquet"])
+}
+
+fn positive(value: &str) -> Result<f64, String> {
+    match value.parse::<f64>() {
+        Ok(number) if number.is_finite() && number > 0.0 => Ok(number),
+        _ => Err("须为正数".to_string()),
+    }
+}
+
+fn heatmap_target(value: &str) -> Result<HeatmapTarget, String> {
+    match value {
+        "potential" => Ok(HeatmapTarget::Potential),
+        substance => substance
+            .parse()
+            .map(HeatmapTarget::Substance)
+            .map_err(|e| e.to_string()),
+    }
+}
+
+/// 解析 `最小值,最大值`
+fn value_range(value: &str) -> Result<(f64, f64), String> {
+    let invalid = || "应为 最小值,最大值，且最小值小于最大值".to_string();
+    let (min, max) = value.split_once(',').ok_or_else(invalid)?;
+    let min: f64 = min.trim().parse().map_err(|_| invalid())?;
+    let max: f64 = max.trim().parse().map_err(|_| invalid())?;
+    if min >= max {
+        return Err(invalid());
+    }
+    Ok((min, max))
+}
+
+fn metric_list(value: &str) -> Result<MetricList, String> {
+    let metrics = Metric::parse_list(value).map_err(|e| e.to_string())?;
+    if metrics.is_empty() {
+        return Err("至少包含一个指标".to_string());
+    }
+    Ok(MetricList(metrics))
+}
@@ -0,0 +1,33 @@
+use crate::args::CreateArgs;
+use crate::error_handling::CliError;
+use crate::snapshot::WorldSnapshot;
+use crate::world_config::WorldConfig;
+use game::{SimulationState, WorldCommand};
+use std::process::ExitCode;
+
+/// `create <配置.toml> -o <快照.json>`
+pub(crate) fn run(args: CreateArgs) -> Result<ExitCode, CliError> {
+    let config = WorldConfig::load(&args.config)?;
+    let out = &args.out;
+
+    let params = config.world_params();
+    let mut state = SimulationState::new(&params);
+    for substance in config.substances() {
+        state.apply(&WorldCommand::AddSubstance {
+            substance_type: substance.substance_type,
+            noise_params: substance.noise_params.clone().unwrap_or_default(),
+        })?;
+    }
+
+    WorldSnapshot::new(state.gravity_const(), state.landscape().clone()).save(out)?;
+    let (height, width) = params.map_size().as_tuple();
+    println!(
+        "已创建世界 {}：{}×{}，{} 种物质，写入 {}",
+        params.civilization_id(),
+        width,
+        height,
+        config.substances().len(),
+        out.display()
+    );
+    Ok(ExitCode::SUCCESS)
+}
@@ -0,0 +1,170 @@
+use crate::args::DiffArgs;
+use crate::error_handling::CliError;
+use crate::snapshot::{substance_label, WorldSnapshot};
+use game::{HexUnit, SubstanceDistribution, SubstanceType};
+use std::collections::BTreeSet;
+use std::process::ExitCode;
+
+/// 同一种物质在两个快照中的逐单元格差异
+#[derive(Debug, Default)]
+struct DistributionDiff {
+    /// 摩尔数或运动状态不同的单元格数
+    changed_cells: usize,
+    /// 摩尔数不同的单元格数
+    mole_changed: usize,
+    /// 总摩尔数的变化（B - A）
+    net_moles: i128,
+    /// 各单元格摩尔数变化的绝对值之和
+    abs_moles: u128,
+    /// 摩尔数变化最大的单元格及其变化量
+    largest: Option<((usize, usize), i128)>,
+    /// 按行优先顺序的前若干个差异单元格
+    samples: Vec<((usize, usize), HexUnit, HexUnit)>,
+}
+
+impl DistributionDiff {
+    fn compare(a: &SubstanceDistribution, b: &SubstanceDistribution, limit: usize) -> Self {
+        let mut diff = Self::default();
+        for ((index, unit_a), unit_b) in
+            a.distribution().indexed_iter().zip(b.distribution().iter())
+        {
+            let moved = unit_a.movement().y().to_bits() != unit_b.movement().y().to_bits()
+                || unit_a.movement().x().to_bits() != unit_b.movement().x().to_bits();
+            let delta = unit_b.mole() as i128 - unit_a.mole() as i128;
+            if delta == 0 && !moved {
+                continue;
+            }
+
+            diff.changed_cells += 1;
+            if delta != 0 {
+                diff.mole_changed += 1;
+                diff.net_moles += delta;
+                diff.abs_moles += delta.unsigned_abs();
+                if diff
+                    .largest
+                    .is_none_or(|(_, largest)| delta.abs() > largest.abs())
+                {
+                    diff.largest = Some((index, delta));
+                }
+            }
+            if diff.samples.len() < limit {
+                diff.samples.push((index, *unit_a, *unit_b));
+            }
+        }
+        diff
+    }
+}
+
+/// `diff <快照A.json> <快照B.json> [--substance <m/n>] [--limit <条数>]`
+///
+/// 两个快照完全相同时退出码为 0，存在差异时为 1。
+pub(crate) fn run(args: DiffArgs) -> Result<ExitCode, CliError> {
+    let a = WorldSnapshot::load(&args.a)?;
+    let b = WorldSnapshot::load(&args.b)?;
+    let limit = args.limit;
+
+    let size_a = a.landscape.map_size().as_tuple();
+    let size_b = b.landscape.map_size().as_tuple();
+    if size_a != size_b {
+        return Err(CliError::Mismatch(format!(
+            "地图尺寸不同，无法逐单元格比较: {:?} 与 {:?}",
+            size_a, size_b
+        )));
+    }
+
+    let mut differs = false;
+    println!("步数: {} → {}", a.landscape.tick(), b.landscape.tick());
+    if a.gravity_const.to_bits() != b.gravity_const.to_bits() {
+        differs = true;
+        println!("重力常数: {} → {}", a.gravity_const, b.gravity_const);
+    }
+
+    let substance_types: BTreeSet<SubstanceType> = match args.substance {
+        Some(substance_type) => BTreeSet::from([substance_type]),
+        None => a
+            .distributions()
+            .into_iter()
+            .chain(b.distributions())
+            .map(|distribution| *distribution.substance_type())
+            .collect(),
+    };
+    for substance_type in substance_types {
+        let label = substance_label(&substance_type);
+        let (distribution_a, distribution_b) = match (
+            a.distribution(substance_type),
+            b.distribution(substance_type),
+        ) {
+            (Ok(distribution_a), Ok(distribution_b)) => (distribution_a, distribution_b),
+            (Ok(_), Err(_)) => {
+                differs = true;
+                println!("物质 {}: 只存在于 A", label);
+                continue;
+            }
+            (Err(_), Ok(_)) => {
+                differs = true;
+                println!("物质 {}: 只存在于 B", label);
+                continue;
+            }
+            (Err(e), Err(_)) => return Err(e),
+        };
+
+        let diff = DistributionDiff::compare(distribution_a, distribution_b, limit);
+        if diff.changed_cells == 0 {
+            println!("物质 {}: 相同", label);
+            continue;
+        }
+        differs = true;
+        println!(
+            "物质 {}: {} 个单元格不同（摩尔数不同 {} 个），总摩尔数变化 {:+}，变化绝对值之和 {}",
+            label, diff.changed_cells, diff.mole_changed, diff.net_moles, diff.abs_moles
+        );
+        if let Some(((row, col), delta)) = diff.largest {
+            println!("  摩尔数变化最大: ({}, {}) {:+}", row, col, delta);
+        }
+        for ((row, col), unit_a, unit_b) in &diff.samples {
+            println!(
+                "  ({}, {}) 摩尔数 {} → {}，运动 ({:.4}, {:.4}) → ({:.4}, {:.4})",
+                row,
+                col,
+                unit_a.mole(),
+                unit_b.mole(),
+                unit_a.movement().y(),
+                unit_a.movement().x(),
+                unit_b.movement().y(),
+                unit_b.movement().x()
+            );
+        }
+        if diff.changed_cells > diff.samples.len() {
+            println!(
+                "  …… 另有 {} 个单元格不同",
+                diff.changed_cells - diff.samples.len()
+            );
+        }
+    }
+
+    // 势能场强由物质分布决定，只作汇总
+    let (potential_changed, potential_max) = a
+        .landscape
+        .potential()
+        .distribution()
+        .iter()
+        .zip(b.landscape.potential().distribution().iter())
+        .filter(|(value_a, value_b)| value_a.to_bits() != value_b.to_bits())
+        .fold((0usize, 0.0f64), |(count, max), (value_a, value_b)| {
+            (count + 1, max.max((value_b - value_a).abs()))
+        });
+    if potential_changed > 0 {
+        differs = true;
+        println!(
+            "势能场强: {} 个单元格不同，最大差值 {:.6}",
+            potential_changed, potential_max
+        );
+    }
+
+    if differs {
+        Ok(ExitCode::FAILURE)
+    } else {
+        println!("两个快照完全相同");
+        Ok(ExitCode::SUCCESS)
+    }
+}
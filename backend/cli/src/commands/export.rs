@@ -0,0 +1,54 @@
+use crate::args::{ExportArgs, HeatmapArgs};
+use crate::error_handling::CliError;
+use crate::snapshot::{substance_label, WorldSnapshot};
+use game::{HeatmapRenderer, Statistical};
+use std::process::ExitCode;
+
+/// `export <快照.json> --substance <m/n> -o <文件.csv|文件.png> [--mole-scale <倍数>]`
+///
+/// CSV 默认输出摩尔数本身；PNG 默认以最大摩尔数为纯白。
+pub(crate) fn export(args: ExportArgs) -> Result<ExitCode, CliError> {
+    let snapshot = WorldSnapshot::load(&args.snapshot)?;
+    let substance_type = args.substance;
+    let distribution = snapshot.distribution(substance_type)?;
+    let out = args.out.as_path();
+
+    // clap 已校验扩展名为 .csv 或 .png
+    match out.extension().and_then(|extension| extension.to_str()) {
+        Some("png") => {
+            let max_mole = distribution.max().mole().max(1) as f64;
+            distribution.export_png(out, args.mole_scale.unwrap_or(max_mole))?
+        }
+        _ => distribution.export_csv(out, args.mole_scale.unwrap_or(1.0))?,
+    }
+    println!(
+        "已导出第 {} 步物质 {} 的分布到 {}",
+        snapshot.landscape.tick(),
+        substance_label(&substance_type),
+        out.display()
+    );
+    Ok(ExitCode::SUCCESS)
+}
+
+/// `heatmap <快照.json> --target <m/n|potential> -o <文件.png> [--color-map <名称>]
+/// [--pixels-per-unit <像素>] [--arrows <间隔>] [--range <最小值,最大值>]`
+pub(crate) fn heatmap(args: HeatmapArgs) -> Result<ExitCode, CliError> {
+    let snapshot = WorldSnapshot::load(&args.snapshot)?;
+
+    let mut renderer = HeatmapRenderer::new(args.color_map, args.pixels_per_unit);
+    if let Some(stride) = args.arrows {
+        renderer = renderer.with_arrows(stride);
+    }
+    if let Some((min, max)) = args.range {
+        renderer = renderer.with_value_range(min, max);
+    }
+
+    let frame = snapshot.landscape.heatmap_frame(args.target)?;
+    renderer.render(&frame).save_png(&args.out)?;
+    println!(
+        "已渲染第 {} 步的热力图到 {}",
+        snapshot.landscape.tick(),
+        args.out.display()
+    );
+    Ok(ExitCode::SUCCESS)
+}
@@ -0,0 +1,8 @@
+//! 各子命令的实现，每个子命令返回进程的退出码
+
+pub(crate) mod create;
+pub(crate) mod diff;
+pub(crate) mod export;
+pub(crate) mod replay;
+pub(crate) mod run;
+pub(crate) mod stats;
@@ -0,0 +1,45 @@
+use crate::args::ReplayArgs;
+use crate::error_handling::CliError;
+use crate::metrics_output::MetricsOutput;
+use crate::snapshot::WorldSnapshot;
+use game::{open_event_log, Replayer};
+use std::process::ExitCode;
+
+/// `replay <事件日志.jsonl> [--until <步数>] [-o <快照.json>]
+/// [--metrics <列表> [--metrics-interval <步数>] --metrics-out <文件.csv|文件.parquet>]`
+///
+/// 出现不一致时，写出的快照停在不一致的那一步，便于与正常运行的快照 `diff`。
+pub(crate) fn run(args: ReplayArgs) -> Result<ExitCode, CliError> {
+    let metrics = MetricsOutput::from_args(&args.metrics);
+    let mut replayer = Replayer::new(open_event_log(&args.log)?);
+    if let Some(metrics) = &metrics {
+        replayer = replayer.with_metrics(metrics.recorder());
+    }
+    let report = replayer.replay(args.until)?;
+
+    let state = report.state();
+    println!(
+        "重放到第 {} 步，校验通过 {} 个状态哈希，当前状态哈希为 {:016x}",
+        state.tick(),
+        report.verified_hashes(),
+        state.state_hash()
+    );
+    if let Some(out) = &args.out {
+        WorldSnapshot::new(state.gravity_const(), state.landscape().clone()).save(out)?;
+        println!("已写入快照 {}", out.display());
+    }
+    if let Some(metrics) = &metrics {
+        metrics.export()?;
+    }
+
+    match report.divergence() {
+        Some(divergence) => {
+            println!(
+                "第 {} 步（第 {} 号事件）状态哈希不一致: 记录为 {:016x}，重放为 {:016x}",
+                divergence.tick, divergence.sequence, divergence.expected, divergence.actual
+            );
+            Ok(ExitCode::FAILURE)
+        }
+        None => Ok(ExitCode::SUCCESS),
+    }
+}
@@ -0,0 +1,47 @@
+use crate::args::RunArgs;
+use crate::error_handling::CliError;
+use crate::metrics_output::MetricsOutput;
+use crate::snapshot::WorldSnapshot;
+use std::process::ExitCode;
+use std::time::Instant;
+
+/// `run <快照.json> --ticks <步数> [-o <快照.json>] [--snapshot-every <步数> --snapshot-dir <目录>]
+/// [--metrics <列表> [--metrics-interval <步数>] --metrics-out <文件.csv|文件.parquet>]`
+pub(crate) fn run(args: RunArgs) -> Result<ExitCode, CliError> {
+    let out = args.out.as_ref().unwrap_or(&args.snapshot);
+    // clap 保证两者同时出现
+    let periodic = args.snapshot_every.zip(args.snapshot_dir.as_deref());
+    let metrics = MetricsOutput::from_args(&args.metrics);
+
+    let mut snapshot = WorldSnapshot::load(&args.snapshot)?;
+    if let Some(metrics) = &metrics {
+        snapshot.landscape = snapshot.landscape.with_metrics(metrics.recorder());
+    }
+    let start_tick = snapshot.landscape.tick();
+    let started = Instant::now();
+    for _ in 0..args.ticks {
+        // 与重放一致：先按当前分布更新势能场强，再扩散
+        snapshot.landscape.update_potential_distribution();
+        snapshot.landscape.diffuse();
+
+        let tick = snapshot.landscape.tick();
+        if let Some((every, dir)) = periodic {
+            if tick.is_multiple_of(every) {
+                snapshot.save(dir.join(format!("tick-{:08}.json", tick)))?;
+            }
+        }
+    }
+    snapshot.save(out)?;
+
+    println!(
+        "第 {} 步 → 第 {} 步，用时 {:.2?}，写入 {}",
+        start_tick,
+        snapshot.landscape.tick(),
+        started.elapsed(),
+        out.display()
+    );
+    if let Some(metrics) = &metrics {
+        metrics.export()?;
+    }
+    Ok(ExitCode::SUCCESS)
+}
@@ -0,0 +1,128 @@
+use crate::args::StatsArgs;
+use crate::error_handling::CliError;
+use crate::snapshot::{substance_label, WorldSnapshot};
+use game::{Statistical, StatisticsSummary, SubstanceDistribution};
+use serde::Serialize;
+use std::process::ExitCode;
+
+/// 一个图层的统计摘要
+#[derive(Debug, Serialize)]
+struct LayerReport {
+    /// 物质类型 `m/n`，势能场强为 `potential`
+    layer: String,
+    #[serde(flatten)]
+    summary: StatisticsSummary,
+    median: f64,
+    p90: f64,
+    /// 含负值的图层（势能场强）没有基尼系数
+    gini: Option<f64>,
+    morans_i: f64,
+    /// 仅物质分布：总质量
+    #[serde(skip_serializing_if = "Option::is_none")]
+    total_mass: Option<f64>,
+    /// 仅物质分布：运动向量模长的平均值
+    #[serde(skip_serializing_if = "Option::is_none")]
+    mean_movement: Option<f64>,
+}
+
+impl LayerReport {
+    fn from_layer(layer: String, statistical: &impl Statistical) -> Self {
+        let quantiles = statistical.quantiles(&[0.5, 0.9]);
+        Self {
+            layer,
+            summary: statistical.summary(),
+            median: quantiles[0],
+            p90: quantiles[1],
+            gini: statistical.gini(),
+            morans_i: statistical.morans_i(),
+            total_mass: None,
+            mean_movement: None,
+        }
+    }
+
+    fn from_distribution(distribution: &SubstanceDistribution) -> Self {
+        Self {
+            total_mass: Some(distribution.total_mass()),
+            mean_movement: Some(distribution.mean_movement_magnitude()),
+            ..Self::from_layer(substance_label(distribution.substance_type()), distribution)
+        }
+    }
+}
+
+/// `stats <快照.json> [--substance <m/n>] [--json]`
+pub(crate) fn run(args: StatsArgs) -> Result<ExitCode, CliError> {
+    let snapshot = WorldSnapshot::load(&args.snapshot)?;
+
+    let reports = match args.substance {
+        Some(substance_type) => vec![LayerReport::from_distribution(
+            snapshot.distribution(substance_type)?,
+        )],
+        None => snapshot
+            .distributions()
+            .into_iter()
+            .map(LayerReport::from_distribution)
+            .chain(std::iter::once(LayerReport::from_layer(
+                "potential".to_string(),
+                snapshot.landscape.potential(),
+            )))
+            .collect(),
+    };
+
+    if args.json {
+        let json = serde_json::json!({
+            "tick": snapshot.landscape.tick(),
+            "gravity_const": snapshot.gravity_const,
+            "layers": reports,
+        });
+        println!("{}", json);
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    let (height, width) = snapshot.landscape.map_size().as_tuple();
+    println!(
+        "第 {} 步，{}×{}，重力常数 {}",
+        snapshot.landscape.tick(),
+        width,
+        height,
+        snapshot.gravity_const
+    );
+    println!(
+        "{:<10} {:>14} {:>10} {:>10} {:>10} {:>10} {:>12} {:>8} {:>8} {:>9} {:>14} {:>10}",
+        "layer",
+        "total",
+        "min",
+        "max",
+        "mean",
+        "median",
+        "variance",
+        "entropy",
+        "gini",
+        "moran_i",
+        "total_mass",
+        "movement"
+    );
+    for report in &reports {
+        let optional = |value: Option<f64>, precision: usize| {
+            value.map_or_else(
+                || "-".to_string(),
+                |value| format!("{:.*}", precision, value),
+            )
+        };
+        println!(
+            "{:<10} {:>14.2} {:>10.2} {:>10.2} {:>10.3} {:>10.3} {:>12.3} {:>8.4} {:>8} {:>9.4} {:>14} {:>10}",
+            report.layer,
+            report.summary.total,
+            report.summary.min,
+            report.summary.max,
+            report.summary.mean,
+            report.median,
+            report.summary.variance,
+            report.summary.entropy,
+            optional(report.gini, 4),
+            report.morans_i,
+            optional(report.total_mass, 3),
+            optional(report.mean_movement, 4),
+        );
+    }
+    Ok(ExitCode::SUCCESS)
+}
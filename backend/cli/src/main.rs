@@ -0,0 +1,146 @@
+//! 不依赖数据库与 HTTP 服务的命令行工具，直接使用 `game` crate 批量运行和检查模拟
+//!
+//! 世界以快照文件保存：`create` 从配置文件创建，`run` 推进若干步，
+//! 其余子命令读取快照输出统计、导出图像或比较差异。
+
+mod args;
+mod commands;
+mod metrics_output;
+mod snapshot;
+mod world_config;
+
+use args::{Cli, Command};
+use clap::Parser;
+use error_handling::CliError;
+use game::PropertyRegistry;
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
+    // 参数有误时 clap 打印错误与用法，并以退出码 2 退出
+    let cli = Cli::parse();
+
+    // 与服务端一致，先加载属性目录
+    if let Err(e) = PropertyRegistry::init(cli.property_catalogue.as_deref()) {
+        eprintln!("{}", CliError::from(e));
+        return ExitCode::FAILURE;
+    }
+
+    let result = match cli.command {
+        Command::Create(args) => commands::create::run(args),
+        Command::Run(args) => commands::run::run(args),
+        Command::Stats(args) => commands::stats::run(args),
+        Command::Export(args) => commands::export::export(args),
+        Command::Heatmap(args) => commands::export::heatmap(args),
+        Command::Diff(args) => commands::diff::run(args),
+        Command::Replay(args) => commands::replay::run(args),
+    };
+
+    match result {
+        Ok(code) => code,
+        Err(e) => {
+            eprintln!("{}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+mod error_handling {
+    use game::{CodecError, MetricsError, PropertyRegistryError, RenderError, ReplayError};
+    use std::fmt;
+    use std::path::PathBuf;
+
+    #[derive(Debug)]
+    pub enum CliError {
+        /// 读写文件失败
+        Io {
+            path: PathBuf,
+            source: std::io::Error,
+        },
+        /// 配置文件格式有误
+        Config { path: PathBuf, message: String },
+        /// 快照无法（反）序列化
+        Snapshot {
+            path: PathBuf,
+            source: serde_json::Error,
+        },
+        /// 快照与参数不匹配，例如快照中没有该物质
+        Mismatch(String),
+        /// 导出文件失败
+        Codec(CodecError),
+        /// 渲染热力图失败
+        Render(RenderError),
+        /// 创建世界或重放事件日志失败
+        Replay(ReplayError),
+        /// 加载属性目录失败
+        Catalogue(PropertyRegistryError),
+        /// 导出指标失败
+        Metrics(MetricsError),
+    }
+
+    impl fmt::Display for CliError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                CliError::Io { path, source } => {
+                    write!(f, "读写 {} 失败: {}", path.display(), source)
+                }
+                CliError::Config { path, message } => {
+                    write!(f, "配置文件 {} 有误: {}", path.display(), message)
+                }
+                CliError::Snapshot { path, source } => {
+                    write!(f, "快照 {} 无法（反）序列化: {}", path.display(), source)
+                }
+                CliError::Mismatch(message) => write!(f, "{}", message),
+                CliError::Codec(e) => write!(f, "导出失败: {}", e),
+                CliError::Render(e) => write!(f, "渲染失败: {}", e),
+                CliError::Replay(e) => write!(f, "{}", e),
+                CliError::Catalogue(e) => write!(f, "加载属性目录失败: {}", e),
+                CliError::Metrics(e) => write!(f, "{}", e),
+            }
+        }
+    }
+
+    impl std::error::Error for CliError {
+        fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+            match self {
+                CliError::Io { source, .. } => Some(source),
+                CliError::Snapshot { source, .. } => Some(source),
+                CliError::Codec(e) => Some(e),
+                CliError::Render(e) => Some(e),
+                CliError::Replay(e) => Some(e),
+                CliError::Catalogue(e) => Some(e),
+                CliError::Metrics(e) => Some(e),
+                _ => None,
+            }
+        }
+    }
+
+    impl From<CodecError> for CliError {
+        fn from(e: CodecError) -> Self {
+            CliError::Codec(e)
+        }
+    }
+
+    impl From<RenderError> for CliError {
+        fn from(e: RenderError) -> Self {
+            CliError::Render(e)
+        }
+    }
+
+    impl From<ReplayError> for CliError {
+        fn from(e: ReplayError) -> Self {
+            CliError::Replay(e)
+        }
+    }
+
+    impl From<PropertyRegistryError> for CliError {
+        fn from(e: PropertyRegistryError) -> Self {
+            CliError::Catalogue(e)
+        }
+    }
+
+    impl From<MetricsError> for CliError {
+        fn from(e: MetricsError) -> Self {
+            CliError::Metrics(e)
+        }
+    }
+}
@@ -0,0 +1,51 @@
+use crate::args::MetricsArgs;
+use crate::error_handling::CliError;
+use game::{MetricsRecorder, SharedMetricsRecorder};
+use std::path::PathBuf;
+
+/// `--metrics <列表> [--metrics-interval <步数>] --metrics-out <文件.csv|文件.parquet>`
+///
+/// 模拟期间挂载在地貌上的记录器，以及结束后导出的文件。
+pub(crate) struct MetricsOutput {
+    recorder: SharedMetricsRecorder,
+    out: PathBuf,
+}
+
+impl MetricsOutput {
+    /// 按指标选项创建记录器，未提供 `--metrics` 时返回 `None`
+    ///
+    /// 选项之间的依赖与取值已由 clap 校验，`--metrics` 与 `--metrics-out` 总是同时出现。
+    pub(crate) fn from_args(args: &MetricsArgs) -> Option<Self> {
+        let (Some(metrics), Some(out)) = (&args.metrics, &args.metrics_out) else {
+            return None;
+        };
+        Some(Self {
+            recorder: MetricsRecorder::new(metrics.0.clone(), args.metrics_interval).shared(),
+            out: out.clone(),
+        })
+    }
+
+    /// 挂载到地貌上的记录器
+    pub(crate) fn recorder(&self) -> SharedMetricsRecorder {
+        self.recorder.clone()
+    }
+
+    /// 按文件扩展名导出已记录的指标
+    pub(crate) fn export(&self) -> Result<(), CliError> {
+        let recorder = self.recorder.read().expect("未能获取读锁，导出指标失败");
+        match self
+            .out
+            .extension()
+            .and_then(|extension| extension.to_str())
+        {
+            Some("parquet") => recorder.export_parquet(&self.out)?,
+            _ => recorder.export_csv(&self.out)?,
+        }
+        println!(
+            "已导出 {} 次采样的指标到 {}",
+            recorder.len(),
+            self.out.display()
+        );
+        Ok(())
+    }
+}
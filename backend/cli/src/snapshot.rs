@@ -0,0 +1,91 @@
+use crate::error_handling::CliError;
+use game::game_context::GameContext;
+use game::{Landscape, SubstanceDistribution, SubstanceType};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+/// 世界快照：地貌及其依赖的全局参数
+///
+/// 重力常数保存在全局的 `GameContext` 中而不在地貌里，因此一并保存，读取时写回上下文。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct WorldSnapshot {
+    pub(crate) gravity_const: f64,
+    pub(crate) landscape: Landscape,
+}
+
+impl WorldSnapshot {
+    pub(crate) fn new(gravity_const: f64, landscape: Landscape) -> Self {
+        Self {
+            gravity_const,
+            landscape,
+        }
+    }
+
+    /// 读取快照，并将地图尺寸、重力常数与文明 ID 写入全局的 `GameContext`
+    pub(crate) fn load(path: impl AsRef<Path>) -> Result<Self, CliError> {
+        let path = path.as_ref();
+        let bytes = fs::read(path).map_err(|source| CliError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        let snapshot: Self =
+            serde_json::from_slice(&bytes).map_err(|source| CliError::Snapshot {
+                path: path.to_path_buf(),
+                source,
+            })?;
+
+        GameContext::update_game_map_size(*snapshot.landscape.map_size());
+        GameContext::update_game_gravity_const(snapshot.gravity_const);
+        if let Some(civilization_id) = snapshot.landscape.civilization_id() {
+            GameContext::update_game_civilization_id(civilization_id);
+        }
+        Ok(snapshot)
+    }
+
+    pub(crate) fn save(&self, path: impl AsRef<Path>) -> Result<(), CliError> {
+        let path = path.as_ref();
+        let json = serde_json::to_vec(self).map_err(|source| CliError::Snapshot {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            fs::create_dir_all(dir).map_err(|source| CliError::Io {
+                path: dir.to_path_buf(),
+                source,
+            })?;
+        }
+        fs::write(path, json).map_err(|source| CliError::Io {
+            path: path.to_path_buf(),
+            source,
+        })
+    }
+
+    /// 按物质类型排序的全部物质分布，使输出的顺序稳定
+    pub(crate) fn distributions(&self) -> Vec<&SubstanceDistribution> {
+        let mut distributions: Vec<_> = self.landscape.subtance_distributions().iter().collect();
+        distributions.sort_by_key(|distribution| *distribution.substance_type());
+        distributions
+    }
+
+    pub(crate) fn distribution(
+        &self,
+        substance_type: SubstanceType,
+    ) -> Result<&SubstanceDistribution, CliError> {
+        self.landscape
+            .subtance_distributions()
+            .iter()
+            .find(|distribution| *distribution.substance_type() == substance_type)
+            .ok_or_else(|| {
+                CliError::Mismatch(format!(
+                    "快照中没有物质 {} 的分布",
+                    substance_label(&substance_type)
+                ))
+            })
+    }
+}
+
+/// 物质类型的简写，例如 `1/2`
+pub(crate) fn substance_label(substance_type: &SubstanceType) -> String {
+    format!("{}/{}", substance_type.numer(), substance_type.denom())
+}
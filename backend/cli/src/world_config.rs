@@ -0,0 +1,77 @@
+use crate::error_handling::CliError;
+use game::{
+    CouplingParams, DiffusionMode, MapSize, NoiseParams, StabilityParams, SubstanceType,
+    TerrainParams, WorldParams,
+};
+use serde::Deserialize;
+use std::fs;
+use std::path::Path;
+use uuid::Uuid;
+
+/// 创建世界的配置文件
+///
+/// 除 `map_size` 外都可以省略：文明 ID 随机生成，重力常数为 10，地图平坦，物质独立扩散。
+/// 示例见 `cli/world.example.toml`。
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct WorldConfig {
+    #[serde(default)]
+    civilization_id: Option<Uuid>,
+    #[serde(default)]
+    gravity_const: Option<f64>,
+    map_size: MapSize,
+    #[serde(default)]
+    diffusion_mode: DiffusionMode,
+    #[serde(default)]
+    stability: Option<StabilityParams>,
+    #[serde(default)]
+    coupling: Option<CouplingParams>,
+    #[serde(default)]
+    terrain: Option<TerrainParams>,
+    /// 初始的物质分布，按顺序加入地貌
+    #[serde(default)]
+    substances: Vec<SubstanceConfig>,
+}
+
+/// 一种物质的初始分布
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct SubstanceConfig {
+    pub(crate) substance_type: SubstanceType,
+    /// 噪声参数，省略时随机生成；随机得到的种子会记录在快照中
+    #[serde(default)]
+    pub(crate) noise_params: Option<NoiseParams>,
+}
+
+impl WorldConfig {
+    pub(crate) fn load(path: impl AsRef<Path>) -> Result<Self, CliError> {
+        let path = path.as_ref();
+        let text = fs::read_to_string(path).map_err(|source| CliError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        toml::from_str(&text).map_err(|e| CliError::Config {
+            path: path.to_path_buf(),
+            message: e.to_string(),
+        })
+    }
+
+    pub(crate) fn world_params(&self) -> WorldParams {
+        let mut params = WorldParams::new(self.civilization_id, self.map_size, self.gravity_const)
+            .with_diffusion_mode(self.diffusion_mode);
+        if let Some(stability) = &self.stability {
+            params = params.with_stability(stability.clone());
+        }
+        if let Some(coupling) = &self.coupling {
+            params = params.with_coupling(coupling.clone());
+        }
+        if let Some(terrain) = &self.terrain {
+            params = params.with_terrain(terrain.clone());
+        }
+        params
+    }
+
+    pub(crate) fn substances(&self) -> &[SubstanceConfig] {
+        &self.substances
+    }
+}
@@ -0,0 +1,426 @@
+//! 以子进程运行 `re-hive-cli` 的测试：在临时目录中创建、推进、统计、导出与重放世界，
+//! 比较快照以及参数有误时的退出码
+
+use game::{EventLog, MapSize, NoiseParams, RecordedWorld, WorldParams};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const WORLD: &str = r#"
+civilization_id = "00000000-0000-0000-0000-000000000001"
+gravity_const = 9.8
+
+[map_size]
+width = 8
+height = 8
+
+[[substances]]
+substance_type = "1/2"
+
+[substances.noise_params]
+seed = 42
+scale = 3.0
+"#;
+
+/// 测试用的临时目录，结束（包括失败）时删除
+struct TempDir(PathBuf);
+
+impl TempDir {
+    fn new(name: &str) -> Self {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let path = std::env::temp_dir().join(format!(
+            "re-hive-cli-{}-{}-{}",
+            name,
+            std::process::id(),
+            nanos
+        ));
+        fs::create_dir_all(&path).unwrap();
+        Self(path)
+    }
+
+    fn path(&self, name: &str) -> String {
+        self.0.join(name).to_string_lossy().into_owned()
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+fn cli(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_re-hive-cli"))
+        .args(args)
+        .env_remove("PROPERTY_CATALOGUE_PATH")
+        .output()
+        .unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+/// 在 `dir` 中按 `world` 配置创建名为 `name` 的快照
+fn create(dir: &TempDir, name: &str, world: &str) -> String {
+    let config = dir.path(&format!("{}.toml", name));
+    fs::write(&config, world).unwrap();
+    let snapshot = dir.path(name);
+    let output = cli(&["create", &config, "-o", &snapshot]);
+    assert!(output.status.success(), "{:?}", output);
+    snapshot
+}
+
+/// 运行并断言成功，返回标准输出
+fn succeed(args: &[&str]) -> String {
+    let output = cli(args);
+    assert_eq!(output.status.code(), Some(0), "{:?}: {:?}", args, output);
+    stdout(&output)
+}
+
+/// PNG 文件头中的宽度与高度
+fn png_size(path: &str) -> (u32, u32) {
+    let bytes = fs::read(path).unwrap();
+    assert!(bytes.starts_with(b"\x89PNG\r\n\x1a\n"), "{} 不是 PNG", path);
+    assert_eq!(&bytes[12..16], b"IHDR");
+    let read = |offset: usize| u32::from_be_bytes(bytes[offset..offset + 4].try_into().unwrap());
+    (read(16), read(20))
+}
+
+/// 按逗号拆分 CSV 的每一行
+fn csv_rows(path: &str) -> Vec<Vec<String>> {
+    fs::read_to_string(path)
+        .unwrap()
+        .lines()
+        .map(|line| line.split(',').map(str::to_string).collect())
+        .collect()
+}
+
+/// 用与 `WORLD` 相同的参数记录一个推进了 `ticks` 步的世界，把事件日志写入 `path`
+fn record(path: &Path, ticks: u64) {
+    let params = WorldParams::new(
+        Some("00000000-0000-0000-0000-000000000001".parse().unwrap()),
+        MapSize::new(Some(8), Some(8)),
+        Some(9.8),
+    );
+    let mut world = RecordedWorld::create(params, EventLog::new(Vec::new()), Some(2)).unwrap();
+    world
+        .add_substance(
+            "1/2".parse().unwrap(),
+            NoiseParams::new(Some(42), Some(3.0)),
+        )
+        .unwrap();
+    for _ in 0..ticks {
+        world.step().unwrap();
+    }
+    fs::write(path, world.finish().unwrap()).unwrap();
+}
+
+#[test]
+fn run_advances_the_world_and_writes_snapshots_and_metrics() {
+    let dir = TempDir::new("run");
+    let world = create(&dir, "world.json", WORLD);
+    let out = dir.path("out.json");
+    let snapshots = dir.path("snapshots");
+    let metrics = dir.path("metrics.csv");
+
+    succeed(&[
+        "run",
+        &world,
+        "--ticks",
+        "4",
+        "-o",
+        &out,
+        "--snapshot-every",
+        "2",
+        "--snapshot-dir",
+        &snapshots,
+        "--metrics",
+        "mean:1/2,substance_count",
+        "--metrics-out",
+        &metrics,
+    ]);
+
+    // 输入的快照保持不变，中间快照按步数命名
+    let stats = |snapshot: &str| -> serde_json::Value {
+        serde_json::from_str(&succeed(&["stats", snapshot, "--json"])).unwrap()
+    };
+    assert_eq!(stats(&world)["tick"], 0);
+    assert_eq!(stats(&out)["tick"], 4);
+    let mut names: Vec<_> = fs::read_dir(&snapshots)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+        .collect();
+    names.sort();
+    assert_eq!(names, ["tick-00000002.json", "tick-00000004.json"]);
+    let last = Path::new(&snapshots).join("tick-00000004.json");
+    succeed(&["diff", &out, last.to_str().unwrap()]);
+
+    // 每步采样一次：表头为 tick 与各指标，之后每行一个步数
+    let rows = csv_rows(&metrics);
+    assert_eq!(rows[0], ["tick", "mean:1/2", "substance_count"]);
+    assert_eq!(rows.len(), 5);
+    for (tick, row) in (1..).zip(&rows[1..]) {
+        assert_eq!(row[0], tick.to_string());
+        assert!(row[1].parse::<f64>().unwrap() > 0.0);
+        assert_eq!(row[2], "1");
+    }
+
+    // 不指定 -o 时覆盖输入的快照；Parquet 文件首尾都是魔数
+    let parquet = dir.path("metrics.parquet");
+    succeed(&[
+        "run",
+        &world,
+        "--ticks",
+        "2",
+        "--metrics",
+        "gini:potential",
+        "--metrics-interval",
+        "2",
+        "--metrics-out",
+        &parquet,
+    ]);
+    assert_eq!(stats(&world)["tick"], 2);
+    let bytes = fs::read(&parquet).unwrap();
+    assert!(bytes.starts_with(b"PAR1") && bytes.ends_with(b"PAR1"));
+}
+
+#[test]
+fn stats_reports_every_layer() {
+    let dir = TempDir::new("stats");
+    let world = create(&dir, "world.json", WORLD);
+
+    let table = succeed(&["stats", &world]);
+    assert!(table.contains("第 0 步，8×8，重力常数 9.8"));
+    assert!(table.lines().any(|line| line.starts_with("1/2 ")));
+    assert!(table.lines().any(|line| line.starts_with("potential ")));
+
+    let json: serde_json::Value =
+        serde_json::from_str(&succeed(&["stats", &world, "--json"])).unwrap();
+    assert_eq!(json["gravity_const"], 9.8);
+    let layers = json["layers"].as_array().unwrap();
+    let names: Vec<_> = layers.iter().map(|layer| &layer["layer"]).collect();
+    assert_eq!(names, ["1/2", "potential"]);
+    for layer in layers {
+        assert_eq!(layer["count"], 64);
+        let (min, mean, max) = (
+            layer["min"].as_f64().unwrap(),
+            layer["mean"].as_f64().unwrap(),
+            layer["max"].as_f64().unwrap(),
+        );
+        assert!(min <= mean && mean <= max);
+    }
+
+    // 只统计一种物质
+    let json: serde_json::Value =
+        serde_json::from_str(&succeed(&["stats", &world, "--substance", "1/2", "--json"])).unwrap();
+    assert_eq!(json["layers"][0]["layer"], "1/2");
+}
+
+#[test]
+fn export_and_heatmap_write_well_formed_files() {
+    let dir = TempDir::new("export");
+    let world = create(&dir, "world.json", WORLD);
+    let stats: serde_json::Value =
+        serde_json::from_str(&succeed(&["stats", &world, "--json"])).unwrap();
+
+    // CSV 为 8 行 8 列的摩尔数矩阵，总和与统计一致
+    let csv = dir.path("moles.csv");
+    succeed(&["export", &world, "--substance", "1/2", "-o", &csv]);
+    let rows = csv_rows(&csv);
+    assert_eq!(rows.len(), 8);
+    let mut total = 0.0;
+    for row in &rows {
+        assert_eq!(row.len(), 8);
+        total += row
+            .iter()
+            .map(|value| value.parse::<f64>().unwrap())
+            .sum::<f64>();
+    }
+    assert_eq!(total, stats["layers"][0]["total"].as_f64().unwrap());
+
+    // 灰度 PNG 每个单元格一个像素
+    let png = dir.path("moles.png");
+    succeed(&["export", &world, "--substance", "1/2", "-o", &png]);
+    assert_eq!(png_size(&png), (8, 8));
+
+    for (target, name) in [("potential", "potential.png"), ("1/2", "moles-heatmap.png")] {
+        let heatmap = dir.path(name);
+        succeed(&[
+            "heatmap", &world, "--target", target, "-o", &heatmap, "--arrows", "2",
+        ]);
+        let (width, height) = png_size(&heatmap);
+        assert!(width > 0 && height > 0);
+    }
+
+    // 快照中没有的物质
+    let output = cli(&["export", &world, "--substance", "3/4", "-o", &csv]);
+    assert_eq!(output.status.code(), Some(1), "{:?}", output);
+}
+
+#[test]
+fn replay_reproduces_the_state_of_a_run() {
+    let dir = TempDir::new("replay");
+    let log = dir.path("events.jsonl");
+    record(Path::new(&log), 4);
+
+    let world = create(&dir, "world.json", WORLD);
+    let run = dir.path("run.json");
+    succeed(&["run", &world, "--ticks", "4", "-o", &run]);
+
+    let replayed = dir.path("replayed.json");
+    let report = succeed(&["replay", &log, "-o", &replayed]);
+    assert!(report.contains("重放到第 4 步"), "{}", report);
+    succeed(&["diff", &run, &replayed]);
+
+    // 只重放到中途
+    let halfway = dir.path("halfway.json");
+    let partial = dir.path("partial.json");
+    succeed(&["run", &world, "--ticks", "2", "-o", &halfway]);
+    succeed(&["replay", &log, "--until", "2", "-o", &partial]);
+    succeed(&["diff", &halfway, &partial]);
+
+    // 篡改最后一个状态哈希后，重放报告不一致
+    let mut lines: Vec<serde_json::Value> = fs::read_to_string(&log)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    let hash = lines
+        .iter_mut()
+        .rev()
+        .filter(|entry| entry["event"]["event"] == "state_hash")
+        .find_map(|entry| entry.pointer_mut("/event/hash"))
+        .unwrap();
+    *hash = serde_json::json!(hash.as_u64().unwrap() ^ 1);
+    let tampered = dir.path("tampered.jsonl");
+    let contents: Vec<String> = lines.iter().map(|entry| entry.to_string()).collect();
+    fs::write(&tampered, contents.join("\n") + "\n").unwrap();
+    let output = cli(&["replay", &tampered]);
+    assert_eq!(output.status.code(), Some(1), "{:?}", output);
+    assert!(stdout(&output).contains("状态哈希不一致"));
+}
+
+#[test]
+fn property_catalogue_is_loaded_before_the_command() {
+    let dir = TempDir::new("catalogue");
+    let world = create(&dir, "world.json", WORLD);
+    let catalogue = dir.path("catalogue.toml");
+    fs::write(
+        &catalogue,
+        "[[properties]]\nname = \"Toxicity\"\nfrequency_constant = 5\nphase_constant = 1\n",
+    )
+    .unwrap();
+    succeed(&["stats", &world, "--property-catalogue", &catalogue]);
+
+    // 扩展名不受支持或文件不存在时，在执行子命令之前失败
+    fs::write(dir.path("catalogue.yaml"), "").unwrap();
+    for path in [dir.path("catalogue.yaml"), dir.path("missing.toml")] {
+        let output = cli(&["--property-catalogue", &path, "stats", &world]);
+        assert_eq!(output.status.code(), Some(1), "{:?}", output);
+        assert!(output.stdout.is_empty());
+    }
+}
+
+#[test]
+fn diff_of_identical_snapshots_succeeds() {
+    let dir = TempDir::new("identical");
+    let a = create(&dir, "a.json", WORLD);
+    let b = create(&dir, "b.json", WORLD);
+
+    let output = cli(&["diff", &a, &b]);
+    assert_eq!(output.status.code(), Some(0), "{:?}", output);
+    assert!(stdout(&output).contains("两个快照完全相同"));
+}
+
+#[test]
+fn diff_of_different_snapshots_fails_and_lists_cells() {
+    let dir = TempDir::new("different");
+    let a = create(&dir, "a.json", WORLD);
+    let b = create(&dir, "b.json", &WORLD.replace("seed = 42", "seed = 43"));
+
+    let output = cli(&["diff", &a, &b, "--substance", "1/2", "--limit", "2"]);
+    assert_eq!(output.status.code(), Some(1), "{:?}", output);
+    let stdout = stdout(&output);
+    assert!(stdout.contains("物质 1/2: "));
+    assert!(!stdout.contains("两个快照完全相同"));
+    // 逐格列出的差异不超过 --limit
+    let samples = stdout
+        .lines()
+        .filter(|line| line.contains("摩尔数 "))
+        .count();
+    assert!(samples <= 2, "{}", stdout);
+}
+
+#[test]
+fn diff_of_missing_snapshot_fails() {
+    let dir = TempDir::new("missing");
+    let a = create(&dir, "a.json", WORLD);
+
+    let output = cli(&["diff", &a, &dir.path("missing.json")]);
+    assert_eq!(output.status.code(), Some(1), "{:?}", output);
+}
+
+#[test]
+fn help_succeeds() {
+    for args in [&["--help"][..], &["diff", "--help"][..]] {
+        let output = cli(args);
+        assert_eq!(output.status.code(), Some(0), "{:?}", output);
+        assert!(stdout(&output).contains("Usage"));
+    }
+}
+
+#[test]
+fn invalid_arguments_exit_with_usage_error() {
+    let cases: [&[&str]; 11] = [
+        &[],
+        &["unknown"],
+        &["diff", "a.json"],
+        &["diff", "a.json", "b.json", "--limit", "many"],
+        &["diff", "a.json", "b.json", "--substance", "half"],
+        &["run", "a.json"],
+        &["run", "a.json", "--ticks", "1", "--snapshot-every", "2"],
+        &[
+            "run",
+            "a.json",
+            "--ticks",
+            "1",
+            "--snapshot-every",
+            "0",
+            "--snapshot-dir",
+            "out",
+        ],
+        &[
+            "run",
+            "a.json",
+            "--ticks",
+            "1",
+            "--metrics",
+            "substance_count",
+            "--metrics-out",
+            "metrics.json",
+        ],
+        &["export", "a.json", "--substance", "1/2", "-o", "out.bmp"],
+        &[
+            "heatmap",
+            "a.json",
+            "--target",
+            "potential",
+            "-o",
+            "out.png",
+            "--range",
+            "2,1",
+        ],
+    ];
+    for args in cases {
+        let output = cli(args);
+        assert_eq!(output.status.code(), Some(2), "{:?}: {:?}", args, output);
+        // clap 打印错误（或用法），并提示查看 --help
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains("--help"), "{:?}: {}", args, stderr);
+    }
+}
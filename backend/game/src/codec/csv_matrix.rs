@@ -0,0 +1,53 @@
+use crate::codec::error_handling::CodecError;
+use csv::{ReaderBuilder, Trim, Writer};
+use ndarray::Array2;
+
+/// 解析 CSV 数值矩阵
+///
+/// 每行一组以逗号分隔的数值，空行与以 `#` 开头的注释行会被跳过，所有行的列数必须一致。
+///
+/// ### 参数
+/// - `text`: CSV 文本。
+///
+/// ### 返回值
+/// 返回形状为 `(行数, 列数)` 的矩阵。
+pub(crate) fn parse(text: &str) -> Result<Array2<f64>, CodecError> {
+    let mut reader = ReaderBuilder::new()
+        .has_headers(false)
+        .comment(Some(b'#'))
+        .trim(Trim::All)
+        .from_reader(text.as_bytes());
+
+    let mut values = Vec::new();
+    let mut width: Option<usize> = None;
+    let mut height = 0;
+
+    for record in reader.records() {
+        let record = record?;
+        let line = record
+            .position()
+            .map_or(0, |position| position.line() as usize);
+        for cell in &record {
+            values.push(cell.parse::<f64>().map_err(|_| CodecError::Csv {
+                line,
+                message: format!("无法解析数值 {:?}", cell),
+            })?);
+        }
+        width = Some(record.len());
+        height += 1;
+    }
+
+    let width = width.ok_or(CodecError::EmptyMatrix)?;
+    Array2::from_shape_vec((height, width), values).map_err(|_| CodecError::EmptyMatrix)
+}
+
+/// 将矩阵写为 CSV 文本，每行一组以逗号分隔的数值
+pub(crate) fn format(values: &Array2<f64>) -> Result<Vec<u8>, CodecError> {
+    let mut writer = Writer::from_writer(Vec::new());
+    for row in values.rows() {
+        writer.write_record(row.iter().map(|value| value.to_string()))?;
+    }
+    writer
+        .into_inner()
+        .map_err(|e| CodecError::Io(e.into_error()))
+}
@@ -0,0 +1,94 @@
+pub(crate) mod csv_matrix;
+pub(crate) mod png;
+pub(crate) mod resample;
+
+pub mod error_handling {
+    use std::fmt;
+
+    #[derive(Debug)]
+    pub enum CodecError {
+        /// 读写文件失败
+        Io(std::io::Error),
+        /// PNG 格式错误或不受支持
+        Png(String),
+        /// zlib/DEFLATE 数据损坏
+        Inflate(String),
+        /// CSV 第 `line` 行格式错误
+        Csv { line: usize, message: String },
+        /// 矩阵为空
+        EmptyMatrix,
+        /// 摩尔数换算倍数不是大于 0 的有限数
+        InvalidMoleScale(f64),
+    }
+
+    impl fmt::Display for CodecError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                CodecError::Io(e) => write!(f, "读写文件失败: {}", e),
+                CodecError::Png(message) => write!(f, "PNG 解析失败: {}", message),
+                CodecError::Inflate(message) => write!(f, "解压失败: {}", message),
+                CodecError::Csv { line, message } => {
+                    write!(f, "CSV 第 {} 行格式错误: {}", line, message)
+                }
+                CodecError::EmptyMatrix => write!(f, "矩阵为空"),
+                CodecError::InvalidMoleScale(mole_scale) => {
+                    write!(
+                        f,
+                        "摩尔数换算倍数必须为大于 0 的有限数，实际为 {}",
+                        mole_scale
+                    )
+                }
+            }
+        }
+    }
+
+    impl std::error::Error for CodecError {
+        fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+            match self {
+                CodecError::Io(e) => Some(e),
+                _ => None,
+            }
+        }
+    }
+
+    impl From<std::io::Error> for CodecError {
+        fn from(e: std::io::Error) -> Self {
+            CodecError::Io(e)
+        }
+    }
+
+    impl From<png::DecodingError> for CodecError {
+        fn from(e: png::DecodingError) -> Self {
+            match e {
+                png::DecodingError::IoError(e) => CodecError::Io(e),
+                e => CodecError::Png(e.to_string()),
+            }
+        }
+    }
+
+    impl From<png::EncodingError> for CodecError {
+        fn from(e: png::EncodingError) -> Self {
+            match e {
+                png::EncodingError::IoError(e) => CodecError::Io(e),
+                e => CodecError::Png(e.to_string()),
+            }
+        }
+    }
+
+    impl From<csv::Error> for CodecError {
+        fn from(e: csv::Error) -> Self {
+            let line = e.position().map_or(0, |position| position.line() as usize);
+            let message = e.to_string();
+            match e.into_kind() {
+                csv::ErrorKind::Io(e) => CodecError::Io(e),
+                csv::ErrorKind::UnequalLengths {
+                    expected_len, len, ..
+                } => CodecError::Csv {
+                    line,
+                    message: format!("列数为 {}，与首行的 {} 列不一致", len, expected_len),
+                },
+                _ => CodecError::Csv { line, message },
+            }
+        }
+    }
+}
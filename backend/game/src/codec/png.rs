@@ -0,0 +1,166 @@
+use crate::codec::error_handling::CodecError;
+use ndarray::Array2;
+use png::{BitDepth, Decoder, Encoder, Transformations};
+use std::io::Cursor;
+
+pub(crate) use png::ColorType;
+
+/// 灰度换算时 R、G、B 三个通道的权重（ITU-R BT.601）
+const LUMA_WEIGHTS: [f64; 3] = [0.299, 0.587, 0.114];
+
+/// 读取 PNG 并换算为灰度
+///
+/// 调色板与低位深图像先展开为 8 位采样，彩色图像按 BT.601 权重换算为亮度，透明度通道被忽略。
+///
+/// ### 参数
+/// - `bytes`: PNG 文件内容。
+///
+/// ### 返回值
+/// 返回形状为 `(height, width)`、取值范围为 [0, 1] 的灰度矩阵。
+pub(crate) fn decode_grayscale(bytes: &[u8]) -> Result<Array2<f64>, CodecError> {
+    let mut decoder = Decoder::new(Cursor::new(bytes));
+    decoder.set_transformations(Transformations::EXPAND);
+    let mut reader = decoder.read_info()?;
+
+    let mut buffer = vec![
+        0;
+        reader
+            .output_buffer_size()
+            .ok_or_else(|| CodecError::Png("图像过大".to_string()))?
+    ];
+    let frame = reader.next_frame(&mut buffer)?;
+    let buffer = &buffer[..frame.buffer_size()];
+
+    let samples: Vec<f64> = match frame.bit_depth {
+        BitDepth::Sixteen => buffer
+            .chunks_exact(2)
+            .map(|pair| u16::from_be_bytes([pair[0], pair[1]]) as f64 / u16::MAX as f64)
+            .collect(),
+        _ => buffer
+            .iter()
+            .map(|&sample| sample as f64 / u8::MAX as f64)
+            .collect(),
+    };
+
+    let channels = frame.color_type.samples();
+    let luminance: Vec<f64> = samples
+        .chunks_exact(channels)
+        .map(|pixel| match channels {
+            1 | 2 => pixel[0],
+            _ => pixel
+                .iter()
+                .zip(LUMA_WEIGHTS.iter())
+                .map(|(sample, weight)| sample * weight)
+                .sum(),
+        })
+        .collect();
+
+    Array2::from_shape_vec((frame.height as usize, frame.width as usize), luminance)
+        .map_err(|e| CodecError::Png(e.to_string()))
+}
+
+/// 将灰度矩阵编码为 16 位灰度 PNG
+///
+/// ### 参数
+/// - `values`: 形状为 `(height, width)` 的矩阵，取值会被截断到 [0, 1]。
+///
+/// ### 返回值
+/// 返回 PNG 文件内容。
+pub(crate) fn encode_grayscale16(values: &Array2<f64>) -> Result<Vec<u8>, CodecError> {
+    let (height, width) = values.dim();
+    let samples: Vec<u8> = values
+        .iter()
+        .flat_map(|value| {
+            let sample = (value.clamp(0.0, 1.0) * u16::MAX as f64).round() as u16;
+            sample.to_be_bytes()
+        })
+        .collect();
+
+    encode(width, height, ColorType::Grayscale, 16, &samples)
+}
+
+/// 将采样编码为 PNG
+///
+/// ### 参数
+/// - `width` / `height`: 图像尺寸。
+/// - `color_type`: 颜色类型，不支持调色板。
+/// - `bit_depth`: 位深，8 或 16；16 位采样按大端序存放。
+/// - `samples`: 按行优先存放的采样字节。
+///
+/// ### 返回值
+/// 返回 PNG 文件内容。
+pub(crate) fn encode(
+    width: usize,
+    height: usize,
+    color_type: ColorType,
+    bit_depth: u8,
+    samples: &[u8],
+) -> Result<Vec<u8>, CodecError> {
+    let mut png = Vec::new();
+    let mut writer = encoder(&mut png, width, height, color_type, bit_depth)?.write_header()?;
+    writer.write_image_data(samples)?;
+    writer.finish()?;
+    Ok(png)
+}
+
+/// 将多帧 8 位采样编码为循环播放的 APNG
+///
+/// 每一帧都覆盖整幅画面，不依赖上一帧的内容；不支持 APNG 的读取器会显示第一帧。
+///
+/// ### 参数
+/// - `width` / `height`: 图像尺寸，所有帧相同。
+/// - `color_type`: 颜色类型，不支持调色板。
+/// - `frames`: 每一帧按行优先存放的采样字节。
+/// - `frame_delay_ms`: 每帧的显示时长，单位为毫秒。
+///
+/// ### 返回值
+/// 返回 APNG 文件内容。
+pub(crate) fn encode_animation(
+    width: usize,
+    height: usize,
+    color_type: ColorType,
+    frames: &[Vec<u8>],
+    frame_delay_ms: u16,
+) -> Result<Vec<u8>, CodecError> {
+    if frames.is_empty() {
+        return Err(CodecError::Png("动画至少需要一帧".to_string()));
+    }
+
+    let mut png = Vec::new();
+    let mut encoder = encoder(&mut png, width, height, color_type, 8)?;
+    // 播放次数为 0 表示无限循环
+    encoder.set_animated(frames.len() as u32, 0)?;
+    encoder.set_frame_delay(frame_delay_ms, 1000)?;
+
+    let mut writer = encoder.write_header()?;
+    for samples in frames {
+        writer.write_image_data(samples)?;
+    }
+    writer.finish()?;
+    Ok(png)
+}
+
+/// 按图像尺寸、颜色类型与位深创建编码器
+fn encoder(
+    png: &mut Vec<u8>,
+    width: usize,
+    height: usize,
+    color_type: ColorType,
+    bit_depth: u8,
+) -> Result<Encoder<'static, &mut Vec<u8>>, CodecError> {
+    if width == 0 || height == 0 {
+        return Err(CodecError::EmptyMatrix);
+    }
+    let bit_depth = match (color_type, bit_depth) {
+        (ColorType::Indexed, _) => None,
+        (_, 8) => Some(BitDepth::Eight),
+        (_, 16) => Some(BitDepth::Sixteen),
+        _ => None,
+    }
+    .ok_or_else(|| CodecError::Png("仅支持写出位深为 8 或 16 的非调色板图像".to_string()))?;
+
+    let mut encoder = Encoder::new(png, width as u32, height as u32);
+    encoder.set_color(color_type);
+    encoder.set_depth(bit_depth);
+    Ok(encoder)
+}
@@ -0,0 +1,43 @@
+use ndarray::{Array2, Zip};
+
+/// 双线性重采样
+///
+/// 源矩阵与目标矩阵的像素中心对齐，超出边界的采样点取最近的边缘值；
+/// 尺寸相同时结果与源矩阵完全一致。
+///
+/// ### 参数
+/// - `source`: 非空的源矩阵。
+/// - `dim`: `(height, width)`，目标形状。
+///
+/// ### 返回值
+/// 返回重采样后的矩阵。
+pub(crate) fn bilinear(source: &Array2<f64>, dim: (usize, usize)) -> Array2<f64> {
+    if source.dim() == dim {
+        return source.clone();
+    }
+
+    let (source_height, source_width) = source.dim();
+    let (height, width) = dim;
+
+    // 目标像素中心在源矩阵中的位置，以及相邻两个源像素的下标与权重
+    let axis_samples = |target: usize, source_len: usize, target_len: usize| {
+        let position = ((target as f64 + 0.5) * source_len as f64 / target_len as f64 - 0.5)
+            .clamp(0.0, (source_len - 1) as f64);
+        let lower = position.floor() as usize;
+        let upper = (lower + 1).min(source_len - 1);
+        (lower, upper, position - lower as f64)
+    };
+
+    let mut resampled = Array2::<f64>::zeros(dim);
+    Zip::indexed(&mut resampled).par_for_each(|(row_index, col_index), value| {
+        let (top, bottom, row_weight) = axis_samples(row_index, source_height, height);
+        let (left, right, col_weight) = axis_samples(col_index, source_width, width);
+
+        let upper = source[[top, left]] * (1.0 - col_weight) + source[[top, right]] * col_weight;
+        let lower =
+            source[[bottom, left]] * (1.0 - col_weight) + source[[bottom, right]] * col_weight;
+        *value = upper * (1.0 - row_weight) + lower * row_weight;
+    });
+
+    resampled
+}
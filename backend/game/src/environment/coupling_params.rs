@@ -0,0 +1,175 @@
+use crate::shared::subtance_type::SubstanceType;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+const DEFAULT_MIXING_LIMIT: f64 = 0.5;
+const DEFAULT_DISPLACEMENT_RATE: f64 = 0.25;
+const DEFAULT_SYNTHESIS_RATE: f64 = 0.05;
+const DEFAULT_MAX_REACTANTS: usize = 2;
+const DEFAULT_MAX_DENOMINATOR: usize = 64;
+const DEFAULT_MAX_SUBSTANCES: usize = 32;
+
+/// 反应规则：在同一单元格内，将一种物质的摩尔数按速率转化为另一种物质
+///
+/// 实际转化量还会乘以两者的相似度 `1 - η`，性质越接近的物质越容易相互转化。
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ReactionRule {
+    /// 反应物
+    reactant: SubstanceType,
+    /// 生成物
+    product: SubstanceType,
+    /// 每步转化的摩尔数比例，取值范围为 [0, 1]
+    rate: f64,
+}
+
+impl ReactionRule {
+    pub fn new(reactant: SubstanceType, product: SubstanceType, rate: f64) -> Self {
+        Self {
+            reactant,
+            product,
+            rate: rate.clamp(0.0, 1.0),
+        }
+    }
+
+    pub fn reactant(&self) -> &SubstanceType {
+        &self.reactant
+    }
+
+    pub fn product(&self) -> &SubstanceType {
+        &self.product
+    }
+
+    pub fn rate(&self) -> f64 {
+        self.rate
+    }
+}
+
+/// 物质合成参数
+///
+/// - `base_rate`: 基础合成速率，实际速率还会乘以相似度 `1 - η` 与两种反应物的流动性。
+/// - `max_reactants`: 模拟精度 Q，每个单元格只有摩尔数最多的前 Q 种物质参与合成，至少为 2。
+/// - `max_denominator`: 生成物物质类型允许的最大分母，用于限制精密度的增长。
+/// - `max_substances`: 地貌中物质种类的上限，达到上限后不再产生新的物质分布。
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SynthesisParams {
+    base_rate: f64,
+    max_reactants: usize,
+    max_denominator: usize,
+    max_substances: usize,
+}
+
+impl SynthesisParams {
+    /// 使用可选参数创建合成参数，未提供时使用默认值
+    pub fn new(
+        base_rate: Option<f64>,
+        max_reactants: Option<usize>,
+        max_denominator: Option<usize>,
+        max_substances: Option<usize>,
+    ) -> Self {
+        Self {
+            base_rate: base_rate.unwrap_or(DEFAULT_SYNTHESIS_RATE).clamp(0.0, 1.0),
+            max_reactants: max_reactants.unwrap_or(DEFAULT_MAX_REACTANTS).max(2),
+            max_denominator: max_denominator.unwrap_or(DEFAULT_MAX_DENOMINATOR).max(1),
+            max_substances: max_substances.unwrap_or(DEFAULT_MAX_SUBSTANCES),
+        }
+    }
+
+    pub fn base_rate(&self) -> f64 {
+        self.base_rate
+    }
+
+    pub fn max_reactants(&self) -> usize {
+        self.max_reactants
+    }
+
+    pub fn max_denominator(&self) -> usize {
+        self.max_denominator
+    }
+
+    pub fn max_substances(&self) -> usize {
+        self.max_substances
+    }
+}
+
+impl Default for SynthesisParams {
+    fn default() -> Self {
+        Self::new(None, None, None, None)
+    }
+}
+
+/// 耦合扩散参数
+///
+/// - `mixing_limit`: 混合上限，较轻物质在含有更致密、且不相似物质的单元格中可占据的最大份额。
+/// - `displacement_rate`: 置换速率，超出混合上限的摩尔数每步被挤出到邻居的比例。
+/// - `reaction_rules`: 反应规则集合，按添加顺序依次执行。
+/// - `synthesis`: 物质合成参数，为 `None` 时不产生新的物质类型。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CouplingParams {
+    mixing_limit: f64,
+    displacement_rate: f64,
+    reaction_rules: Vec<ReactionRule>,
+    synthesis: Option<SynthesisParams>,
+}
+
+impl CouplingParams {
+    /// 使用可选的混合上限和置换速率创建耦合参数，未提供时使用默认值
+    pub fn new(mixing_limit: Option<f64>, displacement_rate: Option<f64>) -> Self {
+        Self {
+            mixing_limit: mixing_limit.unwrap_or(DEFAULT_MIXING_LIMIT).clamp(0.0, 1.0),
+            displacement_rate: displacement_rate
+                .unwrap_or(DEFAULT_DISPLACEMENT_RATE)
+                .clamp(0.0, 1.0),
+            reaction_rules: Vec::new(),
+            synthesis: None,
+        }
+    }
+
+    /// 追加一条反应规则（可链式调用）
+    pub fn with_reaction_rule(mut self, rule: ReactionRule) -> Self {
+        self.reaction_rules.push(rule);
+        self
+    }
+
+    /// 启用物质合成（可链式调用）
+    pub fn with_synthesis(mut self, synthesis: SynthesisParams) -> Self {
+        self.synthesis = Some(synthesis);
+        self
+    }
+
+    pub fn mixing_limit(&self) -> f64 {
+        self.mixing_limit
+    }
+
+    pub fn displacement_rate(&self) -> f64 {
+        self.displacement_rate
+    }
+
+    pub fn reaction_rules(&self) -> &[ReactionRule] {
+        &self.reaction_rules
+    }
+
+    pub fn synthesis(&self) -> Option<&SynthesisParams> {
+        self.synthesis.as_ref()
+    }
+}
+
+impl Default for CouplingParams {
+    fn default() -> Self {
+        Self::new(None, None)
+    }
+}
+
+impl fmt::Display for CouplingParams {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match serde_json::to_string_pretty(&self) {
+            Ok(json_str) => write!(f, "{}", json_str),
+            Err(_) => write!(
+                f,
+                "CouplingParams(mixing_limit: {}, displacement_rate: {})",
+                self.mixing_limit, self.displacement_rate
+            ),
+        }
+    }
+}
@@ -0,0 +1,49 @@
+use crate::environment::hexagon::{hex_unit::HexUnit, unit_change::UnitChange};
+use ndarray::Array2;
+
+/// 扩散计算所用的预分配缓冲区
+///
+/// - `flux`: 每个单元格沿 6 条边流出的通量，按 `NeighbourRelation::ALL` 顺序存放。
+/// - `back`: 双缓冲中的后台分布，汇总完成后与前台分布交换。
+///
+/// 两者只在地图尺寸变化时重新分配，之后每一步扩散都复用同一块内存。
+#[derive(Debug, Clone, Default)]
+pub(crate) struct DiffusionBuffer {
+    flux: Array2<[UnitChange; 6]>,
+    back: Array2<HexUnit>,
+}
+
+impl DiffusionBuffer {
+    /// 确保缓冲区形状与分布一致，仅在形状变化时重新分配
+    ///
+    /// ### 参数
+    /// - `dim`: `(height, width)`，物质分布的形状。
+    pub(crate) fn ensure_shape(&mut self, dim: (usize, usize)) {
+        if self.flux.dim() != dim {
+            self.flux = Array2::from_elem(dim, [UnitChange::default(); 6]);
+        }
+        if self.back.dim() != dim {
+            self.back = Array2::from_elem(dim, HexUnit::default());
+        }
+    }
+
+    /// 获取只读的通量缓冲区
+    pub(crate) fn flux(&self) -> &Array2<[UnitChange; 6]> {
+        &self.flux
+    }
+
+    /// 获取可写的通量缓冲区
+    pub(crate) fn flux_mut(&mut self) -> &mut Array2<[UnitChange; 6]> {
+        &mut self.flux
+    }
+
+    /// 获取可写的后台分布
+    pub(crate) fn back_mut(&mut self) -> &mut Array2<HexUnit> {
+        &mut self.back
+    }
+
+    /// 同时获取只读的通量缓冲区与可写的后台分布，供汇总阶段使用
+    pub(crate) fn split_mut(&mut self) -> (&Array2<[UnitChange; 6]>, &mut Array2<HexUnit>) {
+        (&self.flux, &mut self.back)
+    }
+}
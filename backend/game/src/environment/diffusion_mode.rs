@@ -0,0 +1,14 @@
+use serde::{Deserialize, Serialize};
+
+/// 扩散时驱动物质运动的势能力的计算方式
+///
+/// - `OppositePairs`: 比较三对相对邻居的势能差，按差值把反向位移累加为驱动力。
+///   在正六边形网格上它等于 `-Σ φ_i e_i`，即未归一化的中心差分。
+/// - `Gradient`: 以六邻居最小二乘梯度的反方向 `-∇φ` 为驱动力，
+///   与基向量的取法无关，量纲与势能场强的空间变化率一致。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum DiffusionMode {
+    #[default]
+    OppositePairs,
+    Gradient,
+}
@@ -0,0 +1,169 @@
+use crate::codec::{csv_matrix, error_handling::CodecError, png, resample};
+use crate::environment::{map_size::MapSize, subtance_distribution::SubstanceDistribution};
+use crate::shared::subtance_type::SubstanceType;
+use ndarray::{Array2, Zip};
+use std::fs;
+use std::path::Path;
+
+/// 从文件导入物质分布
+///
+/// 文件中的数值先按双线性插值重采样到 `map_size`，再乘以 `mole_scale` 并四舍五入为摩尔数，
+/// 负值按 0 处理。PNG 的数值为归一化灰度，纯白为 1；CSV 的数值按原样读取。
+impl SubstanceDistribution {
+    /// 从数值矩阵创建物质分布
+    ///
+    /// ### 参数
+    /// - `substance_type`: 物质类型。
+    /// - `values`: 任意尺寸的非空数值矩阵。
+    /// - `map_size`: 目标地图尺寸。
+    /// - `mole_scale`: 数值 1 对应的摩尔数。
+    ///
+    /// ### 返回值
+    /// 返回尺寸为 `map_size` 的物质分布。
+    pub fn from_matrix(
+        substance_type: SubstanceType,
+        values: &Array2<f64>,
+        map_size: MapSize,
+        mole_scale: f64,
+    ) -> Result<Self, CodecError> {
+        check_mole_scale(mole_scale)?;
+        if values.is_empty() {
+            return Err(CodecError::EmptyMatrix);
+        }
+
+        let resampled = resample::bilinear(values, map_size.as_tuple());
+        let mut substance_dist = Self::new(substance_type, map_size, None);
+        Zip::from(substance_dist.distribution_mut())
+            .and(&resampled)
+            .par_for_each(|unit, &value| {
+                unit.set_mole((value * mole_scale).round().max(0.0) as usize);
+            });
+
+        Ok(substance_dist)
+    }
+
+    /// 从灰度 PNG 导入物质分布，彩色图像会先换算为亮度
+    ///
+    /// ```no_run
+    /// use game::{MapSize, SubstanceDistribution, SubstanceType};
+    ///
+    /// let substance_dist = SubstanceDistribution::from_png(
+    ///     "1/2".parse::<SubstanceType>().unwrap(),
+    ///     "scenarios/iron.png",
+    ///     MapSize::new(Some(128), Some(128)),
+    ///     255.0,
+    /// )
+    /// .unwrap();
+    /// substance_dist.export_csv("scenarios/iron.csv", 1.0).unwrap();
+    /// ```
+    pub fn from_png(
+        substance_type: SubstanceType,
+        path: impl AsRef<Path>,
+        map_size: MapSize,
+        mole_scale: f64,
+    ) -> Result<Self, CodecError> {
+        let values = png::decode_grayscale(&fs::read(path)?)?;
+        Self::from_matrix(substance_type, &values, map_size, mole_scale)
+    }
+
+    /// 从 CSV 数值矩阵导入物质分布
+    ///
+    /// ```
+    /// use game::{MapSize, SubstanceDistribution, SubstanceType};
+    ///
+    /// let path = std::env::temp_dir().join("re-hive-from-csv-doctest.csv");
+    /// std::fs::write(&path, "# 2 x 3\n1, 2, 3\n\n4,5,6\n").unwrap();
+    /// let substance_dist = SubstanceDistribution::from_csv(
+    ///     "1/2".parse::<SubstanceType>().unwrap(),
+    ///     &path,
+    ///     MapSize::new(Some(3), Some(2)),
+    ///     10.0,
+    /// )
+    /// .unwrap();
+    /// let moles: Vec<usize> = substance_dist.distribution().iter().map(|unit| unit.mole()).collect();
+    /// assert_eq!(moles, [10, 20, 30, 40, 50, 60]);
+    ///
+    /// std::fs::write(&path, "1,2,3\n4,5\n").unwrap();
+    /// let ragged = SubstanceDistribution::from_csv(
+    ///     "1/2".parse::<SubstanceType>().unwrap(),
+    ///     &path,
+    ///     MapSize::new(Some(3), Some(2)),
+    ///     10.0,
+    /// );
+    /// assert!(ragged.is_err());
+    /// ```
+    pub fn from_csv(
+        substance_type: SubstanceType,
+        path: impl AsRef<Path>,
+        map_size: MapSize,
+        mole_scale: f64,
+    ) -> Result<Self, CodecError> {
+        let values = csv_matrix::parse(&fs::read_to_string(path)?)?;
+        Self::from_matrix(substance_type, &values, map_size, mole_scale)
+    }
+}
+
+/// 将物质分布导出到文件，换算方式与导入互逆
+impl SubstanceDistribution {
+    /// 将摩尔数除以 `mole_scale`，得到与导入时一致的数值矩阵
+    ///
+    /// `mole_scale` 必须为大于 0 的有限数。
+    ///
+    /// ```
+    /// use game::{CodecError, MapSize, SubstanceDistribution, SubstanceType};
+    ///
+    /// let substance_dist = SubstanceDistribution::new(
+    ///     "1/2".parse::<SubstanceType>().unwrap(),
+    ///     MapSize::new(Some(4), Some(4)),
+    ///     None,
+    /// );
+    /// assert!(substance_dist.to_matrix(2.0).is_ok());
+    /// assert!(matches!(
+    ///     substance_dist.to_matrix(f64::NAN),
+    ///     Err(CodecError::InvalidMoleScale(_))
+    /// ));
+    /// assert!(substance_dist.to_matrix(0.0).is_err());
+    /// ```
+    pub fn to_matrix(&self, mole_scale: f64) -> Result<Array2<f64>, CodecError> {
+        check_mole_scale(mole_scale)?;
+        Ok(self
+            .distribution()
+            .mapv(|unit| unit.mole() as f64 / mole_scale))
+    }
+
+    /// 导出为 16 位灰度 PNG，数值超出 [0, 1] 的部分会被截断
+    ///
+    /// ```
+    /// use game::{MapSize, NoiseGeneratable, NoiseParams, SubstanceDistribution, SubstanceType};
+    ///
+    /// let map_size = MapSize::new(Some(6), Some(5));
+    /// let substance_type: SubstanceType = "1/2".parse().unwrap();
+    /// let mut substance_dist =
+    ///     SubstanceDistribution::new(substance_type, map_size, Some(NoiseParams::new(Some(3), Some(1.0))));
+    /// substance_dist.generate_simplex_noise();
+    ///
+    /// let path = std::env::temp_dir().join("re-hive-export-png-doctest.png");
+    /// substance_dist.export_png(&path, 255.0).unwrap();
+    /// let restored = SubstanceDistribution::from_png(substance_type, &path, map_size, 255.0).unwrap();
+    /// assert_eq!(restored.distribution(), substance_dist.distribution());
+    /// ```
+    pub fn export_png(&self, path: impl AsRef<Path>, mole_scale: f64) -> Result<(), CodecError> {
+        fs::write(path, png::encode_grayscale16(&self.to_matrix(mole_scale)?)?)?;
+        Ok(())
+    }
+
+    /// 导出为 CSV 数值矩阵
+    pub fn export_csv(&self, path: impl AsRef<Path>, mole_scale: f64) -> Result<(), CodecError> {
+        fs::write(path, csv_matrix::format(&self.to_matrix(mole_scale)?)?)?;
+        Ok(())
+    }
+}
+
+/// 检查 `mole_scale` 是否为大于 0 的有限数
+fn check_mole_scale(mole_scale: f64) -> Result<(), CodecError> {
+    if mole_scale.is_finite() && mole_scale > 0.0 {
+        Ok(())
+    } else {
+        Err(CodecError::InvalidMoleScale(mole_scale))
+    }
+}
@@ -0,0 +1,100 @@
+use crate::environment::cartesian_vec_2d::CartesianVec2D;
+use crate::environment::hexagon::hex_coord::HexCoord;
+use crate::environment::hexagon::neighbour_relation::NeighbourRelation;
+use crate::environment::t_indexed::Indexed;
+use ndarray::{Array2, Zip};
+
+/// 六边形网格上的离散微分算子
+///
+/// 单元格 `(row, col)` 的中心位于笛卡尔坐标 `col × x_base + row × y_base`，
+/// 记 6 个邻居相对中心的位移为 `e_i`，`M = Σ e_i e_iᵀ`：
+/// - 梯度取 6 个邻居的最小二乘拟合：`∇φ ≈ Σ M⁻¹ e_i (φ_i - φ_0)`，对线性场精确。
+/// - 散度使用同一组权重：`∇·F ≈ Σ M⁻¹ e_i · F_i`，对线性向量场精确。
+/// - 拉普拉斯算子使用紧凑的 7 点模板：`Δφ ≈ 4 / tr(M) × Σ (φ_i - φ_0)`，
+///   在正六边形网格上对二次场精确。
+///
+/// 地图上下、左右环绕，与扩散时的邻居关系一致。
+pub(crate) struct HexCalculus {
+    /// 梯度与散度的权重 `M⁻¹ e_i`，按 `NeighbourRelation::ALL` 顺序排列
+    weights: [CartesianVec2D; 6],
+    /// 拉普拉斯算子的系数 `4 / tr(M)`
+    laplacian_scale: f64,
+}
+
+impl HexCalculus {
+    /// 按全局上下文中的六边形基向量构建算子
+    pub(crate) fn new() -> Self {
+        let edges = NeighbourRelation::ALL.map(|relation| relation.displacement().to_cartesian());
+
+        let (mut m_xx, mut m_xy, mut m_yy) = (0.0, 0.0, 0.0);
+        for edge in &edges {
+            m_xx += edge.x() * edge.x();
+            m_xy += edge.x() * edge.y();
+            m_yy += edge.y() * edge.y();
+        }
+        let det = m_xx * m_yy - m_xy * m_xy;
+
+        let weights = edges.map(|edge| {
+            CartesianVec2D::new(
+                (m_xx * edge.y() - m_xy * edge.x()) / det,
+                (m_yy * edge.x() - m_xy * edge.y()) / det,
+            )
+        });
+
+        Self {
+            weights,
+            laplacian_scale: 4.0 / (m_xx + m_yy),
+        }
+    }
+
+    /// 标量场的梯度
+    pub(crate) fn gradient(&self, field: &Array2<f64>) -> Array2<CartesianVec2D> {
+        let mut gradient = Array2::from_elem(field.dim(), CartesianVec2D::default());
+        Zip::indexed(&mut gradient).par_for_each(|(row_index, col_index), cell| {
+            let center = field[[row_index, col_index]];
+            *cell = Self::neighbours(field, row_index, col_index)
+                .iter()
+                .zip(&self.weights)
+                .fold(CartesianVec2D::default(), |acc, (&neighbour, weight)| {
+                    acc + weight.scale(neighbour - center)
+                });
+        });
+        gradient
+    }
+
+    /// 向量场的散度
+    pub(crate) fn divergence(&self, field: &Array2<CartesianVec2D>) -> Array2<f64> {
+        let mut divergence = Array2::zeros(field.dim());
+        Zip::indexed(&mut divergence).par_for_each(|(row_index, col_index), cell| {
+            *cell = Self::neighbours(field, row_index, col_index)
+                .iter()
+                .zip(&self.weights)
+                .map(|(neighbour, weight)| weight.dot(*neighbour))
+                .sum();
+        });
+        divergence
+    }
+
+    /// 标量场的拉普拉斯算子
+    pub(crate) fn laplacian(&self, field: &Array2<f64>) -> Array2<f64> {
+        let mut laplacian = Array2::zeros(field.dim());
+        Zip::indexed(&mut laplacian).par_for_each(|(row_index, col_index), cell| {
+            let center = field[[row_index, col_index]];
+            let sum: f64 = Self::neighbours(field, row_index, col_index)
+                .iter()
+                .map(|neighbour| neighbour - center)
+                .sum();
+            *cell = self.laplacian_scale * sum;
+        });
+        laplacian
+    }
+
+    /// 按 `NeighbourRelation::ALL` 顺序读取 6 个邻居的值
+    fn neighbours<T: Copy>(field: &Array2<T>, row_index: usize, col_index: usize) -> [T; 6] {
+        let current_coord = HexCoord::new(row_index, col_index);
+        NeighbourRelation::ALL.map(|relation| {
+            let neighbour_coord = current_coord.shift_within(relation.displacement(), field.dim());
+            field[[neighbour_coord.y(), neighbour_coord.x()]]
+        })
+    }
+}
@@ -1,10 +1,7 @@
 use crate::environment::hexagon::hex_displacemant::HexDisplacement;
-use crate::environment::hexagon::t_hexa_distanced::HexaDistanced;
-use crate::environment::hexagon::t_hexa_relational::HexaRelational;
 use crate::environment::t_indexed::Indexed;
 use crate::game_context::GameContext;
 use serde::Serialize;
-use std::collections::HashMap;
 use std::ops::{Add, Mul, Sub};
 
 #[derive(Clone, Copy, Eq, PartialEq, Hash, Debug, Serialize)]
@@ -21,34 +18,22 @@ impl HexCoord {
         Self { y, x }
     }
 
-    /// 计算几何关系对应的坐标映射
-    /// 该方法会根据传入的关系类型 `R` 返回一个包含各个方向对应坐标的映射表。
-    pub(crate) fn get_relations_map<R>(&self) -> HashMap<R, Self>
-    where
-        R: HexaRelational,
-    {
-        // 获取地图的宽高信息
-        let (height, width) = GameContext::get_map_size().as_tuple();
-
-        // 遍历方向与偏移量的映射，计算每个方向对应的新坐标
-        R::from_relation_to_coordinate_shift()
-            .iter()
-            .map(|(&relation, coordinate_shift)| {
-                // 计算新的 y 和 x 坐标，考虑宽高的环绕（模数）效果
-                let new_y =
-                    (self.y as isize + coordinate_shift.dy()).rem_euclid(height as isize) as usize;
-                let new_x =
-                    (self.x as isize + coordinate_shift.dx()).rem_euclid(width as isize) as usize;
-
-                // 返回方向与新的坐标
-                (relation, Self::new(new_y, new_x))
-            })
-            .collect()
-    }
-
-    /// 缩放坐标
-    pub(crate) fn scale(&self, scale: usize) -> Self {
-        Self::new(self.y * scale, self.x * scale)
+    /// 在给定地图尺寸下计算带环绕效果的偏移坐标
+    ///
+    /// 与 `Add<HexDisplacement>` 不同，该方法不读取全局上下文，适合在并行热路径中逐格调用。
+    ///
+    /// ### 参数
+    /// - `shift`: 坐标偏移量。
+    /// - `map_size`: `(height, width)`，地图的大小。
+    ///
+    /// ### 返回值
+    /// 返回环绕后的新坐标。
+    pub(crate) fn shift_within(&self, shift: HexDisplacement, map_size: (usize, usize)) -> Self {
+        let (height, width) = map_size;
+        Self {
+            y: (self.y as isize + shift.dy()).rem_euclid(height as isize) as usize,
+            x: (self.x as isize + shift.dx()).rem_euclid(width as isize) as usize,
+        }
     }
 
     /// 带环绕效果的加法运算
@@ -79,21 +64,6 @@ impl HexCoord {
     }
 }
 
-impl HexaDistanced for HexCoord {
-    /// 立方体坐标下的隐藏第三轴
-    fn z(&self) -> isize {
-        -(self.x as isize + self.y as isize)
-    }
-
-    /// 计算两坐标之间的六边形距离
-    fn distance_to(&self, other: &HexCoord) -> usize {
-        let dx = (self.x as isize - other.x as isize).abs();
-        let dy = (self.y as isize - other.y as isize).abs();
-        let dz = (self.z() - other.z()).abs();
-        ((dx + dy + dz) / 2) as usize
-    }
-}
-
 impl Add<Self> for HexCoord {
     type Output = Self;
 
@@ -4,9 +4,6 @@ use serde::Serialize;
 use std::iter::Sum;
 use std::ops::{Add, Mul, Sub};
 
-type Radians = f64;
-type Degrees = f64;
-
 /// 方向对应的坐标偏移量
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Default)]
 pub(crate) struct HexDisplacement {
@@ -39,28 +36,13 @@ impl HexDisplacement {
     ///
     /// ### 返回值
     /// 返回对应的笛卡尔坐标系中的向量。
-    pub(crate) fn to_cartesian(&self) -> CartesianVec2D {
+    pub(crate) fn to_cartesian(self) -> CartesianVec2D {
         // 获取基础向量并进行线性组合
         let x_component = GameContext::get_x_base_vector().scale(self.dx as f64);
         let y_component = GameContext::get_y_base_vector().scale(self.dy as f64);
 
         x_component + y_component
     }
-
-    /// 计算当前坐标偏移量的模长
-    ///
-    /// ### 适用于等边六边形地图
-    /// - 每个单元格表示一个六边形单元。
-    /// - 返回模长，即从起点到目标点所需的最小步数。
-    ///
-    /// ### 返回值
-    /// 返回值为 `usize` 类型，表示非负整数的步数。
-    pub(crate) fn magnitude(&self) -> usize {
-        let dx = self.dx.abs() as usize;
-        let dy = self.dy.abs() as usize;
-        let dz = (-(self.dx + self.dy)).abs() as usize; // 第三个方向，用于六边形坐标系统
-        *[dx, dy, dz].iter().max().unwrap()
-    }
 }
 
 // 实现加法运算
@@ -1,16 +1,13 @@
 use crate::environment::cartesian_vec_2d::CartesianVec2D;
-use crate::environment::diffuse_info::DiffuseInfo;
-use crate::environment::hexagon::hex_block::HexBlock;
 use crate::environment::hexagon::hex_displacemant::HexDisplacement;
 use crate::environment::hexagon::neighbour_relation::NeighbourRelation;
 use crate::environment::hexagon::t_hexa_relational::HexaRelational;
 use crate::environment::hexagon::unit_change::UnitChange;
-use serde::Serialize;
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
 use std::f64::consts::PI;
 
-#[derive(Clone, Debug, PartialEq, Eq, Hash, Copy, Serialize)]
-pub(crate) struct HexUnit {
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Copy, Serialize, Deserialize)]
+pub struct HexUnit {
     /// 本单元所含物质的摩尔数
     mole: usize,
     /// 本单元当下的运动状态（实际上是未消耗的完的势能，但是带有方向）
@@ -19,78 +16,95 @@ pub(crate) struct HexUnit {
 
 /// 关于字段的基本操作
 impl HexUnit {
-    pub(crate) fn new(mole: usize, movement: CartesianVec2D) -> Self {
+    pub fn new(mole: usize, movement: CartesianVec2D) -> Self {
         Self { mole, movement }
     }
 
-    pub(crate) fn mole(&self) -> usize {
+    pub fn mole(&self) -> usize {
         self.mole
     }
 
-    pub(crate) fn movement(&self) -> CartesianVec2D {
+    pub fn movement(&self) -> CartesianVec2D {
         self.movement
     }
 
-    pub(crate) fn set_mole(&mut self, mole: usize) {
+    pub fn set_mole(&mut self, mole: usize) {
         self.mole = mole;
     }
 
-    pub(crate) fn set_movement(&mut self, movement: CartesianVec2D) {
+    pub fn set_movement(&mut self, movement: CartesianVec2D) {
         self.movement = movement;
     }
 
-    pub(crate) fn adjust_mole(&mut self, mole: isize) {
-        self.mole = (self.mole as isize + mole as isize) as usize;
-    }
-
-    pub(crate) fn adjust_movement(&mut self, movement: CartesianVec2D) {
-        self.movement = self.movement + movement;
-    }
-
     pub(crate) fn fit_change(&mut self, unit_change: UnitChange) {
-        self.mole = (self.mole as isize + unit_change.mole_change() as isize) as usize;
+        self.mole = (self.mole as isize + unit_change.mole_change()) as usize;
         self.movement = self.movement + unit_change.movement_change();
     }
 }
 
 /// 关于扩散逻辑的集合
 impl HexUnit {
+    /// 计算本单元沿 6 条边流向各邻居的通量
+    ///
+    /// ### 参数
+    /// - `fluidity`: 本物质的流动性。
+    /// - `neighbour_potentials`: 按 `NeighbourRelation::ALL` 顺序排列的邻居势能场强。
+    ///
+    /// ### 返回值
+    /// 返回按 `NeighbourRelation::ALL` 顺序排列的流出通量，
+    /// 中心单元自身的变化量即为这些通量之和的相反数。
     pub(crate) fn diffuse(
         &self,
         fluidity: f64,
-        block_info: &HexBlock<DiffuseInfo>,
-    ) -> HexBlock<UnitChange> {
+        neighbour_potentials: &[f64; 6],
+    ) -> [UnitChange; 6] {
         // 1. 计算三对邻居势能差
-        let reduced_potential = self.calculate_reduced_potential(block_info);
+        let reduced_potential = self.calculate_reduced_potential(neighbour_potentials);
 
         // 2. 累加自身movement到笛卡尔坐标
         let total_cartesian_shift = self.calculate_total_cartesian_shift(&reduced_potential);
 
-        // 3. 为每个邻居计算UnitChange
-        let neighbour_changes =
-            self.calculate_neighbour_changes(fluidity, block_info, total_cartesian_shift);
-
-        // 4. 累加到 self_change 并做守恒性修正
-        let self_change = self.calculate_self_change(&neighbour_changes);
+        // 3. 为每条边计算流出的UnitChange
+        self.calculate_neighbour_changes(fluidity, neighbour_potentials, total_cartesian_shift)
+    }
 
-        // 5. 组合成 HexBlock
-        HexBlock::new(self_change, neighbour_changes)
+    /// 以给定的驱动力代替三对邻居的势能差，计算流向各邻居的通量
+    ///
+    /// 势垒取邻居与本单元格的势能场强之差（只计正值），流向更低处的边没有势垒。
+    ///
+    /// ### 参数
+    /// - `fluidity`: 本物质的流动性。
+    /// - `own_potential`: 本单元格的势能场强。
+    /// - `neighbour_potentials`: 按 `NeighbourRelation::ALL` 顺序排列的邻居势能场强。
+    /// - `force`: 本单元格所受的势能力，通常为 `-∇φ`。
+    ///
+    /// ### 返回值
+    /// 返回按 `NeighbourRelation::ALL` 顺序排列的流出通量。
+    pub(crate) fn diffuse_with_force(
+        &self,
+        fluidity: f64,
+        own_potential: f64,
+        neighbour_potentials: &[f64; 6],
+        force: CartesianVec2D,
+    ) -> [UnitChange; 6] {
+        let barriers = neighbour_potentials.map(|potential| (potential - own_potential).max(0.0));
+        self.calculate_neighbour_changes(fluidity, &barriers, self.movement() + force)
     }
 
     fn calculate_reduced_potential(
         &self,
-        block_info: &HexBlock<DiffuseInfo>,
+        neighbour_potentials: &[f64; 6],
     ) -> [(HexDisplacement, f64); 3] {
         NeighbourRelation::opposite_pairs().map(|(dir_a, dir_b)| {
-            let potential_a = block_info.get_from_neighbours(dir_a).potential();
-            let potential_b = block_info.get_from_neighbours(dir_b).potential();
+            let potential_a = neighbour_potentials[dir_a.index()];
+            let potential_b = neighbour_potentials[dir_b.index()];
 
             let delta = potential_a - potential_b;
 
             // 根据势能差计算反向位移
-            let shift =
-                NeighbourRelation::to_coordinate_shift(if delta > 0.0 { dir_a } else { dir_b })
-                    .reverse();
+            let shift = if delta > 0.0 { dir_a } else { dir_b }
+                .displacement()
+                .reverse();
 
             (shift, delta.abs())
         })
@@ -111,56 +125,28 @@ impl HexUnit {
     fn calculate_neighbour_changes(
         &self,
         fluidity: f64,
-        block_info: &HexBlock<DiffuseInfo>,
+        neighbour_potentials: &[f64; 6],
         total_cartesian_shift: CartesianVec2D,
-    ) -> HashMap<NeighbourRelation, UnitChange> {
-        NeighbourRelation::from_relation_to_coordinate_shift()
-            .iter()
-            .map(|(&relation, shift)| {
-                let weight = (2.0 * PI
-                    - shift
-                        .to_cartesian()
-                        .angle_between(total_cartesian_shift)
-                        .abs())
-                    / (9.0 * PI);
-
-                let mut partial_mole = (self.mole() as f64 * weight * fluidity).round() as usize;
-                let mut partial_movement = shift
-                    .to_cartesian()
-                    .scale(total_cartesian_shift.magnitude() * weight);
-
-                let barrier = block_info.get_from_neighbours(relation).potential();
-
-                if partial_movement.magnitude() > barrier {
-                    partial_movement = shift
-                        .to_cartesian()
-                        .scale(partial_movement.magnitude() - barrier);
-                } else {
-                    partial_movement = shift.to_cartesian().scale(0.0);
-                    partial_mole = 0;
-                }
-
-                let change = UnitChange::new(partial_mole as isize, partial_movement);
-                (relation, change)
-            })
-            .collect()
-    }
+    ) -> [UnitChange; 6] {
+        NeighbourRelation::ALL.map(|relation| {
+            let edge = relation.displacement().to_cartesian();
 
-    fn calculate_self_change(
-        &self,
-        neighbour_changes: &HashMap<NeighbourRelation, UnitChange>,
-    ) -> UnitChange {
-        let mut self_change = UnitChange::new(0, CartesianVec2D::new(0.0, 0.0));
+            let weight = (2.0 * PI - edge.angle_between(total_cartesian_shift).abs()) / (9.0 * PI);
 
-        for change in neighbour_changes.values() {
-            self_change.accumulate_change(change);
-        }
+            let mut partial_mole = (self.mole() as f64 * weight * fluidity).round() as usize;
+            let mut partial_movement = edge.scale(total_cartesian_shift.magnitude() * weight);
+
+            let barrier = neighbour_potentials[relation.index()];
 
-        // 保持守恒性：反转符号
-        self_change.set_mole_change(-self_change.mole_change());
-        self_change.set_movement_change(self_change.movement_change().scale(-1.0));
+            if partial_movement.magnitude() > barrier {
+                partial_movement = edge.scale(partial_movement.magnitude() - barrier);
+            } else {
+                partial_movement = edge.scale(0.0);
+                partial_mole = 0;
+            }
 
-        self_change
+            UnitChange::new(partial_mole as isize, partial_movement)
+        })
     }
 }
 
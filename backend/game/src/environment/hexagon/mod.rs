@@ -1,12 +1,7 @@
-pub(crate) mod t_hexa_distanced;
 pub(crate) mod t_hexa_relational;
 
-pub(crate) mod diagonal_relation;
-pub(crate) mod hex_block;
 pub(crate) mod hex_coord;
 pub(crate) mod hex_displacemant;
-pub(crate) mod hex_spoke;
 pub(crate) mod hex_unit;
-pub(crate) mod indexed_unit_change;
 pub(crate) mod neighbour_relation;
 pub(crate) mod unit_change;
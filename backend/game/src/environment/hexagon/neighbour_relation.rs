@@ -1,6 +1,5 @@
 use crate::environment::hexagon::hex_displacemant::HexDisplacement;
 use crate::environment::hexagon::t_hexa_relational::HexaRelational;
-use std::collections::HashMap;
 
 #[derive(Clone, Copy, Eq, PartialEq, Hash, Debug)]
 pub(crate) enum NeighbourRelation {
@@ -12,18 +11,42 @@ pub(crate) enum NeighbourRelation {
     Degree300 = 300,
 }
 
-impl HexaRelational for NeighbourRelation {
-    fn from_relation_to_coordinate_shift() -> HashMap<Self, HexDisplacement> {
-        HashMap::from([
-            (NeighbourRelation::Degree0, HexDisplacement::new(-1, 0)),
-            (NeighbourRelation::Degree60, HexDisplacement::new(-1, 1)),
-            (NeighbourRelation::Degree120, HexDisplacement::new(0, 1)),
-            (NeighbourRelation::Degree180, HexDisplacement::new(1, 0)),
-            (NeighbourRelation::Degree240, HexDisplacement::new(1, -1)),
-            (NeighbourRelation::Degree300, HexDisplacement::new(0, -1)),
-        ])
+impl NeighbourRelation {
+    /// 固定顺序的全部邻居关系，
+    /// 扩散通量缓冲区中每个单元格的 6 条边均按此顺序存放
+    pub(crate) const ALL: [Self; 6] = [
+        NeighbourRelation::Degree0,
+        NeighbourRelation::Degree60,
+        NeighbourRelation::Degree120,
+        NeighbourRelation::Degree180,
+        NeighbourRelation::Degree240,
+        NeighbourRelation::Degree300,
+    ];
+
+    /// 获取当前关系在 `ALL` 中的下标
+    pub(crate) fn index(self) -> usize {
+        self as usize / 60
+    }
+
+    /// 获取相隔 180 度的相反关系
+    pub(crate) fn opposite(self) -> Self {
+        Self::ALL[(self.index() + 3) % 6]
     }
 
+    /// 获取当前关系对应的坐标偏移量
+    pub(crate) fn displacement(self) -> HexDisplacement {
+        match self {
+            NeighbourRelation::Degree0 => HexDisplacement::new(-1, 0),
+            NeighbourRelation::Degree60 => HexDisplacement::new(-1, 1),
+            NeighbourRelation::Degree120 => HexDisplacement::new(0, 1),
+            NeighbourRelation::Degree180 => HexDisplacement::new(1, 0),
+            NeighbourRelation::Degree240 => HexDisplacement::new(1, -1),
+            NeighbourRelation::Degree300 => HexDisplacement::new(0, -1),
+        }
+    }
+}
+
+impl HexaRelational for NeighbourRelation {
     fn opposite_pairs() -> [(Self, Self); 3] {
         [
             (NeighbourRelation::Degree0, NeighbourRelation::Degree180),
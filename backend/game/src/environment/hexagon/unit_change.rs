@@ -38,25 +38,9 @@ impl UnitChange {
         self.movement_change = movement_change;
     }
 
-    /// 设置单元变化
-    pub(crate) fn set_change(&mut self, unit_change: UnitChange) {
-        self.mole_change = unit_change.mole_change;
-        self.movement_change = unit_change.movement_change;
-    }
-
-    /// 累积摩尔数变化
-    pub(crate) fn accumulate_mole_change(&mut self, mole_change: isize) {
-        self.mole_change = self.mole_change + mole_change;
-    }
-
-    /// 累积运动状态变化
-    pub(crate) fn accumulate_movement_change(&mut self, movement_change: CartesianVec2D) {
-        self.movement_change = self.movement_change + movement_change;
-    }
-
     /// 累积单元变化
     pub(crate) fn accumulate_change(&mut self, other: &UnitChange) {
-        self.mole_change = self.mole_change + other.mole_change;
+        self.mole_change += other.mole_change;
         self.movement_change = self.movement_change + other.movement_change;
     }
 }
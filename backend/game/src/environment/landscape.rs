@@ -1,46 +1,232 @@
+use crate::environment::coupling_params::CouplingParams;
+use crate::environment::diffusion_mode::DiffusionMode;
+use crate::environment::hexagon::hex_coord::HexCoord;
+use crate::environment::hexagon::neighbour_relation::NeighbourRelation;
 use crate::environment::potential::Potential;
+use crate::environment::stability_params::StabilityParams;
+use crate::environment::substance_interaction::SubstanceInteraction;
+use crate::environment::substance_synthesis::SubstanceSynthesis;
+use crate::environment::t_indexed::Indexed;
+use crate::environment::terrain::Terrain;
+use crate::environment::terrain_params::TerrainParams;
 use crate::environment::{map_size::MapSize, subtance_distribution::SubstanceDistribution};
 use crate::game_context::GameContext;
+use crate::metrics::recorder::SharedMetricsRecorder;
+use crate::shared::substance_registry::SubstanceRegistry;
+use crate::shared::subtance_type::SubstanceType;
+use crate::telemetry::tick_span;
 use ndarray::parallel::prelude::*;
-use serde::Serialize;
-use std::collections::HashSet;
+use ndarray::{Array2, Array3, Axis, Zip};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashSet};
+use uuid::Uuid;
 
-#[derive(Debug, Clone, Serialize)]
-pub(crate) struct Landscape {
+/// 地貌：同一张地图上全部物质分布及其势能场强
+///
+/// ```
+/// use game::game_context::GameContext;
+/// use game::{
+///     Landscape, MapSize, NoiseGeneratable, NoiseParams, Statistical, SubstanceDistribution,
+///     SubstanceType,
+/// };
+///
+/// GameContext::update_game_gravity_const(9.8);
+/// let map_size = MapSize::new(Some(8), Some(8));
+/// let mut distribution = SubstanceDistribution::new(
+///     "1/2".parse::<SubstanceType>().unwrap(),
+///     map_size,
+///     Some(NoiseParams::new(Some(42), Some(2.0))),
+/// );
+/// distribution.generate_simplex_noise();
+///
+/// let mut landscape = Landscape::new(map_size);
+/// landscape.add_resource_distribution(distribution);
+/// landscape.update_potential_distribution();
+/// landscape.diffuse();
+///
+/// let distribution = landscape.subtance_distributions().iter().next().unwrap();
+/// assert!(distribution.mean() >= 0.0);
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Landscape {
     map_size: MapSize,
     subtance_distributions: HashSet<SubstanceDistribution>,
     potential: Potential,
+    /// 耦合扩散参数，为 `None` 时各物质独立扩散
+    coupling: Option<CouplingParams>,
+    /// 地形图层，为 `None` 时地图是平坦、均匀的
+    terrain: Option<Terrain>,
+    /// 扩散时势能力的计算方式
+    #[serde(default)]
+    diffusion_mode: DiffusionMode,
+    /// 扩散的数值稳定性参数
+    #[serde(default)]
+    stability: StabilityParams,
+    /// 已经扩散的步数
+    #[serde(default)]
+    tick: u64,
+    /// 所属世界的标识，记录在每步模拟的日志 span 中
+    #[serde(default)]
+    civilization_id: Option<Uuid>,
+    /// 本世界中已经出现过的物质
+    #[serde(default)]
+    discovered: BTreeSet<SubstanceType>,
+    /// 合成生成物尚未凑满一摩尔的部分，按物质类型排序，逐格记录摩尔数的小数部分
+    #[serde(default)]
+    synthesis_remainders: Vec<(SubstanceType, Array2<f64>)>,
+    /// 分母超出物质空间精密度、无法记录发现的物质，只用于避免重复警告；不随地貌序列化
+    #[serde(skip)]
+    undiscoverable: BTreeSet<SubstanceType>,
+    /// 指标记录器，每步扩散后采样；不随地貌序列化
+    #[serde(skip)]
+    metrics: Option<SharedMetricsRecorder>,
 }
 
 impl Landscape {
-    pub(crate) fn new(map_size: MapSize) -> Self {
+    pub fn new(map_size: MapSize) -> Self {
         // 更新全局上下文中的 map_size
-        GameContext::update_game_map_size(map_size.clone());
+        GameContext::update_game_map_size(map_size);
 
         // 创建新的 Landscape
         Self {
             map_size,
             subtance_distributions: HashSet::new(),
             potential: Potential::new(map_size.as_tuple()),
+            coupling: None,
+            terrain: None,
+            diffusion_mode: DiffusionMode::default(),
+            stability: StabilityParams::default(),
+            tick: 0,
+            civilization_id: None,
+            discovered: BTreeSet::new(),
+            synthesis_remainders: Vec::new(),
+            undiscoverable: BTreeSet::new(),
+            metrics: None,
         }
     }
 
-    pub(crate) fn map_size(&self) -> &MapSize {
+    /// 启用耦合扩散模式（可链式调用）
+    pub fn with_coupling(mut self, coupling: CouplingParams) -> Self {
+        self.coupling = Some(coupling);
+        self
+    }
+
+    /// 按地形参数生成海拔、湿度与温度图层（可链式调用）
+    pub fn with_terrain(mut self, params: TerrainParams) -> Self {
+        self.terrain = Some(Terrain::new(self.map_size, params));
+        self
+    }
+
+    /// 设置扩散时势能力的计算方式（可链式调用）
+    pub fn with_diffusion_mode(mut self, diffusion_mode: DiffusionMode) -> Self {
+        self.diffusion_mode = diffusion_mode;
+        self
+    }
+
+    /// 设置扩散的数值稳定性参数（可链式调用）
+    pub fn with_stability(mut self, stability: StabilityParams) -> Self {
+        self.stability = stability;
+        self
+    }
+
+    /// 设置所属世界的标识，之后每步模拟的日志都带有该标识（可链式调用）
+    pub fn with_civilization_id(mut self, civilization_id: Uuid) -> Self {
+        self.civilization_id = Some(civilization_id);
+        self
+    }
+
+    /// 挂载指标记录器（可链式调用）
+    pub fn with_metrics(mut self, metrics: SharedMetricsRecorder) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    pub fn map_size(&self) -> &MapSize {
         &self.map_size
     }
 
-    pub(crate) fn subtance_distributions(&self) -> &HashSet<SubstanceDistribution> {
+    pub fn subtance_distributions(&self) -> &HashSet<SubstanceDistribution> {
         &self.subtance_distributions
     }
 
-    pub(crate) fn potential(&self) -> &Potential {
+    pub fn potential(&self) -> &Potential {
         &self.potential
     }
 
-    pub(crate) fn add_resource_distribution(
-        &mut self,
-        subtance_distribution: SubstanceDistribution,
-    ) {
+    pub fn coupling(&self) -> Option<&CouplingParams> {
+        self.coupling.as_ref()
+    }
+
+    pub fn terrain(&self) -> Option<&Terrain> {
+        self.terrain.as_ref()
+    }
+
+    pub fn diffusion_mode(&self) -> DiffusionMode {
+        self.diffusion_mode
+    }
+
+    pub fn stability(&self) -> &StabilityParams {
+        &self.stability
+    }
+
+    pub fn tick(&self) -> u64 {
+        self.tick
+    }
+
+    pub fn civilization_id(&self) -> Option<Uuid> {
+        self.civilization_id
+    }
+
+    pub fn metrics(&self) -> Option<&SharedMetricsRecorder> {
+        self.metrics.as_ref()
+    }
+
+    /// 获取本世界中已经出现过的物质，按物质类型排序
+    ///
+    /// 发现记录属于各个世界，随地貌一起序列化，互不影响。
+    ///
+    /// ```
+    /// use game::game_context::GameContext;
+    /// use game::{Landscape, MapSize, NoiseGeneratable, NoiseParams, SubstanceDistribution, SubstanceType};
+    ///
+    /// GameContext::update_game_gravity_const(9.8);
+    /// let map_size = MapSize::new(Some(6), Some(6));
+    /// let substance_type: SubstanceType = "1/2".parse().unwrap();
+    /// let mut distribution =
+    ///     SubstanceDistribution::new(substance_type, map_size, Some(NoiseParams::new(Some(7), Some(1.0))));
+    /// distribution.generate_simplex_noise();
+    ///
+    /// let mut landscape = Landscape::new(map_size);
+    /// landscape.add_resource_distribution(distribution);
+    /// landscape.update_potential_distribution();
+    /// landscape.diffuse();
+    /// assert!(landscape.is_discovered(&substance_type));
+    ///
+    /// // 另一个世界的发现记录是独立的
+    /// assert!(Landscape::new(map_size).discovered().is_empty());
+    ///
+    /// let restored: Landscape =
+    ///     serde_json::from_str(&serde_json::to_string(&landscape).unwrap()).unwrap();
+    /// assert!(restored.is_discovered(&substance_type));
+    /// ```
+    pub fn discovered(&self) -> &BTreeSet<SubstanceType> {
+        &self.discovered
+    }
+
+    /// 获取合成生成物尚未凑满一摩尔的部分，按物质类型排序
+    ///
+    /// 生成物的摩尔数只取整数部分，小数部分留在这里，凑满一摩尔后再计入物质分布，
+    /// 因此物质分布的总质量加上这些余量的质量在合成前后不变。
+    pub fn synthesis_remainders(&self) -> &[(SubstanceType, Array2<f64>)] {
+        &self.synthesis_remainders
+    }
+
+    /// 判断物质是否已在本世界中被发现
+    pub fn is_discovered(&self, substance_type: &SubstanceType) -> bool {
+        self.discovered.contains(substance_type)
+    }
+
+    pub fn add_resource_distribution(&mut self, subtance_distribution: SubstanceDistribution) {
         // 检查集合中是否已存在相同的 `resource_type`
         let exists = self
             .subtance_distributions
@@ -63,18 +249,88 @@ impl Landscape {
         }
     }
 
-    pub(crate) fn update_potential_distribution(&mut self) {
-        // 计算势能场强分布
-        self.potential
-            .update(&self.subtance_distributions, self.map_size.as_tuple());
+    pub fn update_potential_distribution(&mut self) {
+        // 计算势能场强分布，存在地形时叠加海拔
+        self.potential.update(
+            &self.subtance_distributions,
+            self.map_size.as_tuple(),
+            self.terrain.as_ref().map(Terrain::altitude),
+        );
     }
 }
 
 /// 关于扩散逻辑的集合
 impl Landscape {
-    pub(crate) fn diffuse(&mut self) {
+    pub fn diffuse(&mut self) {
+        // 这一步的全部日志都带有世界与步数
+        let span = tick_span(self.civilization_id, self.tick + 1);
+        let _entered = span.enter();
+
         // 更新当前状态
         self.update_distributions(None);
+
+        // 耦合模式下，扩散后再执行单元格内的多物质交互
+        self.interact();
+
+        // 推进季节
+        if let Some(terrain) = &mut self.terrain {
+            terrain.advance();
+        }
+
+        self.record_discoveries();
+
+        self.tick += 1;
+        self.observe_metrics();
+    }
+
+    /// 让挂载的指标记录器按采样间隔对当前状态采样
+    fn observe_metrics(&self) {
+        if let Some(metrics) = &self.metrics {
+            match metrics.write() {
+                Ok(mut recorder) => {
+                    recorder.observe(self.tick, self);
+                }
+                Err(e) => tracing::warn!("无法获取指标记录器的写锁: {}", e),
+            }
+        }
+    }
+
+    /// 将世界中已有摩尔数、但尚未被发现的物质记录为已发现
+    ///
+    /// 分母超出物质空间精密度的物质不会被记录，每种物质只警告一次。
+    pub fn record_discoveries(&mut self) {
+        for substance_dist in self.subtance_distributions.iter() {
+            let substance_type = substance_dist.substance_type();
+            if self.discovered.contains(substance_type)
+                || self.undiscoverable.contains(substance_type)
+            {
+                continue;
+            }
+
+            let present = substance_dist
+                .distribution()
+                .par_iter()
+                .any(|unit| unit.mole() > 0);
+            if !present {
+                continue;
+            }
+
+            match SubstanceRegistry::substance(substance_type) {
+                Ok(substance) => {
+                    tracing::info!(
+                        "发现新物质 {}: {}/{}",
+                        substance.name(),
+                        substance_type.ratio.numer(),
+                        substance_type.ratio.denom()
+                    );
+                    self.discovered.insert(*substance_type);
+                }
+                Err(e) => {
+                    tracing::warn!("无法记录物质发现: {}", e);
+                    self.undiscoverable.insert(*substance_type);
+                }
+            }
+        }
     }
 
     /// 更新物质分布集合
@@ -92,14 +348,176 @@ impl Landscape {
     }
 
     /// 计算扩散后的新物质分布状态
-    fn calculate_diffusion(&self) -> HashSet<SubstanceDistribution> {
-        self.subtance_distributions
-            .par_iter()
-            .map(|substance_dist| {
-                let mut updated = substance_dist.clone();
-                updated.diffuse(self.potential());
-                updated
+    ///
+    /// 取出当前集合的所有权后原地扩散，避免复制每个分布及其缓冲区。
+    /// 梯度模式下势能梯度场只计算一次，由全部物质共享。
+    fn calculate_diffusion(&mut self) -> HashSet<SubstanceDistribution> {
+        let potential = &self.potential;
+        let stability = &self.stability;
+        let fluidity_factor = self.terrain.as_ref().map(Terrain::fluidity_factor);
+        let gradient = match self.diffusion_mode {
+            DiffusionMode::OppositePairs => None,
+            DiffusionMode::Gradient => Some(potential.gradient()),
+        };
+        // rayon 的工作线程不继承当前 span，需要在任务中重新进入
+        let span = tracing::Span::current();
+        std::mem::take(&mut self.subtance_distributions)
+            .into_par_iter()
+            .map(|mut substance_dist| {
+                let _entered = span.enter();
+                substance_dist.diffuse(potential, fluidity_factor, gradient.as_ref(), stability);
+                substance_dist
             })
             .collect() // Rayon 的并行收集可以直接构建 HashSet
     }
 }
+
+/// 关于耦合扩散逻辑的集合
+impl Landscape {
+    /// 在每个单元格内执行多物质交互，未启用耦合扩散时不做任何事
+    ///
+    /// `diffuse` 在扩散之后调用；单独调用时只交互、不扩散，也不推进步数。
+    ///
+    /// 1. 为反应规则中尚不存在的生成物补充空的物质分布。
+    /// 2. 将所有物质的摩尔数按固定顺序堆叠为 `(物质, 行, 列)` 的三维数组。
+    /// 3. 并行计算置换：较轻且不相似的物质被挤出，平均分给 6 个邻居。
+    /// 4. 并行汇总邻居置换过来的摩尔数。
+    /// 5. 并行执行反应规则。
+    /// 6. 若启用了物质合成，并行执行合成，生成物可能是新的物质类型。
+    /// 7. 将结果写回各物质分布，为有摩尔数的新生成物创建物质分布。
+    pub fn interact(&mut self) {
+        let Some(coupling) = self.coupling.clone() else {
+            return;
+        };
+        self.ensure_reaction_products(&coupling);
+
+        // 按物质类型排序，保证下标与结果的确定性
+        let mut distributions: Vec<SubstanceDistribution> =
+            std::mem::take(&mut self.subtance_distributions)
+                .into_iter()
+                .collect();
+        distributions.sort_by_key(|distribution| distribution.substance_type().ratio);
+
+        let mut substance_types: Vec<_> = distributions
+            .iter()
+            .map(|sd| *sd.substance_type())
+            .collect();
+
+        // 合成可能产生新的物质类型，它们会被追加到 `substance_types` 末尾
+        let synthesis = coupling
+            .synthesis()
+            .map(|params| SubstanceSynthesis::new(&mut substance_types, params));
+        let interaction = SubstanceInteraction::new(&substance_types, &coupling);
+
+        let map_size = self.map_size.as_tuple();
+        let mut moles = Array3::<usize>::zeros((substance_types.len(), map_size.0, map_size.1));
+        for (index, substance_dist) in distributions.iter().enumerate() {
+            moles
+                .index_axis_mut(Axis(0), index)
+                .assign(&substance_dist.distribution().mapv(|unit| unit.mole()));
+        }
+
+        // 置换：每个单元格只写自己的摩尔数与置换量
+        let mut displaced = Array3::<usize>::zeros(moles.raw_dim());
+        Zip::from(moles.lanes_mut(Axis(0)))
+            .and(displaced.lanes_mut(Axis(0)))
+            .par_for_each(|mut cell_moles, mut cell_displaced| {
+                interaction.displace(&mut cell_moles, &mut cell_displaced);
+            });
+
+        // 汇总：每个单元格读取邻居沿反方向边分给自己的部分
+        Zip::indexed(moles.lanes_mut(Axis(0))).par_for_each(
+            |(row_index, col_index), mut cell_moles| {
+                let current_coord = HexCoord::new(row_index, col_index);
+                for relation in NeighbourRelation::ALL {
+                    let neighbour_coord =
+                        current_coord.shift_within(relation.displacement(), map_size);
+                    let edge_index = relation.opposite().index();
+                    for (index, mole) in cell_moles.iter_mut().enumerate() {
+                        *mole += SubstanceInteraction::edge_share(
+                            displaced[[index, neighbour_coord.y(), neighbour_coord.x()]],
+                            edge_index,
+                        );
+                    }
+                }
+            },
+        );
+
+        // 反应：每个单元格内独立进行
+        Zip::from(moles.lanes_mut(Axis(0))).par_for_each(|mut cell_moles| {
+            interaction.react(&mut cell_moles);
+        });
+
+        // 合成：每个单元格内独立进行，生成物不足一摩尔的部分跨步累积
+        if let Some(synthesis) = &synthesis {
+            let mut remainders = Array3::<f64>::zeros(moles.raw_dim());
+            for (index, substance_type) in substance_types.iter().enumerate() {
+                if let Some((_, remainder)) = self
+                    .synthesis_remainders
+                    .iter()
+                    .find(|(st, _)| st == substance_type)
+                {
+                    remainders.index_axis_mut(Axis(0), index).assign(remainder);
+                }
+            }
+
+            Zip::from(moles.lanes_mut(Axis(0)))
+                .and(remainders.lanes_mut(Axis(0)))
+                .par_for_each(|mut cell_moles, mut cell_remainders| {
+                    synthesis.synthesize(&mut cell_moles, &mut cell_remainders);
+                });
+
+            // 本步未参与合成的物质保留原有余量
+            self.synthesis_remainders
+                .retain(|(st, _)| !substance_types.contains(st));
+            for (index, substance_type) in substance_types.iter().enumerate() {
+                let remainder = remainders.index_axis(Axis(0), index);
+                if remainder.iter().any(|&value| value > 0.0) {
+                    self.synthesis_remainders
+                        .push((*substance_type, remainder.to_owned()));
+                }
+            }
+            self.synthesis_remainders.sort_by_key(|(st, _)| *st);
+        }
+
+        // 为真正产生了摩尔数的新生成物创建物质分布，并记录每个分布对应的摩尔数行
+        let mut rows: Vec<usize> = (0..distributions.len()).collect();
+        for (index, product) in substance_types.iter().enumerate().skip(rows.len()) {
+            if moles.index_axis(Axis(0), index).sum() > 0 {
+                tracing::debug!("合成产生了新的物质类型: {:?}", product);
+                distributions.push(SubstanceDistribution::new(*product, self.map_size, None));
+                rows.push(index);
+            }
+        }
+
+        distributions
+            .par_iter_mut()
+            .zip(rows.par_iter())
+            .for_each(|(substance_dist, &row)| {
+                Zip::from(substance_dist.distribution_mut())
+                    .and(moles.index_axis(Axis(0), row))
+                    .for_each(|unit, &mole| unit.set_mole(mole));
+            });
+
+        self.subtance_distributions = distributions.into_iter().collect();
+    }
+
+    /// 为反应规则中尚不存在的生成物补充空的物质分布
+    fn ensure_reaction_products(&mut self, coupling: &CouplingParams) {
+        for rule in coupling.reaction_rules() {
+            let exists = self
+                .subtance_distributions
+                .iter()
+                .any(|sd| sd.substance_type() == rule.product());
+
+            if !exists {
+                tracing::debug!("为反应生成物补充空的物质分布: {:?}", rule.product());
+                self.add_resource_distribution(SubstanceDistribution::new(
+                    *rule.product(),
+                    self.map_size,
+                    None,
+                ));
+            }
+        }
+    }
+}
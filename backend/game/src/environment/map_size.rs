@@ -1,12 +1,12 @@
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use std::fmt;
 
 const DEFAULT_WIDTH: usize = 255; // 默认宽度常量
 const DEFAULT_HEIGHT: usize = DEFAULT_WIDTH; // 默认高度常量，设为与宽度相同
 
 /// 地图参数结构体
-#[derive(Debug, Clone, Serialize, Copy)]
-pub(crate) struct MapSize {
+#[derive(Debug, Clone, Serialize, Deserialize, Copy)]
+pub struct MapSize {
     height: usize, // 地图高度
     width: usize,  // 地图宽度
 }
@@ -14,7 +14,7 @@ pub(crate) struct MapSize {
 impl MapSize {
     /// 使用可选的宽度和高度创建一个新的 `MapSize` 实例。
     /// 如果宽度或高度未提供，则使用默认值。
-    pub(crate) fn new(width: Option<usize>, height: Option<usize>) -> Self {
+    pub fn new(width: Option<usize>, height: Option<usize>) -> Self {
         Self {
             height: height.unwrap_or(Self::default_height()), // 如果未提供高度，使用默认高度
             width: width.unwrap_or(Self::default_width()),    // 如果未提供宽度，使用默认宽度
@@ -23,7 +23,7 @@ impl MapSize {
 
     /// 从元组创建一个新的 `MapSize` 实例
     /// - 元组第一个元素为高度，第二个元素为宽度
-    pub(crate) fn from_tuple(tuple: (usize, usize)) -> Self {
+    pub fn from_tuple(tuple: (usize, usize)) -> Self {
         Self {
             height: tuple.0,
             width: tuple.1,
@@ -41,17 +41,17 @@ impl MapSize {
     }
 
     /// 获取 `MapSize` 的高度
-    pub(crate) fn height(&self) -> usize {
+    pub fn height(&self) -> usize {
         self.height
     }
 
     /// 获取 `MapSize` 的宽度
-    pub(crate) fn width(&self) -> usize {
+    pub fn width(&self) -> usize {
         self.width
     }
 
     /// 将 `MapSize` 转换为元组形式 (height, width)
-    pub(crate) fn as_tuple(&self) -> (usize, usize) {
+    pub fn as_tuple(&self) -> (usize, usize) {
         (self.height, self.width)
     }
 }
@@ -3,10 +3,21 @@ pub(crate) mod t_noise_generatable;
 pub(crate) mod t_statistical;
 
 pub(crate) mod cartesian_vec_2d;
-pub(crate) mod diffuse_info;
+pub(crate) mod coupling_params;
+pub(crate) mod diffusion_buffer;
+pub(crate) mod diffusion_mode;
+pub(crate) mod distribution_io;
+pub(crate) mod hex_calculus;
 pub(crate) mod hexagon;
 pub(crate) mod landscape;
 pub(crate) mod map_size;
 pub(crate) mod noise_params;
 pub(crate) mod potential;
+pub(crate) mod stability_params;
+pub(crate) mod stability_report;
+pub(crate) mod statistics;
+pub(crate) mod substance_interaction;
+pub(crate) mod substance_synthesis;
 pub(crate) mod subtance_distribution;
+pub(crate) mod terrain;
+pub(crate) mod terrain_params;
@@ -1,36 +1,321 @@
+use ndarray::{Array2, Zip};
+use noise::{NoiseFn, OpenSimplex};
 use rand::Rng;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use std::f64::consts::PI;
 use std::fmt;
 use std::hash::{Hash, Hasher};
 
 const DEFAULT_SCALE_RANGE: std::ops::Range<f64> = 1.0..10.0;
+const DEFAULT_OCTAVES: usize = 1;
+const DEFAULT_LACUNARITY: f64 = 2.0;
+const DEFAULT_PERSISTENCE: f64 = 0.5;
+/// 叠加的倍频程数上限，超过后高频细节已小于网格分辨率
+const MAX_OCTAVES: usize = 16;
+/// 域扭曲噪声相对主噪声的种子偏移，避免两者相关
+const WARP_SEED_OFFSET: u32 = 0x9E37_79B9;
 
-#[derive(Debug, Clone, Serialize)]
-pub(crate) struct NoiseParams {
+/// 单个倍频程噪声值的变换方式
+///
+/// - `Standard`: 原始噪声，形成平滑起伏的团块。
+/// - `Ridged`: `2(1 - |n|)² - 1`，在零值处形成尖锐的山脊，适合脉状矿藏。
+/// - `Billow`: `2|n| - 1`，在零值处形成圆润的谷底，适合云团状聚集。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum NoiseVariant {
+    #[default]
+    Standard,
+    Ridged,
+    Billow,
+}
+
+impl NoiseVariant {
+    /// 变换 [-1, 1] 范围内的噪声值，结果仍在 [-1, 1] 内
+    fn shape(self, value: f64) -> f64 {
+        match self {
+            NoiseVariant::Standard => value,
+            NoiseVariant::Ridged => 2.0 * (1.0 - value.abs()).powi(2) - 1.0,
+            NoiseVariant::Billow => 2.0 * value.abs() - 1.0,
+        }
+    }
+}
+
+/// 噪声参数
+///
+/// - `seed`: 随机种子，每个倍频程在此基础上偏移。
+/// - `scale`: 基础尺度，越大则地图上的团块越多、越小。
+/// - `octaves`: 叠加的倍频程数，取值范围为 [1, `MAX_OCTAVES`]。
+/// - `lacunarity`: 相邻倍频程之间的频率倍数。
+/// - `persistence`: 相邻倍频程之间的振幅倍数。
+/// - `variant`: 单个倍频程噪声值的变换方式。
+/// - `warp_strength`: 域扭曲强度，以地图尺寸为单位，0 表示不扭曲。
+/// - `mole_budget`: 物质分布的总摩尔数目标，为 `None` 时每个单元格按噪声值放大到 [0, 255]。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NoiseParams {
     pub(crate) seed: u32,
     pub(crate) scale: f64,
+    #[serde(default = "NoiseParams::default_octaves")]
+    octaves: usize,
+    #[serde(default = "NoiseParams::default_lacunarity")]
+    lacunarity: f64,
+    #[serde(default = "NoiseParams::default_persistence")]
+    persistence: f64,
+    #[serde(default)]
+    variant: NoiseVariant,
+    #[serde(default)]
+    warp_strength: f64,
+    #[serde(default)]
+    mole_budget: Option<usize>,
 }
 
 impl NoiseParams {
-    pub(crate) fn new(seed: Option<u32>, scale: Option<f64>) -> Self {
+    pub fn new(seed: Option<u32>, scale: Option<f64>) -> Self {
         Self {
             seed: seed.unwrap_or_else(Self::default_seed),
             scale: scale.unwrap_or_else(Self::default_scale),
+            octaves: Self::default_octaves(),
+            lacunarity: Self::default_lacunarity(),
+            persistence: Self::default_persistence(),
+            variant: NoiseVariant::default(),
+            warp_strength: 0.0,
+            mole_budget: None,
         }
     }
 
-    pub(crate) fn default_seed() -> u32 {
-        rand::thread_rng().gen::<u32>()
+    /// 设置分形叠加的倍频程数、频率倍数与振幅倍数（可链式调用）
+    pub fn with_octaves(mut self, octaves: usize, lacunarity: f64, persistence: f64) -> Self {
+        self.octaves = octaves.clamp(1, MAX_OCTAVES);
+        self.lacunarity = lacunarity.max(1.0);
+        self.persistence = persistence.clamp(0.0, 1.0);
+        self
+    }
+
+    /// 设置噪声变换方式（可链式调用）
+    pub fn with_variant(mut self, variant: NoiseVariant) -> Self {
+        self.variant = variant;
+        self
+    }
+
+    /// 设置域扭曲强度（可链式调用）
+    pub fn with_domain_warp(mut self, warp_strength: f64) -> Self {
+        self.warp_strength = warp_strength.max(0.0);
+        self
+    }
+
+    /// 设置物质分布的总摩尔数目标（可链式调用）
+    ///
+    /// ```
+    /// use game::{
+    ///     MapSize, NoiseGeneratable, NoiseParams, NoiseVariant, SubstanceDistribution,
+    ///     SubstanceType,
+    /// };
+    ///
+    /// let noise_params = NoiseParams::new(Some(7), Some(1.5))
+    ///     .with_octaves(4, 2.0, 0.5)
+    ///     .with_variant(NoiseVariant::Ridged)
+    ///     .with_domain_warp(0.1)
+    ///     .with_mole_budget(10_000);
+    /// let mut distribution = SubstanceDistribution::new(
+    ///     SubstanceType::try_new(1, 3).unwrap(),
+    ///     MapSize::new(Some(16), Some(12)),
+    ///     Some(noise_params),
+    /// );
+    /// distribution.generate_simplex_noise();
+    ///
+    /// let total: usize = distribution.distribution().iter().map(|unit| unit.mole()).sum();
+    /// assert_eq!(total, 10_000);
+    /// ```
+    pub fn with_mole_budget(mut self, mole_budget: usize) -> Self {
+        self.mole_budget = Some(mole_budget);
+        self
+    }
+
+    pub fn seed(&self) -> u32 {
+        self.seed
+    }
+
+    pub fn scale(&self) -> f64 {
+        self.scale
+    }
+
+    pub fn octaves(&self) -> usize {
+        self.octaves
+    }
+
+    pub fn lacunarity(&self) -> f64 {
+        self.lacunarity
+    }
+
+    pub fn persistence(&self) -> f64 {
+        self.persistence
+    }
+
+    pub fn variant(&self) -> NoiseVariant {
+        self.variant
+    }
+
+    pub fn warp_strength(&self) -> f64 {
+        self.warp_strength
+    }
+
+    pub fn mole_budget(&self) -> Option<usize> {
+        self.mole_budget
+    }
+
+    pub fn default_seed() -> u32 {
+        rand::rng().random::<u32>()
+    }
+
+    pub fn default_scale() -> f64 {
+        rand::rng().random_range(DEFAULT_SCALE_RANGE)
+    }
+
+    fn default_octaves() -> usize {
+        DEFAULT_OCTAVES
+    }
+
+    fn default_lacunarity() -> f64 {
+        DEFAULT_LACUNARITY
+    }
+
+    fn default_persistence() -> f64 {
+        DEFAULT_PERSISTENCE
+    }
+}
+
+/// 噪声采样
+impl NoiseParams {
+    /// 在二维环面上采样噪声场
+    ///
+    /// 归一化坐标 `(x, y)` 先被映射到四维空间中两个圆的乘积上，
+    /// 圆的半径决定尺度，因此无论尺度、频率倍数取何值，噪声在地图上下、左右边界处都无缝衔接。
+    /// 启用域扭曲时，先用另一组噪声偏移 `(x, y)`，偏移量本身也是周期性的。
+    ///
+    /// 单个倍频程且不扭曲时沿用原有的映射：坐标按 `scale` 放大角度后落在单位圆上，
+    /// 保证已有种子与尺度生成的地图保持不变。
+    ///
+    /// ### 参数
+    /// - `dim`: `(height, width)`，噪声场的形状。
+    ///
+    /// ### 返回值
+    /// 返回取值范围为 [0, 1] 的噪声场。
+    ///
+    /// ### 示例
+    /// ```
+    /// use game::NoiseParams;
+    /// use noise::{NoiseFn, OpenSimplex};
+    /// use std::f64::consts::PI;
+    ///
+    /// let field = NoiseParams::new(Some(42), Some(3.0)).sample_torus((4, 5));
+    ///
+    /// let simplex = OpenSimplex::new(42);
+    /// let frequency = 3.0 * 2.0 * PI;
+    /// let (x, y) = (2.0 / 5.0 * frequency, 1.0 / 4.0 * frequency);
+    /// let expected = (simplex.get([x.sin(), x.cos(), y.sin(), y.cos()]) + 1.0) * 0.5;
+    /// assert!((field[[1, 2]] - expected).abs() < 1e-12);
+    /// ```
+    pub fn sample_torus(&self, dim: (usize, usize)) -> Array2<f64> {
+        if self.octaves == 1 && self.warp_strength <= 0.0 {
+            return self.sample_single_octave(dim);
+        }
+
+        let (height, width) = dim;
+
+        // 每个倍频程使用不同的种子，避免各层在原点附近相关
+        let octaves: Vec<(OpenSimplex, f64, f64)> = (0..self.octaves)
+            .map(|octave| {
+                (
+                    OpenSimplex::new(self.seed.wrapping_add(octave as u32)),
+                    self.scale * self.lacunarity.powi(octave as i32),
+                    self.persistence.powi(octave as i32),
+                )
+            })
+            .collect();
+        let total_amplitude: f64 = octaves.iter().map(|(_, _, amplitude)| amplitude).sum();
+
+        let warp = (self.warp_strength > 0.0).then(|| {
+            (
+                OpenSimplex::new(self.seed.wrapping_add(WARP_SEED_OFFSET)),
+                OpenSimplex::new(self.seed.wrapping_add(WARP_SEED_OFFSET).wrapping_add(1)),
+            )
+        });
+
+        let mut field = Array2::<f64>::zeros(dim);
+        Zip::indexed(&mut field).par_for_each(|(row_index, col_index), value| {
+            // 计算当前元素在矩阵中的归一化坐标，确保噪声在整个地图范围内分布均匀
+            let mut x = col_index as f64 / width as f64;
+            let mut y = row_index as f64 / height as f64;
+
+            // 域扭曲：用两组基础尺度的周期噪声偏移采样坐标
+            if let Some((warp_x, warp_y)) = &warp {
+                let point = Self::torus_point(x, y, self.scale);
+                x += self.warp_strength * warp_x.get(point);
+                y += self.warp_strength * warp_y.get(point);
+            }
+
+            let sum: f64 = octaves
+                .iter()
+                .map(|(simplex, radius, amplitude)| {
+                    amplitude
+                        * self
+                            .variant
+                            .shape(simplex.get(Self::torus_point(x, y, *radius)))
+                })
+                .sum();
+
+            // 将 [-1, 1] 的加权平均值归一化到 [0, 1]
+            *value = ((sum / total_amplitude + 1.0) * 0.5).clamp(0.0, 1.0);
+        });
+
+        field
+    }
+
+    /// 以原有映射采样单个倍频程：角度为 `x × scale × 2π`，落在单位圆上
+    fn sample_single_octave(&self, dim: (usize, usize)) -> Array2<f64> {
+        // 初始化Simplex噪声生成器，使用指定的种子确保噪声的可重复性
+        let simplex = OpenSimplex::new(self.seed);
+        // 设置噪声频率，控制噪声的扩展和分布范围
+        let frequency = self.scale * 2.0 * PI;
+        let (height, width) = dim;
+
+        let mut field = Array2::<f64>::zeros(dim);
+        Zip::indexed(&mut field).par_for_each(|(row_index, col_index), value| {
+            let x_angle = col_index as f64 / width as f64 * frequency;
+            let y_angle = row_index as f64 / height as f64 * frequency;
+
+            // 通过sin和cos创建周期性
+            let noise = simplex.get([x_angle.sin(), x_angle.cos(), y_angle.sin(), y_angle.cos()]);
+
+            // 将 [-1, 1] 的噪声值归一化到 [0, 1]
+            *value = (self.variant.shape(noise) + 1.0) * 0.5;
+        });
+
+        field
     }
 
-    pub(crate) fn default_scale() -> f64 {
-        rand::thread_rng().gen_range(DEFAULT_SCALE_RANGE)
+    /// 将归一化坐标映射到四维空间中两个半径为 `radius` 的圆的乘积上
+    ///
+    /// 通过sin和cos创建周期性，`x`、`y` 每增加 1 都回到同一点，从而确保边界处无缝连接。
+    fn torus_point(x: f64, y: f64, radius: f64) -> [f64; 4] {
+        let (x_angle, y_angle) = (x * 2.0 * PI, y * 2.0 * PI);
+        [
+            radius * x_angle.sin(), // 横向环绕的sin分量
+            radius * x_angle.cos(), // 横向环绕的cos分量
+            radius * y_angle.sin(), // 纵向环绕的sin分量
+            radius * y_angle.cos(), // 纵向环绕的cos分量
+        ]
     }
 }
 
 impl PartialEq for NoiseParams {
     fn eq(&self, other: &Self) -> bool {
-        self.seed == other.seed && (self.scale - other.scale).abs() < f64::EPSILON
+        self.seed == other.seed
+            && (self.scale - other.scale).abs() < f64::EPSILON
+            && self.octaves == other.octaves
+            && (self.lacunarity - other.lacunarity).abs() < f64::EPSILON
+            && (self.persistence - other.persistence).abs() < f64::EPSILON
+            && self.variant == other.variant
+            && (self.warp_strength - other.warp_strength).abs() < f64::EPSILON
+            && self.mole_budget == other.mole_budget
     }
 }
 
@@ -41,6 +326,9 @@ impl Hash for NoiseParams {
         self.seed.hash(state);
         // 将 f64 转换为整数来计算哈希，以便更稳定的哈希值
         (self.scale.to_bits()).hash(state);
+        self.octaves.hash(state);
+        self.variant.hash(state);
+        self.mole_budget.hash(state);
     }
 }
 
@@ -1,36 +1,109 @@
+use crate::environment::cartesian_vec_2d::CartesianVec2D;
+use crate::environment::hex_calculus::HexCalculus;
 use crate::environment::subtance_distribution::SubstanceDistribution;
+use crate::environment::t_statistical::Statistical;
 use crate::game_context::GameContext;
 use crate::shared::property::Property;
-use ndarray::{parallel::prelude::*, Array2};
-use serde::Serialize;
+use ndarray::{parallel::prelude::*, Array2, Zip};
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
 use std::collections::HashSet;
 
-#[derive(Debug, Clone, Serialize)]
-pub(crate) struct Potential {
+/// 势能场强分布
+///
+/// 除了场强本身，还提供六边形网格上的梯度、散度与拉普拉斯算子。
+/// 单元格 `(row, col)` 的中心位于笛卡尔坐标 `col × x_base + row × y_base`，
+/// 地图上下、左右环绕，因此下面只在不受环绕影响的内部单元格上与解析解比较：
+///
+/// ```
+/// use game::{CartesianVec2D, Potential};
+/// use ndarray::Array2;
+///
+/// // 单元格中心的笛卡尔坐标 (y, x)
+/// let center = |row: usize, col: usize| {
+///     (col as f64 * 0.5 + row as f64, col as f64 * 3.0f64.sqrt() / 2.0)
+/// };
+/// let interior = |row: usize, col: usize| (1..7).contains(&row) && (1..7).contains(&col);
+///
+/// // 线性场 φ = 3y + 2x 的梯度处处为 (3, 2)
+/// let linear = Potential::from_distribution(Array2::from_shape_fn((8, 8), |(row, col)| {
+///     let (y, x) = center(row, col);
+///     3.0 * y + 2.0 * x
+/// }));
+/// for ((row, col), gradient) in linear.gradient().indexed_iter() {
+///     if interior(row, col) {
+///         assert!((gradient.y() - 3.0).abs() < 1e-9 && (gradient.x() - 2.0).abs() < 1e-9);
+///     }
+/// }
+///
+/// // 二次场 φ = x² + y² 的拉普拉斯算子处处为 4
+/// let quadratic = Potential::from_distribution(Array2::from_shape_fn((8, 8), |(row, col)| {
+///     let (y, x) = center(row, col);
+///     x * x + y * y
+/// }));
+/// for ((row, col), laplacian) in quadratic.laplacian().indexed_iter() {
+///     if interior(row, col) {
+///         assert!((laplacian - 4.0).abs() < 1e-9);
+///     }
+/// }
+///
+/// // 向量场 F = (y, x) 的散度处处为 2
+/// let field = Array2::from_shape_fn((8, 8), |(row, col)| {
+///     let (y, x) = center(row, col);
+///     CartesianVec2D::new(y, x)
+/// });
+/// for ((row, col), divergence) in Potential::divergence(&field).indexed_iter() {
+///     if interior(row, col) {
+///         assert!((divergence - 2.0).abs() < 1e-9);
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Potential {
     potential_distribution: Array2<f64>,
 }
 
 impl Potential {
     /// 创建新的 `Potential`
-    pub(crate) fn new(map_size: (usize, usize)) -> Self {
+    pub fn new(map_size: (usize, usize)) -> Self {
         Self {
             potential_distribution: Array2::<f64>::zeros(map_size),
         }
     }
 
+    /// 由给定的势能场强分布创建 `Potential`
+    pub fn from_distribution(potential_distribution: Array2<f64>) -> Self {
+        Self {
+            potential_distribution,
+        }
+    }
+
     /// 获取当前的势能场强分布
-    pub(crate) fn distribution(&self) -> &Array2<f64> {
+    pub fn distribution(&self) -> &Array2<f64> {
         &self.potential_distribution
     }
 
-    /// 更新势能场强分布
-    pub(crate) fn update(
+    /// 重新计算势能场强分布
+    ///
+    /// ### 参数
+    /// - `subtance_distributions`: 全部物质分布。
+    /// - `map_size`: `(height, width)`，地图尺寸。
+    /// - `altitude`: 可选的海拔图层，会乘以重力常数叠加到势能场强上。
+    pub fn update(
         &mut self,
         subtance_distributions: &HashSet<SubstanceDistribution>,
         map_size: (usize, usize),
+        altitude: Option<&Array2<f64>>,
     ) {
-        self.potential_distribution =
+        let mut potential_distribution =
             self.calculate_potential_distribution(subtance_distributions, map_size);
+
+        if let Some(altitude) = altitude {
+            let gravity_const = GameContext::get_gravity_const();
+            potential_distribution.scaled_add(gravity_const, altitude);
+        }
+
+        self.potential_distribution = potential_distribution;
     }
 
     /// 计算势能场强分布
@@ -96,3 +169,74 @@ impl Potential {
         .expect("未能从向量创建势能场强分布") // 确保向量长度与地图形状匹配
     }
 }
+
+/// 六边形网格上的微分算子
+impl Potential {
+    /// 势能场强的梯度场
+    ///
+    /// ### 返回值
+    /// 返回每个单元格在笛卡尔空间中的梯度向量，由 6 个邻居的势能差最小二乘拟合得到，
+    /// 对线性场精确。`-∇φ` 即单元格所受的势能力。
+    pub fn gradient(&self) -> Array2<CartesianVec2D> {
+        HexCalculus::new().gradient(&self.potential_distribution)
+    }
+
+    /// 势能场强的拉普拉斯算子
+    ///
+    /// ### 返回值
+    /// 返回每个单元格的 `Δφ`，使用 7 点模板，对二次场精确。
+    pub fn laplacian(&self) -> Array2<f64> {
+        HexCalculus::new().laplacian(&self.potential_distribution)
+    }
+
+    /// 六边形网格上任意向量场的散度
+    ///
+    /// ### 参数
+    /// - `vector_field`: 每个单元格的笛卡尔向量，例如 `gradient` 或 `flux` 的结果。
+    ///
+    /// ### 返回值
+    /// 返回每个单元格的 `∇·F`，对线性向量场精确。
+    pub fn divergence(vector_field: &Array2<CartesianVec2D>) -> Array2<f64> {
+        HexCalculus::new().divergence(vector_field)
+    }
+
+    /// 某种物质在势能场中的通量
+    ///
+    /// ### 参数
+    /// - `substance_dist`: 物质分布，形状须与势能场强分布一致。
+    ///
+    /// ### 返回值
+    /// 返回每个单元格的通量 `J = -n∇φ`，`n` 为摩尔数；
+    /// `-∇·J` 即势能力驱动下各单元格摩尔数的变化率。
+    pub fn flux(&self, substance_dist: &SubstanceDistribution) -> Array2<CartesianVec2D> {
+        let mut flux = self.gradient();
+        Zip::from(&mut flux)
+            .and(substance_dist.distribution())
+            .par_for_each(|gradient, unit| *gradient = gradient.scale(-(unit.mole() as f64)));
+        flux
+    }
+}
+
+impl Statistical for Potential {
+    type Item = f64;
+
+    fn min(&self) -> Self::Item {
+        Statistical::min(&self.potential_distribution)
+    }
+
+    fn max(&self) -> Self::Item {
+        Statistical::max(&self.potential_distribution)
+    }
+
+    fn mean(&self) -> f64 {
+        Statistical::mean(&self.potential_distribution)
+    }
+
+    fn variance(&self) -> f64 {
+        Statistical::variance(&self.potential_distribution)
+    }
+
+    fn scalar_layer(&self) -> Cow<'_, Array2<f64>> {
+        Cow::Borrowed(&self.potential_distribution)
+    }
+}
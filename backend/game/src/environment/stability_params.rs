@@ -0,0 +1,118 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+const DEFAULT_FRICTION: f64 = 0.0;
+const DEFAULT_MAX_MOVEMENT: f64 = 1.0e6;
+const DEFAULT_MAX_SUBSTEPS: usize = 16;
+
+/// 扩散的数值稳定性参数
+///
+/// - `friction`: 每步扩散后运动状态的衰减比例，取值范围为 [0, 1]，
+///   运动状态乘以 `1 - friction`；为 0 时运动状态只被势垒消耗。
+/// - `max_movement`: 运动状态模长的上限，超出（或出现非有限值）即视为数值发散，
+///   模长会被截断到上限。
+/// - `max_substeps`: 单步扩散最多细分的子步数，至少为 1。
+///   某个单元格的流出量超过自身摩尔数时，子步数逐次加倍，每个子步按比例缩小流动量；
+///   达到上限仍不稳定时，按顺序截断各条边的流出量。
+///
+/// ```
+/// use game::{CartesianVec2D, MapSize, Potential, StabilityParams, SubstanceDistribution};
+///
+/// let map_size = MapSize::new(Some(8), Some(8));
+/// let mut distribution = SubstanceDistribution::new("1/2".parse().unwrap(), map_size, None);
+/// let unit = &mut distribution.distribution_mut()[[4, 4]];
+/// unit.set_mole(100);
+/// unit.set_movement(CartesianVec2D::new(0.0, 10.0));
+/// let potential = Potential::new(map_size.as_tuple());
+/// let total_movement = |distribution: &SubstanceDistribution| -> f64 {
+///     distribution.distribution().iter().map(|unit| unit.movement().magnitude()).sum()
+/// };
+///
+/// // 摩擦使运动状态按比例衰减
+/// let mut free = distribution.clone();
+/// free.diffuse(&potential, None, None, &StabilityParams::default());
+/// let mut damped = distribution.clone();
+/// damped.diffuse(&potential, None, None, &StabilityParams::default().with_friction(0.5));
+/// assert!(total_movement(&free) > 0.0);
+/// assert!((total_movement(&damped) - 0.5 * total_movement(&free)).abs() < 1e-9);
+///
+/// // 模长超过上限的运动状态被截断，报告中列出这些单元格
+/// let stability = StabilityParams::default().with_max_movement(1.0);
+/// let report = distribution.diffuse(&potential, None, None, &stability);
+/// assert!(!report.clamped_movement_cells.is_empty());
+/// assert!(distribution
+///     .distribution()
+///     .iter()
+///     .all(|unit| unit.movement().magnitude() <= 1.0 + 1e-9));
+/// for &(row, col) in &report.clamped_movement_cells {
+///     let magnitude = distribution.distribution()[[row, col]].movement().magnitude();
+///     assert!((magnitude - 1.0).abs() < 1e-9);
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StabilityParams {
+    friction: f64,
+    max_movement: f64,
+    max_substeps: usize,
+}
+
+impl StabilityParams {
+    /// 使用可选的参数创建稳定性参数，未提供时使用默认值
+    pub fn new(
+        friction: Option<f64>,
+        max_movement: Option<f64>,
+        max_substeps: Option<usize>,
+    ) -> Self {
+        Self {
+            friction: friction.unwrap_or(DEFAULT_FRICTION).clamp(0.0, 1.0),
+            max_movement: max_movement.unwrap_or(DEFAULT_MAX_MOVEMENT).max(0.0),
+            max_substeps: max_substeps.unwrap_or(DEFAULT_MAX_SUBSTEPS).max(1),
+        }
+    }
+
+    /// 设置运动状态的衰减比例（可链式调用）
+    pub fn with_friction(mut self, friction: f64) -> Self {
+        self.friction = friction.clamp(0.0, 1.0);
+        self
+    }
+
+    /// 设置运动状态模长的上限（可链式调用）
+    pub fn with_max_movement(mut self, max_movement: f64) -> Self {
+        self.max_movement = max_movement.max(0.0);
+        self
+    }
+
+    /// 设置单步扩散最多细分的子步数（可链式调用）
+    pub fn with_max_substeps(mut self, max_substeps: usize) -> Self {
+        self.max_substeps = max_substeps.max(1);
+        self
+    }
+
+    pub fn friction(&self) -> f64 {
+        self.friction
+    }
+
+    pub fn max_movement(&self) -> f64 {
+        self.max_movement
+    }
+
+    pub fn max_substeps(&self) -> usize {
+        self.max_substeps
+    }
+}
+
+impl Default for StabilityParams {
+    fn default() -> Self {
+        Self::new(None, None, None)
+    }
+}
+
+impl fmt::Display for StabilityParams {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "StabilityParams(friction: {}, max_movement: {}, max_substeps: {})",
+            self.friction, self.max_movement, self.max_substeps
+        )
+    }
+}
@@ -0,0 +1,69 @@
+use serde::{Deserialize, Serialize};
+
+/// 报告中最多列出的单元格数
+const MAX_LISTED_CELLS: usize = 8;
+
+/// 一步扩散的数值稳定性报告
+///
+/// - `substeps`: 实际使用的子步数，为 1 表示无需细分。
+/// - `overdrawn_cells`: 细分到上限后流出量仍超过自身摩尔数、被截断的单元格 `(row, col)`。
+/// - `clamped_movement_cells`: 运动状态模长超过上限或出现非有限值、被截断的单元格 `(row, col)`。
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StabilityReport {
+    pub substeps: usize,
+    pub overdrawn_cells: Vec<(usize, usize)>,
+    pub clamped_movement_cells: Vec<(usize, usize)>,
+}
+
+impl StabilityReport {
+    /// 本步扩散是否无需任何细分或截断
+    pub fn is_stable(&self) -> bool {
+        self.substeps <= 1
+            && self.overdrawn_cells.is_empty()
+            && self.clamped_movement_cells.is_empty()
+    }
+
+    /// 通过 `tracing` 报告不稳定的情况
+    ///
+    /// ### 参数
+    /// - `label`: 出现不稳定的物质分布，用于日志。
+    pub(crate) fn trace(&self, label: &str) {
+        if self.substeps > 1 {
+            tracing::warn!(
+                "物质 {} 的扩散流出量超过单元格摩尔数，已细分为 {} 个子步",
+                label,
+                self.substeps
+            );
+        }
+        if !self.overdrawn_cells.is_empty() {
+            tracing::warn!(
+                "物质 {} 在细分到上限后仍有 {} 个单元格流出量超过摩尔数，已截断: {}",
+                label,
+                self.overdrawn_cells.len(),
+                Self::list_cells(&self.overdrawn_cells)
+            );
+        }
+        if !self.clamped_movement_cells.is_empty() {
+            tracing::warn!(
+                "物质 {} 有 {} 个单元格的运动状态发散，已截断: {}",
+                label,
+                self.clamped_movement_cells.len(),
+                Self::list_cells(&self.clamped_movement_cells)
+            );
+        }
+    }
+
+    /// 列出前若干个单元格，其余以数量代替
+    fn list_cells(cells: &[(usize, usize)]) -> String {
+        let listed = cells
+            .iter()
+            .take(MAX_LISTED_CELLS)
+            .map(|(row, col)| format!("({}, {})", row, col))
+            .collect::<Vec<_>>()
+            .join(", ");
+        match cells.len().checked_sub(MAX_LISTED_CELLS) {
+            Some(rest) if rest > 0 => format!("{} 等另外 {} 个", listed, rest),
+            _ => listed,
+        }
+    }
+}
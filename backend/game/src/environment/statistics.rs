@@ -0,0 +1,272 @@
+use crate::environment::hexagon::neighbour_relation::NeighbourRelation;
+use ndarray::{parallel::prelude::*, Array2, Axis};
+use rayon::slice::ParallelSliceMut;
+use serde::{Deserialize, Serialize};
+
+/// 一次遍历得到的汇总统计
+///
+/// - `count`: 单元格数。
+/// - `total`: 总和，对物质分布而言即总摩尔数。
+/// - `min` / `max` / `mean`: 最小值、最大值与平均值。
+/// - `variance`: 样本方差，单元格数不足 2 时为 0。
+/// - `entropy`: 把各单元格的非负值视为占总和的比例时的香农熵（以 e 为底），
+///   全部集中在一个单元格时为 0，均匀分布时为 `ln(count)`。
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct StatisticsSummary {
+    pub count: usize,
+    pub total: f64,
+    pub min: f64,
+    pub max: f64,
+    pub mean: f64,
+    pub variance: f64,
+    pub entropy: f64,
+}
+
+/// 等宽直方图
+///
+/// - `min` / `max`: 直方图覆盖的取值范围，最大值落在最后一个区间内。
+/// - `counts`: 每个区间中的单元格数。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Histogram {
+    pub min: f64,
+    pub max: f64,
+    pub counts: Vec<usize>,
+}
+
+impl Histogram {
+    /// 每个区间的宽度
+    pub fn bin_width(&self) -> f64 {
+        (self.max - self.min) / self.counts.len() as f64
+    }
+
+    /// 全部 `counts.len() + 1` 个区间边界
+    pub fn bin_edges(&self) -> Vec<f64> {
+        let width = self.bin_width();
+        (0..=self.counts.len())
+            .map(|index| self.min + width * index as f64)
+            .collect()
+    }
+}
+
+/// 汇总统计的并行累加器
+///
+/// 方差使用 Chan 等人的合并公式，各线程的部分结果可以按任意顺序合并。
+#[derive(Debug, Clone, Copy)]
+struct Accumulator {
+    count: usize,
+    total: f64,
+    min: f64,
+    max: f64,
+    mean: f64,
+    m2: f64,
+    /// 非负值之和，用于计算熵
+    positive_total: f64,
+    /// 非负值的 `x ln x` 之和，用于计算熵
+    positive_x_ln_x: f64,
+}
+
+impl Default for Accumulator {
+    fn default() -> Self {
+        Self {
+            count: 0,
+            total: 0.0,
+            min: f64::INFINITY,
+            max: f64::NEG_INFINITY,
+            mean: 0.0,
+            m2: 0.0,
+            positive_total: 0.0,
+            positive_x_ln_x: 0.0,
+        }
+    }
+}
+
+impl Accumulator {
+    fn push(mut self, value: f64) -> Self {
+        self.count += 1;
+        self.total += value;
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+        let delta = value - self.mean;
+        self.mean += delta / self.count as f64;
+        self.m2 += delta * (value - self.mean);
+        if value > 0.0 {
+            self.positive_total += value;
+            self.positive_x_ln_x += value * value.ln();
+        }
+        self
+    }
+
+    fn merge(self, other: Self) -> Self {
+        if self.count == 0 {
+            return other;
+        }
+        if other.count == 0 {
+            return self;
+        }
+
+        let count = self.count + other.count;
+        let delta = other.mean - self.mean;
+        Self {
+            count,
+            total: self.total + other.total,
+            min: self.min.min(other.min),
+            max: self.max.max(other.max),
+            mean: self.mean + delta * other.count as f64 / count as f64,
+            m2: self.m2
+                + other.m2
+                + delta * delta * (self.count as f64 * other.count as f64) / count as f64,
+            positive_total: self.positive_total + other.positive_total,
+            positive_x_ln_x: self.positive_x_ln_x + other.positive_x_ln_x,
+        }
+    }
+
+    fn finish(self) -> StatisticsSummary {
+        // H = -Σ p ln p，其中 p = x / S，化简为 ln S - Σ x ln x / S
+        let entropy = if self.positive_total > 0.0 {
+            (self.positive_total.ln() - self.positive_x_ln_x / self.positive_total).max(0.0)
+        } else {
+            0.0
+        };
+
+        StatisticsSummary {
+            count: self.count,
+            total: self.total,
+            min: self.min,
+            max: self.max,
+            mean: self.mean,
+            variance: if self.count > 1 {
+                self.m2 / (self.count - 1) as f64
+            } else {
+                0.0
+            },
+            entropy,
+        }
+    }
+}
+
+/// 并行地一次遍历图层，得到汇总统计
+pub(crate) fn summarize(layer: &Array2<f64>) -> StatisticsSummary {
+    layer
+        .par_iter()
+        .fold(Accumulator::default, |acc, &value| acc.push(value))
+        .reduce(Accumulator::default, Accumulator::merge)
+        .finish()
+}
+
+/// 并行排序后的全部取值
+pub(crate) fn sorted(layer: &Array2<f64>) -> Vec<f64> {
+    let mut values: Vec<f64> = layer.iter().copied().collect();
+    values.par_sort_unstable_by(f64::total_cmp);
+    values
+}
+
+/// 在已排序的取值上按线性插值计算分位数
+///
+/// ### 参数
+/// - `sorted`: 升序排列的取值，为空时返回 `NaN`。
+/// - `q`: 分位点，会被截断到 [0, 1]。
+pub(crate) fn quantile_of_sorted(sorted: &[f64], q: f64) -> f64 {
+    if sorted.is_empty() {
+        return f64::NAN;
+    }
+
+    let position = q.clamp(0.0, 1.0) * (sorted.len() - 1) as f64;
+    let lower = position.floor() as usize;
+    let upper = position.ceil() as usize;
+    sorted[lower] + (sorted[upper] - sorted[lower]) * (position - lower as f64)
+}
+
+/// 在已排序的非负取值上计算基尼系数
+///
+/// `G = Σ (2i - n - 1) x_i / (n Σ x)`，`i` 从 1 开始；总和为 0 时视为完全均匀，返回 0。
+/// 基尼系数只对非负取值有意义，取值为空或存在负值时返回 `None`。
+pub(crate) fn gini_of_sorted(sorted: &[f64]) -> Option<f64> {
+    if sorted.first().is_none_or(|&min| min < 0.0) {
+        return None;
+    }
+
+    let n = sorted.len() as f64;
+    let (weighted, total) = sorted
+        .par_iter()
+        .enumerate()
+        .map(|(index, &value)| ((2.0 * (index + 1) as f64 - n - 1.0) * value, value))
+        .reduce(|| (0.0, 0.0), |a, b| (a.0 + b.0, a.1 + b.1));
+
+    if total <= 0.0 {
+        Some(0.0)
+    } else {
+        Some(weighted / (n * total))
+    }
+}
+
+/// 等宽直方图，区间范围取图层的最小、最大值
+pub(crate) fn histogram(layer: &Array2<f64>, bins: usize, min: f64, max: f64) -> Histogram {
+    let bins = bins.max(1);
+    let span = max - min;
+    let counts = layer
+        .par_iter()
+        .fold(
+            || vec![0usize; bins],
+            |mut counts, &value| {
+                let index = if span > 0.0 {
+                    (((value - min) / span * bins as f64) as usize).min(bins - 1)
+                } else {
+                    0
+                };
+                counts[index] += 1;
+                counts
+            },
+        )
+        .reduce(
+            || vec![0usize; bins],
+            |mut a, b| {
+                a.iter_mut().zip(b).for_each(|(a, b)| *a += b);
+                a
+            },
+        );
+
+    Histogram { min, max, counts }
+}
+
+/// 六边形网格上的 Moran's I 空间自相关系数
+///
+/// 每个单元格与其 6 个邻居（地图上下、左右环绕）的权重为 1，其余为 0，
+/// 因此 `I = Σ_i z_i Σ_j z_j / (6 Σ_i z_i²)`，`z` 为与均值之差。
+/// 取值接近 1 表示高值与高值聚集，接近 0 表示随机分布，为负表示高低交错；
+/// 图层为常数时返回 0。
+pub(crate) fn morans_i(layer: &Array2<f64>, mean: f64) -> f64 {
+    let (height, width) = layer.dim();
+    let shifts = NeighbourRelation::ALL.map(NeighbourRelation::displacement);
+
+    let (cross, squares) = layer
+        .axis_iter(Axis(0))
+        .into_par_iter()
+        .enumerate()
+        .map(|(row, values)| {
+            values
+                .iter()
+                .enumerate()
+                .fold((0.0, 0.0), |(cross, squares), (col, &value)| {
+                    let deviation = value - mean;
+                    let neighbours: f64 = shifts
+                        .iter()
+                        .map(|shift| {
+                            let y = (row as isize + shift.dy()).rem_euclid(height as isize);
+                            let x = (col as isize + shift.dx()).rem_euclid(width as isize);
+                            layer[[y as usize, x as usize]] - mean
+                        })
+                        .sum();
+                    (
+                        cross + deviation * neighbours,
+                        squares + deviation * deviation,
+                    )
+                })
+        })
+        .reduce(|| (0.0, 0.0), |a, b| (a.0 + b.0, a.1 + b.1));
+
+    if squares <= f64::EPSILON {
+        0.0
+    } else {
+        cross / (shifts.len() as f64 * squares)
+    }
+}
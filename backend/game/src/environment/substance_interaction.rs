@@ -0,0 +1,126 @@
+use crate::environment::coupling_params::CouplingParams;
+use crate::shared::{property::Property, subtance_type::SubstanceType};
+use ndarray::{Array2, ArrayViewMut1};
+
+/// 单元格内多物质交互的预计算表
+///
+/// 每一步耦合扩散开始前，根据参与交互的物质（已按固定顺序排列）构建一次，
+/// 之后在所有单元格上并行复用，避免逐格重复计算属性值与相似度。
+#[derive(Debug, Clone)]
+pub(crate) struct SubstanceInteraction {
+    /// 各物质的密度
+    densities: Vec<f64>,
+    /// 物质两两之间的汉明比值 η
+    hamming_ratios: Array2<f64>,
+    /// 已解析为下标的反应规则：(反应物下标, 生成物下标, 有效速率)
+    reactions: Vec<(usize, usize, f64)>,
+    mixing_limit: f64,
+    displacement_rate: f64,
+}
+
+impl SubstanceInteraction {
+    /// 构建交互预计算表
+    ///
+    /// ### 参数
+    /// - `substance_types`: 参与交互的物质，下标与单元格内摩尔数数组的下标一一对应。
+    /// - `params`: 耦合扩散参数。
+    ///
+    /// ### 返回值
+    /// 返回预计算表。引用了未参与交互物质的反应规则会被忽略。
+    pub(crate) fn new(substance_types: &[SubstanceType], params: &CouplingParams) -> Self {
+        let count = substance_types.len();
+
+        let densities = substance_types
+            .iter()
+            .map(|st| Property::calculate_property(Property::Density, st))
+            .collect();
+
+        let hamming_ratios = Array2::from_shape_fn((count, count), |(a, b)| {
+            substance_types[a].hamming_ratio(&substance_types[b])
+        });
+
+        let index_of = |target: &SubstanceType| substance_types.iter().position(|st| st == target);
+        let reactions = params
+            .reaction_rules()
+            .iter()
+            .filter_map(|rule| {
+                let reactant = index_of(rule.reactant())?;
+                let product = index_of(rule.product())?;
+                // 性质越接近的物质越容易相互转化
+                let rate = rule.rate() * (1.0 - hamming_ratios[[reactant, product]]);
+                Some((reactant, product, rate))
+            })
+            .collect();
+
+        Self {
+            densities,
+            hamming_ratios,
+            reactions,
+            mixing_limit: params.mixing_limit(),
+            displacement_rate: params.displacement_rate(),
+        }
+    }
+
+    /// 计算单元格内各物质被更致密物质置换出去的摩尔数
+    ///
+    /// 对物质 a，若单元格中存在密度更大的物质 b，则 a 可占据的份额为
+    /// `mixing_limit + (1 - mixing_limit) * (1 - η(a, b))`，取所有 b 中的最小值；
+    /// 超出份额的摩尔数按 `displacement_rate` 比例被挤出，即较轻的物质让位，致密物质沉积下来。
+    ///
+    /// ### 参数
+    /// - `moles`: 单元格内各物质的摩尔数，被置换的部分会直接从中扣除。
+    /// - `displaced`: 输出参数，写入各物质被置换出去的摩尔数。
+    pub(crate) fn displace(
+        &self,
+        moles: &mut ArrayViewMut1<usize>,
+        displaced: &mut ArrayViewMut1<usize>,
+    ) {
+        let total: usize = moles.iter().sum();
+        if total == 0 {
+            displaced.fill(0);
+            return;
+        }
+
+        for a in 0..moles.len() {
+            let allowed_share = (0..moles.len())
+                .filter(|&b| moles[b] > 0 && self.densities[b] > self.densities[a])
+                .map(|b| {
+                    self.mixing_limit
+                        + (1.0 - self.mixing_limit) * (1.0 - self.hamming_ratios[[a, b]])
+                })
+                .fold(1.0, f64::min);
+
+            let allowed = total as f64 * allowed_share;
+            let excess = (moles[a] as f64 - allowed).max(0.0);
+            displaced[a] = ((excess * self.displacement_rate).floor() as usize).min(moles[a]);
+        }
+
+        for a in 0..moles.len() {
+            moles[a] -= displaced[a];
+        }
+    }
+
+    /// 在单元格内按顺序执行所有反应规则
+    ///
+    /// ### 参数
+    /// - `moles`: 单元格内各物质的摩尔数，反应结果直接写回。
+    pub(crate) fn react(&self, moles: &mut ArrayViewMut1<usize>) {
+        for &(reactant, product, rate) in &self.reactions {
+            let converted = (moles[reactant] as f64 * rate).floor() as usize;
+            moles[reactant] -= converted;
+            moles[product] += converted;
+        }
+    }
+
+    /// 将置换出去的摩尔数平均分到 6 条边上，余数依次分给靠前的边
+    ///
+    /// ### 参数
+    /// - `displaced`: 被置换出去的摩尔数。
+    /// - `edge_index`: 边在 `NeighbourRelation::ALL` 中的下标。
+    ///
+    /// ### 返回值
+    /// 返回沿该边流出的摩尔数，6 条边之和恰好等于 `displaced`。
+    pub(crate) fn edge_share(displaced: usize, edge_index: usize) -> usize {
+        displaced / 6 + usize::from(edge_index < displaced % 6)
+    }
+}
@@ -0,0 +1,139 @@
+use crate::environment::coupling_params::SynthesisParams;
+use crate::shared::substance_registry::SubstanceRegistry;
+use crate::shared::{property::Property, subtance_type::SubstanceType};
+use ndarray::{Array2, ArrayViewMut1};
+
+/// 一对反应物的合成结果
+#[derive(Debug, Clone, Copy)]
+struct PairProduct {
+    /// 生成物在物质列表中的下标
+    product: usize,
+    /// 有效合成速率
+    rate: f64,
+    /// 每摩尔反应进度对应的生成物摩尔数，即 `(M_A + M_B) / M_C`
+    mass_factor: f64,
+}
+
+/// 单元格内物质合成的预计算表
+///
+/// 物质两两组合的生成物只与物质类型有关，因此在每一步开始前统一计算，
+/// 之后在所有单元格上并行复用。
+#[derive(Debug, Clone)]
+pub(crate) struct SubstanceSynthesis {
+    /// `[[a, b]]` 为以 a 为目标物质、b 为配对物质时的合成结果
+    pair_products: Array2<Option<PairProduct>>,
+    max_reactants: usize,
+}
+
+impl SubstanceSynthesis {
+    /// 构建合成预计算表
+    ///
+    /// ### 参数
+    /// - `substance_types`: 现有物质，下标与单元格内摩尔数数组的下标一一对应；
+    ///   尚不存在的生成物会被追加到末尾，数量受 `max_substances` 限制。
+    /// - `params`: 物质合成参数。
+    ///
+    /// ### 返回值
+    /// 返回预计算表。生成物因种类上限无法加入的组合不会发生合成。
+    pub(crate) fn new(substance_types: &mut Vec<SubstanceType>, params: &SynthesisParams) -> Self {
+        let existing = substance_types.len();
+        // 生成物的分母同时受合成参数与物质空间精密度的限制
+        let max_denominator = params
+            .max_denominator()
+            .min(SubstanceRegistry::max_denominator());
+
+        let molar_masses: Vec<f64> = substance_types
+            .iter()
+            .map(|st| Property::calculate_property(Property::MolarMass, st))
+            .collect();
+        let fluidities: Vec<f64> = substance_types
+            .iter()
+            .map(|st| Property::calculate_property(Property::Fluidity, st))
+            .collect();
+
+        let mut pair_products = Array2::from_elem((existing, existing), None);
+        for target in 0..existing {
+            for partner in (0..existing).filter(|&partner| partner != target) {
+                let product_type = substance_types[target]
+                    .combine(&substance_types[partner])
+                    .limit_denominator(max_denominator);
+
+                let product = match substance_types.iter().position(|st| *st == product_type) {
+                    Some(index) => index,
+                    None if substance_types.len() < params.max_substances() => {
+                        substance_types.push(product_type);
+                        substance_types.len() - 1
+                    }
+                    None => continue,
+                };
+
+                // 性质越接近、流动性越强，两种物质越容易相遇并发生合成
+                let eta = substance_types[target].hamming_ratio(&substance_types[partner]);
+                let rate =
+                    params.base_rate() * (1.0 - eta) * fluidities[target] * fluidities[partner];
+
+                let product_molar_mass =
+                    Property::calculate_property(Property::MolarMass, &product_type);
+                let mass_factor =
+                    (molar_masses[target] + molar_masses[partner]) / product_molar_mass;
+
+                pair_products[[target, partner]] = Some(PairProduct {
+                    product,
+                    rate,
+                    mass_factor,
+                });
+            }
+        }
+
+        Self {
+            pair_products,
+            max_reactants: params.max_reactants(),
+        }
+    }
+
+    /// 在单元格内执行物质合成
+    ///
+    /// 1. 选出摩尔数最多的前 Q 种物质，按摩尔数从多到少排列。
+    /// 2. 依次以靠前的物质为目标物质 A，与其后的物质 B 两两合成。
+    /// 3. 反应进度为 `min(n_A, n_B) * rate`，A 与 B 各消耗该摩尔数，
+    ///    生成物按摩尔质量守恒换算为 `进度 * (M_A + M_B) / M_C` 摩尔。
+    ///
+    /// 摩尔数为整数，生成物只写入整数部分，不足一摩尔的部分累积在 `remainders` 中，
+    /// 凑满一摩尔后再写入，因此摩尔数与余量合计的摩尔质量守恒。
+    ///
+    /// ### 参数
+    /// - `moles`: 单元格内各物质的摩尔数，合成结果直接写回。
+    /// - `remainders`: 单元格内各物质尚未凑满一摩尔的生成物，取值位于 [0, 1)，结果直接写回。
+    pub(crate) fn synthesize(
+        &self,
+        moles: &mut ArrayViewMut1<usize>,
+        remainders: &mut ArrayViewMut1<f64>,
+    ) {
+        let existing = self.pair_products.nrows();
+
+        let mut candidates: Vec<usize> = (0..existing).filter(|&index| moles[index] > 0).collect();
+        candidates.sort_by(|&a, &b| moles[b].cmp(&moles[a]).then(a.cmp(&b)));
+        candidates.truncate(self.max_reactants);
+
+        for (position, &target) in candidates.iter().enumerate() {
+            for &partner in &candidates[position + 1..] {
+                let Some(pair) = self.pair_products[[target, partner]] else {
+                    continue;
+                };
+
+                let extent =
+                    (moles[target].min(moles[partner]) as f64 * pair.rate).floor() as usize;
+                if extent == 0 {
+                    continue;
+                }
+
+                moles[target] -= extent;
+                moles[partner] -= extent;
+                let produced = extent as f64 * pair.mass_factor + remainders[pair.product];
+                let whole = produced.floor();
+                remainders[pair.product] = produced - whole;
+                moles[pair.product] += whole as usize;
+            }
+        }
+    }
+}
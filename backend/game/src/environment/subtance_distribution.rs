@@ -1,40 +1,41 @@
 use crate::environment::{
-    diffuse_info::DiffuseInfo,
+    cartesian_vec_2d::CartesianVec2D,
+    diffusion_buffer::DiffusionBuffer,
     hexagon::{
-        hex_block::HexBlock, hex_coord::HexCoord, hex_unit::HexUnit,
-        indexed_unit_change::IndexedUnitChange, neighbour_relation::NeighbourRelation,
+        hex_coord::HexCoord, hex_unit::HexUnit, neighbour_relation::NeighbourRelation,
         unit_change::UnitChange,
     },
     map_size::MapSize,
     noise_params::NoiseParams,
     potential::Potential,
+    stability_params::StabilityParams,
+    stability_report::StabilityReport,
     t_indexed::Indexed,
     t_noise_generatable::NoiseGeneratable,
     t_statistical::Statistical,
 };
 use crate::shared::{property::Property, subtance_type::SubstanceType};
 use ndarray::{Array2, Zip};
-use noise::{NoiseFn, OpenSimplex};
 use rayon::prelude::*;
-use serde::Serialize;
-use std::array;
-use std::{
-    collections::HashMap,
-    hash::{Hash, Hasher},
-};
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::hash::{Hash, Hasher};
 
 const ENLARGE_FACTOR: usize = 255;
 
-#[derive(Debug, Clone, Serialize, Eq)]
-pub(crate) struct SubstanceDistribution {
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubstanceDistribution {
     substance_type: SubstanceType,
     distribution: Array2<HexUnit>,
     noise_params: NoiseParams,
+    /// 扩散计算复用的通量缓冲区与后台分布，不参与序列化
+    #[serde(skip)]
+    buffer: DiffusionBuffer,
 }
 
 /// 字段基本操作
 impl SubstanceDistribution {
-    pub(crate) fn new(
+    pub fn new(
         substance_type: SubstanceType,
         map_size: MapSize,
         noise_params: Option<NoiseParams>,
@@ -46,18 +47,23 @@ impl SubstanceDistribution {
             substance_type,
             distribution,
             noise_params,
+            buffer: DiffusionBuffer::default(),
         }
     }
 
-    pub(crate) fn substance_type(&self) -> &SubstanceType {
+    pub fn substance_type(&self) -> &SubstanceType {
         &self.substance_type
     }
 
-    pub(crate) fn distribution(&self) -> &Array2<HexUnit> {
+    pub fn distribution(&self) -> &Array2<HexUnit> {
         &self.distribution
     }
 
-    pub(crate) fn noise_params(&self) -> &NoiseParams {
+    pub fn distribution_mut(&mut self) -> &mut Array2<HexUnit> {
+        &mut self.distribution
+    }
+
+    pub fn noise_params(&self) -> &NoiseParams {
         &self.noise_params
     }
 }
@@ -65,158 +71,270 @@ impl SubstanceDistribution {
 /// 扩散逻辑
 impl SubstanceDistribution {
     /// 对整个网格执行扩散逻辑的主函数。
-    /// 1. 使用 `compute_changes` 并行计算所有单元格及其邻居的变化量。
-    /// 2. 使用 `apply_changes` 串行地将变化量应用到分布中，从而更新每个单元格的状态。
-    pub(crate) fn diffuse(&mut self, now_potential: &Potential) {
-        // 1. 并行计算变化量：获取每个格子和其邻居的变化结果。
-        let changes = self.compute_changes(now_potential);
-
-        // 2. 串行应用变化量：将变化写入 self.distribution 中，完成分布的更新。
-        self.apply_changes(changes);
+    /// 1. 使用 `compute_fluxes` 并行计算每个单元格沿 6 条边的流出通量，写入预分配的通量缓冲区。
+    ///    若有单元格的流出量超过自身摩尔数，子步数逐次加倍后重新试算，直到稳定或达到上限。
+    /// 2. 对每个子步：使用 `gather_fluxes` 并行汇总每个单元格的流入与流出通量，写入后台分布，
+    ///    再交换前后台分布。达到上限仍不稳定时，先用 `clamp_outflows` 截断流出量。
+    /// 3. 使用 `damp_movement` 按摩擦系数衰减运动状态，并截断发散的运动状态。
+    ///
+    /// 子步之间势能场强保持不变。
+    ///
+    /// `fluidity_factor` 为可选的逐单元格流动性系数（例如由湿度换算而来），
+    /// 与物质本身的流动性相乘后截断到 [0, 1]。
+    ///
+    /// `gradient` 为可选的势能梯度场（见 `Potential::gradient`），提供时以 `-∇φ` 为驱动力，
+    /// 否则比较三对相对邻居的势能差。
+    ///
+    /// ### 返回值
+    /// 返回本步的稳定性报告，不稳定的情况同时通过 `tracing` 输出警告。
+    ///
+    /// ### 示例
+    /// 流动性系数很大时流动性被截断为 1，各条边的流出量分别取整后之和超过单元格的摩尔数，
+    /// 扩散自动细分为多个子步，总摩尔数保持不变：
+    ///
+    /// ```
+    /// use game::{CartesianVec2D, MapSize, Potential, StabilityParams, Statistical,
+    ///     SubstanceDistribution};
+    /// use ndarray::Array2;
+    ///
+    /// let map_size = MapSize::new(Some(8), Some(8));
+    /// let mut distribution = SubstanceDistribution::new("1/2".parse().unwrap(), map_size, None);
+    /// let unit = &mut distribution.distribution_mut()[[4, 4]];
+    /// unit.set_mole(5);
+    /// unit.set_movement(CartesianVec2D::new(0.0, 1.0));
+    ///
+    /// let potential = Potential::new(map_size.as_tuple());
+    /// let fluidity_factor = Array2::from_elem(map_size.as_tuple(), 1.0e6);
+    /// let report = distribution.diffuse(
+    ///     &potential,
+    ///     Some(&fluidity_factor),
+    ///     None,
+    ///     &StabilityParams::default(),
+    /// );
+    ///
+    /// assert!(report.substeps > 1);
+    /// assert!(report.overdrawn_cells.is_empty());
+    /// assert_eq!(distribution.total(), 5.0);
+    /// ```
+    pub fn diffuse(
+        &mut self,
+        now_potential: &Potential,
+        fluidity_factor: Option<&Array2<f64>>,
+        gradient: Option<&Array2<CartesianVec2D>>,
+        stability: &StabilityParams,
+    ) -> StabilityReport {
+        self.buffer.ensure_shape(self.distribution.dim());
+
+        // 1. 并行计算通量：每个格子只写自己的 6 条边，互不冲突。不稳定时加倍子步数重新试算。
+        let mut substeps = 1;
+        let mut overdrawn = loop {
+            self.compute_fluxes(
+                now_potential,
+                fluidity_factor,
+                gradient,
+                1.0 / substeps as f64,
+            );
+            let overdrawn = self.overdrawn_cells();
+            if overdrawn.is_empty() || substeps >= stability.max_substeps() {
+                break overdrawn;
+            }
+            substeps = (substeps * 2).min(stability.max_substeps());
+        };
+
+        let mut report = StabilityReport {
+            substeps,
+            ..Default::default()
+        };
+
+        for substep in 0..substeps {
+            if substep > 0 {
+                self.compute_fluxes(
+                    now_potential,
+                    fluidity_factor,
+                    gradient,
+                    1.0 / substeps as f64,
+                );
+                overdrawn = self.overdrawn_cells();
+            }
+            if !overdrawn.is_empty() {
+                self.clamp_outflows();
+                report.overdrawn_cells.append(&mut overdrawn);
+            }
+
+            // 2. 并行汇总通量：每个格子只读邻居指向自己的边，写入后台分布。
+            self.gather_fluxes();
+
+            // 双缓冲交换：后台分布成为新的前台分布。
+            std::mem::swap(&mut self.distribution, self.buffer.back_mut());
+        }
+        report.overdrawn_cells.sort_unstable();
+        report.overdrawn_cells.dedup();
+
+        // 3. 摩擦衰减并截断发散的运动状态
+        report.clamped_movement_cells = self.damp_movement(stability);
+
+        if !report.is_stable() {
+            report.trace(&format!(
+                "{}/{}",
+                self.substance_type.numer(),
+                self.substance_type.denom()
+            ));
+        }
+        report
     }
 
-    /// 并行计算所有单元格及其邻居的变化量。
+    /// 并行计算所有单元格沿 6 条边的流出通量。
     ///
-    /// 返回值是一个 `Vec<(IndexedUnitChange, [IndexedUnitChange; 6])>`:
-    /// - `IndexedUnitChange`：表示中心单元格的变化量以及其行列坐标。
-    /// - `[IndexedUnitChange; 6]`：表示该中心格子6个邻居的变化量（固定数量），
-    ///   其中每个 `IndexedUnitChange` 中也包含行列坐标和变化信息。
+    /// 通量缓冲区的形状与分布一致，每个元素是 `[UnitChange; 6]`，
+    /// 按 `NeighbourRelation::ALL` 的顺序记录该单元格流向对应邻居的变化量。
     ///
     /// 整个过程：
-    /// - 使用 `indexed_iter()` 获取分布中的每个单元格及其索引 `(row_index, col_index)`。
-    /// - `par_bridge()` 将普通迭代器转换为并行迭代器，在多核环境下并行处理每个格子。
-    /// - 对每个单元格构造 `HexBlock<DiffuseInfo>`（中心+邻居），调用 `old_unit.diffuse(...)` 获得 `HexBlock<UnitChange>`.
-    /// - 将结果组装为 `(IndexedUnitChange, [IndexedUnitChange; 6])` 返回。
-    fn compute_changes(
-        &self,
+    /// - 物质本身的流动性只与物质类型相关，整个网格只计算一次，再按单元格乘以流动性系数。
+    /// - 使用 `Zip::indexed` 将通量缓冲区与分布打包，并行处理每个格子。
+    /// - 对每个单元格读取 6 个邻居的势能场强，调用 `old_unit.diffuse(...)` 得到流出通量；
+    ///   提供梯度场时改为调用 `old_unit.diffuse_with_force(...)`，驱动力为 `-∇φ`。
+    /// - `step_fraction` 为子步占整步的比例，流动性与传递的运动状态都按此比例缩小。
+    fn compute_fluxes(
+        &mut self,
         now_potential: &Potential,
-    ) -> Vec<(IndexedUnitChange, [IndexedUnitChange; 6])> {
-        self.distribution
-            .indexed_iter()
-            .par_bridge() // 并行化处理每个单元格
-            .map(|((row_index, col_index), old_unit)| {
-                // 为当前单元格构造扩散所需的上下文信息块
-                let (block_of_info, relations_map) =
-                    self.build_hex_block_of_info(row_index, col_index, now_potential, old_unit);
-
-                // 调用当前单元格的扩散方法，得到中心和邻居的变化量（HexBlock<UnitChange>）
-                let fluidity =
-                    Property::calculate_property(Property::Fluidity, &self.substance_type);
-                let block_of_change = old_unit.diffuse(fluidity, &block_of_info);
-
-                // 构建中心格子的变化信息
-                let center_change =
-                    IndexedUnitChange::new(row_index, col_index, *block_of_change.center());
-
-                // 将邻居变化量从 HashMap 转换为一个固定长度数组 [IndexedUnitChange; 6]
-                // 我们假定有且仅有6个邻居，使用 from_fn 创建数组
-                let neighbour_changes: [IndexedUnitChange; 6] = array::from_fn(|i| {
-                    let (relation, unit_change) = block_of_change
-                        .neighbors()
-                        .iter()
-                        .nth(i) // 使用索引 i 获取第 i 个元素
-                        .expect("邻居关系缺失！");
-                    let neighbour_coord = relations_map.get(relation).expect("邻居关系缺失！");
-                    IndexedUnitChange::new(neighbour_coord.y(), neighbour_coord.x(), *unit_change)
+        fluidity_factor: Option<&Array2<f64>>,
+        gradient: Option<&Array2<CartesianVec2D>>,
+        step_fraction: f64,
+    ) {
+        let map_size = self.distribution.dim();
+        let potentials = now_potential.distribution();
+        let fluidity = Property::calculate_property(Property::Fluidity, &self.substance_type);
+
+        Zip::indexed(self.buffer.flux_mut())
+            .and(&self.distribution)
+            .par_for_each(|(row_index, col_index), flux, old_unit| {
+                let current_coord = HexCoord::new(row_index, col_index);
+
+                // 按固定顺序读取邻居的势能场强
+                let neighbour_potentials = NeighbourRelation::ALL.map(|relation| {
+                    let neighbour_coord =
+                        current_coord.shift_within(relation.displacement(), map_size);
+                    potentials[[neighbour_coord.y(), neighbour_coord.x()]]
                 });
 
-                // 返回中心变化和6个邻居变化
-                (center_change, neighbour_changes)
-            })
-            .collect()
+                let cell_fluidity = fluidity_factor.map_or(fluidity, |factor| {
+                    (fluidity * factor[[row_index, col_index]]).clamp(0.0, 1.0)
+                }) * step_fraction;
+
+                *flux = match gradient {
+                    Some(gradient) => old_unit.diffuse_with_force(
+                        cell_fluidity,
+                        potentials[[row_index, col_index]],
+                        &neighbour_potentials,
+                        gradient[[row_index, col_index]].scale(-1.0),
+                    ),
+                    None => old_unit.diffuse(cell_fluidity, &neighbour_potentials),
+                };
+
+                if step_fraction < 1.0 {
+                    for edge in flux.iter_mut() {
+                        edge.set_movement_change(edge.movement_change().scale(step_fraction));
+                    }
+                }
+            });
     }
 
-    /// 根据给定的行列索引和当前势能场强，构建包含中心和邻居信息的 HexBlock<DiffuseInfo>。
+    /// 流出量超过自身摩尔数的单元格
     ///
-    /// 返回：
-    /// - `HexBlock<DiffuseInfo>`：中心单元+邻居单元的势能场强和状态信息构成的上下文块，用于扩散计算。
-    /// - `HashMap<NeighbourRelation, Coordinate>`：邻居关系到坐标的映射表，供后续获取邻居坐标使用。
-    fn build_hex_block_of_info(
-        &self,
-        row_index: usize,
-        col_index: usize,
-        now_potential: &Potential,
-        old_unit: &HexUnit,
-    ) -> (HexBlock<DiffuseInfo>, HashMap<NeighbourRelation, HexCoord>) {
-        // 当前格子的坐标
-        let current_coord = HexCoord::new(row_index, col_index);
-
-        // 获取邻居关系与坐标的映射表（如 Relation::Degree60 -> (row, col)）
-        let relations_map = current_coord.get_relations_map::<NeighbourRelation>();
-
-        // 构建邻居单元的 DiffuseInfo Map
-        // DiffuseInfo 包含邻居单元的状态和它的势能场强
-        let neighbors_map: HashMap<NeighbourRelation, DiffuseInfo> = relations_map
-            .iter()
-            .map(|(relation, neighbour_coord)| {
-                // 获取邻居单元格状态
-                let neighbour_unit = self
-                    .distribution
-                    .get([neighbour_coord.y(), neighbour_coord.x()])
-                    .expect("邻居单元格越界");
-
-                // 获取邻居单元的势能场强值
-                let neighbour_potential = now_potential
-                    .distribution()
-                    .get([neighbour_coord.y(), neighbour_coord.x()])
-                    .expect("邻居势能场强分布越界");
-
-                // 构造邻居的 DiffuseInfo
-                (
-                    *relation,
-                    DiffuseInfo::new(*neighbour_unit, *neighbour_potential),
-                )
+    /// 每条边的流出量分别取整，流动性接近 1 时它们之和可能超过单元格的摩尔数。
+    fn overdrawn_cells(&self) -> Vec<(usize, usize)> {
+        self.buffer
+            .flux()
+            .indexed_iter()
+            .zip(self.distribution.iter())
+            .filter(|((_, edges), unit)| {
+                edges.iter().map(UnitChange::mole_change).sum::<isize>() > unit.mole() as isize
             })
-            .collect();
+            .map(|((index, _), _)| index)
+            .collect()
+    }
 
-        // 中心单元的势能场强值
-        let center_potential = now_potential
-            .distribution()
-            .get([row_index, col_index])
-            .expect("中心单元势能场强分布越界");
-        let center_info = DiffuseInfo::new(*old_unit, *center_potential);
+    /// 按 `NeighbourRelation::ALL` 的顺序从剩余摩尔数中扣除各条边的流出量，
+    /// 使每个单元格的流出量不超过自身摩尔数
+    fn clamp_outflows(&mut self) {
+        Zip::from(self.buffer.flux_mut())
+            .and(&self.distribution)
+            .par_for_each(|edges, unit| {
+                let mut remaining = unit.mole() as isize;
+                for edge in edges.iter_mut() {
+                    let mole_change = edge.mole_change().min(remaining);
+                    edge.set_mole_change(mole_change);
+                    remaining -= mole_change;
+                }
+            });
+    }
 
-        // 构建HexBlock：由中心信息和邻居信息共同组成扩散所需的上下文
-        let block_of_info = HexBlock::new(center_info, neighbors_map);
+    /// 按摩擦系数衰减运动状态，并截断发散的运动状态
+    ///
+    /// ### 返回值
+    /// 返回运动状态模长超过上限或出现非有限值的单元格。
+    fn damp_movement(&mut self, stability: &StabilityParams) -> Vec<(usize, usize)> {
+        let retain = 1.0 - stability.friction();
+        let max_movement = stability.max_movement();
 
-        (block_of_info, relations_map)
+        self.distribution
+            .indexed_iter_mut()
+            .filter_map(|(index, unit)| {
+                let movement = unit.movement().scale(retain);
+                let magnitude = movement.magnitude();
+                if !magnitude.is_finite() {
+                    unit.set_movement(CartesianVec2D::default());
+                    Some(index)
+                } else if magnitude > max_movement {
+                    unit.set_movement(movement.scale(max_movement / magnitude));
+                    Some(index)
+                } else {
+                    unit.set_movement(movement);
+                    None
+                }
+            })
+            .collect()
     }
 
-    /// 将并行计算得到的变化量应用到 distribution 上，从而完成对所有单元格状态的更新。
+    /// 并行汇总通量，将更新后的单元格写入后台分布。
     ///
-    /// 参数：
-    /// - `changes`: 来自 `compute_changes` 的返回值，一个包含多组 (中心变化, 邻居变化数组) 的列表。
-    ///
-    /// 流程：
-    /// 1. 使用 `fold` 将所有变化量汇总到 `change_dist` 数组中。
-    ///    `change_dist` 是一个与 `self.distribution` 同尺寸的二维数组，每个元素是 `UnitChange` 的累积。
-    /// 2. 遍历 `change_dist` 与 `self.distribution`，将累积的变化量应用到实际的单元格中。
-    fn apply_changes(&mut self, changes: Vec<(IndexedUnitChange, [IndexedUnitChange; 6])>) {
-        // 初始化 change_dist 为与 distribution 同大小的 UnitChange 数组，全部默认值
-        let change_dist = changes.iter().fold(
-            Array2::from_elem(self.distribution.dim(), UnitChange::default()),
-            |mut acc, (center_change, neighbour_changes)| {
-                // 应用中心变化量到 acc 中指定坐标处
-                acc[[center_change.y(), center_change.x()]]
-                    .accumulate_change(center_change.change());
-
-                // 应用邻居变化量
-                for nb_change in neighbour_changes.iter() {
-                    acc[[nb_change.y(), nb_change.x()]].accumulate_change(nb_change.change());
+    /// 对每个单元格：
+    /// 1. 将自身 6 条边的流出通量求和并取反，作为守恒性修正后的自身变化量。
+    /// 2. 对每个方向的邻居，读取其反方向边（即指向本单元格的边）的通量，作为流入量累加。
+    /// 3. 将累积的变化量应用到旧单元格的副本上，写入后台分布。
+    fn gather_fluxes(&mut self) {
+        let map_size = self.distribution.dim();
+        let (flux, back) = self.buffer.split_mut();
+
+        Zip::indexed(back).and(&self.distribution).par_for_each(
+            |(row_index, col_index), next_unit, old_unit| {
+                let current_coord = HexCoord::new(row_index, col_index);
+
+                // 自身流出量求和后取反，保持守恒性
+                let mut change = flux[[row_index, col_index]].iter().fold(
+                    UnitChange::default(),
+                    |mut acc, outflow| {
+                        acc.accumulate_change(outflow);
+                        acc
+                    },
+                );
+                change.set_mole_change(-change.mole_change());
+                change.set_movement_change(change.movement_change().scale(-1.0));
+
+                // 累加每个邻居沿反方向边流入本单元格的通量
+                for relation in NeighbourRelation::ALL {
+                    let neighbour_coord =
+                        current_coord.shift_within(relation.displacement(), map_size);
+                    change.accumulate_change(
+                        &flux[[neighbour_coord.y(), neighbour_coord.x()]]
+                            [relation.opposite().index()],
+                    );
                 }
 
-                acc
+                *next_unit = *old_unit;
+                next_unit.fit_change(change);
             },
         );
-
-        // 将所有计算出的变更应用到 self.distribution
-        // 使用 Zip 将 distribution 与 change_dist 对应位置打包在一起
-        Zip::from(&mut self.distribution)
-            .and(&change_dist)
-            .for_each(|unit, change| {
-                // 每个单元格应用对应的变化量
-                unit.fit_change(*change);
-            });
     }
 }
 
@@ -234,34 +352,70 @@ impl PartialEq for SubstanceDistribution {
     }
 }
 
+impl Eq for SubstanceDistribution {}
+
 impl NoiseGeneratable for SubstanceDistribution {
     fn generate_simplex_noise(&mut self) {
-        // 初始化Simplex噪声生成器，使用指定的种子确保噪声的可重复性
-        let simplex = OpenSimplex::new(self.noise_params.seed);
-        // 设置噪声频率，控制噪声的扩展和分布范围
-        let frequency = self.noise_params.scale * 2.0 * std::f64::consts::PI;
-
-        let width = self.distribution.shape()[1];
-        let height = self.distribution.shape()[0];
-
-        // 使用并行处理来加速矩阵中每个元素的噪声计算
-        Zip::indexed(&mut self.distribution).par_for_each(|(row_index, col_index), unit| {
-            // 计算当前元素在矩阵中的归一化坐标，确保噪声在整个地图范围内分布均匀
-            let normalized_colidx = col_index as f64 / width as f64;
-            let normalized_rowidx = row_index as f64 / height as f64;
-
-            // 将二维平面坐标映射到四维周期性空间，生成环绕噪声
-            // 通过sin和cos创建周期性，确保在边界处噪声无缝连接，实现地图的平滑环绕效果
-            let s = (normalized_colidx * frequency).sin(); // 横向环绕的sin分量
-            let c = (normalized_colidx * frequency).cos(); // 横向环绕的cos分量
-            let t = (normalized_rowidx * frequency).sin(); // 纵向环绕的sin分量
-            let u = (normalized_rowidx * frequency).cos(); // 纵向环绕的cos分量
-
-            // 生成噪声值，基于四维空间中的坐标，确保噪声在整个地图上连续
-            // 归一化噪声值到[0, 255]范围，便于后续使用或显示
-            let noise_value = simplex.get([s, c, t, u]);
-            unit.set_mole(((noise_value + 1.0) * 0.5 * ENLARGE_FACTOR as f64) as usize);
-        });
+        // 在环面上采样 [0, 1] 的噪声场，确保噪声在整个地图上连续且边界无缝
+        let noise = self.noise_params.sample_torus(self.distribution.dim());
+
+        match self.noise_params.mole_budget() {
+            // 按噪声值的比例分配总摩尔数
+            Some(budget) => {
+                let moles = Self::allocate_budget(&noise, budget);
+                Zip::from(&mut self.distribution)
+                    .and(&moles)
+                    .par_for_each(|unit, &mole| unit.set_mole(mole));
+            }
+            // 放大到 [0, 255] 范围作为摩尔数
+            None => {
+                Zip::from(&mut self.distribution)
+                    .and(&noise)
+                    .par_for_each(|unit, &noise_value| {
+                        unit.set_mole((noise_value * ENLARGE_FACTOR as f64) as usize);
+                    });
+            }
+        }
+    }
+}
+
+impl SubstanceDistribution {
+    /// 按权重将总摩尔数分配到各单元格
+    ///
+    /// 使用最大余数法：先按比例向下取整，再把剩余的摩尔数逐个分给小数部分最大的单元格，
+    /// 因此分配结果之和恰好等于 `budget`。权重全为零时按均匀权重分配。
+    ///
+    /// ### 参数
+    /// - `weights`: 非负权重，通常为噪声场。
+    /// - `budget`: 总摩尔数。
+    ///
+    /// ### 返回值
+    /// 返回与 `weights` 形状相同的摩尔数分布。
+    fn allocate_budget(weights: &Array2<f64>, budget: usize) -> Array2<usize> {
+        let total_weight: f64 = weights.iter().map(|weight| weight.max(0.0)).sum();
+        let shares = if total_weight > 0.0 {
+            weights.mapv(|weight| weight.max(0.0) / total_weight * budget as f64)
+        } else {
+            Array2::from_elem(weights.dim(), budget as f64 / weights.len().max(1) as f64)
+        };
+
+        let mut moles = shares.mapv(|share| share.floor() as usize);
+        let remainder = budget.saturating_sub(moles.sum());
+
+        // 小数部分从大到小排序，相同时按下标排序以保证确定性
+        let mut fractions: Vec<(usize, f64)> = shares
+            .iter()
+            .map(|share| share - share.floor())
+            .enumerate()
+            .collect();
+        fractions.par_sort_unstable_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+
+        let width = weights.ncols();
+        for &(index, _) in fractions.iter().take(remainder) {
+            moles[[index / width, index % width]] += 1;
+        }
+
+        moles
     }
 }
 
@@ -272,24 +426,31 @@ impl Statistical for SubstanceDistribution {
         self.distribution
             .par_iter()
             .min_by_key(|unit| unit.mole())
+            .copied()
             .expect("分布为空")
-            .clone()
     }
 
     fn max(&self) -> Self::Item {
         self.distribution
             .par_iter()
             .max_by_key(|unit| unit.mole())
+            .copied()
             .expect("分布为空")
-            .clone()
     }
 
     fn mean(&self) -> f64 {
+        if self.distribution.is_empty() {
+            return 0.0;
+        }
         let sum: usize = self.distribution.par_iter().map(|unit| unit.mole()).sum();
         sum as f64 / self.distribution.len() as f64
     }
 
     fn variance(&self) -> f64 {
+        // 与 `summary` 一致，单元格数不足 2 时方差为 0
+        if self.distribution.len() < 2 {
+            return 0.0;
+        }
         let mean = self.mean();
         let sum_of_squares: f64 = self
             .distribution
@@ -302,4 +463,27 @@ impl Statistical for SubstanceDistribution {
 
         sum_of_squares / (self.distribution.len() - 1) as f64
     }
+
+    fn scalar_layer(&self) -> Cow<'_, Array2<f64>> {
+        Cow::Owned(self.distribution.mapv(|unit| unit.mole() as f64))
+    }
+}
+
+/// 物质分布特有的统计
+impl SubstanceDistribution {
+    /// 总质量：总摩尔数乘以摩尔质量
+    pub fn total_mass(&self) -> f64 {
+        let moles: usize = self.distribution.par_iter().map(|unit| unit.mole()).sum();
+        moles as f64 * Property::calculate_property(Property::MolarMass, &self.substance_type)
+    }
+
+    /// 全部单元格运动向量模长的平均值
+    pub fn mean_movement_magnitude(&self) -> f64 {
+        let sum: f64 = self
+            .distribution
+            .par_iter()
+            .map(|unit| unit.movement().magnitude())
+            .sum();
+        sum / self.distribution.len() as f64
+    }
 }
@@ -1,4 +1,4 @@
 /// 生成特质，用于生成噪声地形
-pub(crate) trait NoiseGeneratable {
+pub trait NoiseGeneratable {
     fn generate_simplex_noise(&mut self);
 }
@@ -1,7 +1,113 @@
-pub(crate) trait Statistical {
+use crate::environment::statistics::{self, Histogram, StatisticsSummary};
+use ndarray::Array2;
+use std::borrow::Cow;
+
+/// 图层统计
+///
+/// 实现者只需给出 `min`、`max`、`mean`、`variance` 以及逐单元格的标量图层，
+/// 其余统计量都基于 `scalar_layer` 并行计算。`mean` 与 `variance` 须与 `summary` 一致：
+/// 图层为空时均值为 0，单元格数不足 2 时方差为 0。
+///
+/// ```
+/// use game::Statistical;
+/// use ndarray::{array, Array2};
+///
+/// let layer = array![[0.0, 1.0], [2.0, 3.0]];
+/// let summary = layer.summary();
+/// assert_eq!(summary.total, 6.0);
+/// assert_eq!(layer.quantile(0.5), 1.5);
+/// assert_eq!(layer.histogram(3).counts, vec![1, 1, 2]);
+/// assert!(layer.gini().unwrap() > 0.0);
+///
+/// // 含负值的图层（例如势能场强）没有基尼系数，空图层的分位数为 NaN
+/// assert_eq!(array![[-1.0, 1.0]].gini(), None);
+/// assert!(Array2::<f64>::zeros((0, 3)).quantile(0.5).is_nan());
+///
+/// // 空图层的均值、只有一个单元格的图层的方差都为 0
+/// assert_eq!(Statistical::mean(&Array2::<f64>::zeros((0, 3))), 0.0);
+/// assert_eq!(array![[4.0]].variance(), 0.0);
+/// ```
+pub trait Statistical {
     type Item;
     fn min(&self) -> Self::Item;
     fn max(&self) -> Self::Item;
     fn mean(&self) -> f64;
     fn variance(&self) -> f64;
+
+    /// 逐单元格的标量图层，形状为 `(height, width)`
+    fn scalar_layer(&self) -> Cow<'_, Array2<f64>>;
+
+    /// 一次并行遍历得到总和、极值、均值、方差与熵
+    fn summary(&self) -> StatisticsSummary {
+        statistics::summarize(&self.scalar_layer())
+    }
+
+    /// 全部单元格之和
+    fn total(&self) -> f64 {
+        self.summary().total
+    }
+
+    /// 按线性插值计算 `q` 分位数，`q` 会被截断到 [0, 1]；图层为空时返回 `NaN`
+    fn quantile(&self, q: f64) -> f64 {
+        self.quantiles(&[q])[0]
+    }
+
+    /// 一次排序计算多个分位数
+    fn quantiles(&self, qs: &[f64]) -> Vec<f64> {
+        let sorted = statistics::sorted(&self.scalar_layer());
+        qs.iter()
+            .map(|&q| statistics::quantile_of_sorted(&sorted, q))
+            .collect()
+    }
+
+    /// 在最小值与最大值之间划分 `bins` 个等宽区间的直方图
+    fn histogram(&self, bins: usize) -> Histogram {
+        let layer = self.scalar_layer();
+        let summary = statistics::summarize(&layer);
+        statistics::histogram(&layer, bins, summary.min, summary.max)
+    }
+
+    /// 把各单元格视为占总和的比例时的香农熵
+    fn entropy(&self) -> f64 {
+        self.summary().entropy
+    }
+
+    /// 基尼系数，0 表示完全均匀，接近 1 表示集中在少数单元格
+    ///
+    /// 图层为空或含有负值时返回 `None`。
+    fn gini(&self) -> Option<f64> {
+        statistics::gini_of_sorted(&statistics::sorted(&self.scalar_layer()))
+    }
+
+    /// 六边形网格上的 Moran's I 空间自相关系数
+    fn morans_i(&self) -> f64 {
+        let layer = self.scalar_layer();
+        let mean = statistics::summarize(&layer).mean;
+        statistics::morans_i(&layer, mean)
+    }
+}
+
+/// 标量图层（地形、势能场强等）的统计
+impl Statistical for Array2<f64> {
+    type Item = f64;
+
+    fn min(&self) -> Self::Item {
+        self.iter().copied().fold(f64::INFINITY, f64::min)
+    }
+
+    fn max(&self) -> Self::Item {
+        self.iter().copied().fold(f64::NEG_INFINITY, f64::max)
+    }
+
+    fn mean(&self) -> f64 {
+        statistics::summarize(self).mean
+    }
+
+    fn variance(&self) -> f64 {
+        statistics::summarize(self).variance
+    }
+
+    fn scalar_layer(&self) -> Cow<'_, Array2<f64>> {
+        Cow::Borrowed(self)
+    }
 }
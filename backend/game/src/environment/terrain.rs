@@ -0,0 +1,139 @@
+use crate::environment::{
+    map_size::MapSize, t_noise_generatable::NoiseGeneratable, terrain_params::TerrainParams,
+};
+use ndarray::{Array2, Zip};
+use serde::{Deserialize, Serialize};
+use std::f64::consts::PI;
+
+/// 地形：与物质分布并存的标量图层
+///
+/// - `altitude`: 海拔，静态图层，取值范围为 [0, `max_altitude`]，叠加到势能场强上。
+/// - `humidity`: 湿度，静态图层，取值范围为 [0, 1]，影响物质的流动性。
+/// - `base_temperature`: 不含季节变化的温度，静态图层，海拔越高温度越低。
+/// - `temperature`: 当前温度，动态图层，在 `base_temperature` 上叠加季节变化。
+///   仅作为输出图层供指标统计读取，不参与势能场强与扩散的计算。
+/// - `fluidity_factor`: 由湿度换算得到的流动性系数，在生成时预先计算。
+/// - `tick`: 已经推进的步数，决定当前所处的季节。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Terrain {
+    params: TerrainParams,
+    altitude: Array2<f64>,
+    humidity: Array2<f64>,
+    base_temperature: Array2<f64>,
+    temperature: Array2<f64>,
+    fluidity_factor: Array2<f64>,
+    tick: usize,
+}
+
+impl Terrain {
+    /// 按地形参数生成地形
+    ///
+    /// ### 参数
+    /// - `map_size`: 地图尺寸，与物质分布的尺寸一致。
+    /// - `params`: 地形参数。
+    ///
+    /// ### 返回值
+    /// 返回已经完成噪声生成、处于第 0 步的地形。
+    pub fn new(map_size: MapSize, params: TerrainParams) -> Self {
+        let dim = map_size.as_tuple();
+        let mut terrain = Self {
+            params,
+            altitude: Array2::zeros(dim),
+            humidity: Array2::zeros(dim),
+            base_temperature: Array2::zeros(dim),
+            temperature: Array2::zeros(dim),
+            fluidity_factor: Array2::ones(dim),
+            tick: 0,
+        };
+        terrain.generate_simplex_noise();
+        terrain
+    }
+
+    pub fn params(&self) -> &TerrainParams {
+        &self.params
+    }
+
+    pub fn altitude(&self) -> &Array2<f64> {
+        &self.altitude
+    }
+
+    pub fn humidity(&self) -> &Array2<f64> {
+        &self.humidity
+    }
+
+    /// 获取当前温度图层
+    ///
+    /// 温度只是随季节变化的观测量，势能场强与流动性均不读取它。
+    pub fn temperature(&self) -> &Array2<f64> {
+        &self.temperature
+    }
+
+    pub fn fluidity_factor(&self) -> &Array2<f64> {
+        &self.fluidity_factor
+    }
+
+    pub fn tick(&self) -> usize {
+        self.tick
+    }
+}
+
+/// 季节变化
+impl Terrain {
+    /// 推进一步，并按新的季节更新温度图层
+    pub fn advance(&mut self) {
+        self.tick += 1;
+        self.update_temperature();
+    }
+
+    /// 计算当前步的季节温度偏移
+    ///
+    /// ### 返回值
+    /// 返回 `amplitude × sin(2π × tick / season_length)`。
+    pub fn seasonal_offset(&self) -> f64 {
+        let phase =
+            (self.tick % self.params.season_length()) as f64 / self.params.season_length() as f64;
+        self.params.seasonal_amplitude() * (2.0 * PI * phase).sin()
+    }
+
+    /// 用静态温度加上季节偏移更新当前温度
+    fn update_temperature(&mut self) {
+        let offset = self.seasonal_offset();
+        Zip::from(&mut self.temperature)
+            .and(&self.base_temperature)
+            .par_for_each(|temperature, &base| *temperature = base + offset);
+    }
+}
+
+impl NoiseGeneratable for Terrain {
+    fn generate_simplex_noise(&mut self) {
+        let dim = self.altitude.dim();
+        let params = &self.params;
+
+        // 海拔：噪声直接映射到 [0, max_altitude]
+        self.altitude = params.altitude_noise().sample_torus(dim) * params.max_altitude();
+
+        // 湿度：噪声本身即为 [0, 1]，并换算为流动性系数
+        self.humidity = params.humidity_noise().sample_torus(dim);
+        let sensitivity = params.humidity_sensitivity();
+        self.fluidity_factor = self
+            .humidity
+            .mapv(|humidity| 1.0 + sensitivity * (2.0 * humidity - 1.0));
+
+        // 温度：平均温度加上空间波动，再随海拔递减
+        let (mean, variation, lapse_rate) = (
+            params.mean_temperature(),
+            params.temperature_variation(),
+            params.lapse_rate(),
+        );
+        let mut base_temperature = params.temperature_noise().sample_torus(dim);
+        Zip::from(&mut base_temperature)
+            .and(&self.altitude)
+            .par_for_each(|temperature, &altitude| {
+                *temperature =
+                    mean + variation * (2.0 * *temperature - 1.0) - lapse_rate * altitude;
+            });
+        self.base_temperature = base_temperature;
+
+        self.update_temperature();
+    }
+}
@@ -0,0 +1,144 @@
+use crate::environment::noise_params::NoiseParams;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+const DEFAULT_MAX_ALTITUDE: f64 = 32.0;
+const DEFAULT_HUMIDITY_SENSITIVITY: f64 = 0.5;
+const DEFAULT_MEAN_TEMPERATURE: f64 = 15.0;
+const DEFAULT_TEMPERATURE_VARIATION: f64 = 10.0;
+const DEFAULT_LAPSE_RATE: f64 = 0.3;
+const DEFAULT_SEASONAL_AMPLITUDE: f64 = 10.0;
+const DEFAULT_SEASON_LENGTH: usize = 360;
+
+/// 地形参数
+///
+/// - `altitude_noise` / `humidity_noise` / `temperature_noise`: 各图层的噪声参数。
+/// - `max_altitude`: 海拔上限，海拔以势能场强的高度单位计，会乘以重力常数叠加到势能场强上。
+/// - `humidity_sensitivity`: 湿度对流动性的影响程度，取值范围为 [0, 1]，
+///   流动性乘以 `1 + sensitivity × (2 × humidity - 1)`。
+/// - `mean_temperature`: 全图平均温度。
+/// - `temperature_variation`: 温度在空间上的波动幅度。
+/// - `lapse_rate`: 每单位海拔的降温幅度。
+/// - `seasonal_amplitude`: 季节变化的振幅。
+/// - `season_length`: 一个季节周期包含的步数，至少为 1。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TerrainParams {
+    altitude_noise: NoiseParams,
+    humidity_noise: NoiseParams,
+    temperature_noise: NoiseParams,
+    max_altitude: f64,
+    humidity_sensitivity: f64,
+    mean_temperature: f64,
+    temperature_variation: f64,
+    lapse_rate: f64,
+    seasonal_amplitude: f64,
+    season_length: usize,
+}
+
+impl TerrainParams {
+    /// 使用可选的各图层噪声参数创建地形参数，未提供时使用默认值
+    pub fn new(
+        altitude_noise: Option<NoiseParams>,
+        humidity_noise: Option<NoiseParams>,
+        temperature_noise: Option<NoiseParams>,
+    ) -> Self {
+        Self {
+            altitude_noise: altitude_noise.unwrap_or_default(),
+            humidity_noise: humidity_noise.unwrap_or_default(),
+            temperature_noise: temperature_noise.unwrap_or_default(),
+            max_altitude: DEFAULT_MAX_ALTITUDE,
+            humidity_sensitivity: DEFAULT_HUMIDITY_SENSITIVITY,
+            mean_temperature: DEFAULT_MEAN_TEMPERATURE,
+            temperature_variation: DEFAULT_TEMPERATURE_VARIATION,
+            lapse_rate: DEFAULT_LAPSE_RATE,
+            seasonal_amplitude: DEFAULT_SEASONAL_AMPLITUDE,
+            season_length: DEFAULT_SEASON_LENGTH,
+        }
+    }
+
+    /// 设置海拔上限（可链式调用）
+    pub fn with_max_altitude(mut self, max_altitude: f64) -> Self {
+        self.max_altitude = max_altitude.max(0.0);
+        self
+    }
+
+    /// 设置湿度对流动性的影响程度（可链式调用）
+    pub fn with_humidity_sensitivity(mut self, humidity_sensitivity: f64) -> Self {
+        self.humidity_sensitivity = humidity_sensitivity.clamp(0.0, 1.0);
+        self
+    }
+
+    /// 设置温度的平均值、空间波动幅度与海拔降温幅度（可链式调用）
+    pub fn with_temperature(mut self, mean: f64, variation: f64, lapse_rate: f64) -> Self {
+        self.mean_temperature = mean;
+        self.temperature_variation = variation.max(0.0);
+        self.lapse_rate = lapse_rate;
+        self
+    }
+
+    /// 设置季节变化的振幅与周期（可链式调用）
+    pub fn with_seasons(mut self, amplitude: f64, length: usize) -> Self {
+        self.seasonal_amplitude = amplitude.max(0.0);
+        self.season_length = length.max(1);
+        self
+    }
+
+    pub fn altitude_noise(&self) -> &NoiseParams {
+        &self.altitude_noise
+    }
+
+    pub fn humidity_noise(&self) -> &NoiseParams {
+        &self.humidity_noise
+    }
+
+    pub fn temperature_noise(&self) -> &NoiseParams {
+        &self.temperature_noise
+    }
+
+    pub fn max_altitude(&self) -> f64 {
+        self.max_altitude
+    }
+
+    pub fn humidity_sensitivity(&self) -> f64 {
+        self.humidity_sensitivity
+    }
+
+    pub fn mean_temperature(&self) -> f64 {
+        self.mean_temperature
+    }
+
+    pub fn temperature_variation(&self) -> f64 {
+        self.temperature_variation
+    }
+
+    pub fn lapse_rate(&self) -> f64 {
+        self.lapse_rate
+    }
+
+    pub fn seasonal_amplitude(&self) -> f64 {
+        self.seasonal_amplitude
+    }
+
+    pub fn season_length(&self) -> usize {
+        self.season_length
+    }
+}
+
+impl Default for TerrainParams {
+    fn default() -> Self {
+        Self::new(None, None, None)
+    }
+}
+
+impl fmt::Display for TerrainParams {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match serde_json::to_string_pretty(&self) {
+            Ok(json_str) => write!(f, "{}", json_str),
+            Err(_) => write!(
+                f,
+                "TerrainParams(max_altitude: {}, season_length: {})",
+                self.max_altitude, self.season_length
+            ),
+        }
+    }
+}
@@ -4,7 +4,6 @@ use my_proc_macro::Literal;
 use once_cell::sync::Lazy;
 use share_and_commute::errors::context_error::ContextError;
 use std::sync::{Arc, RwLock};
-use thiserror::Error;
 use uuid::Uuid;
 
 pub type GameContextError = ContextError<GameContext>;
@@ -148,7 +147,7 @@ impl GameContext {
     /// ### 返回值
     /// 返回地图大小的 `MapSize` 对象。
     pub fn get_map_size() -> MapSize {
-        Self::get_optional_field(|ctx| ctx.map_size.clone(), GameContext::MAP_SIZE)
+        Self::get_optional_field(|ctx| ctx.map_size, GameContext::MAP_SIZE)
     }
 
     /// 获取文明 ID。
@@ -1,5 +1,95 @@
+//! 可计算社会的游戏逻辑
+//!
+//! 对外公开的类型统一从 crate 根导出，内部模块结构不属于公开接口。
+//!
+//! ```
+//! use game::{Property, SubstanceType};
+//!
+//! let substance_type: SubstanceType = "3/4".parse().unwrap();
+//! let molar_mass = Property::calculate_property(Property::MolarMass, &substance_type);
+//! assert!(molar_mass > 0.0 && molar_mass <= 1.0);
+//! ```
+
 // 以下概念是 component 的子概念
 // mod agent;
+mod codec;
 mod environment;
 pub mod game_context;
+mod metrics;
+mod persistence;
+mod render;
+mod replay;
 mod shared;
+mod telemetry;
+mod world_sync;
+
+// 物质
+pub use shared::substance_registry::{error_handling::SubstanceRegistryError, SubstanceRegistry};
+pub use shared::subtance::Substance;
+pub use shared::subtance_type::{error_handling::SubtanceTypeError, SubstanceType};
+
+// 属性
+pub use shared::property::{error_handling::PropertyParseError, Property};
+pub use shared::property_cache::PropertyCache;
+pub use shared::property_param::PropertyParam;
+pub use shared::property_registry::{
+    error_handling::PropertyRegistryError, PropertyCatalogue, PropertyDefinition, PropertyRegistry,
+};
+
+// 文件导入导出
+pub use codec::error_handling::CodecError;
+
+// 地形
+pub use environment::cartesian_vec_2d::CartesianVec2D;
+pub use environment::coupling_params::{CouplingParams, ReactionRule, SynthesisParams};
+pub use environment::diffusion_mode::DiffusionMode;
+pub use environment::hexagon::hex_unit::HexUnit;
+pub use environment::landscape::Landscape;
+pub use environment::map_size::MapSize;
+pub use environment::noise_params::{NoiseParams, NoiseVariant};
+pub use environment::potential::Potential;
+pub use environment::stability_params::StabilityParams;
+pub use environment::stability_report::StabilityReport;
+pub use environment::subtance_distribution::SubstanceDistribution;
+pub use environment::t_noise_generatable::NoiseGeneratable;
+pub use environment::terrain::Terrain;
+pub use environment::terrain_params::TerrainParams;
+
+// 指标
+pub use metrics::error_handling::MetricsError;
+pub use metrics::metric::{LayerStatistic, Metric, MetricSource};
+pub use metrics::recorder::{
+    DownsampledSeries, MetricsRecorder, SeriesEnvelope, SeriesQuery, SharedMetricsRecorder,
+};
+pub use metrics::snapshot::MetricsSnapshot;
+
+// 渲染
+pub use render::animation::HeatmapTarget;
+pub use render::color_map::ColorMap;
+pub use render::error_handling::RenderError;
+pub use render::heatmap::{Heatmap, HeatmapAnimation, HeatmapFrame, HeatmapRenderer};
+
+// 统计
+pub use environment::statistics::{Histogram, StatisticsSummary};
+pub use environment::t_statistical::Statistical;
+
+// 跨实例同步
+pub use world_sync::error_handling::WorldSyncError;
+pub use world_sync::world_cache::WorldCache;
+pub use world_sync::world_event::WorldEvent;
+
+// 日志
+pub use telemetry::tick_span;
+
+// 持久化
+pub use persistence::error_handling::PersistenceError;
+pub use persistence::world_archive::WorldArchive;
+
+// 事件日志与重放
+pub use replay::error_handling::ReplayError;
+pub use replay::event_log::{open_event_log, read_event_log, EventLog};
+pub use replay::recorded_world::RecordedWorld;
+pub use replay::replayer::{HashDivergence, ReplayReport, Replayer};
+pub use replay::sim_event::{EventLogEntry, SimEvent, WorldCommand};
+pub use replay::simulation_state::SimulationState;
+pub use replay::world_params::WorldParams;
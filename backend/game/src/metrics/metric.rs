@@ -0,0 +1,331 @@
+use crate::environment::landscape::Landscape;
+use crate::environment::statistics::{self, StatisticsSummary};
+use crate::environment::subtance_distribution::SubstanceDistribution;
+use crate::environment::t_statistical::Statistical;
+use crate::metrics::error_handling::MetricsError;
+use crate::shared::subtance_type::SubstanceType;
+use ndarray::Array2;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::cell::OnceCell;
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+/// 指标所统计的图层
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MetricSource {
+    /// 某种物质的摩尔数分布
+    Substance(SubstanceType),
+    /// 势能场强
+    Potential,
+    /// 地形海拔
+    Altitude,
+    /// 地形湿度
+    Humidity,
+    /// 地形当前温度
+    Temperature,
+}
+
+/// 图层上的统计量，与 `Statistical` 的方法一一对应
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LayerStatistic {
+    Min,
+    Max,
+    Mean,
+    Variance,
+    Total,
+    /// 分位数，取值范围为 [0, 1]
+    Quantile(f64),
+    Entropy,
+    /// 基尼系数，图层为空或含负值（例如势能场强）时为 `NaN`
+    Gini,
+    MoransI,
+}
+
+/// 可被记录的指标
+///
+/// 以 `统计量:图层` 的字符串形式序列化与解析，例如 `mean:1/2`、`q0.95:potential`、
+/// `morans_i:temperature`、`total_mass:3/4`、`substance_count`。
+///
+/// ```
+/// use game::Metric;
+///
+/// let metric: Metric = "q0.9:1/2".parse().unwrap();
+/// assert_eq!(metric.to_string(), "q0.9:1/2");
+/// assert!("median:potential".parse::<Metric>().is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum Metric {
+    /// 图层统计量
+    Layer(LayerStatistic, MetricSource),
+    /// 总质量：总摩尔数乘以摩尔质量
+    TotalMass(SubstanceType),
+    /// 运动向量模长的平均值
+    MeanMovement(SubstanceType),
+    /// 地貌中物质分布的数量
+    SubstanceCount,
+    /// 本世界中已被发现的物质数量
+    DiscoveredCount,
+}
+
+impl Metric {
+    /// 对地貌的当前状态采样一次，所统计的图层不存在时返回 `NaN`
+    pub fn sample(&self, landscape: &Landscape) -> f64 {
+        Sampler::new(landscape).sample(self)
+    }
+
+    /// 解析逗号分隔的指标列表，空项被忽略
+    ///
+    /// ```
+    /// use game::Metric;
+    ///
+    /// let metrics = Metric::parse_list("mean:1/2, gini:1/2,").unwrap();
+    /// assert_eq!(metrics.len(), 2);
+    /// assert!(Metric::parse_list("").unwrap().is_empty());
+    /// assert!(Metric::parse_list("mean:1/2,median:1/2").is_err());
+    /// ```
+    pub fn parse_list(names: &str) -> Result<Vec<Metric>, MetricsError> {
+        names
+            .split(',')
+            .filter(|name| !name.trim().is_empty())
+            .map(str::parse)
+            .collect()
+    }
+}
+
+impl fmt::Display for MetricSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MetricSource::Substance(substance_type) => {
+                write!(f, "{}/{}", substance_type.numer(), substance_type.denom())
+            }
+            MetricSource::Potential => write!(f, "potential"),
+            MetricSource::Altitude => write!(f, "altitude"),
+            MetricSource::Humidity => write!(f, "humidity"),
+            MetricSource::Temperature => write!(f, "temperature"),
+        }
+    }
+}
+
+impl FromStr for MetricSource {
+    type Err = MetricsError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "potential" => Ok(MetricSource::Potential),
+            "altitude" => Ok(MetricSource::Altitude),
+            "humidity" => Ok(MetricSource::Humidity),
+            "temperature" => Ok(MetricSource::Temperature),
+            other => other
+                .parse::<SubstanceType>()
+                .map(MetricSource::Substance)
+                .map_err(|_| MetricsError::InvalidMetric(s.to_string())),
+        }
+    }
+}
+
+impl fmt::Display for LayerStatistic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LayerStatistic::Min => write!(f, "min"),
+            LayerStatistic::Max => write!(f, "max"),
+            LayerStatistic::Mean => write!(f, "mean"),
+            LayerStatistic::Variance => write!(f, "variance"),
+            LayerStatistic::Total => write!(f, "total"),
+            LayerStatistic::Quantile(q) => write!(f, "q{}", q),
+            LayerStatistic::Entropy => write!(f, "entropy"),
+            LayerStatistic::Gini => write!(f, "gini"),
+            LayerStatistic::MoransI => write!(f, "morans_i"),
+        }
+    }
+}
+
+impl FromStr for LayerStatistic {
+    type Err = MetricsError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || MetricsError::InvalidMetric(s.to_string());
+        match s.trim() {
+            "min" => Ok(LayerStatistic::Min),
+            "max" => Ok(LayerStatistic::Max),
+            "mean" => Ok(LayerStatistic::Mean),
+            "variance" => Ok(LayerStatistic::Variance),
+            "total" => Ok(LayerStatistic::Total),
+            "entropy" => Ok(LayerStatistic::Entropy),
+            "gini" => Ok(LayerStatistic::Gini),
+            "morans_i" => Ok(LayerStatistic::MoransI),
+            other => {
+                let q = other
+                    .strip_prefix('q')
+                    .and_then(|q| q.parse::<f64>().ok())
+                    .ok_or_else(invalid)?;
+                if (0.0..=1.0).contains(&q) {
+                    Ok(LayerStatistic::Quantile(q))
+                } else {
+                    Err(invalid())
+                }
+            }
+        }
+    }
+}
+
+impl fmt::Display for Metric {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Metric::Layer(statistic, source) => write!(f, "{}:{}", statistic, source),
+            Metric::TotalMass(substance_type) => {
+                write!(f, "total_mass:{}", MetricSource::Substance(*substance_type))
+            }
+            Metric::MeanMovement(substance_type) => write!(
+                f,
+                "mean_movement:{}",
+                MetricSource::Substance(*substance_type)
+            ),
+            Metric::SubstanceCount => write!(f, "substance_count"),
+            Metric::DiscoveredCount => write!(f, "discovered_count"),
+        }
+    }
+}
+
+impl FromStr for Metric {
+    type Err = MetricsError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || MetricsError::InvalidMetric(s.to_string());
+        let substance = |source: &str| match source.parse::<MetricSource>() {
+            Ok(MetricSource::Substance(substance_type)) => Ok(substance_type),
+            _ => Err(invalid()),
+        };
+
+        match s.trim().split_once(':') {
+            None => match s.trim() {
+                "substance_count" => Ok(Metric::SubstanceCount),
+                "discovered_count" => Ok(Metric::DiscoveredCount),
+                _ => Err(invalid()),
+            },
+            Some(("total_mass", source)) => substance(source).map(Metric::TotalMass),
+            Some(("mean_movement", source)) => substance(source).map(Metric::MeanMovement),
+            Some((statistic, source)) => Ok(Metric::Layer(
+                statistic.parse().map_err(|_| invalid())?,
+                source.parse()?,
+            )),
+        }
+    }
+}
+
+impl TryFrom<String> for Metric {
+    type Error = MetricsError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<Metric> for String {
+    fn from(metric: Metric) -> Self {
+        metric.to_string()
+    }
+}
+
+/// 一次采样中某个图层的缓存，汇总统计与排序结果只计算一次
+struct LayerCache<'a> {
+    layer: Cow<'a, Array2<f64>>,
+    summary: OnceCell<StatisticsSummary>,
+    sorted: OnceCell<Vec<f64>>,
+}
+
+impl LayerCache<'_> {
+    fn summary(&self) -> &StatisticsSummary {
+        self.summary
+            .get_or_init(|| statistics::summarize(&self.layer))
+    }
+
+    fn sorted(&self) -> &[f64] {
+        self.sorted.get_or_init(|| statistics::sorted(&self.layer))
+    }
+
+    fn statistic(&self, statistic: LayerStatistic) -> f64 {
+        match statistic {
+            LayerStatistic::Min => self.summary().min,
+            LayerStatistic::Max => self.summary().max,
+            LayerStatistic::Mean => self.summary().mean,
+            LayerStatistic::Variance => self.summary().variance,
+            LayerStatistic::Total => self.summary().total,
+            LayerStatistic::Entropy => self.summary().entropy,
+            LayerStatistic::Quantile(q) => statistics::quantile_of_sorted(self.sorted(), q),
+            LayerStatistic::Gini => statistics::gini_of_sorted(self.sorted()).unwrap_or(f64::NAN),
+            LayerStatistic::MoransI => statistics::morans_i(&self.layer, self.summary().mean),
+        }
+    }
+}
+
+/// 对同一时刻的地貌采样多个指标，同一图层的中间结果在指标之间共享
+pub(crate) struct Sampler<'a> {
+    landscape: &'a Landscape,
+    layers: HashMap<MetricSource, Option<LayerCache<'a>>>,
+}
+
+impl<'a> Sampler<'a> {
+    pub(crate) fn new(landscape: &'a Landscape) -> Self {
+        Self {
+            landscape,
+            layers: HashMap::new(),
+        }
+    }
+
+    pub(crate) fn sample(&mut self, metric: &Metric) -> f64 {
+        let landscape = self.landscape;
+        match metric {
+            Metric::Layer(statistic, source) => self
+                .layers
+                .entry(*source)
+                .or_insert_with(|| {
+                    Self::layer(landscape, *source).map(|layer| LayerCache {
+                        layer,
+                        summary: OnceCell::new(),
+                        sorted: OnceCell::new(),
+                    })
+                })
+                .as_ref()
+                .map_or(f64::NAN, |cache| cache.statistic(*statistic)),
+            Metric::TotalMass(substance_type) => Self::distribution(landscape, substance_type)
+                .map_or(f64::NAN, |substance_dist| substance_dist.total_mass()),
+            Metric::MeanMovement(substance_type) => Self::distribution(landscape, substance_type)
+                .map_or(f64::NAN, |substance_dist| {
+                    substance_dist.mean_movement_magnitude()
+                }),
+            Metric::SubstanceCount => landscape.subtance_distributions().len() as f64,
+            Metric::DiscoveredCount => landscape.discovered().len() as f64,
+        }
+    }
+
+    fn distribution<'b>(
+        landscape: &'b Landscape,
+        substance_type: &SubstanceType,
+    ) -> Option<&'b SubstanceDistribution> {
+        landscape
+            .subtance_distributions()
+            .iter()
+            .find(|substance_dist| substance_dist.substance_type() == substance_type)
+    }
+
+    fn layer(landscape: &'a Landscape, source: MetricSource) -> Option<Cow<'a, Array2<f64>>> {
+        match source {
+            MetricSource::Substance(substance_type) => {
+                Self::distribution(landscape, &substance_type).map(Statistical::scalar_layer)
+            }
+            MetricSource::Potential => Some(landscape.potential().scalar_layer()),
+            MetricSource::Altitude => landscape
+                .terrain()
+                .map(|terrain| Cow::Borrowed(terrain.altitude())),
+            MetricSource::Humidity => landscape
+                .terrain()
+                .map(|terrain| Cow::Borrowed(terrain.humidity())),
+            MetricSource::Temperature => landscape
+                .terrain()
+                .map(|terrain| Cow::Borrowed(terrain.temperature())),
+        }
+    }
+}
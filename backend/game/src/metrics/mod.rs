@@ -0,0 +1,65 @@
+pub(crate) mod metric;
+pub(crate) mod recorder;
+pub(crate) mod snapshot;
+
+pub mod error_handling {
+    use crate::codec::error_handling::CodecError;
+    use std::fmt;
+
+    #[derive(Debug)]
+    pub enum MetricsError {
+        /// 无法解析的指标名称
+        InvalidMetric(String),
+        /// 记录器中没有该指标
+        UnknownMetric(String),
+        /// 导出失败
+        Codec(CodecError),
+        /// 写出 Parquet 失败
+        Parquet(parquet::errors::ParquetError),
+    }
+
+    impl fmt::Display for MetricsError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                MetricsError::InvalidMetric(name) => write!(f, "无法解析的指标: {}", name),
+                MetricsError::UnknownMetric(name) => write!(f, "未记录的指标: {}", name),
+                MetricsError::Codec(e) => write!(f, "导出指标失败: {}", e),
+                MetricsError::Parquet(e) => write!(f, "导出 Parquet 失败: {}", e),
+            }
+        }
+    }
+
+    impl std::error::Error for MetricsError {
+        fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+            match self {
+                MetricsError::Codec(e) => Some(e),
+                MetricsError::Parquet(e) => Some(e),
+                _ => None,
+            }
+        }
+    }
+
+    impl From<CodecError> for MetricsError {
+        fn from(e: CodecError) -> Self {
+            MetricsError::Codec(e)
+        }
+    }
+
+    impl From<parquet::errors::ParquetError> for MetricsError {
+        fn from(e: parquet::errors::ParquetError) -> Self {
+            MetricsError::Parquet(e)
+        }
+    }
+
+    impl From<arrow_schema::ArrowError> for MetricsError {
+        fn from(e: arrow_schema::ArrowError) -> Self {
+            MetricsError::Parquet(e.into())
+        }
+    }
+
+    impl From<std::io::Error> for MetricsError {
+        fn from(e: std::io::Error) -> Self {
+            MetricsError::Codec(CodecError::Io(e))
+        }
+    }
+}
@@ -0,0 +1,349 @@
+use crate::environment::landscape::Landscape;
+use crate::metrics::error_handling::MetricsError;
+use crate::metrics::metric::{Metric, Sampler};
+use arrow_array::{ArrayRef, Float64Array, Int64Array, RecordBatch};
+use arrow_schema::{DataType, Field, Schema};
+use parquet::arrow::ArrowWriter;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::sync::{Arc, RwLock};
+
+const DEFAULT_INTERVAL: usize = 1;
+const DEFAULT_MAX_POINTS: usize = 500;
+
+/// 可在模拟线程与 HTTP 接口之间共享的指标记录器
+pub type SharedMetricsRecorder = Arc<RwLock<MetricsRecorder>>;
+
+/// 时间序列指标记录器
+///
+/// 每隔 `interval` 步对地貌采样一次，结果按列存放：`ticks` 为采样时的步数，
+/// `columns[i]` 为第 `i` 个指标的全部采样值，长度与 `ticks` 相同。
+///
+/// ```
+/// use game::game_context::GameContext;
+/// use game::{Landscape, MapSize, MetricsRecorder, NoiseGeneratable, NoiseParams,
+///     SeriesQuery, SubstanceDistribution, SubstanceType};
+/// use std::sync::{Arc, RwLock};
+///
+/// GameContext::update_game_gravity_const(9.8);
+/// let map_size = MapSize::new(Some(8), Some(8));
+/// let mut distribution = SubstanceDistribution::new(
+///     "1/2".parse::<SubstanceType>().unwrap(),
+///     map_size,
+///     Some(NoiseParams::new(Some(42), Some(2.0))),
+/// );
+/// distribution.generate_simplex_noise();
+///
+/// let recorder = MetricsRecorder::new(
+///     vec!["total:1/2".parse().unwrap(), "morans_i:potential".parse().unwrap()],
+///     Some(2),
+/// );
+/// let recorder = Arc::new(RwLock::new(recorder));
+/// let mut landscape = Landscape::new(map_size).with_metrics(recorder.clone());
+/// landscape.add_resource_distribution(distribution);
+/// for _ in 0..10 {
+///     landscape.update_potential_distribution();
+///     landscape.diffuse();
+/// }
+///
+/// let recorder = recorder.read().unwrap();
+/// assert_eq!(recorder.ticks(), &[2, 4, 6, 8, 10]);
+///
+/// let query = SeriesQuery { max_points: Some(2), ..Default::default() };
+/// let series = recorder.query(&query).unwrap();
+/// assert_eq!(series.ticks, vec![2, 6]);
+/// assert!(recorder.to_csv().starts_with("tick,total:1/2,morans_i:potential\n"));
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetricsRecorder {
+    metrics: Vec<Metric>,
+    interval: usize,
+    ticks: Vec<u64>,
+    columns: Vec<Vec<f64>>,
+}
+
+/// 时间序列查询条件，可直接用作 HTTP 查询参数
+///
+/// - `metrics`: 逗号分隔的指标名称，为 `None` 时返回全部指标。
+/// - `from` / `to`: 步数的闭区间，为 `None` 时不限制。
+/// - `max_points`: 降采样后的最大点数，默认为 500。
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SeriesQuery {
+    pub metrics: Option<String>,
+    pub from: Option<u64>,
+    pub to: Option<u64>,
+    pub max_points: Option<usize>,
+}
+
+/// 降采样后一个指标的时间序列
+///
+/// 每个点对应一段连续的采样，给出其平均值与上下包络，
+/// 高频振荡在降采样后仍能从 `min`、`max` 中看出来。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SeriesEnvelope {
+    pub mean: Vec<f64>,
+    pub min: Vec<f64>,
+    pub max: Vec<f64>,
+}
+
+/// 查询结果：`ticks` 为每段采样的起始步数
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DownsampledSeries {
+    pub ticks: Vec<u64>,
+    pub series: BTreeMap<String, SeriesEnvelope>,
+}
+
+impl MetricsRecorder {
+    /// 创建记录器
+    ///
+    /// ### 参数
+    /// - `metrics`: 要记录的指标。
+    /// - `interval`: 采样间隔步数，默认为 1，至少为 1。
+    pub fn new(metrics: Vec<Metric>, interval: Option<usize>) -> Self {
+        let columns = vec![Vec::new(); metrics.len()];
+        Self {
+            metrics,
+            interval: interval.unwrap_or(DEFAULT_INTERVAL).max(1),
+            ticks: Vec::new(),
+            columns,
+        }
+    }
+
+    /// 包装为可共享的记录器
+    pub fn shared(self) -> SharedMetricsRecorder {
+        Arc::new(RwLock::new(self))
+    }
+
+    pub fn metrics(&self) -> &[Metric] {
+        &self.metrics
+    }
+
+    pub fn interval(&self) -> usize {
+        self.interval
+    }
+
+    pub fn ticks(&self) -> &[u64] {
+        &self.ticks
+    }
+
+    /// 已记录的采样次数
+    pub fn len(&self) -> usize {
+        self.ticks.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ticks.is_empty()
+    }
+
+    /// 获取某个指标的全部采样值
+    pub fn series(&self, metric: &Metric) -> Option<&[f64]> {
+        self.metrics
+            .iter()
+            .position(|recorded| recorded == metric)
+            .map(|index| self.columns[index].as_slice())
+    }
+
+    /// 清空已记录的采样，保留指标与采样间隔
+    pub fn clear(&mut self) {
+        self.ticks.clear();
+        self.columns.iter_mut().for_each(Vec::clear);
+    }
+}
+
+/// 采样
+impl MetricsRecorder {
+    /// 若 `tick` 是采样间隔的整数倍则采样一次
+    ///
+    /// ### 返回值
+    /// 返回本次是否采样。
+    pub fn observe(&mut self, tick: u64, landscape: &Landscape) -> bool {
+        let due = tick.is_multiple_of(self.interval as u64);
+        if due {
+            self.record(tick, landscape);
+        }
+        due
+    }
+
+    /// 无视采样间隔，立即采样一次
+    pub fn record(&mut self, tick: u64, landscape: &Landscape) {
+        let mut sampler = Sampler::new(landscape);
+        for (metric, column) in self.metrics.iter().zip(self.columns.iter_mut()) {
+            column.push(sampler.sample(metric));
+        }
+        self.ticks.push(tick);
+    }
+}
+
+/// 查询与导出
+impl MetricsRecorder {
+    /// 按查询条件截取并降采样
+    ///
+    /// 选中的采样被均分为至多 `max_points` 段，每段输出一个点。
+    ///
+    /// ### 返回值
+    /// 返回降采样后的序列；指标名称无法解析或未被记录时返回错误。
+    pub fn query(&self, query: &SeriesQuery) -> Result<DownsampledSeries, MetricsError> {
+        let indices: Vec<usize> = match &query.metrics {
+            None => (0..self.metrics.len()).collect(),
+            Some(names) => Metric::parse_list(names)?
+                .into_iter()
+                .map(|metric| {
+                    self.metrics
+                        .iter()
+                        .position(|recorded| *recorded == metric)
+                        .ok_or_else(|| MetricsError::UnknownMetric(metric.to_string()))
+                })
+                .collect::<Result<_, _>>()?,
+        };
+
+        let start = query
+            .from
+            .map_or(0, |from| self.ticks.partition_point(|&tick| tick < from));
+        let end = query.to.map_or(self.ticks.len(), |to| {
+            self.ticks.partition_point(|&tick| tick <= to)
+        });
+        let selected = start..end.max(start);
+
+        let count = selected.len();
+        let points = count.min(query.max_points.unwrap_or(DEFAULT_MAX_POINTS).max(1));
+        let buckets: Vec<(usize, usize)> = (0..points)
+            .map(|bucket| {
+                (
+                    selected.start + bucket * count / points,
+                    selected.start + (bucket + 1) * count / points,
+                )
+            })
+            .collect();
+
+        let ticks = buckets.iter().map(|&(from, _)| self.ticks[from]).collect();
+        let series = indices
+            .into_iter()
+            .map(|index| {
+                let column = &self.columns[index];
+                let mut envelope = SeriesEnvelope {
+                    mean: Vec::with_capacity(points),
+                    min: Vec::with_capacity(points),
+                    max: Vec::with_capacity(points),
+                };
+                for &(from, to) in &buckets {
+                    let (mean, min, max) = Self::aggregate(&column[from..to]);
+                    envelope.mean.push(mean);
+                    envelope.min.push(min);
+                    envelope.max.push(max);
+                }
+                (self.metrics[index].to_string(), envelope)
+            })
+            .collect();
+
+        Ok(DownsampledSeries { ticks, series })
+    }
+
+    /// 一段采样的平均值、最小值与最大值，`NaN` 不参与计算
+    fn aggregate(values: &[f64]) -> (f64, f64, f64) {
+        let (count, sum, min, max) = values.iter().filter(|value| !value.is_nan()).fold(
+            (0usize, 0.0, f64::INFINITY, f64::NEG_INFINITY),
+            |(count, sum, min, max), &value| {
+                (count + 1, sum + value, min.min(value), max.max(value))
+            },
+        );
+
+        if count == 0 {
+            (f64::NAN, f64::NAN, f64::NAN)
+        } else {
+            (sum / count as f64, min, max)
+        }
+    }
+
+    /// 导出为 CSV：首行为 `tick` 与各指标名称，`NaN` 写为空值
+    pub fn to_csv(&self) -> String {
+        let mut csv = std::iter::once("tick".to_string())
+            .chain(self.metrics.iter().map(Metric::to_string))
+            .collect::<Vec<_>>()
+            .join(",");
+        csv.push('\n');
+
+        for (row, tick) in self.ticks.iter().enumerate() {
+            csv.push_str(&tick.to_string());
+            for column in &self.columns {
+                csv.push(',');
+                if !column[row].is_nan() {
+                    csv.push_str(&column[row].to_string());
+                }
+            }
+            csv.push('\n');
+        }
+
+        csv
+    }
+
+    pub fn export_csv(&self, path: impl AsRef<Path>) -> Result<(), MetricsError> {
+        fs::write(path, self.to_csv())?;
+        Ok(())
+    }
+
+    /// 导出为 Parquet：`tick` 列为 INT64，各指标列为 DOUBLE，`NaN` 原样写出
+    ///
+    /// ```
+    /// use arrow_array::{Array, Float64Array};
+    /// use game::game_context::GameContext;
+    /// use game::{Landscape, MapSize, MetricsRecorder};
+    /// use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+    ///
+    /// GameContext::update_game_gravity_const(9.8);
+    /// let landscape = Landscape::new(MapSize::new(Some(4), Some(4)));
+    /// let mut recorder = MetricsRecorder::new(
+    ///     vec!["substance_count".parse().unwrap(), "mean:1/2".parse().unwrap()],
+    ///     None,
+    /// );
+    /// recorder.record(0, &landscape);
+    /// recorder.record(1, &landscape);
+    ///
+    /// let path = std::env::temp_dir().join("recorder_to_parquet.parquet");
+    /// recorder.export_parquet(&path).unwrap();
+    ///
+    /// let file = std::fs::File::open(&path).unwrap();
+    /// let batch = ParquetRecordBatchReaderBuilder::try_new(file)
+    ///     .unwrap()
+    ///     .build()
+    ///     .unwrap()
+    ///     .next()
+    ///     .unwrap()
+    ///     .unwrap();
+    /// assert_eq!(batch.num_rows(), 2);
+    /// assert_eq!(batch.schema().field(2).name(), "mean:1/2");
+    /// // 地貌中没有物质 1/2，平均值为 NaN
+    /// let mean = batch.column(2).as_any().downcast_ref::<Float64Array>().unwrap();
+    /// assert!(mean.value(0).is_nan());
+    /// # std::fs::remove_file(path).unwrap();
+    /// ```
+    pub fn to_parquet(&self) -> Result<Vec<u8>, MetricsError> {
+        let fields: Vec<Field> = std::iter::once(Field::new("tick", DataType::Int64, false))
+            .chain(
+                self.metrics
+                    .iter()
+                    .map(|metric| Field::new(metric.to_string(), DataType::Float64, false)),
+            )
+            .collect();
+        let columns: Vec<ArrayRef> = std::iter::once(Arc::new(Int64Array::from_iter_values(
+            self.ticks.iter().map(|&tick| tick as i64),
+        )) as ArrayRef)
+        .chain(
+            self.columns
+                .iter()
+                .map(|column| Arc::new(Float64Array::from(column.clone())) as ArrayRef),
+        )
+        .collect();
+        let batch = RecordBatch::try_new(Arc::new(Schema::new(fields)), columns)?;
+
+        let mut writer = ArrowWriter::try_new(Vec::new(), batch.schema(), None)?;
+        writer.write(&batch)?;
+        Ok(writer.into_inner()?)
+    }
+
+    pub fn export_parquet(&self, path: impl AsRef<Path>) -> Result<(), MetricsError> {
+        fs::write(path, self.to_parquet()?)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,55 @@
+use crate::environment::landscape::Landscape;
+use crate::metrics::metric::{Metric, Sampler};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// 某一步时若干指标的取值，用于跨实例共享一个世界的最新统计
+///
+/// 以指标名称为键；所统计的图层不存在（采样结果为 `NaN`）的指标不会出现。
+///
+/// ```
+/// use game::game_context::GameContext;
+/// use game::{Landscape, MapSize, MetricsSnapshot};
+///
+/// GameContext::update_game_gravity_const(9.8);
+/// let landscape = Landscape::new(MapSize::new(Some(4), Some(4)));
+/// let metrics = ["substance_count".parse().unwrap(), "mean:altitude".parse().unwrap()];
+///
+/// let snapshot = MetricsSnapshot::sample(&landscape, &metrics);
+/// assert_eq!(snapshot.tick, 0);
+/// assert_eq!(snapshot.get(&metrics[0]), Some(0.0));
+/// assert_eq!(snapshot.get(&metrics[1]), None);
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MetricsSnapshot {
+    pub tick: u64,
+    pub values: BTreeMap<String, f64>,
+}
+
+impl MetricsSnapshot {
+    /// 对地貌的当前状态采样
+    ///
+    /// ### 参数
+    /// - `landscape`: 地貌，其步数作为快照的步数。
+    /// - `metrics`: 要采样的指标。
+    pub fn sample(landscape: &Landscape, metrics: &[Metric]) -> Self {
+        let mut sampler = Sampler::new(landscape);
+        let values = metrics
+            .iter()
+            .filter_map(|metric| {
+                let value = sampler.sample(metric);
+                (!value.is_nan()).then(|| (metric.to_string(), value))
+            })
+            .collect();
+
+        Self {
+            tick: landscape.tick(),
+            values,
+        }
+    }
+
+    /// 获取某个指标的取值
+    pub fn get(&self, metric: &Metric) -> Option<f64> {
+        self.values.get(&metric.to_string()).copied()
+    }
+}
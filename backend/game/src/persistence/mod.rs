@@ -0,0 +1,59 @@
+pub(crate) mod world_archive;
+
+pub mod error_handling {
+    use crate::codec::error_handling::CodecError;
+    use back_core::repository::error_handling::RepositoryError;
+    use std::fmt;
+
+    #[derive(Debug)]
+    pub enum PersistenceError {
+        /// 读写数据库失败
+        Repository(RepositoryError),
+        /// 检查点快照无法解压
+        Codec(CodecError),
+        /// 地貌无法（反）序列化
+        Serialization(serde_json::Error),
+        /// 数值超出数据库列的取值范围
+        OutOfRange(String),
+    }
+
+    impl fmt::Display for PersistenceError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                PersistenceError::Repository(e) => write!(f, "持久化失败: {}", e),
+                PersistenceError::Codec(e) => write!(f, "检查点快照已损坏: {}", e),
+                PersistenceError::Serialization(e) => write!(f, "地貌无法（反）序列化: {}", e),
+                PersistenceError::OutOfRange(message) => write!(f, "数值超出范围: {}", message),
+            }
+        }
+    }
+
+    impl std::error::Error for PersistenceError {
+        fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+            match self {
+                PersistenceError::Repository(e) => Some(e),
+                PersistenceError::Codec(e) => Some(e),
+                PersistenceError::Serialization(e) => Some(e),
+                PersistenceError::OutOfRange(_) => None,
+            }
+        }
+    }
+
+    impl From<RepositoryError> for PersistenceError {
+        fn from(e: RepositoryError) -> Self {
+            PersistenceError::Repository(e)
+        }
+    }
+
+    impl From<CodecError> for PersistenceError {
+        fn from(e: CodecError) -> Self {
+            PersistenceError::Codec(e)
+        }
+    }
+
+    impl From<serde_json::Error> for PersistenceError {
+        fn from(e: serde_json::Error) -> Self {
+            PersistenceError::Serialization(e)
+        }
+    }
+}
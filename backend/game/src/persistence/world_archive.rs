@@ -0,0 +1,226 @@
+use crate::codec::error_handling::CodecError;
+use crate::environment::landscape::Landscape;
+use crate::environment::map_size::MapSize;
+use crate::persistence::error_handling::PersistenceError;
+use back_core::repository::checkpoint::{CheckpointSummary, RetentionPolicy};
+use back_core::repository::run::{RunRecord, RunStatus};
+use back_core::repository::world::WorldRecord;
+use back_core::storage::Storage;
+use flate2::read::ZlibDecoder;
+use flate2::write::ZlibEncoder;
+use flate2::Compression;
+use std::io::{Read, Write};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// 世界、运行与检查点的持久化
+///
+/// 检查点快照是地貌 JSON 经 zlib 压缩后的字节；地貌中大面积相近的数值可以被有效压缩。
+/// 数据保存在全局配置的存储中，可以是 Postgres、SQLite 或内存。
+///
+/// ```no_run
+/// use back_core::context::core_context::AppContext;
+/// use back_core::repository::checkpoint::RetentionPolicy;
+/// use back_core::repository::run::RunStatus;
+/// use game::{Landscape, MapSize, WorldArchive};
+///
+/// # async fn run(world_id: uuid::Uuid) -> Result<(), game::PersistenceError> {
+/// let archive = WorldArchive::new(AppContext::get_storage());
+/// let map_size = MapSize::new(Some(8), Some(8));
+/// archive.register_world(world_id, 42, &map_size, 10.0).await?;
+///
+/// let mut landscape = Landscape::new(map_size);
+/// let run = archive.start_run(world_id, &landscape, 10).await?;
+/// for _ in 0..100 {
+///     landscape.diffuse();
+///     archive.checkpoint(&run, &landscape).await?;
+/// }
+/// archive.finish_run(&run, RunStatus::Completed).await?;
+/// archive.prune(world_id, &RetentionPolicy::new(Some(3))).await?;
+///
+/// // 之后从最新的检查点恢复
+/// let restored = archive.latest_landscape(world_id).await?.unwrap();
+/// assert_eq!(restored.tick(), 100);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct WorldArchive {
+    storage: Arc<dyn Storage>,
+}
+
+impl WorldArchive {
+    pub fn new(storage: Arc<dyn Storage>) -> Self {
+        Self { storage }
+    }
+
+    /// 将地貌编码为检查点快照
+    pub fn encode_landscape(landscape: &Landscape) -> Result<Vec<u8>, PersistenceError> {
+        let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+        encoder
+            .write_all(&serde_json::to_vec(landscape)?)
+            .and_then(|_| encoder.finish())
+            .map_err(|e| CodecError::Io(e).into())
+    }
+
+    /// 从检查点快照还原地貌
+    pub fn decode_landscape(snapshot: &[u8]) -> Result<Landscape, PersistenceError> {
+        let mut json = Vec::new();
+        ZlibDecoder::new(snapshot)
+            .read_to_end(&mut json)
+            .map_err(|e| CodecError::Inflate(e.to_string()))?;
+        Ok(serde_json::from_slice(&json)?)
+    }
+}
+
+/// 写入
+impl WorldArchive {
+    /// 登记一个新世界
+    ///
+    /// ### 参数
+    /// - `world_id`: 世界的标识，通常为 `civilization_id`。
+    /// - `seed`: 生成地形与噪声的随机种子。
+    /// - `map_size`: 地图尺寸。
+    /// - `gravity`: 重力常数。
+    pub async fn register_world(
+        &self,
+        world_id: Uuid,
+        seed: u32,
+        map_size: &MapSize,
+        gravity: f64,
+    ) -> Result<WorldRecord, PersistenceError> {
+        Ok(self
+            .storage
+            .create_world(
+                world_id,
+                i64::from(seed),
+                to_column("map_size.width", map_size.width())?,
+                to_column("map_size.height", map_size.height())?,
+                gravity,
+            )
+            .await?)
+    }
+
+    /// 开始一次运行，并以地貌的当前状态保存第一个检查点
+    ///
+    /// ### 参数
+    /// - `world_id`: 运行所属的世界，必须已登记。
+    /// - `landscape`: 运行开始时的地貌，新世界或从检查点恢复的地貌。
+    /// - `checkpoint_interval`: 每隔多少步保存一次检查点。
+    pub async fn start_run(
+        &self,
+        world_id: Uuid,
+        landscape: &Landscape,
+        checkpoint_interval: u32,
+    ) -> Result<RunRecord, PersistenceError> {
+        let start_tick = to_column("tick", landscape.tick())?;
+        let interval = to_column("checkpoint_interval", checkpoint_interval)?;
+        let run = self
+            .storage
+            .start_run(world_id, start_tick, interval)
+            .await?;
+        self.save_checkpoint(&run, landscape).await?;
+        Ok(run)
+    }
+
+    /// 步数是检查点间隔的整数倍时保存检查点，否则什么也不做
+    ///
+    /// 每步模拟结束后调用即可。
+    ///
+    /// ### 返回值
+    /// 返回是否保存了检查点。
+    pub async fn checkpoint(
+        &self,
+        run: &RunRecord,
+        landscape: &Landscape,
+    ) -> Result<bool, PersistenceError> {
+        let interval = u64::try_from(run.checkpoint_interval.max(1)).unwrap_or(1);
+        if !landscape.tick().is_multiple_of(interval) {
+            return Ok(false);
+        }
+        self.save_checkpoint(run, landscape).await?;
+        Ok(true)
+    }
+
+    /// 无论步数如何都保存检查点，例如在运行结束前
+    ///
+    /// ### 返回值
+    /// 返回检查点的 `checkpoint_id`。
+    #[tracing::instrument(name = "tick", skip_all, fields(civilization_id = %run.world_id, tick = landscape.tick()))]
+    pub async fn save_checkpoint(
+        &self,
+        run: &RunRecord,
+        landscape: &Landscape,
+    ) -> Result<i64, PersistenceError> {
+        let tick = to_column("tick", landscape.tick())?;
+        let snapshot = Self::encode_landscape(landscape)?;
+        Ok(self
+            .storage
+            .save_checkpoint(run.run_id, tick, &snapshot)
+            .await?)
+    }
+
+    /// 结束运行，记录最终状态
+    pub async fn finish_run(
+        &self,
+        run: &RunRecord,
+        status: RunStatus,
+    ) -> Result<(), PersistenceError> {
+        self.storage.finish_run(run.run_id, status).await?;
+        Ok(())
+    }
+
+    /// 按保留策略删除世界的旧检查点
+    ///
+    /// ### 返回值
+    /// 返回删除的检查点数。
+    pub async fn prune(
+        &self,
+        world_id: Uuid,
+        policy: &RetentionPolicy,
+    ) -> Result<u64, PersistenceError> {
+        Ok(self.storage.prune_checkpoints(world_id, policy).await?)
+    }
+}
+
+/// 读取
+impl WorldArchive {
+    /// 读取世界，不存在时返回 `None`
+    pub async fn world(&self, world_id: Uuid) -> Result<Option<WorldRecord>, PersistenceError> {
+        Ok(self.storage.find_world(world_id).await?)
+    }
+
+    /// 读取世界的全部运行，最新开始的在前
+    pub async fn list_runs(&self, world_id: Uuid) -> Result<Vec<RunRecord>, PersistenceError> {
+        Ok(self.storage.list_runs(world_id).await?)
+    }
+
+    /// 列出世界的全部检查点（不含快照内容），步数大的在前
+    pub async fn list_checkpoints(
+        &self,
+        world_id: Uuid,
+    ) -> Result<Vec<CheckpointSummary>, PersistenceError> {
+        Ok(self.storage.list_checkpoints(world_id).await?)
+    }
+
+    /// 从世界最新的检查点还原地貌，没有检查点时返回 `None`
+    pub async fn latest_landscape(
+        &self,
+        world_id: Uuid,
+    ) -> Result<Option<Landscape>, PersistenceError> {
+        self.storage
+            .latest_checkpoint(world_id)
+            .await?
+            .map(|checkpoint| Self::decode_landscape(&checkpoint.snapshot))
+            .transpose()
+    }
+}
+
+/// 将数值转换为数据库列的类型，超出范围时返回 `OutOfRange`
+fn to_column<T, C>(name: &str, value: T) -> Result<C, PersistenceError>
+where
+    T: Copy + std::fmt::Display,
+    C: TryFrom<T>,
+{
+    C::try_from(value).map_err(|_| PersistenceError::OutOfRange(format!("{} = {}", name, value)))
+}
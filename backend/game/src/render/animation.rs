@@ -0,0 +1,62 @@
+use crate::environment::landscape::Landscape;
+use crate::render::error_handling::RenderError;
+use crate::render::heatmap::{HeatmapAnimation, HeatmapFrame, HeatmapRenderer};
+use crate::shared::subtance_type::SubstanceType;
+use std::ops::Range;
+
+/// 热力图的渲染对象
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HeatmapTarget {
+    /// 某种物质的摩尔数分布
+    Substance(SubstanceType),
+    /// 势能场强
+    Potential,
+}
+
+/// 热力图导出
+impl Landscape {
+    /// 截取地貌当前状态中渲染对象的一帧
+    pub fn heatmap_frame(&self, target: HeatmapTarget) -> Result<HeatmapFrame, RenderError> {
+        match target {
+            HeatmapTarget::Substance(substance_type) => self
+                .subtance_distributions()
+                .iter()
+                .find(|substance_dist| *substance_dist.substance_type() == substance_type)
+                .map(HeatmapFrame::from_distribution)
+                .ok_or(RenderError::UnknownSubstance(substance_type)),
+            HeatmapTarget::Potential => Ok(HeatmapFrame::from_potential(self.potential())),
+        }
+    }
+
+    /// 推进地貌并录制一段热力图动画
+    ///
+    /// 每一步先更新势能场强再扩散；第 `ticks.start` 步之前只推进、不录制，
+    /// 之后每一步在扩散前截取一帧。
+    ///
+    /// ### 参数
+    /// - `target`: 渲染对象。
+    /// - `renderer`: 热力图渲染器。
+    /// - `ticks`: 以当前状态为第 0 步的录制区间。
+    /// - `frame_delay_ms`: 每帧的显示时长，单位为毫秒。
+    ///
+    /// ### 返回值
+    /// 返回动画；渲染对象不存在或区间为空时返回错误。
+    pub fn record_heatmap_animation(
+        &mut self,
+        target: HeatmapTarget,
+        renderer: &HeatmapRenderer,
+        ticks: Range<usize>,
+        frame_delay_ms: u16,
+    ) -> Result<HeatmapAnimation, RenderError> {
+        let mut frames = Vec::with_capacity(ticks.len());
+        for tick in 0..ticks.end {
+            self.update_potential_distribution();
+            if ticks.contains(&tick) {
+                frames.push(self.heatmap_frame(target)?);
+            }
+            self.diffuse();
+        }
+
+        renderer.render_animation(&frames, frame_delay_ms)
+    }
+}
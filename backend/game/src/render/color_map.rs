@@ -0,0 +1,88 @@
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+/// viridis 色图的等距采样点
+const VIRIDIS: [[u8; 3]; 9] = [
+    [68, 1, 84],
+    [71, 44, 122],
+    [59, 81, 139],
+    [44, 113, 142],
+    [33, 144, 141],
+    [39, 173, 129],
+    [92, 200, 99],
+    [170, 220, 50],
+    [253, 231, 37],
+];
+/// inferno 色图的等距采样点
+const INFERNO: [[u8; 3]; 9] = [
+    [0, 0, 4],
+    [31, 12, 72],
+    [85, 15, 109],
+    [136, 34, 106],
+    [186, 54, 85],
+    [227, 89, 51],
+    [249, 142, 9],
+    [249, 203, 53],
+    [252, 255, 164],
+];
+/// 冷暖发散色图的等距采样点
+const COOLWARM: [[u8; 3]; 3] = [[59, 76, 192], [221, 221, 221], [180, 4, 38]];
+
+/// 热力图色图
+///
+/// - `Grayscale`: 黑到白。
+/// - `Viridis`: 感知均匀的蓝绿黄色图，适合摩尔数等非负量。
+/// - `Inferno`: 感知均匀的黑红黄色图，适合突出高值。
+/// - `Coolwarm`: 蓝白红发散色图，适合以中间值为基准的势能场强。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ColorMap {
+    Grayscale,
+    #[default]
+    Viridis,
+    Inferno,
+    Coolwarm,
+}
+
+impl ColorMap {
+    /// 将 [0, 1] 范围内的归一化值映射为 RGB 颜色，超出范围的值会被截断
+    pub fn color(self, t: f64) -> [u8; 3] {
+        let t = if t.is_nan() { 0.0 } else { t.clamp(0.0, 1.0) };
+        match self {
+            ColorMap::Grayscale => {
+                let level = (t * u8::MAX as f64).round() as u8;
+                [level, level, level]
+            }
+            ColorMap::Viridis => Self::interpolate(&VIRIDIS, t),
+            ColorMap::Inferno => Self::interpolate(&INFERNO, t),
+            ColorMap::Coolwarm => Self::interpolate(&COOLWARM, t),
+        }
+    }
+
+    /// 在等距采样点之间线性插值
+    fn interpolate(stops: &[[u8; 3]], t: f64) -> [u8; 3] {
+        let position = t * (stops.len() - 1) as f64;
+        let index = (position.floor() as usize).min(stops.len() - 2);
+        let fraction = position - index as f64;
+
+        let (from, to) = (stops[index], stops[index + 1]);
+        [0, 1, 2].map(|channel| {
+            (from[channel] as f64 + (to[channel] as f64 - from[channel] as f64) * fraction).round()
+                as u8
+        })
+    }
+}
+
+impl FromStr for ColorMap {
+    type Err = String;
+
+    /// 按名称解析色图，不区分大小写，例如 `"viridis"`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "grayscale" | "gray" => Ok(ColorMap::Grayscale),
+            "viridis" => Ok(ColorMap::Viridis),
+            "inferno" => Ok(ColorMap::Inferno),
+            "coolwarm" => Ok(ColorMap::Coolwarm),
+            _ => Err(format!("未知的色图: {}", s)),
+        }
+    }
+}
@@ -0,0 +1,434 @@
+use crate::codec::{error_handling::CodecError, png};
+use crate::environment::cartesian_vec_2d::CartesianVec2D;
+use crate::environment::potential::Potential;
+use crate::environment::subtance_distribution::SubstanceDistribution;
+use crate::environment::t_statistical::Statistical;
+use crate::render::{color_map::ColorMap, error_handling::RenderError, hex_layout::HexLayout};
+use ndarray::Array2;
+use rayon::prelude::*;
+use std::fs;
+use std::path::Path;
+
+const DEFAULT_PIXELS_PER_UNIT: f64 = 6.0;
+const DEFAULT_ARROW_COLOR: [u8; 4] = [255, 255, 255, 255];
+/// 箭头头部两翼相对箭杆的长度比例
+const ARROW_HEAD_RATIO: f64 = 0.35;
+/// 箭头头部两翼相对箭杆的夹角
+const ARROW_HEAD_ANGLE: f64 = 2.6;
+
+/// 待渲染的一帧数据
+///
+/// - `values`: 每个单元格的标量值，形状为 `(height, width)`。
+/// - `movement`: 可选的每个单元格的运动向量，用于绘制箭头。
+#[derive(Debug, Clone)]
+pub struct HeatmapFrame {
+    values: Array2<f64>,
+    movement: Option<Array2<CartesianVec2D>>,
+}
+
+impl HeatmapFrame {
+    pub fn new(values: Array2<f64>, movement: Option<Array2<CartesianVec2D>>) -> Self {
+        Self { values, movement }
+    }
+
+    /// 以摩尔数为标量值、`HexUnit::movement` 为运动向量
+    pub fn from_distribution(substance_dist: &SubstanceDistribution) -> Self {
+        let distribution = substance_dist.distribution();
+        Self {
+            values: distribution.mapv(|unit| unit.mole() as f64),
+            movement: Some(distribution.mapv(|unit| unit.movement())),
+        }
+    }
+
+    /// 以势能场强为标量值，没有运动向量
+    pub fn from_potential(potential: &Potential) -> Self {
+        Self {
+            values: potential.distribution().clone(),
+            movement: None,
+        }
+    }
+
+    pub fn values(&self) -> &Array2<f64> {
+        &self.values
+    }
+
+    pub fn movement(&self) -> Option<&Array2<CartesianVec2D>> {
+        self.movement.as_ref()
+    }
+
+    /// 运动向量的最大模长，没有运动向量时为 0
+    fn max_magnitude(&self) -> f64 {
+        self.movement.as_ref().map_or(0.0, |movement| {
+            movement
+                .iter()
+                .map(CartesianVec2D::magnitude)
+                .fold(0.0, f64::max)
+        })
+    }
+}
+
+/// 渲染得到的 RGBA 图像
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Heatmap {
+    width: usize,
+    height: usize,
+    /// 按行优先存放的 RGBA 字节
+    pixels: Vec<u8>,
+}
+
+impl Heatmap {
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn pixels(&self) -> &[u8] {
+        &self.pixels
+    }
+
+    /// 获取 `(y, x)` 处像素的 RGBA 值
+    pub fn pixel(&self, y: usize, x: usize) -> [u8; 4] {
+        let offset = (y * self.width + x) * 4;
+        self.pixels[offset..offset + 4]
+            .try_into()
+            .expect("切片长度恰好为 4")
+    }
+
+    /// 编码为 PNG
+    pub fn to_png(&self) -> Result<Vec<u8>, CodecError> {
+        png::encode(
+            self.width,
+            self.height,
+            png::ColorType::Rgba,
+            8,
+            &self.pixels,
+        )
+    }
+
+    /// 编码为 PNG 并写入文件
+    pub fn save_png(&self, path: impl AsRef<Path>) -> Result<(), CodecError> {
+        fs::write(path, self.to_png()?)?;
+        Ok(())
+    }
+
+    /// 在 `(y, x)` 处写入一个像素，超出图像的坐标会被忽略
+    fn put(&mut self, y: isize, x: isize, color: [u8; 4]) {
+        if y >= 0 && x >= 0 && (y as usize) < self.height && (x as usize) < self.width {
+            let offset = (y as usize * self.width + x as usize) * 4;
+            self.pixels[offset..offset + 4].copy_from_slice(&color);
+        }
+    }
+
+    /// 以逐像素步进的方式画一条线段
+    fn line(&mut self, from: (f64, f64), to: (f64, f64), color: [u8; 4]) {
+        let steps = (to.0 - from.0).abs().max((to.1 - from.1).abs()).ceil() as usize;
+        for step in 0..=steps {
+            let t = if steps == 0 {
+                0.0
+            } else {
+                step as f64 / steps as f64
+            };
+            let y = from.0 + (to.0 - from.0) * t;
+            let x = from.1 + (to.1 - from.1) * t;
+            self.put(y.round() as isize, x.round() as isize, color);
+        }
+    }
+}
+
+/// 多帧热力图，可导出为循环播放的 APNG
+#[derive(Debug, Clone)]
+pub struct HeatmapAnimation {
+    frames: Vec<Heatmap>,
+    frame_delay_ms: u16,
+}
+
+impl HeatmapAnimation {
+    pub fn frames(&self) -> &[Heatmap] {
+        &self.frames
+    }
+
+    pub fn frame_delay_ms(&self) -> u16 {
+        self.frame_delay_ms
+    }
+
+    /// 编码为 APNG
+    pub fn to_apng(&self) -> Result<Vec<u8>, CodecError> {
+        let first = &self.frames[0];
+        let frames: Vec<Vec<u8>> = self
+            .frames
+            .iter()
+            .map(|frame| frame.pixels.clone())
+            .collect();
+        png::encode_animation(
+            first.width,
+            first.height,
+            png::ColorType::Rgba,
+            &frames,
+            self.frame_delay_ms,
+        )
+    }
+
+    /// 编码为 APNG 并写入文件
+    pub fn save_apng(&self, path: impl AsRef<Path>) -> Result<(), CodecError> {
+        fs::write(path, self.to_apng()?)?;
+        Ok(())
+    }
+}
+
+/// 热力图渲染器
+///
+/// - `color_map`: 色图。
+/// - `pixels_per_unit`: 相邻单元格中心之间的像素距离。
+/// - `value_range`: 映射到色图两端的取值范围，为 `None` 时取数据自身的最小、最大值。
+/// - `arrow_stride`: 每隔多少行、列绘制一个运动箭头，为 `None` 时不绘制。
+/// - `arrow_color`: 箭头颜色（RGBA）。
+///
+/// ```
+/// use game::{ColorMap, HeatmapRenderer, MapSize, NoiseGeneratable, NoiseParams,
+///     SubstanceDistribution, SubstanceType};
+///
+/// let mut distribution = SubstanceDistribution::new(
+///     "1/2".parse::<SubstanceType>().unwrap(),
+///     MapSize::new(Some(16), Some(16)),
+///     Some(NoiseParams::new(Some(1), Some(2.0))),
+/// );
+/// distribution.generate_simplex_noise();
+///
+/// let heatmap = HeatmapRenderer::new(Some(ColorMap::Inferno), Some(4.0))
+///     .with_arrows(2)
+///     .render_distribution(&distribution);
+/// assert!(heatmap.width() > 0 && heatmap.height() > 0);
+/// assert!(heatmap.to_png().unwrap().starts_with(b"\x89PNG"));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct HeatmapRenderer {
+    color_map: ColorMap,
+    pixels_per_unit: f64,
+    value_range: Option<(f64, f64)>,
+    arrow_stride: Option<usize>,
+    arrow_color: [u8; 4],
+}
+
+impl HeatmapRenderer {
+    pub fn new(color_map: Option<ColorMap>, pixels_per_unit: Option<f64>) -> Self {
+        Self {
+            color_map: color_map.unwrap_or_default(),
+            pixels_per_unit: pixels_per_unit.unwrap_or(DEFAULT_PIXELS_PER_UNIT).max(1.0),
+            value_range: None,
+            arrow_stride: None,
+            arrow_color: DEFAULT_ARROW_COLOR,
+        }
+    }
+
+    /// 固定映射到色图两端的取值范围（可链式调用）
+    pub fn with_value_range(mut self, min: f64, max: f64) -> Self {
+        self.value_range = Some((min, max));
+        self
+    }
+
+    /// 每隔 `stride` 行、列绘制一个运动箭头（可链式调用）
+    pub fn with_arrows(mut self, stride: usize) -> Self {
+        self.arrow_stride = Some(stride.max(1));
+        self
+    }
+
+    /// 设置箭头颜色（可链式调用）
+    pub fn with_arrow_color(mut self, arrow_color: [u8; 4]) -> Self {
+        self.arrow_color = arrow_color;
+        self
+    }
+
+    pub fn color_map(&self) -> ColorMap {
+        self.color_map
+    }
+
+    pub fn pixels_per_unit(&self) -> f64 {
+        self.pixels_per_unit
+    }
+
+    pub fn value_range(&self) -> Option<(f64, f64)> {
+        self.value_range
+    }
+
+    pub fn arrow_stride(&self) -> Option<usize> {
+        self.arrow_stride
+    }
+}
+
+/// 渲染
+impl HeatmapRenderer {
+    /// 渲染物质分布的摩尔数，启用箭头时叠加运动向量
+    pub fn render_distribution(&self, substance_dist: &SubstanceDistribution) -> Heatmap {
+        self.render(&HeatmapFrame::from_distribution(substance_dist))
+    }
+
+    /// 渲染势能场强
+    pub fn render_potential(&self, potential: &Potential) -> Heatmap {
+        self.render(&HeatmapFrame::from_potential(potential))
+    }
+
+    /// 渲染单帧
+    ///
+    /// 空地图渲染为 0×0 的热力图，无法编码为 PNG。
+    ///
+    /// ```
+    /// use game::{HeatmapFrame, HeatmapRenderer};
+    /// use ndarray::Array2;
+    ///
+    /// let frame = HeatmapFrame::new(Array2::zeros((0, 4)), None);
+    /// let heatmap = HeatmapRenderer::new(None, None).render(&frame);
+    /// assert_eq!((heatmap.width(), heatmap.height()), (0, 0));
+    /// assert!(heatmap.to_png().is_err());
+    /// ```
+    pub fn render(&self, frame: &HeatmapFrame) -> Heatmap {
+        let layout = HexLayout::new(frame.values.dim(), self.pixels_per_unit);
+        let range = self
+            .value_range
+            .unwrap_or_else(|| (frame.values.min(), frame.values.max()));
+        self.render_with(&layout, frame, range, frame.max_magnitude())
+    }
+
+    /// 渲染多帧动画
+    ///
+    /// 所有帧共用同一个取值范围与箭头长度比例，颜色在帧之间可以直接比较。
+    ///
+    /// ### 参数
+    /// - `frames`: 按时间顺序排列的帧，尺寸必须一致。
+    /// - `frame_delay_ms`: 每帧的显示时长，单位为毫秒。
+    ///
+    /// ### 返回值
+    /// 返回动画；没有帧或帧尺寸不一致时返回错误。
+    pub fn render_animation(
+        &self,
+        frames: &[HeatmapFrame],
+        frame_delay_ms: u16,
+    ) -> Result<HeatmapAnimation, RenderError> {
+        let first = frames.first().ok_or(RenderError::EmptyAnimation)?;
+        let dim = first.values.dim();
+        if let Some(frame) = frames.iter().find(|frame| frame.values.dim() != dim) {
+            return Err(RenderError::FrameSizeMismatch {
+                expected: dim,
+                found: frame.values.dim(),
+            });
+        }
+
+        let range = self.value_range.unwrap_or_else(|| {
+            frames
+                .iter()
+                .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), frame| {
+                    (min.min(frame.values.min()), max.max(frame.values.max()))
+                })
+        });
+        let max_magnitude = frames
+            .iter()
+            .map(HeatmapFrame::max_magnitude)
+            .fold(0.0, f64::max);
+
+        let layout = HexLayout::new(dim, self.pixels_per_unit);
+        let frames = frames
+            .iter()
+            .map(|frame| self.render_with(&layout, frame, range, max_magnitude))
+            .collect();
+
+        Ok(HeatmapAnimation {
+            frames,
+            frame_delay_ms,
+        })
+    }
+
+    /// 按给定投影、取值范围与最大运动模长渲染一帧
+    fn render_with(
+        &self,
+        layout: &HexLayout,
+        frame: &HeatmapFrame,
+        (min, max): (f64, f64),
+        max_magnitude: f64,
+    ) -> Heatmap {
+        let span = max - min;
+        let values = frame
+            .values
+            .as_standard_layout()
+            .iter()
+            .copied()
+            .collect::<Vec<f64>>();
+
+        let pixels: Vec<u8> = layout
+            .cells()
+            .par_iter()
+            .flat_map_iter(|cell| match cell {
+                Some(index) => {
+                    let t = if span > 0.0 {
+                        (values[*index] - min) / span
+                    } else {
+                        0.0
+                    };
+                    let [r, g, b] = self.color_map.color(t);
+                    [r, g, b, u8::MAX]
+                }
+                None => [0; 4],
+            })
+            .collect();
+
+        let (height, width) = layout.image_size();
+        let mut heatmap = Heatmap {
+            width,
+            height,
+            pixels,
+        };
+
+        if let (Some(stride), Some(movement)) = (self.arrow_stride, &frame.movement) {
+            self.draw_arrows(&mut heatmap, layout, movement, stride, max_magnitude);
+        }
+
+        heatmap
+    }
+
+    /// 每隔 `stride` 个单元格绘制一个运动箭头
+    ///
+    /// 箭头从单元格中心出发，长度按全图最大模长归一化，最长为一个单元格间距。
+    fn draw_arrows(
+        &self,
+        heatmap: &mut Heatmap,
+        layout: &HexLayout,
+        movement: &Array2<CartesianVec2D>,
+        stride: usize,
+        max_magnitude: f64,
+    ) {
+        if max_magnitude <= f64::EPSILON {
+            return;
+        }
+
+        let spacing = layout.cell_spacing() * stride as f64;
+        let (height, width) = movement.dim();
+        for row in (stride / 2..height).step_by(stride) {
+            for col in (stride / 2..width).step_by(stride) {
+                let vector = movement[[row, col]];
+                let magnitude = vector.magnitude();
+                if magnitude <= f64::EPSILON {
+                    continue;
+                }
+
+                let length = spacing * magnitude / max_magnitude;
+                let angle = vector.y().atan2(vector.x());
+                let from = layout.cell_center(row, col);
+                let tip = (from.0 + length * angle.sin(), from.1 + length * angle.cos());
+                heatmap.line(from, tip, self.arrow_color);
+
+                let head = length * ARROW_HEAD_RATIO;
+                for wing in [angle + ARROW_HEAD_ANGLE, angle - ARROW_HEAD_ANGLE] {
+                    let end = (tip.0 + head * wing.sin(), tip.1 + head * wing.cos());
+                    heatmap.line(tip, end, self.arrow_color);
+                }
+            }
+        }
+    }
+}
+
+impl Default for HeatmapRenderer {
+    fn default() -> Self {
+        Self::new(None, None)
+    }
+}
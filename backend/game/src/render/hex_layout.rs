@@ -0,0 +1,179 @@
+use crate::environment::cartesian_vec_2d::CartesianVec2D;
+use crate::game_context::GameContext;
+use rayon::prelude::*;
+
+/// 六边形地图到像素平面的投影
+///
+/// 单元格 `(row, col)` 的中心位于笛卡尔坐标 `col × x_base + row × y_base`，
+/// 因此整张地图呈平行四边形。每个像素反解出分数坐标后按立方体坐标取整，
+/// 落在哪个单元格就涂上哪个单元格的颜色，地图之外的像素保持透明。
+pub(crate) struct HexLayout {
+    /// 地图尺寸 `(height, width)`
+    map_size: (usize, usize),
+    /// 图像尺寸 `(height, width)`，单位为像素
+    image_size: (usize, usize),
+    /// 每单位笛卡尔长度对应的像素数
+    pixels_per_unit: f64,
+    /// 图像左上角对应的笛卡尔坐标
+    origin: CartesianVec2D,
+    x_base_vector: CartesianVec2D,
+    y_base_vector: CartesianVec2D,
+    /// 每个像素所属单元格在行优先展开后的下标
+    cells: Vec<Option<usize>>,
+}
+
+impl HexLayout {
+    /// 按全局上下文中的基向量计算投影
+    ///
+    /// ### 参数
+    /// - `map_size`: `(height, width)`，地图的大小。
+    /// - `pixels_per_unit`: 相邻单元格中心距离为 1 时，每单位长度对应的像素数。
+    ///
+    /// 空地图没有可投影的单元格，得到 0×0 的图像。
+    pub(crate) fn new(map_size: (usize, usize), pixels_per_unit: f64) -> Self {
+        let x_base_vector = GameContext::get_x_base_vector();
+        let y_base_vector = GameContext::get_y_base_vector();
+        let (height, width) = map_size;
+
+        if height == 0 || width == 0 {
+            return Self {
+                map_size,
+                image_size: (0, 0),
+                pixels_per_unit,
+                origin: CartesianVec2D::new(0.0, 0.0),
+                x_base_vector,
+                y_base_vector,
+                cells: Vec::new(),
+            };
+        }
+
+        // 四个角上单元格的中心决定了地图的外接矩形，再向外扩展一个六边形外接圆半径
+        let radius = x_base_vector.magnitude().min(y_base_vector.magnitude()) / 3.0f64.sqrt();
+        let corners = [
+            (0, 0),
+            (0, width - 1),
+            (height - 1, 0),
+            (height - 1, width - 1),
+        ]
+        .map(|(row, col)| Self::project(row as f64, col as f64, x_base_vector, y_base_vector));
+        let min_y = corners.iter().map(|c| c.y()).fold(f64::INFINITY, f64::min) - radius;
+        let max_y = corners
+            .iter()
+            .map(|c| c.y())
+            .fold(f64::NEG_INFINITY, f64::max)
+            + radius;
+        let min_x = corners.iter().map(|c| c.x()).fold(f64::INFINITY, f64::min) - radius;
+        let max_x = corners
+            .iter()
+            .map(|c| c.x())
+            .fold(f64::NEG_INFINITY, f64::max)
+            + radius;
+
+        let image_size = (
+            ((max_y - min_y) * pixels_per_unit).ceil().max(1.0) as usize,
+            ((max_x - min_x) * pixels_per_unit).ceil().max(1.0) as usize,
+        );
+
+        let mut layout = Self {
+            map_size,
+            image_size,
+            pixels_per_unit,
+            origin: CartesianVec2D::new(min_y, min_x),
+            x_base_vector,
+            y_base_vector,
+            cells: Vec::new(),
+        };
+        layout.cells = layout.rasterize();
+        layout
+    }
+
+    pub(crate) fn image_size(&self) -> (usize, usize) {
+        self.image_size
+    }
+
+    /// 每个像素所属单元格的下标，按行优先排列
+    pub(crate) fn cells(&self) -> &[Option<usize>] {
+        &self.cells
+    }
+
+    /// 相邻单元格中心之间的像素距离
+    pub(crate) fn cell_spacing(&self) -> f64 {
+        self.x_base_vector
+            .magnitude()
+            .min(self.y_base_vector.magnitude())
+            * self.pixels_per_unit
+    }
+
+    /// 单元格中心的像素坐标 `(y, x)`
+    pub(crate) fn cell_center(&self, row: usize, col: usize) -> (f64, f64) {
+        let center = Self::project(
+            row as f64,
+            col as f64,
+            self.x_base_vector,
+            self.y_base_vector,
+        );
+        (
+            (center.y() - self.origin.y()) * self.pixels_per_unit,
+            (center.x() - self.origin.x()) * self.pixels_per_unit,
+        )
+    }
+
+    /// 六边形坐标到笛卡尔坐标的投影
+    fn project(
+        row: f64,
+        col: f64,
+        x_base_vector: CartesianVec2D,
+        y_base_vector: CartesianVec2D,
+    ) -> CartesianVec2D {
+        x_base_vector.scale(col) + y_base_vector.scale(row)
+    }
+
+    /// 计算每个像素所属的单元格
+    fn rasterize(&self) -> Vec<Option<usize>> {
+        let (image_height, image_width) = self.image_size;
+        let (xb, yb) = (self.x_base_vector, self.y_base_vector);
+        let determinant = xb.x() * yb.y() - yb.x() * xb.y();
+
+        (0..image_height * image_width)
+            .into_par_iter()
+            .map(|pixel| {
+                // 像素中心对应的笛卡尔坐标
+                let y = (pixel / image_width) as f64 + 0.5;
+                let x = (pixel % image_width) as f64 + 0.5;
+                let y = y / self.pixels_per_unit + self.origin.y();
+                let x = x / self.pixels_per_unit + self.origin.x();
+
+                // 解出分数形式的列、行坐标
+                let col = (x * yb.y() - yb.x() * y) / determinant;
+                let row = (xb.x() * y - x * xb.y()) / determinant;
+                let (row, col) = Self::cube_round(row, col);
+
+                let (height, width) = self.map_size;
+                (row >= 0 && col >= 0 && (row as usize) < height && (col as usize) < width)
+                    .then(|| row as usize * width + col as usize)
+            })
+            .collect()
+    }
+
+    /// 将分数坐标取整到最近的六边形中心
+    ///
+    /// 以 `(col, row, -col - row)` 作为立方体坐标分别取整，
+    /// 再修正误差最大的一维，使三者之和仍为零。
+    fn cube_round(row: f64, col: f64) -> (isize, isize) {
+        let third = -row - col;
+        let (mut rounded_row, mut rounded_col, rounded_third) =
+            (row.round(), col.round(), third.round());
+
+        let row_error = (rounded_row - row).abs();
+        let col_error = (rounded_col - col).abs();
+        let third_error = (rounded_third - third).abs();
+
+        if col_error > row_error && col_error > third_error {
+            rounded_col = -rounded_row - rounded_third;
+        } else if row_error > third_error {
+            rounded_row = -rounded_col - rounded_third;
+        }
+
+        (rounded_row as isize, rounded_col as isize)
+    }
+}
@@ -0,0 +1,60 @@
+pub(crate) mod animation;
+pub(crate) mod color_map;
+pub(crate) mod heatmap;
+pub(crate) mod hex_layout;
+
+pub mod error_handling {
+    use crate::codec::error_handling::CodecError;
+    use crate::shared::subtance_type::SubstanceType;
+    use std::fmt;
+
+    #[derive(Debug)]
+    pub enum RenderError {
+        /// 地貌中没有该物质的分布
+        UnknownSubstance(SubstanceType),
+        /// 动画没有任何帧
+        EmptyAnimation,
+        /// 动画各帧的地图尺寸不一致
+        FrameSizeMismatch {
+            expected: (usize, usize),
+            found: (usize, usize),
+        },
+        /// 编码或写入图像失败
+        Codec(CodecError),
+    }
+
+    impl fmt::Display for RenderError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                RenderError::UnknownSubstance(substance_type) => {
+                    write!(
+                        f,
+                        "地貌中没有物质 {}/{} 的分布",
+                        substance_type.numer(),
+                        substance_type.denom()
+                    )
+                }
+                RenderError::EmptyAnimation => write!(f, "动画至少需要一帧"),
+                RenderError::FrameSizeMismatch { expected, found } => {
+                    write!(f, "帧尺寸不一致: 期望 {:?}，实际 {:?}", expected, found)
+                }
+                RenderError::Codec(e) => write!(f, "图像编码失败: {}", e),
+            }
+        }
+    }
+
+    impl std::error::Error for RenderError {
+        fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+            match self {
+                RenderError::Codec(e) => Some(e),
+                _ => None,
+            }
+        }
+    }
+
+    impl From<CodecError> for RenderError {
+        fn from(e: CodecError) -> Self {
+            RenderError::Codec(e)
+        }
+    }
+}
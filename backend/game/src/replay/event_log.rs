@@ -0,0 +1,96 @@
+use crate::replay::error_handling::ReplayError;
+use crate::replay::sim_event::{EventLogEntry, SimEvent};
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+
+/// 只追加的事件日志
+///
+/// 每行一条 JSON 格式的 `EventLogEntry`，每写入一条即刷新，进程意外退出时也最多丢失正在写入的一条。
+pub struct EventLog<W: Write> {
+    writer: W,
+    next_sequence: u64,
+}
+
+impl EventLog<BufWriter<File>> {
+    /// 在 `path` 创建新的事件日志文件，文件已存在时返回错误，不会覆盖已有的日志
+    pub fn create(path: impl AsRef<Path>) -> Result<Self, ReplayError> {
+        let file = OpenOptions::new().write(true).create_new(true).open(path)?;
+        Ok(Self::new(BufWriter::new(file)))
+    }
+}
+
+impl<W: Write> EventLog<W> {
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            next_sequence: 0,
+        }
+    }
+
+    /// 下一条事件的序号，即已写入的事件数
+    pub fn next_sequence(&self) -> u64 {
+        self.next_sequence
+    }
+
+    /// 追加一条事件
+    ///
+    /// ### 参数
+    /// - `tick`: 事件发生时世界的步数。
+    /// - `event`: 事件。
+    ///
+    /// ### 返回值
+    /// 返回写入的日志行。
+    pub fn append(&mut self, tick: u64, event: SimEvent) -> Result<EventLogEntry, ReplayError> {
+        let entry = EventLogEntry {
+            sequence: self.next_sequence,
+            tick,
+            event,
+        };
+        serde_json::to_writer(&mut self.writer, &entry)?;
+        self.writer.write_all(b"\n")?;
+        self.writer.flush()?;
+        self.next_sequence += 1;
+        Ok(entry)
+    }
+
+    /// 取回底层的写入器
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+/// 读取整个事件日志
+///
+/// 忽略空行；任何一行无法解析或序号不连续时返回错误。
+///
+/// ### 参数
+/// - `reader`: 事件日志的内容。
+pub fn read_event_log<R: BufRead>(reader: R) -> Result<Vec<EventLogEntry>, ReplayError> {
+    let mut entries = Vec::new();
+    for (index, line) in reader.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let entry: EventLogEntry =
+            serde_json::from_str(&line).map_err(|source| ReplayError::Malformed {
+                line: index + 1,
+                source,
+            })?;
+        let expected = entries.len() as u64;
+        if entry.sequence != expected {
+            return Err(ReplayError::Sequence {
+                expected,
+                found: entry.sequence,
+            });
+        }
+        entries.push(entry);
+    }
+    Ok(entries)
+}
+
+/// 读取 `path` 处的事件日志文件
+pub fn open_event_log(path: impl AsRef<Path>) -> Result<Vec<EventLogEntry>, ReplayError> {
+    read_event_log(BufReader::new(File::open(path)?))
+}
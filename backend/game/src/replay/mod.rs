@@ -0,0 +1,99 @@
+pub(crate) mod event_log;
+pub(crate) mod recorded_world;
+pub(crate) mod replayer;
+pub(crate) mod sim_event;
+pub(crate) mod simulation_state;
+pub(crate) mod world_params;
+
+pub mod error_handling {
+    use crate::shared::subtance_type::SubstanceType;
+    use std::fmt;
+    use uuid::Uuid;
+
+    #[derive(Debug)]
+    pub enum ReplayError {
+        /// 读写事件日志失败
+        Io(std::io::Error),
+        /// 事件无法序列化
+        Serialization(serde_json::Error),
+        /// 事件日志第 `line` 行无法解析
+        Malformed {
+            line: usize,
+            source: serde_json::Error,
+        },
+        /// 事件序号不连续，日志被截断或篡改
+        Sequence { expected: u64, found: u64 },
+        /// 事件日志为空，或第一条事件不是创建世界
+        MissingCreateWorld,
+        /// 创建世界之后再次出现创建世界的事件
+        UnexpectedCreateWorld { sequence: u64 },
+        /// 事件的步数早于世界当前的步数
+        TickOutOfOrder { sequence: u64, tick: u64 },
+        /// 地貌中已经存在该物质
+        DuplicateSubstance(SubstanceType),
+        /// 已经存在该智能体
+        DuplicateAgent(Uuid),
+        /// 当前加载的属性目录与创建世界时不同
+        CatalogueMismatch { expected: u64, actual: u64 },
+    }
+
+    impl fmt::Display for ReplayError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                ReplayError::Io(e) => write!(f, "读写事件日志失败: {}", e),
+                ReplayError::Serialization(e) => write!(f, "事件无法序列化: {}", e),
+                ReplayError::Malformed { line, source } => {
+                    write!(f, "事件日志第 {} 行无法解析: {}", line, source)
+                }
+                ReplayError::Sequence { expected, found } => write!(
+                    f,
+                    "事件序号不连续，应为 {}，实为 {}，日志可能被截断或篡改",
+                    expected, found
+                ),
+                ReplayError::MissingCreateWorld => write!(f, "事件日志不是以创建世界开始"),
+                ReplayError::UnexpectedCreateWorld { sequence } => {
+                    write!(f, "第 {} 号事件重复创建世界", sequence)
+                }
+                ReplayError::TickOutOfOrder { sequence, tick } => {
+                    write!(f, "第 {} 号事件的步数 {} 早于之前的事件", sequence, tick)
+                }
+                ReplayError::DuplicateSubstance(substance_type) => {
+                    write!(f, "地貌中已经存在物质 {}", substance_type)
+                }
+                ReplayError::DuplicateAgent(agent_id) => {
+                    write!(f, "已经存在智能体 {}", agent_id)
+                }
+                ReplayError::CatalogueMismatch { expected, actual } => write!(
+                    f,
+                    "属性目录与创建世界时不同（记录为 {:016x}，当前为 {:016x}），\
+                     请加载创建世界时使用的属性目录（服务端为 simulation.property_catalogue，\
+                     命令行工具为 --property-catalogue）",
+                    expected, actual
+                ),
+            }
+        }
+    }
+
+    impl std::error::Error for ReplayError {
+        fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+            match self {
+                ReplayError::Io(e) => Some(e),
+                ReplayError::Serialization(e) => Some(e),
+                ReplayError::Malformed { source, .. } => Some(source),
+                _ => None,
+            }
+        }
+    }
+
+    impl From<std::io::Error> for ReplayError {
+        fn from(e: std::io::Error) -> Self {
+            ReplayError::Io(e)
+        }
+    }
+
+    impl From<serde_json::Error> for ReplayError {
+        fn from(e: serde_json::Error) -> Self {
+            ReplayError::Serialization(e)
+        }
+    }
+}
@@ -0,0 +1,162 @@
+use crate::environment::landscape::Landscape;
+use crate::environment::noise_params::NoiseParams;
+use crate::metrics::recorder::SharedMetricsRecorder;
+use crate::replay::error_handling::ReplayError;
+use crate::replay::event_log::EventLog;
+use crate::replay::sim_event::{SimEvent, WorldCommand};
+use crate::replay::simulation_state::SimulationState;
+use crate::replay::world_params::WorldParams;
+use crate::shared::property_registry::PropertyRegistry;
+use crate::shared::subtance_type::SubstanceType;
+use std::collections::BTreeMap;
+use std::io::Write;
+use uuid::Uuid;
+
+/// 默认每隔多少步记录一次状态哈希
+const DEFAULT_HASH_INTERVAL: u64 = 10;
+
+/// 把创建参数、全部外部命令与定期的状态哈希写入事件日志的世界
+///
+/// ```
+/// use game::{
+///     open_event_log, EventLog, MapSize, NoiseParams, RecordedWorld, Replayer, WorldParams,
+/// };
+///
+/// let path = std::env::temp_dir().join(format!("events-{}.jsonl", uuid::Uuid::new_v4()));
+/// let params = WorldParams::new(None, MapSize::new(Some(8), Some(8)), Some(9.8));
+/// let mut world = RecordedWorld::create(params, EventLog::create(&path).unwrap(), Some(5)).unwrap();
+/// world
+///     .add_substance("1/2".parse().unwrap(), NoiseParams::new(Some(42), Some(2.0)))
+///     .unwrap();
+/// for _ in 0..10 {
+///     world.step().unwrap();
+/// }
+/// world.set_gravity(5.0).unwrap();
+/// world.spawn_agent(uuid::Uuid::new_v4()).unwrap();
+/// for _ in 0..10 {
+///     world.step().unwrap();
+/// }
+/// let final_hash = world.state().state_hash();
+/// world.finish().unwrap();
+///
+/// // 从日志重建世界，并逐一校验记录的状态哈希
+/// let report = Replayer::new(open_event_log(&path).unwrap()).replay(None).unwrap();
+/// assert!(report.is_consistent());
+/// assert_eq!(report.state().tick(), 20);
+/// assert_eq!(report.state().state_hash(), final_hash);
+/// # std::fs::remove_file(path).unwrap();
+/// ```
+pub struct RecordedWorld<W: Write> {
+    state: SimulationState,
+    log: EventLog<W>,
+    /// 每隔多少步记录一次状态哈希，为 0 时只在 `finish` 时记录
+    hash_interval: u64,
+    /// 最近一次记录状态哈希时的步数
+    hashed_tick: Option<u64>,
+}
+
+impl<W: Write> RecordedWorld<W> {
+    /// 创建世界，并记录创建参数、当前属性目录的哈希与第 0 步的状态哈希
+    ///
+    /// ### 参数
+    /// - `params`: 世界的创建参数。
+    /// - `log`: 尚未写入任何事件的事件日志。
+    /// - `hash_interval`: 每隔多少步记录一次状态哈希，默认为 10；为 0 时只在 `finish` 时记录。
+    pub fn create(
+        params: WorldParams,
+        log: EventLog<W>,
+        hash_interval: Option<u64>,
+    ) -> Result<Self, ReplayError> {
+        let params = params.with_catalogue_hash(PropertyRegistry::catalogue_hash());
+        let mut world = Self {
+            state: SimulationState::new(&params),
+            log,
+            hash_interval: hash_interval.unwrap_or(DEFAULT_HASH_INTERVAL),
+            hashed_tick: None,
+        };
+        world.log.append(
+            0,
+            SimEvent::CreateWorld {
+                params: Box::new(params),
+            },
+        )?;
+        world.record_hash()?;
+        Ok(world)
+    }
+
+    /// 为世界挂载指标记录器，之后每步结束时按记录器的间隔采样（可链式调用）
+    pub fn with_metrics(mut self, metrics: SharedMetricsRecorder) -> Self {
+        self.state = self.state.with_metrics(metrics);
+        self
+    }
+
+    pub fn state(&self) -> &SimulationState {
+        &self.state
+    }
+
+    pub fn landscape(&self) -> &Landscape {
+        self.state.landscape()
+    }
+
+    pub fn agents(&self) -> &BTreeMap<Uuid, u64> {
+        self.state.agents()
+    }
+
+    /// 执行并记录一条外部命令
+    ///
+    /// 命令先在世界上执行，成功后才写入日志，因此日志中不会出现无效的命令。
+    pub fn apply(&mut self, command: WorldCommand) -> Result<(), ReplayError> {
+        self.state.apply(&command)?;
+        self.log
+            .append(self.state.tick(), SimEvent::Command(command))?;
+        Ok(())
+    }
+
+    /// 加入一种物质，见 `WorldCommand::AddSubstance`
+    pub fn add_substance(
+        &mut self,
+        substance_type: SubstanceType,
+        noise_params: NoiseParams,
+    ) -> Result<(), ReplayError> {
+        self.apply(WorldCommand::AddSubstance {
+            substance_type,
+            noise_params,
+        })
+    }
+
+    /// 修改重力常数，见 `WorldCommand::SetGravity`
+    pub fn set_gravity(&mut self, gravity_const: f64) -> Result<(), ReplayError> {
+        self.apply(WorldCommand::SetGravity { gravity_const })
+    }
+
+    /// 生成一个智能体，见 `WorldCommand::SpawnAgent`
+    pub fn spawn_agent(&mut self, agent_id: Uuid) -> Result<(), ReplayError> {
+        self.apply(WorldCommand::SpawnAgent { agent_id })
+    }
+
+    /// 推进一步，到达记录间隔时记录状态哈希
+    pub fn step(&mut self) -> Result<(), ReplayError> {
+        self.state.step();
+        if self.hash_interval > 0 && self.state.tick().is_multiple_of(self.hash_interval) {
+            self.record_hash()?;
+        }
+        Ok(())
+    }
+
+    /// 立即记录当前的状态哈希
+    pub fn record_hash(&mut self) -> Result<u64, ReplayError> {
+        let hash = self.state.state_hash();
+        self.log
+            .append(self.state.tick(), SimEvent::StateHash { hash })?;
+        self.hashed_tick = Some(self.state.tick());
+        Ok(hash)
+    }
+
+    /// 结束记录：当前步尚未记录状态哈希时补记一次，返回底层的写入器
+    pub fn finish(mut self) -> Result<W, ReplayError> {
+        if self.hashed_tick != Some(self.state.tick()) {
+            self.record_hash()?;
+        }
+        Ok(self.log.into_inner())
+    }
+}
@@ -0,0 +1,173 @@
+use crate::metrics::recorder::SharedMetricsRecorder;
+use crate::replay::error_handling::ReplayError;
+use crate::replay::sim_event::{EventLogEntry, SimEvent};
+use crate::replay::simulation_state::SimulationState;
+use crate::shared::property_registry::PropertyRegistry;
+
+/// 重放时第一处与记录不一致的状态哈希
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HashDivergence {
+    /// 状态哈希事件的序号
+    pub sequence: u64,
+    pub tick: u64,
+    /// 日志中记录的哈希
+    pub expected: u64,
+    /// 重放得到的哈希
+    pub actual: u64,
+}
+
+/// 重放的结果
+#[derive(Debug, Clone)]
+pub struct ReplayReport {
+    state: SimulationState,
+    verified_hashes: usize,
+    divergence: Option<HashDivergence>,
+}
+
+impl ReplayReport {
+    /// 重放结束时的世界；出现不一致时停在不一致的那一步
+    pub fn state(&self) -> &SimulationState {
+        &self.state
+    }
+
+    pub fn into_state(self) -> SimulationState {
+        self.state
+    }
+
+    /// 校验通过的状态哈希数量
+    pub fn verified_hashes(&self) -> usize {
+        self.verified_hashes
+    }
+
+    pub fn divergence(&self) -> Option<&HashDivergence> {
+        self.divergence.as_ref()
+    }
+
+    /// 重放过程中的状态哈希是否全部与记录一致
+    pub fn is_consistent(&self) -> bool {
+        self.divergence.is_none()
+    }
+}
+
+/// 从事件日志重建世界
+///
+/// 按序号依次处理事件：先把世界推进到事件的步数，再执行命令或校验状态哈希。
+/// 遇到第一处不一致的哈希即停止，此时的世界可用于排查不确定性的来源。
+#[derive(Debug, Clone)]
+pub struct Replayer {
+    entries: Vec<EventLogEntry>,
+    metrics: Option<SharedMetricsRecorder>,
+}
+
+impl Replayer {
+    /// ### 参数
+    /// - `entries`: 事件日志的全部内容，通常由 `open_event_log` 读取。
+    pub fn new(entries: Vec<EventLogEntry>) -> Self {
+        Self {
+            entries,
+            metrics: None,
+        }
+    }
+
+    /// 为重放的世界挂载指标记录器（可链式调用）
+    pub fn with_metrics(mut self, metrics: SharedMetricsRecorder) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    pub fn entries(&self) -> &[EventLogEntry] {
+        &self.entries
+    }
+
+    /// 日志中最后一条事件的步数
+    pub fn last_tick(&self) -> Option<u64> {
+        self.entries.last().map(|entry| entry.tick)
+    }
+
+    /// 重放事件日志
+    ///
+    /// ### 参数
+    /// - `until_tick`: 重放到第几步为止，用于回到过去的某一步；
+    ///   晚于该步的事件被忽略，超过日志末尾时继续推进。默认重放到日志的最后一条事件。
+    ///
+    /// ### 返回值
+    /// 返回重放结束时的世界与校验结果；日志格式有误、命令无效，
+    /// 或当前的属性目录与创建世界时不同时返回错误。
+    pub fn replay(&self, until_tick: Option<u64>) -> Result<ReplayReport, ReplayError> {
+        let Some((first, rest)) = self.entries.split_first() else {
+            return Err(ReplayError::MissingCreateWorld);
+        };
+        let SimEvent::CreateWorld { params } = &first.event else {
+            return Err(ReplayError::MissingCreateWorld);
+        };
+
+        // 属性目录不同时，物质的性质随之不同，重放的结果不再可信
+        if let Some(expected) = params.catalogue_hash() {
+            let actual = PropertyRegistry::catalogue_hash();
+            if actual != expected {
+                return Err(ReplayError::CatalogueMismatch { expected, actual });
+            }
+        }
+
+        let mut state = SimulationState::new(params);
+        if let Some(metrics) = &self.metrics {
+            state = state.with_metrics(metrics.clone());
+        }
+        let mut report = ReplayReport {
+            state,
+            verified_hashes: 0,
+            divergence: None,
+        };
+        let state = &mut report.state;
+
+        for entry in rest {
+            if until_tick.is_some_and(|until_tick| entry.tick > until_tick) {
+                break;
+            }
+            if entry.tick < state.tick() {
+                return Err(ReplayError::TickOutOfOrder {
+                    sequence: entry.sequence,
+                    tick: entry.tick,
+                });
+            }
+            while state.tick() < entry.tick {
+                state.step();
+            }
+
+            match &entry.event {
+                SimEvent::CreateWorld { .. } => {
+                    return Err(ReplayError::UnexpectedCreateWorld {
+                        sequence: entry.sequence,
+                    })
+                }
+                SimEvent::Command(command) => state.apply(command)?,
+                SimEvent::StateHash { hash } => {
+                    let actual = state.state_hash();
+                    if actual != *hash {
+                        tracing::warn!(
+                            "重放到第 {} 步时状态哈希不一致: 记录为 {:016x}，重放为 {:016x}",
+                            entry.tick,
+                            hash,
+                            actual
+                        );
+                        report.divergence = Some(HashDivergence {
+                            sequence: entry.sequence,
+                            tick: entry.tick,
+                            expected: *hash,
+                            actual,
+                        });
+                        return Ok(report);
+                    }
+                    report.verified_hashes += 1;
+                }
+            }
+        }
+
+        if let Some(until_tick) = until_tick {
+            while state.tick() < until_tick {
+                state.step();
+            }
+        }
+        Ok(report)
+    }
+}
@@ -0,0 +1,48 @@
+use crate::environment::noise_params::NoiseParams;
+use crate::replay::world_params::WorldParams;
+use crate::shared::subtance_type::SubstanceType;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// 从外部改变世界的命令
+///
+/// 世界的演化只取决于创建参数、这些命令以及它们发生的步数，
+/// 因此绕过事件日志直接修改地貌或 `GameContext` 的操作无法被重放。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "command", rename_all = "snake_case")]
+pub enum WorldCommand {
+    /// 按噪声参数生成一种物质的分布并加入地貌
+    AddSubstance {
+        substance_type: SubstanceType,
+        noise_params: NoiseParams,
+    },
+    /// 通过 `GameContext::update_game_gravity_const` 修改重力常数，并重新计算势能场强
+    SetGravity { gravity_const: f64 },
+    /// 在世界中生成一个智能体
+    SpawnAgent { agent_id: Uuid },
+}
+
+/// 事件日志中的事件
+///
+/// 以 JSON 记录，`event` 字段区分事件类型，命令的 `command` 字段区分命令类型，例如
+/// `{"event":"command","command":"set_gravity","gravity_const":9.8}`。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum SimEvent {
+    /// 创建世界，总是日志中的第一条事件
+    CreateWorld { params: Box<WorldParams> },
+    /// 外部命令
+    Command(WorldCommand),
+    /// 当前步世界状态的哈希，重放时用于校验
+    StateHash { hash: u64 },
+}
+
+/// 事件日志中的一行
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EventLogEntry {
+    /// 从 0 开始连续递增的序号
+    pub sequence: u64,
+    /// 事件发生时世界的步数，命令在这一步扩散之后、下一步扩散之前生效
+    pub tick: u64,
+    pub event: SimEvent,
+}
@@ -0,0 +1,137 @@
+use crate::environment::landscape::Landscape;
+use crate::environment::subtance_distribution::SubstanceDistribution;
+use crate::environment::t_noise_generatable::NoiseGeneratable;
+use crate::game_context::GameContext;
+use crate::metrics::recorder::SharedMetricsRecorder;
+use crate::replay::error_handling::ReplayError;
+use crate::replay::sim_event::WorldCommand;
+use crate::replay::world_params::WorldParams;
+use crc::{Crc, CRC_64_XZ};
+use std::collections::BTreeMap;
+use uuid::Uuid;
+
+const STATE_HASH: Crc<u64> = Crc::<u64>::new(&CRC_64_XZ);
+
+/// 可以被事件日志完整描述的世界状态
+///
+/// 重力常数保存在全局的 `GameContext` 中，同一进程内同时模拟或重放多个世界时会相互干扰。
+#[derive(Debug, Clone)]
+pub struct SimulationState {
+    landscape: Landscape,
+    gravity_const: f64,
+    /// 智能体及其出生的步数
+    agents: BTreeMap<Uuid, u64>,
+}
+
+impl SimulationState {
+    /// 按创建参数生成第 0 步的世界
+    pub fn new(params: &WorldParams) -> Self {
+        Self {
+            landscape: params.build(),
+            gravity_const: params.gravity_const(),
+            agents: BTreeMap::new(),
+        }
+    }
+
+    /// 为地貌挂载指标记录器，之后每步结束时按记录器的间隔采样（可链式调用）
+    pub fn with_metrics(mut self, metrics: SharedMetricsRecorder) -> Self {
+        self.landscape = self.landscape.with_metrics(metrics);
+        self
+    }
+
+    pub fn landscape(&self) -> &Landscape {
+        &self.landscape
+    }
+
+    pub fn gravity_const(&self) -> f64 {
+        self.gravity_const
+    }
+
+    pub fn agents(&self) -> &BTreeMap<Uuid, u64> {
+        &self.agents
+    }
+
+    pub fn tick(&self) -> u64 {
+        self.landscape.tick()
+    }
+
+    /// 执行一条外部命令
+    ///
+    /// 命令无效时返回错误，世界保持不变。
+    pub fn apply(&mut self, command: &WorldCommand) -> Result<(), ReplayError> {
+        match command {
+            WorldCommand::AddSubstance {
+                substance_type,
+                noise_params,
+            } => {
+                let exists = self
+                    .landscape
+                    .subtance_distributions()
+                    .iter()
+                    .any(|distribution| distribution.substance_type() == substance_type);
+                if exists {
+                    return Err(ReplayError::DuplicateSubstance(*substance_type));
+                }
+                let mut distribution = SubstanceDistribution::new(
+                    *substance_type,
+                    *self.landscape.map_size(),
+                    Some(noise_params.clone()),
+                );
+                distribution.generate_simplex_noise();
+                self.landscape.add_resource_distribution(distribution);
+                self.landscape.update_potential_distribution();
+            }
+            WorldCommand::SetGravity { gravity_const } => {
+                GameContext::update_game_gravity_const(*gravity_const);
+                self.gravity_const = *gravity_const;
+                self.landscape.update_potential_distribution();
+            }
+            WorldCommand::SpawnAgent { agent_id } => {
+                if self.agents.contains_key(agent_id) {
+                    return Err(ReplayError::DuplicateAgent(*agent_id));
+                }
+                self.agents.insert(*agent_id, self.tick());
+            }
+        }
+        Ok(())
+    }
+
+    /// 推进一步：先按当前分布更新势能场强，再扩散
+    pub fn step(&mut self) {
+        self.landscape.update_potential_distribution();
+        self.landscape.diffuse();
+    }
+
+    /// 世界状态的哈希
+    ///
+    /// 对步数、重力常数、地图尺寸、按物质类型排序的每个单元格的摩尔数与运动状态（按位），
+    /// 以及智能体计算 CRC-64，不依赖 `HashSet` 的遍历顺序与序列化格式。
+    /// 势能场强与地形图层由这些状态决定，不参与计算。
+    pub fn state_hash(&self) -> u64 {
+        let mut digest = STATE_HASH.digest();
+        digest.update(&self.tick().to_le_bytes());
+        digest.update(&self.gravity_const.to_bits().to_le_bytes());
+        let (height, width) = self.landscape.map_size().as_tuple();
+        digest.update(&(height as u64).to_le_bytes());
+        digest.update(&(width as u64).to_le_bytes());
+
+        let mut distributions: Vec<_> = self.landscape.subtance_distributions().iter().collect();
+        distributions.sort_by_key(|distribution| *distribution.substance_type());
+        for distribution in distributions {
+            let ratio = distribution.substance_type().ratio;
+            digest.update(&(*ratio.numer() as u64).to_le_bytes());
+            digest.update(&(*ratio.denom() as u64).to_le_bytes());
+            for unit in distribution.distribution().iter() {
+                digest.update(&(unit.mole() as u64).to_le_bytes());
+                digest.update(&unit.movement().y().to_bits().to_le_bytes());
+                digest.update(&unit.movement().x().to_bits().to_le_bytes());
+            }
+        }
+
+        for (agent_id, born_tick) in &self.agents {
+            digest.update(agent_id.as_bytes());
+            digest.update(&born_tick.to_le_bytes());
+        }
+        digest.finalize()
+    }
+}
@@ -0,0 +1,138 @@
+use crate::environment::coupling_params::CouplingParams;
+use crate::environment::diffusion_mode::DiffusionMode;
+use crate::environment::landscape::Landscape;
+use crate::environment::map_size::MapSize;
+use crate::environment::stability_params::StabilityParams;
+use crate::environment::terrain_params::TerrainParams;
+use crate::game_context::GameContext;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// 创建世界的全部参数
+///
+/// 同样的参数总是生成同样的初始地貌，因此事件日志只需记录参数，而不是地貌本身。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorldParams {
+    civilization_id: Uuid,
+    map_size: MapSize,
+    gravity_const: f64,
+    #[serde(default)]
+    coupling: Option<CouplingParams>,
+    #[serde(default)]
+    terrain: Option<TerrainParams>,
+    #[serde(default)]
+    diffusion_mode: DiffusionMode,
+    #[serde(default)]
+    stability: StabilityParams,
+    /// 创建世界时属性目录的哈希，见 `PropertyRegistry::catalogue_hash`；旧的事件日志中没有
+    #[serde(default)]
+    catalogue_hash: Option<u64>,
+}
+
+impl WorldParams {
+    /// 创建世界参数：平坦地图、独立扩散
+    ///
+    /// ### 参数
+    /// - `civilization_id`: 世界的标识，默认随机生成。
+    /// - `map_size`: 地图尺寸。
+    /// - `gravity_const`: 重力常数，默认为 10.0。
+    pub fn new(
+        civilization_id: Option<Uuid>,
+        map_size: MapSize,
+        gravity_const: Option<f64>,
+    ) -> Self {
+        Self {
+            civilization_id: civilization_id.unwrap_or_else(Uuid::new_v4),
+            map_size,
+            gravity_const: gravity_const.unwrap_or(10.0),
+            coupling: None,
+            terrain: None,
+            diffusion_mode: DiffusionMode::default(),
+            stability: StabilityParams::default(),
+            catalogue_hash: None,
+        }
+    }
+
+    /// 启用耦合扩散模式（可链式调用）
+    pub fn with_coupling(mut self, coupling: CouplingParams) -> Self {
+        self.coupling = Some(coupling);
+        self
+    }
+
+    /// 按地形参数生成地形图层（可链式调用）
+    pub fn with_terrain(mut self, terrain: TerrainParams) -> Self {
+        self.terrain = Some(terrain);
+        self
+    }
+
+    /// 设置扩散时势能力的计算方式（可链式调用）
+    pub fn with_diffusion_mode(mut self, diffusion_mode: DiffusionMode) -> Self {
+        self.diffusion_mode = diffusion_mode;
+        self
+    }
+
+    /// 设置扩散的数值稳定性参数（可链式调用）
+    pub fn with_stability(mut self, stability: StabilityParams) -> Self {
+        self.stability = stability;
+        self
+    }
+
+    /// 记录创建世界时属性目录的哈希（可链式调用）
+    pub(crate) fn with_catalogue_hash(mut self, catalogue_hash: u64) -> Self {
+        self.catalogue_hash = Some(catalogue_hash);
+        self
+    }
+
+    pub fn civilization_id(&self) -> Uuid {
+        self.civilization_id
+    }
+
+    pub fn map_size(&self) -> &MapSize {
+        &self.map_size
+    }
+
+    pub fn gravity_const(&self) -> f64 {
+        self.gravity_const
+    }
+
+    pub fn coupling(&self) -> Option<&CouplingParams> {
+        self.coupling.as_ref()
+    }
+
+    pub fn terrain(&self) -> Option<&TerrainParams> {
+        self.terrain.as_ref()
+    }
+
+    pub fn diffusion_mode(&self) -> DiffusionMode {
+        self.diffusion_mode
+    }
+
+    pub fn stability(&self) -> &StabilityParams {
+        &self.stability
+    }
+
+    pub fn catalogue_hash(&self) -> Option<u64> {
+        self.catalogue_hash
+    }
+
+    /// 按参数生成第 0 步的地貌
+    ///
+    /// 同时将重力常数与文明 ID 写入全局的 `GameContext`。
+    pub fn build(&self) -> Landscape {
+        GameContext::update_game_gravity_const(self.gravity_const);
+        GameContext::update_game_civilization_id(self.civilization_id);
+
+        let mut landscape = Landscape::new(self.map_size)
+            .with_civilization_id(self.civilization_id)
+            .with_diffusion_mode(self.diffusion_mode)
+            .with_stability(self.stability.clone());
+        if let Some(coupling) = &self.coupling {
+            landscape = landscape.with_coupling(coupling.clone());
+        }
+        if let Some(terrain) = &self.terrain {
+            landscape = landscape.with_terrain(terrain.clone());
+        }
+        landscape.update_potential_distribution();
+        landscape
+    }
+}
@@ -1,4 +1,7 @@
 pub(crate) mod property;
+pub(crate) mod property_cache;
 pub(crate) mod property_param;
+pub(crate) mod property_registry;
+pub(crate) mod substance_registry;
 pub(crate) mod subtance;
 pub(crate) mod subtance_type;
@@ -1,10 +1,14 @@
+use crate::shared::property_cache::PropertyCache;
 use crate::shared::property_param::PropertyParam;
 use crate::shared::subtance_type::SubstanceType;
+use error_handling::PropertyParseError;
+use serde::{Deserialize, Serialize};
 use share_and_commute::enum_map;
+use std::str::FromStr;
 
 enum_map! {
-    #[derive(Clone, Copy)]
-    pub(crate) Property => PropertyParam {
+    #[derive(Clone, Copy, Serialize, Deserialize)]
+    pub Property => PropertyParam {
         // 摩尔质量
         MolarMass => || PropertyParam::new(1, 1, None, None),
         // 密度
@@ -23,11 +27,58 @@ impl Property {
     ///
     /// ### 返回值
     /// 返回计算后的属性值。
-    pub(crate) fn calculate_property(property: Property, substance_type: &SubstanceType) -> f64 {
+    ///
+    /// ### 示例
+    /// ```
+    /// use game::{Property, SubstanceType};
+    ///
+    /// let substance_type = SubstanceType::try_new(1, 2).unwrap();
+    /// let fluidity = Property::calculate_property(Property::Fluidity, &substance_type);
+    /// assert_eq!(fluidity, substance_type.property_calculate(Property::Fluidity, None, None));
+    /// ```
+    pub fn calculate_property(property: Property, substance_type: &SubstanceType) -> f64 {
         // 从静态映射中获取对应的属性参数
-        Property::to_map()
-            .get(&property)
-            .expect("属性未找到")
-            .calculate(substance_type)
+        let param = Property::to_map().get(&property).expect("属性未找到");
+        PropertyCache::get_or_calculate(param, substance_type)
+    }
+}
+
+impl FromStr for Property {
+    type Err = PropertyParseError;
+
+    /// 按变体名称解析属性，例如 `"MolarMass"`
+    ///
+    /// ```
+    /// use game::Property;
+    ///
+    /// assert_eq!("Density".parse::<Property>().unwrap(), Property::Density);
+    /// assert!("Unknown".parse::<Property>().is_err());
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Property::VARIANTS
+            .iter()
+            .find(|property| property.name() == s.trim())
+            .copied()
+            .ok_or_else(|| PropertyParseError::Unknown(s.to_string()))
     }
 }
+
+pub mod error_handling {
+    use std::fmt;
+
+    #[derive(Debug)]
+    pub enum PropertyParseError {
+        /// 不是任何属性变体的名称
+        Unknown(String),
+    }
+
+    impl fmt::Display for PropertyParseError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                PropertyParseError::Unknown(text) => write!(f, "未知的属性: {}", text),
+            }
+        }
+    }
+
+    impl std::error::Error for PropertyParseError {}
+}
@@ -0,0 +1,193 @@
+use crate::shared::property_param::PropertyParam;
+use crate::shared::subtance_type::SubstanceType;
+use back_core::repository::error_handling::RepositoryError;
+use back_core::repository::property::{PropertyRecord, PropertyRepository};
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+/// 属性缓存的条目上限
+///
+/// 键空间随物质类型与属性参数的组合增长，没有天然的上界。
+/// 达到上限后不再写入新条目，未命中的查询直接计算，已缓存的条目不受影响。
+const MAX_ENTRIES: usize = 1 << 16;
+
+/// 全局属性缓存实例
+static PROPERTY_CACHE: Lazy<Arc<RwLock<HashMap<PropertyCacheKey, f64>>>> =
+    Lazy::new(|| Arc::new(RwLock::new(HashMap::new())));
+
+/// 属性缓存的键
+///
+/// 属性值只取决于物质类型与有效常量 `a + c`、`b + d`，
+/// 因此以有效常量作为键：偏移量改变后自然落到新的键上，不会读到过期的值。
+/// 这也与 `properties` 表中 `frequency_constant`、`phase_constant` 两列的含义一致。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) struct PropertyCacheKey {
+    substance_type: SubstanceType,
+    frequency: isize,
+    phase: isize,
+}
+
+impl PropertyCacheKey {
+    pub(crate) fn new(substance_type: SubstanceType, param: &PropertyParam) -> Self {
+        Self {
+            substance_type,
+            frequency: param.frequency_constant + param.frequency_offset,
+            phase: param.phase_constant + param.phase_offset,
+        }
+    }
+}
+
+/// 物质类型 × 属性参数的属性值缓存
+///
+/// 属性值的计算需要一次正弦运算，而扩散、交互与合成会对同一组物质反复查询，
+/// 因此在内存中缓存计算结果，并支持从 `properties` 表预热与写回。
+///
+/// 命中只需读锁；未命中时需要短暂获取全局写锁写入结果。
+/// 缓存最多保存 `MAX_ENTRIES` 条，写满后未命中的查询不再获取写锁，直接返回计算结果。
+pub struct PropertyCache;
+
+// get
+impl PropertyCache {
+    /// 获取缓存的属性值，未命中时计算并写入缓存
+    ///
+    /// ### 参数
+    /// - `param`: 属性参数，包含偏移量。
+    /// - `substance_type`: 物质类型。
+    ///
+    /// ### 返回值
+    /// 返回与 `param.calculate(substance_type)` 相同的属性值。
+    pub fn get_or_calculate(param: &PropertyParam, substance_type: &SubstanceType) -> f64 {
+        let key = PropertyCacheKey::new(*substance_type, param);
+
+        let (cached, full) =
+            Self::access_cache(|cache| (cache.get(&key).copied(), cache.len() >= MAX_ENTRIES));
+        if let Some(value) = cached {
+            return value;
+        }
+
+        let value = param.calculate(substance_type);
+        if !full {
+            Self::update_cache(|cache| {
+                if cache.len() < MAX_ENTRIES {
+                    cache.insert(key, value);
+                }
+            });
+        }
+        value
+    }
+
+    /// 获取当前缓存的条目数
+    pub fn len() -> usize {
+        Self::access_cache(|cache| cache.len())
+    }
+
+    /// 访问全局缓存并执行指定操作
+    fn access_cache<F, T>(accessor: F) -> T
+    where
+        F: FnOnce(&HashMap<PropertyCacheKey, f64>) -> T,
+    {
+        let cache = PROPERTY_CACHE
+            .read()
+            .expect("未能获取读锁，读取属性缓存失败");
+        accessor(&cache)
+    }
+}
+
+// update
+impl PropertyCache {
+    /// 通用更新方法
+    fn update_cache<F, T>(update_fn: F) -> T
+    where
+        F: FnOnce(&mut HashMap<PropertyCacheKey, f64>) -> T,
+    {
+        let cache = PROPERTY_CACHE.clone();
+        let mut cache = cache.write().expect("未能获取写锁，更新属性缓存失败");
+        update_fn(&mut cache)
+    }
+
+    /// 清空全部缓存
+    pub fn clear() {
+        Self::update_cache(|cache| cache.clear());
+    }
+}
+
+// persistence
+impl PropertyCache {
+    /// 从 `properties` 表预热缓存，服务启动时调用
+    ///
+    /// ### 参数
+    /// - `repository`: `properties` 表的仓储。
+    ///
+    /// ### 返回值
+    /// 返回载入缓存的条目数，无法构造合法物质类型的行会被跳过，超出条目上限的行会被忽略。
+    pub async fn warm_from_db(repository: &PropertyRepository) -> Result<usize, RepositoryError> {
+        let entries: Vec<(PropertyCacheKey, f64)> = repository
+            .list()
+            .await?
+            .into_iter()
+            .filter_map(|record| {
+                let substance_type = SubstanceType::try_new(
+                    usize::try_from(record.substance_numerator).ok()?,
+                    usize::try_from(record.substance_denominator).ok()?,
+                )
+                .ok()?;
+                let param = PropertyParam::new(
+                    record.frequency_constant as isize,
+                    record.phase_constant as isize,
+                    None,
+                    None,
+                );
+                Some((
+                    PropertyCacheKey::new(substance_type, &param),
+                    record.property_value,
+                ))
+            })
+            .collect();
+
+        let count = Self::update_cache(|cache| {
+            let before = cache.len();
+            let room = MAX_ENTRIES.saturating_sub(before);
+            cache.extend(entries.into_iter().take(room));
+            cache.len() - before
+        });
+
+        tracing::info!("已从 properties 表预热 {} 条属性缓存", count);
+        Ok(count)
+    }
+
+    /// 将缓存写回 `properties` 表，服务退出时调用
+    ///
+    /// 在同一事务中分批执行 upsert，冲突时更新 `property_value`。
+    /// 表约束要求分子大于零，因此物质类型为 0 的条目不会被写入。
+    ///
+    /// ### 参数
+    /// - `repository`: `properties` 表的仓储。
+    ///
+    /// ### 返回值
+    /// 返回写入的条目数。
+    pub async fn persist_to_db(repository: &PropertyRepository) -> Result<usize, RepositoryError> {
+        let records: Vec<PropertyRecord> = Self::access_cache(|cache| {
+            cache
+                .iter()
+                .filter_map(|(key, value)| {
+                    Some(PropertyRecord {
+                        substance_numerator: i32::try_from(*key.substance_type.ratio.numer())
+                            .ok()
+                            .filter(|n| *n > 0)?,
+                        substance_denominator: i32::try_from(*key.substance_type.ratio.denom())
+                            .ok()?,
+                        frequency_constant: i32::try_from(key.frequency).ok()?,
+                        phase_constant: i32::try_from(key.phase).ok()?,
+                        property_value: *value,
+                    })
+                })
+                .collect()
+        });
+
+        let count = repository.upsert_many(&records).await?;
+
+        tracing::info!("已将 {} 条属性缓存写回 properties 表", count);
+        Ok(count)
+    }
+}
@@ -1,6 +1,6 @@
 use crate::shared::subtance_type::SubstanceType;
 use num::traits::ToPrimitive;
-use std::f64::{consts::PI, EPSILON};
+use std::f64::consts::PI;
 
 /// `PropertyParam` 结构体，用于描述物质的属性
 ///
@@ -9,8 +9,8 @@ use std::f64::{consts::PI, EPSILON};
 /// - `phase_constant`: 属性相位的基础常量（b）
 /// - `environment_frequency_factor`: 环境频率因子（c），用于根据环境影响动态调整频率
 /// - `environment_phase_factor`: 环境相位因子（d），用于根据环境影响动态调整相位
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
-pub(crate) struct PropertyParam {
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct PropertyParam {
     pub(crate) frequency_constant: isize, // 频率常量 a
     pub(crate) phase_constant: isize,     // 相位常量 b
     pub(crate) frequency_offset: isize,   // 环境频率因子 c
@@ -29,9 +29,11 @@ impl PropertyParam {
     ///
     /// ### 示例
     /// ```
-    /// let property = PropertyParam::new(1, 0);
+    /// use game::PropertyParam;
+    ///
+    /// let property = PropertyParam::new(1, 0, None, None);
     /// ```
-    pub(crate) fn new(
+    pub fn new(
         frequency_constant: isize,
         phase_constant: isize,
         frequency_offset: Option<isize>,
@@ -55,11 +57,13 @@ impl PropertyParam {
     ///
     /// ### 示例
     /// ```
-    /// let property = PropertyParam::new(1, 0).with_env_frequency(2);
+    /// use game::PropertyParam;
+    ///
+    /// let property = PropertyParam::new(1, 0, None, None).with_frequency_offset(2);
     /// ```
-    pub(crate) fn with_frequency_offset(mut self, env_frequency: isize) -> Self {
+    pub fn with_frequency_offset(mut self, env_frequency: isize) -> Self {
         self.frequency_offset = env_frequency;
-        return self;
+        self
     }
 
     /// 设置相位常量（可链式调用）
@@ -72,11 +76,13 @@ impl PropertyParam {
     ///
     /// ### 示例
     /// ```
-    /// let property = PropertyParam::new(1, 0).with_env_phase(0);
+    /// use game::PropertyParam;
+    ///
+    /// let property = PropertyParam::new(1, 0, None, None).with_phase_offset(0);
     /// ```
-    pub(crate) fn with_phase_offset(mut self, env_phase: isize) -> Self {
+    pub fn with_phase_offset(mut self, env_phase: isize) -> Self {
         self.phase_offset = env_phase;
-        return self;
+        self
     }
 
     /// 计算属性值
@@ -96,7 +102,7 @@ impl PropertyParam {
     /// - `θ = SubstanceType * π`
     /// - `a` 和 `b` 为基础频率和相位常量
     /// - `c` 和 `d` 为环境频率因子和相位因子
-    pub(crate) fn calculate(&self, st: &SubstanceType) -> f64 {
+    pub fn calculate(&self, st: &SubstanceType) -> f64 {
         // 计算 θ = 资源类型系数 × π
         let theta = st.ratio.to_f64().unwrap() * PI;
 
@@ -108,6 +114,6 @@ impl PropertyParam {
             / 2.0;
 
         // 取值范围为 [0, 1]，但是添加了一个极小值，避免出现 0 的情况
-        result.min(1.0).max(EPSILON)
+        result.clamp(f64::EPSILON, 1.0)
     }
 }
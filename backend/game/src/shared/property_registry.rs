@@ -0,0 +1,472 @@
+use crate::shared::property::Property;
+use crate::shared::property_cache::PropertyCache;
+use crate::shared::property_param::PropertyParam;
+use crate::shared::subtance_type::SubstanceType;
+use crc::{Crc, CRC_64_XZ};
+use error_handling::PropertyRegistryError;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::sync::{Arc, RwLock};
+
+/// 属性目录哈希所用的算法，与状态哈希相同
+const CATALOGUE_HASH: Crc<u64> = Crc::<u64>::new(&CRC_64_XZ);
+
+/// 全局属性注册表实例，初始时只包含 `Property` 枚举中的内置属性
+static PROPERTY_REGISTRY: Lazy<Arc<RwLock<PropertyRegistry>>> =
+    Lazy::new(|| Arc::new(RwLock::new(PropertyRegistry::builtin())));
+
+/// 属性定义
+///
+/// - `name`: 属性名称，作为注册表的键。
+/// - `frequency_constant` / `phase_constant`: 与 `PropertyParam` 相同的频率、相位常量。
+/// - `lower_bound` / `upper_bound`: 属性取值范围，`PropertyParam` 的 [0, 1] 结果会线性映射到该范围。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PropertyDefinition {
+    name: String,
+    frequency_constant: isize,
+    phase_constant: isize,
+    #[serde(default = "PropertyDefinition::default_lower_bound")]
+    lower_bound: f64,
+    #[serde(default = "PropertyDefinition::default_upper_bound")]
+    upper_bound: f64,
+}
+
+impl PropertyDefinition {
+    pub fn new(
+        name: impl Into<String>,
+        frequency_constant: isize,
+        phase_constant: isize,
+        lower_bound: Option<f64>,
+        upper_bound: Option<f64>,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            frequency_constant,
+            phase_constant,
+            lower_bound: lower_bound.unwrap_or_else(Self::default_lower_bound),
+            upper_bound: upper_bound.unwrap_or_else(Self::default_upper_bound),
+        }
+    }
+
+    fn default_lower_bound() -> f64 {
+        0.0
+    }
+
+    fn default_upper_bound() -> f64 {
+        1.0
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn lower_bound(&self) -> f64 {
+        self.lower_bound
+    }
+
+    pub fn upper_bound(&self) -> f64 {
+        self.upper_bound
+    }
+
+    /// 获取对应的属性参数
+    pub fn param(&self) -> PropertyParam {
+        PropertyParam::new(self.frequency_constant, self.phase_constant, None, None)
+    }
+
+    /// 计算指定物质在本属性上的取值
+    ///
+    /// ### 参数
+    /// - `substance_type`: 物质类型。
+    ///
+    /// ### 返回值
+    /// 返回映射到 [`lower_bound`, `upper_bound`] 范围内的属性值。
+    pub fn calculate(&self, substance_type: &SubstanceType) -> f64 {
+        self.lower_bound + (self.upper_bound - self.lower_bound) * self.normalized(substance_type)
+    }
+
+    /// 计算指定物质在本属性上的归一化取值，范围为 (0, 1]
+    pub fn normalized(&self, substance_type: &SubstanceType) -> f64 {
+        PropertyCache::get_or_calculate(&self.param(), substance_type)
+    }
+
+    /// 校验定义是否合法
+    fn validate(&self) -> Result<(), PropertyRegistryError> {
+        if self.name.trim().is_empty() {
+            return Err(PropertyRegistryError::EmptyName);
+        }
+
+        if !(self.lower_bound.is_finite()
+            && self.upper_bound.is_finite()
+            && self.lower_bound < self.upper_bound)
+        {
+            return Err(PropertyRegistryError::InvalidBounds(self.name.clone()));
+        }
+
+        Ok(())
+    }
+}
+
+/// 属性目录文件的结构，可以写成 JSON 或 TOML
+///
+/// ```json
+/// {
+///     "properties": [
+///         { "name": "Toxicity", "frequency_constant": 5, "phase_constant": 1 },
+///         { "name": "Nutrition", "frequency_constant": 7, "phase_constant": 4, "upper_bound": 10.0 }
+///     ]
+/// }
+/// ```
+///
+/// ```toml
+/// [[properties]]
+/// name = "Toxicity"
+/// frequency_constant = 5
+/// phase_constant = 1
+///
+/// [[properties]]
+/// name = "Nutrition"
+/// frequency_constant = 7
+/// phase_constant = 4
+/// upper_bound = 10.0
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PropertyCatalogue {
+    properties: Vec<PropertyDefinition>,
+}
+
+/// 属性注册表，按名称索引所有属性定义
+///
+/// 内置属性来自 `Property` 枚举，不允许被覆盖，从而保证基于枚举的调用结果不变；
+/// 其余属性可在启动时从属性目录文件加载，无需重新编译。
+#[derive(Debug, Clone, Default)]
+pub struct PropertyRegistry {
+    definitions: BTreeMap<String, PropertyDefinition>,
+}
+
+impl PropertyRegistry {
+    /// 创建只包含内置属性的注册表
+    fn builtin() -> Self {
+        let definitions = Property::VARIANTS
+            .iter()
+            .map(|property| {
+                let param = Property::to_map().get(property).expect("属性未找到");
+                let definition = PropertyDefinition::new(
+                    property.name(),
+                    param.frequency_constant,
+                    param.phase_constant,
+                    None,
+                    None,
+                );
+                (definition.name.clone(), definition)
+            })
+            .collect();
+
+        Self { definitions }
+    }
+
+    /// 判断名称是否属于内置属性
+    fn is_builtin(name: &str) -> bool {
+        Property::VARIANTS
+            .iter()
+            .any(|property| property.name() == name)
+    }
+
+    /// 向本注册表插入一个属性定义
+    fn insert(&mut self, definition: PropertyDefinition) -> Result<(), PropertyRegistryError> {
+        definition.validate()?;
+
+        if Self::is_builtin(&definition.name) {
+            return Err(PropertyRegistryError::BuiltinOverride(definition.name));
+        }
+
+        self.definitions.insert(definition.name.clone(), definition);
+        Ok(())
+    }
+}
+
+// update
+impl PropertyRegistry {
+    /// 通用更新方法
+    fn update_registry<F, T>(update_fn: F) -> T
+    where
+        F: FnOnce(&mut PropertyRegistry) -> T,
+    {
+        let registry = PROPERTY_REGISTRY.clone();
+        let mut registry = registry.write().expect("未能获取写锁，更新属性注册表失败");
+        update_fn(&mut registry)
+    }
+
+    /// 注册单个属性定义，同名的非内置属性会被替换
+    pub fn register(definition: PropertyDefinition) -> Result<(), PropertyRegistryError> {
+        Self::update_registry(|registry| registry.insert(definition))
+    }
+
+    /// 注册属性目录中的全部定义
+    ///
+    /// 先在副本上校验并插入全部定义，全部成功后才替换全局注册表，
+    /// 因此目录中任一定义不合法时，全局注册表保持不变。
+    ///
+    /// ### 返回值
+    /// 返回成功注册的属性数量。
+    pub fn register_catalogue(
+        catalogue: PropertyCatalogue,
+    ) -> Result<usize, PropertyRegistryError> {
+        Self::update_registry(|registry| {
+            let mut staged = registry.clone();
+            let count = catalogue.properties.len();
+            for definition in catalogue.properties {
+                staged.insert(definition)?;
+            }
+            *registry = staged;
+            Ok(count)
+        })
+    }
+
+    /// 从 JSON 字符串加载属性目录
+    pub fn load_json_str(json: &str) -> Result<usize, PropertyRegistryError> {
+        let catalogue: PropertyCatalogue = serde_json::from_str(json)?;
+        Self::register_catalogue(catalogue)
+    }
+
+    /// 从 TOML 字符串加载属性目录
+    ///
+    /// ### 示例
+    /// ```
+    /// use game::PropertyRegistry;
+    ///
+    /// let count = PropertyRegistry::load_toml_str(
+    ///     r#"
+    ///     [[properties]]
+    ///     name = "Toxicity"
+    ///     frequency_constant = 5
+    ///     phase_constant = 1
+    ///     "#,
+    /// )
+    /// .unwrap();
+    /// assert_eq!(count, 1);
+    /// assert!(PropertyRegistry::get("Toxicity").is_some());
+    /// ```
+    pub fn load_toml_str(toml: &str) -> Result<usize, PropertyRegistryError> {
+        let catalogue: PropertyCatalogue = toml::from_str(toml)?;
+        Self::register_catalogue(catalogue)
+    }
+
+    /// 从文件加载属性目录，按扩展名选择格式：`.toml` 或 `.json`
+    pub fn load_file(path: impl AsRef<Path>) -> Result<usize, PropertyRegistryError> {
+        let path = path.as_ref();
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("toml") => Self::load_toml_str(&fs::read_to_string(path)?),
+            Some("json") => Self::load_json_str(&fs::read_to_string(path)?),
+            _ => Err(PropertyRegistryError::UnsupportedFormat(
+                path.display().to_string(),
+            )),
+        }
+    }
+
+    /// 启动时加载属性目录
+    ///
+    /// ### 参数
+    /// - `path`: 配置的属性目录文件，为 `None` 时只使用内置属性。
+    ///
+    /// ### 返回值
+    /// 返回从文件中注册的属性数量。
+    pub fn init(path: Option<&Path>) -> Result<usize, PropertyRegistryError> {
+        match path {
+            Some(path) => {
+                let count = Self::load_file(path)?;
+                tracing::info!("已从 {} 加载 {} 个属性定义", path.display(), count);
+                Ok(count)
+            }
+            None => {
+                tracing::debug!("未配置属性目录，仅使用内置属性");
+                Ok(0)
+            }
+        }
+    }
+}
+
+// get
+impl PropertyRegistry {
+    /// 访问全局注册表并执行指定操作
+    fn access_registry<F, T>(accessor: F) -> T
+    where
+        F: FnOnce(&PropertyRegistry) -> T,
+    {
+        let registry = PROPERTY_REGISTRY
+            .read()
+            .expect("未能获取读锁，读取属性注册表失败");
+        accessor(&registry)
+    }
+
+    /// 按名称获取属性定义
+    pub fn get(name: &str) -> Option<PropertyDefinition> {
+        Self::access_registry(|registry| registry.definitions.get(name).cloned())
+    }
+
+    /// 获取全部属性名称，按字典序排列
+    pub fn names() -> Vec<String> {
+        Self::access_registry(|registry| registry.definitions.keys().cloned().collect())
+    }
+
+    /// 全部属性定义（包括内置属性）的哈希
+    ///
+    /// 按名称顺序对每个定义的名称、频率与相位常量以及取值范围（按位）计算 CRC-64，
+    /// 与加载顺序无关。事件日志记录创建世界时的哈希，重放前据此确认加载了同一份属性目录。
+    ///
+    /// ### 示例
+    /// ```
+    /// use game::PropertyRegistry;
+    ///
+    /// let builtin = PropertyRegistry::catalogue_hash();
+    /// assert_eq!(PropertyRegistry::catalogue_hash(), builtin);
+    ///
+    /// PropertyRegistry::load_json_str(
+    ///     r#"{ "properties": [{ "name": "Toxicity", "frequency_constant": 5, "phase_constant": 1 }] }"#,
+    /// )
+    /// .unwrap();
+    /// assert_ne!(PropertyRegistry::catalogue_hash(), builtin);
+    /// ```
+    pub fn catalogue_hash() -> u64 {
+        Self::access_registry(|registry| {
+            let mut digest = CATALOGUE_HASH.digest();
+            for definition in registry.definitions.values() {
+                digest.update(&(definition.name.len() as u64).to_le_bytes());
+                digest.update(definition.name.as_bytes());
+                digest.update(&(definition.frequency_constant as i64).to_le_bytes());
+                digest.update(&(definition.phase_constant as i64).to_le_bytes());
+                digest.update(&definition.lower_bound.to_bits().to_le_bytes());
+                digest.update(&definition.upper_bound.to_bits().to_le_bytes());
+            }
+            digest.finalize()
+        })
+    }
+
+    /// 按名称计算指定物质的属性值
+    ///
+    /// ### 参数
+    /// - `name`: 属性名称。
+    /// - `substance_type`: 物质类型。
+    ///
+    /// ### 返回值
+    /// 返回映射到该属性取值范围内的属性值；属性不存在时返回错误。
+    pub fn calculate(
+        name: &str,
+        substance_type: &SubstanceType,
+    ) -> Result<f64, PropertyRegistryError> {
+        Self::access_registry(|registry| {
+            registry
+                .definitions
+                .get(name)
+                .map(|definition| definition.calculate(substance_type))
+                .ok_or_else(|| PropertyRegistryError::NotFound(name.to_string()))
+        })
+    }
+
+    /// 计算指定物质在内置属性上的归一化性质向量
+    ///
+    /// 只取内置属性，从目录加载的属性不参与，因此加载目录不会改变耦合与合成所用的相似度。
+    ///
+    /// ### 返回值
+    /// 返回按属性名称字典序排列的归一化属性值，每一维取值范围为 (0, 1]。
+    ///
+    /// ### 示例
+    /// ```
+    /// use game::{PropertyRegistry, SubstanceType};
+    ///
+    /// let substance_type: SubstanceType = "3/4".parse().unwrap();
+    /// let before = PropertyRegistry::property_vector(&substance_type);
+    ///
+    /// PropertyRegistry::load_json_str(
+    ///     r#"{ "properties": [{ "name": "Toxicity", "frequency_constant": 5, "phase_constant": 1 }] }"#,
+    /// )
+    /// .unwrap();
+    /// assert_eq!(PropertyRegistry::property_vector(&substance_type), before);
+    /// ```
+    pub fn property_vector(substance_type: &SubstanceType) -> Vec<f64> {
+        Self::access_registry(|registry| {
+            registry
+                .definitions
+                .iter()
+                .filter(|(name, _)| Self::is_builtin(name))
+                .map(|(_, definition)| definition.normalized(substance_type))
+                .collect()
+        })
+    }
+}
+
+pub mod error_handling {
+    use std::fmt;
+
+    #[derive(Debug)]
+    pub enum PropertyRegistryError {
+        /// 读取属性目录文件失败
+        Io(std::io::Error),
+        /// 解析 JSON 属性目录失败
+        Parse(serde_json::Error),
+        /// 解析 TOML 属性目录失败
+        ParseToml(toml::de::Error),
+        /// 属性目录文件的扩展名既不是 `.toml` 也不是 `.json`
+        UnsupportedFormat(String),
+        /// 属性名称为空
+        EmptyName,
+        /// 属性取值范围不合法
+        InvalidBounds(String),
+        /// 试图覆盖内置属性
+        BuiltinOverride(String),
+        /// 属性不存在
+        NotFound(String),
+    }
+
+    impl fmt::Display for PropertyRegistryError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                PropertyRegistryError::Io(e) => write!(f, "读取属性目录失败: {}", e),
+                PropertyRegistryError::Parse(e) => write!(f, "解析属性目录失败: {}", e),
+                PropertyRegistryError::ParseToml(e) => write!(f, "解析属性目录失败: {}", e),
+                PropertyRegistryError::UnsupportedFormat(path) => {
+                    write!(f, "属性目录 {} 的扩展名须为 .toml 或 .json", path)
+                }
+                PropertyRegistryError::EmptyName => write!(f, "属性名称不能为空"),
+                PropertyRegistryError::InvalidBounds(name) => {
+                    write!(f, "属性 {} 的取值范围不合法，下界必须小于上界", name)
+                }
+                PropertyRegistryError::BuiltinOverride(name) => {
+                    write!(f, "内置属性 {} 不能被覆盖", name)
+                }
+                PropertyRegistryError::NotFound(name) => write!(f, "属性 {} 未注册", name),
+            }
+        }
+    }
+
+    impl std::error::Error for PropertyRegistryError {
+        fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+            match self {
+                PropertyRegistryError::Io(e) => Some(e),
+                PropertyRegistryError::Parse(e) => Some(e),
+                PropertyRegistryError::ParseToml(e) => Some(e),
+                _ => None,
+            }
+        }
+    }
+
+    impl From<std::io::Error> for PropertyRegistryError {
+        fn from(e: std::io::Error) -> Self {
+            PropertyRegistryError::Io(e)
+        }
+    }
+
+    impl From<serde_json::Error> for PropertyRegistryError {
+        fn from(e: serde_json::Error) -> Self {
+            PropertyRegistryError::Parse(e)
+        }
+    }
+
+    impl From<toml::de::Error> for PropertyRegistryError {
+        fn from(e: toml::de::Error) -> Self {
+            PropertyRegistryError::ParseToml(e)
+        }
+    }
+}
@@ -0,0 +1,330 @@
+use crate::shared::subtance::Substance;
+use crate::shared::subtance_type::SubstanceType;
+use error_handling::SubstanceRegistryError;
+use num::integer::gcd;
+use once_cell::sync::Lazy;
+use std::collections::BTreeMap;
+use std::sync::{Arc, RwLock};
+
+/// 默认精密度，对应的最大分母为 99
+const DEFAULT_PRECISION: usize = 2;
+/// 允许的最高精密度，避免枚举规模与分母运算失控
+const MAX_PRECISION: usize = 6;
+/// 生成名称所用的辅音
+const CONSONANTS: [&str; 16] = [
+    "b", "d", "f", "g", "h", "j", "k", "l", "m", "n", "p", "r", "s", "t", "v", "z",
+];
+/// 生成名称所用的元音
+const VOWELS: [&str; 5] = ["a", "e", "i", "o", "u"];
+
+/// 全局物质注册表实例
+static SUBSTANCE_REGISTRY: Lazy<Arc<RwLock<SubstanceRegistry>>> =
+    Lazy::new(|| Arc::new(RwLock::new(SubstanceRegistry::default())));
+
+/// 物质注册表
+///
+/// - `max_denominator`: 物质空间允许的最大分母，决定了物质空间的精密度。
+/// - `names`: 自定义的物质名称，未命名的物质使用按分数生成的名称。
+///
+/// 注册表在进程内共享，只保存与具体世界无关的设定；
+/// 某个世界中已经发现的物质记录在该世界的 `Landscape` 上。
+#[derive(Debug, Clone)]
+pub struct SubstanceRegistry {
+    max_denominator: usize,
+    names: BTreeMap<SubstanceType, String>,
+}
+
+impl Default for SubstanceRegistry {
+    fn default() -> Self {
+        Self {
+            max_denominator: Self::max_denominator_of(DEFAULT_PRECISION),
+            names: BTreeMap::new(),
+        }
+    }
+}
+
+impl SubstanceRegistry {
+    /// 计算某一精密度下允许的最大分母，即 `10^L - 1`
+    fn max_denominator_of(precision: usize) -> usize {
+        10usize.pow(precision as u32) - 1
+    }
+
+    /// 枚举分母不超过 `max_denominator` 的全部物质
+    ///
+    /// 按 `00_资源` 的定义，物质类型为 `m/n`，其中 `n ∈ [1, max_denominator]`、
+    /// `m ∈ [1, 2n]` 且 `m` 与 `n` 互质，从而保证每种物质只出现一次。
+    /// 结果按分母、分子的顺序排列。
+    ///
+    /// ### 参数
+    /// - `max_denominator`: 允许的最大分母。
+    ///
+    /// ### 返回值
+    /// 返回惰性生成物质类型的迭代器。
+    pub fn enumerate_up_to(max_denominator: usize) -> impl Iterator<Item = SubstanceType> {
+        (1..=max_denominator).flat_map(|denominator| {
+            (1..=2 * denominator)
+                .filter(move |&numerator| gcd(numerator, denominator) == 1)
+                .map(move |numerator| {
+                    SubstanceType::try_new(numerator, denominator)
+                        .expect("枚举的物质类型必然位于有效范围内")
+                })
+        })
+    }
+
+    /// 按分数生成物质名称
+    ///
+    /// 先用康托尔配对函数把分子、分母映射为唯一的整数，再按 80 进制转写为辅音加元音的音节，
+    /// 因此不同物质的名称互不相同。
+    fn generated_name(substance_type: &SubstanceType) -> String {
+        let (numerator, denominator) =
+            (*substance_type.ratio.numer(), *substance_type.ratio.denom());
+        let mut index = (numerator + denominator) * (numerator + denominator + 1) / 2 + denominator;
+
+        let base = CONSONANTS.len() * VOWELS.len();
+        let mut name = String::new();
+        loop {
+            let syllable = index % base;
+            name.push_str(CONSONANTS[syllable / VOWELS.len()]);
+            name.push_str(VOWELS[syllable % VOWELS.len()]);
+            index /= base;
+            if index == 0 {
+                break;
+            }
+        }
+
+        let mut chars = name.chars();
+        match chars.next() {
+            Some(first) => first.to_uppercase().chain(chars).collect(),
+            None => name,
+        }
+    }
+
+    /// 校验物质类型是否在本注册表的精密度范围内
+    fn check(&self, substance_type: &SubstanceType) -> Result<(), SubstanceRegistryError> {
+        if *substance_type.ratio.denom() > self.max_denominator {
+            return Err(SubstanceRegistryError::PrecisionExceeded {
+                substance_type: *substance_type,
+                max_denominator: self.max_denominator,
+            });
+        }
+        Ok(())
+    }
+
+    /// 获取物质名称
+    fn name_of(&self, substance_type: &SubstanceType) -> String {
+        self.names
+            .get(substance_type)
+            .cloned()
+            .unwrap_or_else(|| Self::generated_name(substance_type))
+    }
+}
+
+// update
+impl SubstanceRegistry {
+    /// 通用更新方法
+    fn update_registry<F, T>(update_fn: F) -> T
+    where
+        F: FnOnce(&mut SubstanceRegistry) -> T,
+    {
+        let registry = SUBSTANCE_REGISTRY.clone();
+        let mut registry = registry.write().expect("未能获取写锁，更新物质注册表失败");
+        update_fn(&mut registry)
+    }
+
+    /// 设置物质空间的精密度
+    ///
+    /// ### 参数
+    /// - `precision`: 精密度 L，取值范围为 [1, `MAX_PRECISION`]，最大分母随之变为 `10^L - 1`。
+    pub fn update_precision(precision: usize) -> Result<(), SubstanceRegistryError> {
+        if !(1..=MAX_PRECISION).contains(&precision) {
+            return Err(SubstanceRegistryError::InvalidPrecision(precision));
+        }
+
+        Self::update_max_denominator(Self::max_denominator_of(precision))
+    }
+
+    /// 直接设置物质空间允许的最大分母
+    ///
+    /// ### 参数
+    /// - `max_denominator`: 最大分母，取值范围为 [1, `10^MAX_PRECISION - 1`]。
+    pub fn update_max_denominator(max_denominator: usize) -> Result<(), SubstanceRegistryError> {
+        if !(1..=Self::max_denominator_of(MAX_PRECISION)).contains(&max_denominator) {
+            return Err(SubstanceRegistryError::InvalidMaxDenominator(
+                max_denominator,
+            ));
+        }
+
+        Self::update_registry(|registry| registry.max_denominator = max_denominator);
+        Ok(())
+    }
+
+    /// 为物质指定自定义名称
+    pub fn rename(
+        substance_type: &SubstanceType,
+        name: impl Into<String>,
+    ) -> Result<(), SubstanceRegistryError> {
+        let name = name.into();
+        if name.trim().is_empty() {
+            return Err(SubstanceRegistryError::EmptyName);
+        }
+
+        Self::update_registry(|registry| {
+            registry.check(substance_type)?;
+            registry.names.insert(*substance_type, name);
+            Ok(())
+        })
+    }
+}
+
+// get
+impl SubstanceRegistry {
+    /// 访问全局注册表并执行指定操作
+    fn access_registry<F, T>(accessor: F) -> T
+    where
+        F: FnOnce(&SubstanceRegistry) -> T,
+    {
+        let registry = SUBSTANCE_REGISTRY
+            .read()
+            .expect("未能获取读锁，读取物质注册表失败");
+        accessor(&registry)
+    }
+
+    /// 获取物质空间允许的最大分母
+    pub fn max_denominator() -> usize {
+        Self::access_registry(|registry| registry.max_denominator)
+    }
+
+    /// 获取物质空间的精密度，即最大分母的十进制位数
+    pub fn precision() -> usize {
+        Self::access_registry(|registry| registry.max_denominator.to_string().len())
+    }
+
+    /// 校验物质类型是否在当前精密度范围内
+    pub fn validate(substance_type: &SubstanceType) -> Result<(), SubstanceRegistryError> {
+        Self::access_registry(|registry| registry.check(substance_type))
+    }
+
+    /// 按分子、分母创建经过精密度校验的物质
+    pub fn try_substance(
+        numerator: usize,
+        denominator: usize,
+    ) -> Result<Substance, SubstanceRegistryError> {
+        let substance_type = SubstanceType::try_new(numerator, denominator)?;
+        Self::substance(&substance_type)
+    }
+
+    /// 获取物质的完整信息，包括名称与性质向量
+    pub fn substance(substance_type: &SubstanceType) -> Result<Substance, SubstanceRegistryError> {
+        let name = Self::access_registry(|registry| {
+            registry
+                .check(substance_type)
+                .map(|_| registry.name_of(substance_type))
+        })?;
+
+        Ok(Substance::new(*substance_type, name))
+    }
+
+    /// 枚举当前精密度下的全部物质类型
+    pub fn enumerate() -> impl Iterator<Item = SubstanceType> {
+        Self::enumerate_up_to(Self::max_denominator())
+    }
+
+    /// 统计当前精密度下的物质种类数
+    ///
+    /// ### 示例
+    /// ```
+    /// use game::SubstanceRegistry;
+    ///
+    /// assert_eq!(SubstanceRegistry::count(), SubstanceRegistry::enumerate().count());
+    /// ```
+    pub fn count() -> usize {
+        Self::count_up_to(Self::max_denominator())
+    }
+
+    /// 统计分母不超过 `max_denominator` 的物质种类数
+    ///
+    /// 分母为 `n` 时，`[1, 2n]` 中与 `n` 互质的分子恰有 `2φ(n)` 个，
+    /// 因此总数为 `2 Σφ(n)`；欧拉函数用埃氏筛求出，最高精密度下也只需线性级别的时间。
+    pub fn count_up_to(max_denominator: usize) -> usize {
+        // totients[n] 先置为 n，再对每个质数 p 的倍数乘以 (1 - 1/p)
+        let mut totients: Vec<usize> = (0..=max_denominator).collect();
+        for prime in 2..=max_denominator {
+            if totients[prime] == prime {
+                for multiple in (prime..=max_denominator).step_by(prime) {
+                    totients[multiple] -= totients[multiple] / prime;
+                }
+            }
+        }
+        2 * totients.iter().skip(1).sum::<usize>()
+    }
+}
+
+pub mod error_handling {
+    use crate::shared::subtance_type::error_handling::SubtanceTypeError;
+    use crate::shared::subtance_type::SubstanceType;
+    use std::fmt;
+
+    #[derive(Debug)]
+    pub enum SubstanceRegistryError {
+        /// 物质类型本身不合法
+        InvalidSubstanceType(SubtanceTypeError),
+        /// 物质类型的分母超出物质空间的精密度
+        PrecisionExceeded {
+            substance_type: SubstanceType,
+            max_denominator: usize,
+        },
+        /// 精密度不合法
+        InvalidPrecision(usize),
+        /// 最大分母不合法
+        InvalidMaxDenominator(usize),
+        /// 物质名称为空
+        EmptyName,
+    }
+
+    impl fmt::Display for SubstanceRegistryError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                SubstanceRegistryError::InvalidSubstanceType(e) => {
+                    write!(f, "物质类型不合法: {}", e)
+                }
+                SubstanceRegistryError::PrecisionExceeded {
+                    substance_type,
+                    max_denominator,
+                } => write!(
+                    f,
+                    "物质 {}/{} 的分母超出允许的最大分母 {}",
+                    substance_type.ratio.numer(),
+                    substance_type.ratio.denom(),
+                    max_denominator
+                ),
+                SubstanceRegistryError::InvalidPrecision(precision) => {
+                    write!(
+                        f,
+                        "精密度 {} 不合法，有效范围：1 - {}",
+                        precision,
+                        super::MAX_PRECISION
+                    )
+                }
+                SubstanceRegistryError::InvalidMaxDenominator(max_denominator) => {
+                    write!(f, "最大分母 {} 不合法", max_denominator)
+                }
+                SubstanceRegistryError::EmptyName => write!(f, "物质名称不能为空"),
+            }
+        }
+    }
+
+    impl std::error::Error for SubstanceRegistryError {
+        fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+            match self {
+                SubstanceRegistryError::InvalidSubstanceType(e) => Some(e),
+                _ => None,
+            }
+        }
+    }
+
+    impl From<SubtanceTypeError> for SubstanceRegistryError {
+        fn from(e: SubtanceTypeError) -> Self {
+            SubstanceRegistryError::InvalidSubstanceType(e)
+        }
+    }
+}
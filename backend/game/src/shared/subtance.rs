@@ -1,8 +1,54 @@
-use crate::shared::property_param::PropertyParam;
 use crate::shared::subtance_type::SubstanceType;
-use std::collections::HashSet;
+use serde::Serialize;
+use std::fmt;
 
-pub(crate) struct Subtance {
-    subtance_type: SubstanceType,
-    properties: HashSet<PropertyParam>,
+/// 物质
+///
+/// 在物质类型的基础上附加名称、精密度以及按内置属性计算的性质向量。
+/// - `substance_type`: 物质类型，即资源角系数。
+/// - `name`: 物质名称。
+/// - `precision`: 精密度，等于分母的十进制位数。
+/// - `property_vector`: 内置属性的归一化取值，按属性名称字典序排列；从目录加载的属性不计入。
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Substance {
+    substance_type: SubstanceType,
+    name: String,
+    precision: usize,
+    property_vector: Vec<f64>,
+}
+
+impl Substance {
+    pub fn new(substance_type: SubstanceType, name: impl Into<String>) -> Self {
+        Self {
+            substance_type,
+            name: name.into(),
+            precision: substance_type.precision(),
+            property_vector: substance_type.property_vector(),
+        }
+    }
+
+    pub fn substance_type(&self) -> &SubstanceType {
+        &self.substance_type
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn precision(&self) -> usize {
+        self.precision
+    }
+
+    pub fn property_vector(&self) -> &[f64] {
+        &self.property_vector
+    }
+}
+
+impl fmt::Display for Substance {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match serde_json::to_string_pretty(&self) {
+            Ok(json_str) => write!(f, "{}", json_str),
+            Err(e) => write!(f, "将 Substance 序列化为 JSON 时出错: {}", e),
+        }
+    }
 }
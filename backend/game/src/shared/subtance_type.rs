@@ -1,20 +1,72 @@
 use crate::shared::property::Property;
+use crate::shared::property_cache::PropertyCache;
+use crate::shared::property_registry::PropertyRegistry;
 use error_handling::SubtanceTypeError;
 use num::rational::Ratio;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use std::convert::TryFrom;
 use std::fmt;
+use std::str::FromStr;
 
 const LOWER_BOUND: Ratio<usize> = Ratio::new_raw(0, 1);
 const UPPER_BOUND: Ratio<usize> = Ratio::new_raw(2, 1);
+/// 汉明比值计算时，曼哈顿距离量化所用的二进制位数
+const HAMMING_BITS: u32 = 8;
 
-#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq, Serialize)]
-pub(crate) struct SubstanceType {
+/// 物质类型，即资源角系数 `m/n`，取值范围为 [0, 2]
+///
+/// 序列化为 `{"ratio": [m, n]}`；反序列化时既接受该形式，也接受 `"m/n"` 字符串，
+/// 并且都会校验取值范围。
+///
+/// ```
+/// use game::SubstanceType;
+///
+/// let substance_type = SubstanceType::try_new(6, 8).unwrap();
+/// assert_eq!(substance_type.numer(), 3);
+/// assert_eq!(substance_type.denom(), 4);
+///
+/// let json = serde_json::to_string(&substance_type).unwrap();
+/// assert_eq!(serde_json::from_str::<SubstanceType>(&json).unwrap(), substance_type);
+/// assert_eq!(serde_json::from_str::<SubstanceType>("\"3/4\"").unwrap(), substance_type);
+/// assert!(serde_json::from_str::<SubstanceType>("\"5/2\"").is_err());
+/// ```
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq, Ord, PartialOrd, Serialize, Deserialize)]
+#[serde(try_from = "SubstanceTypeRepr")]
+pub struct SubstanceType {
     pub(crate) ratio: Ratio<usize>,
 }
 
+/// 反序列化时接受的物质类型表示形式
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum SubstanceTypeRepr {
+    /// `"m/n"` 或 `"m"` 形式的字符串
+    Text(String),
+    /// 与序列化结果一致的 `{"ratio": [m, n]}` 形式
+    Ratio { ratio: (usize, usize) },
+}
+
+impl TryFrom<SubstanceTypeRepr> for SubstanceType {
+    type Error = SubtanceTypeError;
+
+    fn try_from(repr: SubstanceTypeRepr) -> Result<Self, Self::Error> {
+        match repr {
+            SubstanceTypeRepr::Text(text) => text.parse(),
+            SubstanceTypeRepr::Ratio { ratio } => Self::try_from(ratio),
+        }
+    }
+}
+
 impl SubstanceType {
-    pub(crate) fn try_new(numerator: usize, denominator: usize) -> Result<Self, SubtanceTypeError> {
+    /// 创建物质类型，分数会被约分为最简形式
+    ///
+    /// ### 参数
+    /// - `numerator`: 分子 `m`。
+    /// - `denominator`: 分母 `n`，不能为零。
+    ///
+    /// ### 返回值
+    /// 返回物质类型；分母为零或超出 [0, 2] 范围时返回错误。
+    pub fn try_new(numerator: usize, denominator: usize) -> Result<Self, SubtanceTypeError> {
         if denominator == 0 {
             return Err(SubtanceTypeError::ZeroDenominator);
         }
@@ -30,18 +82,183 @@ impl SubstanceType {
         })
     }
 
-    pub(crate) fn property_calculate(
+    /// 获取最简分数形式的资源角系数
+    pub fn ratio(&self) -> Ratio<usize> {
+        self.ratio
+    }
+
+    /// 获取最简分数形式的分子
+    pub fn numer(&self) -> usize {
+        *self.ratio.numer()
+    }
+
+    /// 获取最简分数形式的分母
+    pub fn denom(&self) -> usize {
+        *self.ratio.denom()
+    }
+
+    pub fn property_calculate(
         &self,
         property: Property,
         frequency_offset: Option<isize>,
         phase_offset: Option<isize>,
     ) -> f64 {
         let param = Property::to_map().get(&property).unwrap();
-        let property_value = param
+        let param = param
             .with_frequency_offset(frequency_offset.unwrap_or(0))
-            .with_phase_offset(phase_offset.unwrap_or(0))
-            .calculate(self);
-        property_value
+            .with_phase_offset(phase_offset.unwrap_or(0));
+        PropertyCache::get_or_calculate(&param, self)
+    }
+
+    /// 计算物质的精密度
+    ///
+    /// 精密度等于最简分数形式下分母的十进制位数，例如 `3/4` 为 1，`7/10` 为 2。
+    pub fn precision(&self) -> usize {
+        let mut denominator = *self.ratio.denom();
+        let mut digits = 1;
+        while denominator >= 10 {
+            denominator /= 10;
+            digits += 1;
+        }
+        digits
+    }
+}
+
+/// 性质空间中的相似性
+impl SubstanceType {
+    /// 计算本物质的性质向量
+    ///
+    /// ### 返回值
+    /// 返回内置属性的归一化取值，按属性名称字典序排列，每一维取值范围为 (0, 1]。
+    /// 从目录加载的属性不计入，详见 `PropertyRegistry::property_vector`。
+    pub fn property_vector(&self) -> Vec<f64> {
+        PropertyRegistry::property_vector(self)
+    }
+
+    /// 计算与另一物质的汉明重量
+    ///
+    /// 先求两者性质向量的曼哈顿距离，归一化后量化为 `HAMMING_BITS` 位二进制数，
+    /// 再统计其中 1 的个数。
+    ///
+    /// ### 参数
+    /// - `other`: 参与比较的另一种物质。
+    ///
+    /// ### 返回值
+    /// 返回 [0, `HAMMING_BITS`] 范围内的汉明重量。
+    pub fn hamming_weight(&self, other: &SubstanceType) -> u32 {
+        let self_vector = self.property_vector();
+        let manhattan_distance: f64 = self_vector
+            .iter()
+            .zip(other.property_vector().iter())
+            .map(|(a, b)| (a - b).abs())
+            .sum();
+
+        // 每一维的差值不超过 1，因此曼哈顿距离不超过维数
+        let normalized = manhattan_distance / self_vector.len().max(1) as f64;
+        let quantized = (normalized * ((1u32 << HAMMING_BITS) - 1) as f64).round() as u32;
+
+        quantized.count_ones()
+    }
+
+    /// 计算与另一物质的汉明比值 η，即汉明重量与位数之比
+    ///
+    /// ### 参数
+    /// - `other`: 参与比较的另一种物质。
+    ///
+    /// ### 返回值
+    /// 返回 [0, 1] 范围内的汉明比值。η 越小，两种物质越相似。
+    pub fn hamming_ratio(&self, other: &SubstanceType) -> f64 {
+        self.hamming_weight(other) as f64 / HAMMING_BITS as f64
+    }
+}
+
+/// 物质合成
+impl SubstanceType {
+    /// 将两种物质组合为新物质
+    ///
+    /// 按 `x_C = (1 - η) x_A + η x_B` 线性组合，其中 η 取有理数形式
+    /// `汉明重量 / HAMMING_BITS`，因此整个计算在有理数域内精确完成。
+    /// 组合系数之和为 1，结果天然落在 [0, 2] 内，但仍会按边界截断以防万一。
+    ///
+    /// ### 参数
+    /// - `other`: 参与组合的另一种物质 B，`self` 为物质 A。
+    ///
+    /// ### 返回值
+    /// 返回组合得到的新物质，分母可能增大，必要时配合 `limit_denominator` 使用。
+    pub fn combine(&self, other: &SubstanceType) -> SubstanceType {
+        let eta = Ratio::new(self.hamming_weight(other) as usize, HAMMING_BITS as usize);
+        let ratio = (Ratio::from_integer(1) - eta) * self.ratio + eta * other.ratio;
+
+        Self::clamped(ratio)
+    }
+
+    /// 依次组合多种物质
+    ///
+    /// ### 参数
+    /// - `substances`: 参与组合的物质，按顺序两两折叠，至少需要一种。
+    ///
+    /// ### 返回值
+    /// 返回组合得到的新物质；若输入为空则返回 `None`。
+    pub fn combine_all(substances: &[SubstanceType]) -> Option<SubstanceType> {
+        substances
+            .iter()
+            .copied()
+            .reduce(|acc, substance| acc.combine(&substance))
+    }
+
+    /// 将物质类型近似为分母不超过 `max_denominator` 的最接近有理数
+    ///
+    /// 使用连分数求最佳有理逼近，用以控制合成过程中分母（精密度）的增长。
+    ///
+    /// ### 参数
+    /// - `max_denominator`: 允许的最大分母，至少为 1。
+    ///
+    /// ### 返回值
+    /// 返回近似后的物质类型，结果仍在 [0, 2] 内。
+    pub fn limit_denominator(&self, max_denominator: usize) -> SubstanceType {
+        let max_denominator = max_denominator.max(1);
+        if *self.ratio.denom() <= max_denominator {
+            return *self;
+        }
+
+        let (mut p0, mut q0, mut p1, mut q1) = (0usize, 1usize, 1usize, 0usize);
+        let (mut n, mut d) = (*self.ratio.numer(), *self.ratio.denom());
+        loop {
+            let a = n / d;
+            let q2 = q0 + a * q1;
+            if q2 > max_denominator {
+                break;
+            }
+            (p0, q0, p1, q1) = (p1, q1, p0 + a * p1, q2);
+            (n, d) = (d, n - a * d);
+        }
+
+        let k = (max_denominator - q0) / q1;
+        let bound1 = Ratio::new(p0 + k * p1, q0 + k * q1);
+        let bound2 = Ratio::new(p1, q1);
+
+        let distance = |x: Ratio<usize>| {
+            if x > self.ratio {
+                x - self.ratio
+            } else {
+                self.ratio - x
+            }
+        };
+
+        let closest = if distance(bound2) <= distance(bound1) {
+            bound2
+        } else {
+            bound1
+        };
+
+        Self::clamped(closest)
+    }
+
+    /// 将有理数截断到 [0, 2] 范围内并构造物质类型
+    fn clamped(ratio: Ratio<usize>) -> SubstanceType {
+        SubstanceType {
+            ratio: ratio.clamp(LOWER_BOUND, UPPER_BOUND),
+        }
     }
 }
 
@@ -53,6 +270,36 @@ impl TryFrom<(usize, usize)> for SubstanceType {
     }
 }
 
+impl FromStr for SubstanceType {
+    type Err = SubtanceTypeError;
+
+    /// 从 `"m/n"` 或 `"m"` 形式的字符串解析物质类型，两侧与斜杠两侧的空白会被忽略
+    ///
+    /// ```
+    /// use game::SubstanceType;
+    ///
+    /// let substance_type: SubstanceType = " 6 / 4 ".parse().unwrap();
+    /// assert_eq!((substance_type.numer(), substance_type.denom()), (3, 2));
+    /// assert_eq!("2".parse::<SubstanceType>().unwrap().denom(), 1);
+    /// assert!("3/0".parse::<SubstanceType>().is_err());
+    /// assert!("abc".parse::<SubstanceType>().is_err());
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse_part = |part: &str| {
+            part.trim()
+                .parse::<usize>()
+                .map_err(|_| SubtanceTypeError::Parse(s.to_string()))
+        };
+
+        match s.split_once('/') {
+            Some((numerator, denominator)) => {
+                Self::try_new(parse_part(numerator)?, parse_part(denominator)?)
+            }
+            None => Self::try_new(parse_part(s)?, 1),
+        }
+    }
+}
+
 impl fmt::Display for SubstanceType {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match serde_json::to_string_pretty(&self) {
@@ -62,14 +309,16 @@ impl fmt::Display for SubstanceType {
     }
 }
 
-pub(crate) mod error_handling {
+pub mod error_handling {
     use super::{LOWER_BOUND, UPPER_BOUND};
     use std::fmt;
 
     #[derive(Debug)]
-    pub(crate) enum SubtanceTypeError {
+    pub enum SubtanceTypeError {
         ZeroDenominator,
         OutOfRange,
+        /// 无法解析的物质类型字符串
+        Parse(String),
     }
 
     impl fmt::Display for SubtanceTypeError {
@@ -79,7 +328,12 @@ pub(crate) mod error_handling {
                 SubtanceTypeError::OutOfRange => {
                     write!(f, "有效范围：{} - {}", LOWER_BOUND, UPPER_BOUND)
                 }
+                SubtanceTypeError::Parse(text) => {
+                    write!(f, "无法解析物质类型 {:?}，应为 \"m/n\" 形式", text)
+                }
             }
         }
     }
+
+    impl std::error::Error for SubtanceTypeError {}
 }
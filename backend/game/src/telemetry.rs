@@ -0,0 +1,28 @@
+use tracing::Span;
+use uuid::Uuid;
+
+/// 一步模拟的 span，其中的日志都带有世界与步数
+///
+/// span 名为 `tick`，字段为 `civilization_id` 与 `tick`，因此可以按世界和步数过滤日志，例如
+/// `RUST_LOG="info,[tick{civilization_id=<UUID>}]=debug"` 只打开某个世界的调试日志。
+/// 扩散、交互以及之后的交易等逻辑都应在该 span 中执行；
+/// 进入 rayon 等线程池时，须在任务中重新进入 `Span::current()`。
+///
+/// ### 参数
+/// - `civilization_id`: 世界的标识，未知时为 `None`，span 中不记录该字段。
+/// - `tick`: 正在模拟的步数，即这一步结束后地貌的步数。
+///
+/// ```
+/// use game::tick_span;
+///
+/// let span = tick_span(Some(uuid::Uuid::from_u128(1)), 42);
+/// let _entered = span.enter();
+/// tracing::debug!("这条日志带有 civilization_id 与 tick");
+/// ```
+pub fn tick_span(civilization_id: Option<Uuid>, tick: u64) -> Span {
+    let span = tracing::info_span!("tick", civilization_id = tracing::field::Empty, tick);
+    if let Some(civilization_id) = civilization_id {
+        span.record("civilization_id", tracing::field::display(civilization_id));
+    }
+    span
+}
@@ -0,0 +1,35 @@
+pub(crate) mod world_cache;
+pub(crate) mod world_event;
+
+pub mod error_handling {
+    use back_core::context::redis::error_handling::RedisStoreError;
+    use std::fmt;
+
+    #[derive(Debug)]
+    pub enum WorldSyncError {
+        /// 读写 Redis 失败，或缓存中的内容无法（反）序列化
+        Store(RedisStoreError),
+    }
+
+    impl fmt::Display for WorldSyncError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                WorldSyncError::Store(e) => write!(f, "同步世界状态失败: {}", e),
+            }
+        }
+    }
+
+    impl std::error::Error for WorldSyncError {
+        fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+            match self {
+                WorldSyncError::Store(e) => Some(e),
+            }
+        }
+    }
+
+    impl From<RedisStoreError> for WorldSyncError {
+        fn from(e: RedisStoreError) -> Self {
+            WorldSyncError::Store(e)
+        }
+    }
+}
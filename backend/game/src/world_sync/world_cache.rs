@@ -0,0 +1,216 @@
+use crate::environment::landscape::Landscape;
+use crate::metrics::recorder::SharedMetricsRecorder;
+use crate::metrics::snapshot::MetricsSnapshot;
+use crate::world_sync::error_handling::WorldSyncError;
+use crate::world_sync::world_event::{WorldEvent, WORLD_EVENTS_CHANNEL};
+use back_core::context::redis::store::RedisStore;
+use futures::stream::{BoxStream, StreamExt};
+use std::time::Duration;
+use uuid::Uuid;
+
+/// 基于 Redis 的世界状态缓存
+///
+/// 每个世界（以 `civilization_id` 区分）在 Redis 中保存最新的地貌快照与统计，
+/// 并通过公共频道广播世界事件。模拟世界的实例在每步结束后调用 `publish_tick`，
+/// 其他实例即可从缓存读取该世界，或订阅事件得知何时有新的快照。
+///
+/// ```no_run
+/// use back_core::context::core_context::AppContext;
+/// use game::{Landscape, MapSize, MetricsSnapshot, WorldCache};
+/// use std::time::Duration;
+///
+/// # async fn run(civilization_id: uuid::Uuid) -> Result<(), game::WorldSyncError> {
+/// let cache = WorldCache::new(AppContext::get_redis()).with_ttl(Duration::from_secs(3600));
+///
+/// let mut landscape = Landscape::new(MapSize::new(Some(8), Some(8)));
+/// landscape.diffuse();
+/// let statistics = MetricsSnapshot::sample(&landscape, &["substance_count".parse().unwrap()]);
+/// cache.publish_tick(civilization_id, &landscape, &statistics).await?;
+///
+/// // 另一个实例
+/// let latest = cache.latest_landscape(civilization_id).await?;
+/// assert_eq!(latest.map(|landscape| landscape.tick()), Some(1));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct WorldCache {
+    store: RedisStore,
+    /// 快照与统计的过期时间，为 `None` 时永不过期
+    ttl: Option<Duration>,
+}
+
+impl WorldCache {
+    /// 使用 Redis 连接创建缓存，缓存的内容默认永不过期
+    pub fn new(store: RedisStore) -> Self {
+        Self { store, ttl: None }
+    }
+
+    /// 设置快照与统计的过期时间（可链式调用）
+    ///
+    /// 模拟世界的实例意外退出后，其缓存会在过期后自动清除。
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = Some(ttl);
+        self
+    }
+
+    pub fn ttl(&self) -> Option<Duration> {
+        self.ttl
+    }
+
+    fn landscape_key(civilization_id: Uuid) -> String {
+        format!("world:{}:landscape", civilization_id)
+    }
+
+    fn statistics_key(civilization_id: Uuid) -> String {
+        format!("world:{}:statistics", civilization_id)
+    }
+}
+
+/// 写入
+impl WorldCache {
+    /// 保存世界的最新地貌快照
+    pub async fn store_landscape(
+        &self,
+        civilization_id: Uuid,
+        landscape: &Landscape,
+    ) -> Result<(), WorldSyncError> {
+        self.store
+            .set_json(&Self::landscape_key(civilization_id), landscape, self.ttl)
+            .await?;
+        Ok(())
+    }
+
+    /// 保存世界的最新统计
+    pub async fn store_statistics(
+        &self,
+        civilization_id: Uuid,
+        statistics: &MetricsSnapshot,
+    ) -> Result<(), WorldSyncError> {
+        self.store
+            .set_json(&Self::statistics_key(civilization_id), statistics, self.ttl)
+            .await?;
+        Ok(())
+    }
+
+    /// 一步模拟结束后保存快照与统计，再广播 `TickCompleted`
+    ///
+    /// ### 返回值
+    /// 返回收到事件的订阅者数量。
+    #[tracing::instrument(name = "tick", skip_all, fields(civilization_id = %civilization_id, tick = landscape.tick()))]
+    pub async fn publish_tick(
+        &self,
+        civilization_id: Uuid,
+        landscape: &Landscape,
+        statistics: &MetricsSnapshot,
+    ) -> Result<usize, WorldSyncError> {
+        self.store_landscape(civilization_id, landscape).await?;
+        self.store_statistics(civilization_id, statistics).await?;
+        self.publish_event(&WorldEvent::TickCompleted {
+            civilization_id,
+            tick: landscape.tick(),
+        })
+        .await
+    }
+
+    /// 广播世界事件
+    ///
+    /// ### 返回值
+    /// 返回收到事件的订阅者数量。
+    pub async fn publish_event(&self, event: &WorldEvent) -> Result<usize, WorldSyncError> {
+        Ok(self.store.publish_json(WORLD_EVENTS_CHANNEL, event).await?)
+    }
+
+    /// 清除世界的缓存，并广播 `Stopped`
+    ///
+    /// ### 返回值
+    /// 返回缓存中原本是否有该世界的快照。
+    pub async fn remove(&self, civilization_id: Uuid) -> Result<bool, WorldSyncError> {
+        let existed = self
+            .store
+            .delete(&Self::landscape_key(civilization_id))
+            .await?;
+        self.store
+            .delete(&Self::statistics_key(civilization_id))
+            .await?;
+        self.publish_event(&WorldEvent::Stopped { civilization_id })
+            .await?;
+        Ok(existed)
+    }
+}
+
+/// 读取
+impl WorldCache {
+    /// 读取世界的最新地貌快照，缓存中没有该世界时返回 `None`
+    pub async fn latest_landscape(
+        &self,
+        civilization_id: Uuid,
+    ) -> Result<Option<Landscape>, WorldSyncError> {
+        Ok(self
+            .store
+            .get_json(&Self::landscape_key(civilization_id))
+            .await?)
+    }
+
+    /// 读取世界的最新统计，缓存中没有该世界时返回 `None`
+    pub async fn latest_statistics(
+        &self,
+        civilization_id: Uuid,
+    ) -> Result<Option<MetricsSnapshot>, WorldSyncError> {
+        Ok(self
+            .store
+            .get_json(&Self::statistics_key(civilization_id))
+            .await?)
+    }
+
+    /// 订阅世界事件
+    ///
+    /// ### 参数
+    /// - `civilization_id`: 只接收该世界的事件，为 `None` 时接收全部世界的事件。
+    ///
+    /// ### 返回值
+    /// 返回事件流；无法解析的消息会被记录并跳过。
+    pub async fn subscribe(
+        &self,
+        civilization_id: Option<Uuid>,
+    ) -> Result<BoxStream<'static, WorldEvent>, WorldSyncError> {
+        let messages = self.store.subscribe(&[WORLD_EVENTS_CHANNEL]).await?;
+        Ok(messages
+            .filter_map(move |message| async move {
+                let event = message
+                    .payload_json::<WorldEvent>()
+                    .map_err(|e| tracing::warn!("忽略无法解析的世界事件: {}", e))
+                    .ok()?;
+                civilization_id
+                    .is_none_or(|id| event.civilization_id() == id)
+                    .then_some(event)
+            })
+            .boxed())
+    }
+
+    /// 跟随世界的模拟：每收到一次 `TickCompleted`，读取缓存中的最新快照交给记录器采样
+    ///
+    /// 用于为其他实例模拟的世界记录指标，按记录器的间隔采样；收到 `Stopped` 或订阅断开时返回。
+    pub async fn follow(
+        &self,
+        civilization_id: Uuid,
+        metrics: SharedMetricsRecorder,
+    ) -> Result<(), WorldSyncError> {
+        let mut events = self.subscribe(Some(civilization_id)).await?;
+        while let Some(event) = events.next().await {
+            match event {
+                WorldEvent::TickCompleted { tick, .. } => {
+                    if let Some(landscape) = self.latest_landscape(civilization_id).await? {
+                        metrics
+                            .write()
+                            .expect("未能获取写锁，记录指标失败")
+                            .observe(tick, &landscape);
+                    }
+                }
+                WorldEvent::Stopped { .. } => break,
+                WorldEvent::Started { .. } | WorldEvent::Notice { .. } => {}
+            }
+        }
+        Ok(())
+    }
+}
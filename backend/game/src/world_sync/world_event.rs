@@ -0,0 +1,42 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// 广播世界事件的 Redis 频道，全部世界共用
+pub(crate) const WORLD_EVENTS_CHANNEL: &str = "world:events";
+
+/// 在实例之间广播的世界事件
+///
+/// 以 JSON 发布，`kind` 字段区分事件类型，例如
+/// `{"kind":"tick_completed","civilization_id":"…","tick":42}`。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum WorldEvent {
+    /// 世界开始由某个实例模拟
+    Started { civilization_id: Uuid },
+    /// 完成一步模拟，缓存中的快照与统计已更新到该步
+    TickCompleted { civilization_id: Uuid, tick: u64 },
+    /// 模拟过程中值得通知其他实例的事情，例如数值不稳定
+    Notice {
+        civilization_id: Uuid,
+        tick: u64,
+        message: String,
+    },
+    /// 世界停止模拟，缓存已被清除
+    Stopped { civilization_id: Uuid },
+}
+
+impl WorldEvent {
+    /// 事件所属世界的文明 ID
+    pub fn civilization_id(&self) -> Uuid {
+        match self {
+            WorldEvent::Started { civilization_id }
+            | WorldEvent::TickCompleted {
+                civilization_id, ..
+            }
+            | WorldEvent::Notice {
+                civilization_id, ..
+            }
+            | WorldEvent::Stopped { civilization_id } => *civilization_id,
+        }
+    }
+}
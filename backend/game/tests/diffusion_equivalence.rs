@@ -0,0 +1,192 @@
+//! 逐边通量扩散内核与旧版扩散算法的等价性测试
+//!
+//! 旧算法为每个单元格生成“中心 + 6 个邻居”的变化块，汇总到变化量数组后逐格应用；
+//! 新内核按边计算通量并双缓冲汇总。两者在不触发子步细分的参考数据上应逐格一致。
+
+use game::game_context::GameContext;
+use game::{
+    CartesianVec2D, HexUnit, MapSize, NoiseGeneratable, NoiseParams, Potential, Property,
+    StabilityParams, SubstanceDistribution, SubstanceType,
+};
+use ndarray::Array2;
+use std::f64::consts::PI;
+
+/// 按 `NeighbourRelation::ALL` 顺序排列的邻居坐标偏移 `(dy, dx)`
+const NEIGHBOURS: [(isize, isize); 6] = [(-1, 0), (-1, 1), (0, 1), (1, 0), (1, -1), (0, -1)];
+
+/// 相隔 180 度的邻居下标对
+const OPPOSITE_PAIRS: [(usize, usize); 3] = [(0, 3), (1, 4), (2, 5)];
+
+/// 固定种子的伪随机数，保证参考数据可复现
+struct XorShift(u64);
+
+impl XorShift {
+    fn next_f64(&mut self) -> f64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        (self.0 >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    fn range(&mut self, low: f64, high: f64) -> f64 {
+        low + (high - low) * self.next_f64()
+    }
+}
+
+fn to_cartesian((dy, dx): (isize, isize)) -> CartesianVec2D {
+    GameContext::get_x_base_vector().scale(dx as f64)
+        + GameContext::get_y_base_vector().scale(dy as f64)
+}
+
+fn neighbour(index: (usize, usize), shift: (isize, isize), dim: (usize, usize)) -> (usize, usize) {
+    (
+        (index.0 as isize + shift.0).rem_euclid(dim.0 as isize) as usize,
+        (index.1 as isize + shift.1).rem_euclid(dim.1 as isize) as usize,
+    )
+}
+
+/// 旧版 `HexUnit::diffuse`：返回中心变化量与 6 个邻居的变化量
+fn legacy_unit_diffuse(
+    unit: &HexUnit,
+    fluidity: f64,
+    neighbour_potentials: &[f64; 6],
+) -> ((isize, CartesianVec2D), [(isize, CartesianVec2D); 6]) {
+    // 1. 三对邻居势能差，位移指向势能较低的一侧
+    let total_shift = OPPOSITE_PAIRS
+        .iter()
+        .map(|&(a, b)| {
+            let delta = neighbour_potentials[a] - neighbour_potentials[b];
+            let (dy, dx) = NEIGHBOURS[if delta > 0.0 { a } else { b }];
+            to_cartesian((-dy, -dx)).scale(delta.abs())
+        })
+        .fold(unit.movement(), |acc, shift| acc + shift);
+
+    // 2. 每个邻居按夹角分配摩尔数与运动状态，运动状态不足以越过势垒时不流动
+    let neighbour_changes: [(isize, CartesianVec2D); 6] = std::array::from_fn(|i| {
+        let edge = to_cartesian(NEIGHBOURS[i]);
+        let weight = (2.0 * PI - edge.angle_between(total_shift).abs()) / (9.0 * PI);
+
+        let partial_mole = (unit.mole() as f64 * weight * fluidity).round() as usize;
+        let partial_movement = edge.scale(total_shift.magnitude() * weight);
+        let barrier = neighbour_potentials[i];
+
+        if partial_movement.magnitude() > barrier {
+            (
+                partial_mole as isize,
+                edge.scale(partial_movement.magnitude() - barrier),
+            )
+        } else {
+            (0, edge.scale(0.0))
+        }
+    });
+
+    // 3. 中心变化量为邻居变化量之和的相反数
+    let (mole, movement) = neighbour_changes.iter().fold(
+        (0, CartesianVec2D::new(0.0, 0.0)),
+        |(mole, movement), (mole_change, movement_change)| {
+            (mole + mole_change, movement + *movement_change)
+        },
+    );
+    ((-mole, movement.scale(-1.0)), neighbour_changes)
+}
+
+/// 旧版 `compute_changes` + `apply_changes`：先为所有单元格生成变化块，再汇总应用
+fn legacy_diffuse(
+    distribution: &Array2<HexUnit>,
+    potential: &Array2<f64>,
+    fluidity: f64,
+) -> Array2<HexUnit> {
+    let dim = distribution.dim();
+    let mut changes = Array2::from_elem(dim, (0isize, CartesianVec2D::new(0.0, 0.0)));
+
+    for (index, unit) in distribution.indexed_iter() {
+        let neighbour_potentials = NEIGHBOURS.map(|shift| potential[neighbour(index, shift, dim)]);
+        let (center_change, neighbour_changes) =
+            legacy_unit_diffuse(unit, fluidity, &neighbour_potentials);
+
+        let center = &mut changes[index];
+        *center = (center.0 + center_change.0, center.1 + center_change.1);
+        for (shift, change) in NEIGHBOURS.iter().zip(neighbour_changes) {
+            let target = &mut changes[neighbour(index, *shift, dim)];
+            *target = (target.0 + change.0, target.1 + change.1);
+        }
+    }
+
+    let mut next = distribution.clone();
+    for (unit, (mole_change, movement_change)) in next.iter_mut().zip(changes.iter()) {
+        unit.set_mole((unit.mole() as isize + mole_change) as usize);
+        unit.set_movement(unit.movement() + *movement_change);
+    }
+    next
+}
+
+/// 以固定种子生成参考数据：噪声摩尔数分布、随机运动状态与势能场强
+fn reference_case(
+    substance_type: &str,
+    seed: u32,
+    dim: (usize, usize),
+) -> (SubstanceDistribution, Potential) {
+    let mut rng = XorShift(0x9E37_79B9_7F4A_7C15 ^ seed as u64);
+    let mut distribution = SubstanceDistribution::new(
+        substance_type.parse::<SubstanceType>().unwrap(),
+        MapSize::from_tuple(dim),
+        Some(NoiseParams::new(Some(seed), Some(0.3))),
+    );
+    distribution.generate_simplex_noise();
+    for unit in distribution.distribution_mut().iter_mut() {
+        unit.set_movement(CartesianVec2D::new(
+            rng.range(-20.0, 20.0),
+            rng.range(-20.0, 20.0),
+        ));
+    }
+    let potential =
+        Potential::from_distribution(Array2::from_shape_fn(dim, |_| rng.range(0.0, 2.0)));
+    (distribution, potential)
+}
+
+#[test]
+fn flux_kernel_matches_legacy_diffusion() {
+    let cases = [
+        ("1/3", 7, (12, 16)),
+        ("3/4", 42, (9, 9)),
+        ("5/7", 2024, (16, 10)),
+    ];
+
+    for (substance_type, seed, dim) in cases {
+        let (mut distribution, potential) = reference_case(substance_type, seed, dim);
+        let fluidity =
+            Property::calculate_property(Property::Fluidity, distribution.substance_type());
+
+        for step in 0..5 {
+            let expected = legacy_diffuse(
+                distribution.distribution(),
+                potential.distribution(),
+                fluidity,
+            );
+            let report = distribution.diffuse(&potential, None, None, &StabilityParams::default());
+            assert_eq!(
+                report.substeps, 1,
+                "{substance_type} 种子 {seed} 第 {step} 步触发了子步细分，不再属于等价的参考数据"
+            );
+
+            for ((index, actual), expected) in distribution
+                .distribution()
+                .indexed_iter()
+                .zip(expected.iter())
+            {
+                assert_eq!(
+                    actual.mole(),
+                    expected.mole(),
+                    "{substance_type} 种子 {seed} 第 {step} 步 {index:?} 的摩尔数不一致"
+                );
+                let delta = actual.movement() + expected.movement().scale(-1.0);
+                assert!(
+                    delta.magnitude() <= 1e-9 * (1.0 + expected.movement().magnitude()),
+                    "{substance_type} 种子 {seed} 第 {step} 步 {index:?} 的运动状态不一致: {:?} != {:?}",
+                    actual.movement(),
+                    expected.movement()
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,99 @@
+//! 从文件加载属性目录的测试：按扩展名选择 TOML 或 JSON
+//!
+//! 属性注册表是全局的，因此这些用例放在单独的测试程序中，并在同一个用例里按顺序修改注册表。
+
+use game::{PropertyRegistry, PropertyRegistryError};
+use std::fs;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// 测试用的临时目录，结束（包括失败）时删除
+struct TempDir(PathBuf);
+
+impl TempDir {
+    fn new(name: &str) -> Self {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let path =
+            std::env::temp_dir().join(format!("game-{}-{}-{}", name, std::process::id(), nanos));
+        fs::create_dir_all(&path).unwrap();
+        Self(path)
+    }
+
+    /// 在目录中写出文件，返回其路径
+    fn write(&self, name: &str, contents: &str) -> PathBuf {
+        let path = self.0.join(name);
+        fs::write(&path, contents).unwrap();
+        path
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+#[test]
+fn catalogue_files_are_parsed_by_extension() {
+    let dir = TempDir::new("catalogue");
+
+    // 未配置属性目录时只使用内置属性
+    let builtin = PropertyRegistry::catalogue_hash();
+    assert_eq!(PropertyRegistry::init(None).unwrap(), 0);
+    assert_eq!(PropertyRegistry::catalogue_hash(), builtin);
+
+    let toml = dir.write(
+        "catalogue.toml",
+        r#"
+        [[properties]]
+        name = "Toxicity"
+        frequency_constant = 5
+        phase_constant = 1
+
+        [[properties]]
+        name = "Nutrition"
+        frequency_constant = 7
+        phase_constant = 4
+        upper_bound = 10.0
+        "#,
+    );
+    assert_eq!(PropertyRegistry::init(Some(&toml)).unwrap(), 2);
+    let nutrition = PropertyRegistry::get("Nutrition").unwrap();
+    assert_eq!(nutrition.upper_bound(), 10.0);
+    assert!(PropertyRegistry::get("Toxicity").is_some());
+
+    let json = dir.write(
+        "catalogue.json",
+        r#"{ "properties": [{ "name": "Acidity", "frequency_constant": 3, "phase_constant": 2 }] }"#,
+    );
+    assert_eq!(PropertyRegistry::load_file(&json).unwrap(), 1);
+    assert!(PropertyRegistry::get("Acidity").is_some());
+
+    // 扩展名决定格式：JSON 内容不能按 TOML 解析
+    let mislabelled = dir.write("json.toml", &fs::read_to_string(&json).unwrap());
+    assert!(matches!(
+        PropertyRegistry::load_file(&mislabelled),
+        Err(PropertyRegistryError::ParseToml(_))
+    ));
+    let invalid = dir.write("invalid.toml", "[[properties]]\nname = \"Salinity\"\n");
+    assert!(matches!(
+        PropertyRegistry::load_file(&invalid),
+        Err(PropertyRegistryError::ParseToml(_))
+    ));
+    assert!(PropertyRegistry::get("Salinity").is_none());
+
+    for name in ["catalogue.yaml", "catalogue"] {
+        let path = dir.write(name, "");
+        assert!(matches!(
+            PropertyRegistry::load_file(&path),
+            Err(PropertyRegistryError::UnsupportedFormat(_))
+        ));
+    }
+    assert!(matches!(
+        PropertyRegistry::init(Some(&dir.0.join("missing.toml"))),
+        Err(PropertyRegistryError::Io(_))
+    ));
+}
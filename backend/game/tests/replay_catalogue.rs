@@ -0,0 +1,71 @@
+//! 重放前校验属性目录的测试
+//!
+//! 属性注册表是全局的，因此这些用例放在单独的测试程序中，并在同一个用例里按顺序修改注册表。
+
+use game::{
+    read_event_log, EventLog, MapSize, NoiseParams, PropertyRegistry, RecordedWorld, ReplayError,
+    Replayer, SimEvent, WorldParams,
+};
+
+/// 用当前的属性目录记录一个推进了 5 步的世界，返回日志的内容
+fn record() -> Vec<u8> {
+    let params = WorldParams::new(None, MapSize::new(Some(6), Some(6)), Some(9.8));
+    let mut world = RecordedWorld::create(params, EventLog::new(Vec::new()), Some(5)).unwrap();
+    world
+        .add_substance("1/2".parse().unwrap(), NoiseParams::new(Some(7), Some(2.0)))
+        .unwrap();
+    for _ in 0..5 {
+        world.step().unwrap();
+    }
+    world.finish().unwrap()
+}
+
+#[test]
+fn replay_requires_the_catalogue_the_world_was_created_with() {
+    let builtin = PropertyRegistry::catalogue_hash();
+    let log = record();
+    let entries = read_event_log(log.as_slice()).unwrap();
+
+    // 创建世界的事件记录了当时的属性目录
+    let SimEvent::CreateWorld { params } = &entries[0].event else {
+        panic!("第一条事件不是创建世界");
+    };
+    assert_eq!(params.catalogue_hash(), Some(builtin));
+    assert!(Replayer::new(entries.clone())
+        .replay(None)
+        .unwrap()
+        .is_consistent());
+
+    // 加载了其他属性之后，重放在开始前即被拒绝
+    PropertyRegistry::load_json_str(
+        r#"{ "properties": [{ "name": "Toxicity", "frequency_constant": 5, "phase_constant": 1 }] }"#,
+    )
+    .unwrap();
+    let actual = PropertyRegistry::catalogue_hash();
+    match Replayer::new(entries).replay(None) {
+        Err(ReplayError::CatalogueMismatch {
+            expected,
+            actual: found,
+        }) => assert_eq!((expected, found), (builtin, actual)),
+        other => panic!("应因属性目录不同而失败: {:?}", other.map(|_| ())),
+    }
+
+    // 没有记录属性目录的旧日志不做校验
+    let legacy: String = String::from_utf8(log)
+        .unwrap()
+        .lines()
+        .map(|line| {
+            let mut entry: serde_json::Value = serde_json::from_str(line).unwrap();
+            if let Some(params) = entry.pointer_mut("/event/params") {
+                params.as_object_mut().unwrap().remove("catalogue_hash");
+            }
+            entry.to_string() + "\n"
+        })
+        .collect();
+    let entries = read_event_log(legacy.as_bytes()).unwrap();
+    let SimEvent::CreateWorld { params } = &entries[0].event else {
+        panic!("第一条事件不是创建世界");
+    };
+    assert_eq!(params.catalogue_hash(), None);
+    assert!(Replayer::new(entries).replay(None).unwrap().is_consistent());
+}
@@ -0,0 +1,53 @@
+//! 图层统计在空图层与单个单元格图层上的测试：`mean`、`variance` 与 `summary` 一致
+
+use game::{MapSize, Statistical, SubstanceDistribution};
+use ndarray::{array, Array2};
+
+/// 单独计算的均值、方差与汇总统计一致
+fn assert_agrees_with_summary(layer: &impl Statistical) {
+    let summary = layer.summary();
+    assert_eq!(layer.mean(), summary.mean);
+    assert_eq!(layer.variance(), summary.variance);
+}
+
+#[test]
+fn empty_layers_have_zero_mean_and_variance() {
+    for shape in [(0, 0), (0, 3), (3, 0)] {
+        let layer = Array2::<f64>::zeros(shape);
+        assert_eq!(Statistical::mean(&layer), 0.0);
+        assert_eq!(layer.variance(), 0.0);
+        assert_agrees_with_summary(&layer);
+        assert_eq!(layer.summary().count, 0);
+        assert!(layer.quantile(0.5).is_nan());
+    }
+
+    let distribution =
+        SubstanceDistribution::new("1/2".parse().unwrap(), MapSize::new(Some(0), Some(3)), None);
+    assert_eq!(distribution.mean(), 0.0);
+    assert_eq!(distribution.variance(), 0.0);
+    assert_agrees_with_summary(&distribution);
+}
+
+#[test]
+fn one_cell_layers_have_zero_variance() {
+    let layer = array![[4.0]];
+    assert_eq!(Statistical::mean(&layer), 4.0);
+    assert_eq!(layer.variance(), 0.0);
+    assert_agrees_with_summary(&layer);
+    assert_eq!(layer.quantile(0.5), 4.0);
+
+    let mut distribution =
+        SubstanceDistribution::new("1/2".parse().unwrap(), MapSize::new(Some(1), Some(1)), None);
+    distribution.distribution_mut()[(0, 0)].set_mole(7);
+    assert_eq!(distribution.mean(), 7.0);
+    assert_eq!(distribution.variance(), 0.0);
+    assert_agrees_with_summary(&distribution);
+}
+
+#[test]
+fn sample_variance_matches_the_summary() {
+    let layer = array![[0.0, 1.0], [2.0, 3.0]];
+    assert_eq!(Statistical::mean(&layer), 1.5);
+    assert!((layer.variance() - 5.0 / 3.0).abs() < 1e-12);
+    assert_agrees_with_summary(&layer);
+}
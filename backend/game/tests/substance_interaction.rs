@@ -0,0 +1,215 @@
+//! 耦合扩散中单元格内多物质交互的测试：置换与汇总、混合上限、反应规则
+//!
+//! 直接调用 `Landscape::interact`，只交互、不扩散，结果可以逐格手算。
+
+use game::{
+    CouplingParams, Landscape, MapSize, Property, ReactionRule, SubstanceDistribution,
+    SubstanceType,
+};
+use ndarray::Array2;
+
+/// 按 `NeighbourRelation::ALL` 顺序排列的邻居坐标偏移 `(dy, dx)`
+const NEIGHBOURS: [(isize, isize); 6] = [(-1, 0), (-1, 1), (0, 1), (1, 0), (1, -1), (0, -1)];
+
+fn substance(text: &str) -> SubstanceType {
+    text.parse().unwrap()
+}
+
+/// 按给定的摩尔数创建物质分布，运动向量为零
+fn distribution(substance_type: SubstanceType, moles: &Array2<usize>) -> SubstanceDistribution {
+    let (height, width) = moles.dim();
+    let map_size = MapSize::new(Some(width), Some(height));
+    let mut distribution = SubstanceDistribution::new(substance_type, map_size, None);
+    for (unit, &mole) in distribution.distribution_mut().iter_mut().zip(moles.iter()) {
+        unit.set_mole(mole);
+    }
+    distribution
+}
+
+fn landscape(coupling: CouplingParams, layers: &[(SubstanceType, Array2<usize>)]) -> Landscape {
+    let (height, width) = layers[0].1.dim();
+    let mut landscape =
+        Landscape::new(MapSize::new(Some(width), Some(height))).with_coupling(coupling);
+    for (substance_type, moles) in layers {
+        landscape.add_resource_distribution(distribution(*substance_type, moles));
+    }
+    landscape
+}
+
+/// 物质在各单元格的摩尔数，地貌中没有该物质时返回 `None`
+fn moles(landscape: &Landscape, substance_type: &SubstanceType) -> Option<Array2<usize>> {
+    landscape
+        .subtance_distributions()
+        .iter()
+        .find(|distribution| distribution.substance_type() == substance_type)
+        .map(|distribution| distribution.distribution().mapv(|unit| unit.mole()))
+}
+
+/// `(轻, 重)`：按密度排列的两种物质
+fn light_and_heavy(a: SubstanceType, b: SubstanceType) -> (SubstanceType, SubstanceType) {
+    let density = |st: &SubstanceType| Property::calculate_property(Property::Density, st);
+    assert_ne!(density(&a), density(&b));
+    if density(&a) < density(&b) {
+        (a, b)
+    } else {
+        (b, a)
+    }
+}
+
+#[test]
+fn displacement_and_gathering_conserve_each_substance() {
+    let types = [substance("1/2"), substance("1/3"), substance("2/5")];
+    let layers: Vec<_> = types
+        .iter()
+        .enumerate()
+        .map(|(index, &substance_type)| {
+            let moles = Array2::from_shape_fn((6, 7), |(row, col)| {
+                (row * 7 + col) * (index + 3) % 23 * 40 + index * 5
+            });
+            (substance_type, moles)
+        })
+        .collect();
+    let mut landscape = landscape(CouplingParams::new(Some(0.1), Some(1.0)), &layers);
+
+    landscape.interact();
+
+    let mut changed = false;
+    for (substance_type, before) in &layers {
+        let after = moles(&landscape, substance_type).unwrap();
+        assert_eq!(
+            after.sum(),
+            before.sum(),
+            "{:?} 的摩尔数不守恒",
+            substance_type
+        );
+        changed |= after != *before;
+    }
+    // 确实发生了置换，守恒不是因为什么都没做
+    assert!(changed);
+    // 交互不推进步数
+    assert_eq!(landscape.tick(), 0);
+}
+
+#[test]
+fn mixing_limit_caps_the_share_of_the_lighter_substance() {
+    let (light, heavy) = light_and_heavy(substance("1/2"), substance("1/3"));
+    let mixing_limit = 0.2;
+    let center = (3, 3);
+    let cell = |mole: usize| {
+        let mut moles = Array2::zeros((7, 7));
+        moles[center] = mole;
+        moles
+    };
+    let mut landscape = landscape(
+        CouplingParams::new(Some(mixing_limit), Some(1.0)),
+        &[(light, cell(900)), (heavy, cell(100))],
+    );
+
+    landscape.interact();
+
+    // 较轻的物质最多占据交互前总摩尔数的 `mixing_limit + (1 - mixing_limit) * (1 - η)`
+    let share = mixing_limit + (1.0 - mixing_limit) * (1.0 - light.hamming_ratio(&heavy));
+    assert!(share < 0.9, "用例需要较轻的物质超出份额");
+    let allowed = 1000.0 * share;
+    let light_after = moles(&landscape, &light).unwrap();
+    let kept = light_after[center];
+    assert!((kept as f64) >= allowed.floor() && (kept as f64) <= allowed.ceil());
+
+    // 挤出的部分平均分给 6 个邻居，余数给靠前的边；更致密的物质留在原处
+    let displaced = 900 - kept;
+    for (index, (dy, dx)) in NEIGHBOURS.iter().enumerate() {
+        let neighbour = (
+            (center.0 as isize + dy) as usize,
+            (center.1 as isize + dx) as usize,
+        );
+        // 中心沿指向该邻居的边分出的份额
+        let expected = displaced / 6 + usize::from(index < displaced % 6);
+        assert_eq!(light_after[neighbour], expected, "邻居 {:?}", neighbour);
+    }
+    assert_eq!(light_after.sum(), 900);
+    assert_eq!(moles(&landscape, &heavy).unwrap(), cell(100));
+}
+
+#[test]
+fn no_displacement_without_a_denser_neighbour_substance_or_below_the_limit() {
+    let (light, heavy) = light_and_heavy(substance("1/2"), substance("1/3"));
+    let layers = [
+        // 较轻的物质单独存在的单元格、份额低于上限的单元格
+        (
+            light,
+            Array2::from_shape_vec((1, 3), vec![500, 10, 0]).unwrap(),
+        ),
+        (
+            heavy,
+            Array2::from_shape_vec((1, 3), vec![0, 990, 300]).unwrap(),
+        ),
+    ];
+    let mut landscape = landscape(CouplingParams::new(Some(0.2), Some(1.0)), &layers);
+
+    landscape.interact();
+
+    for (substance_type, before) in &layers {
+        assert_eq!(moles(&landscape, substance_type).unwrap(), *before);
+    }
+}
+
+#[test]
+fn reaction_rule_converts_exactly_the_stated_amount() {
+    let (reactant, product) = (substance("1/2"), substance("1/3"));
+    let rate = 0.5;
+    let before = Array2::from_shape_fn((4, 5), |(row, col)| (row * 5 + col) * 37 + 3);
+    let mut landscape = landscape(
+        // 置换速率为 0，只有反应改变摩尔数
+        CouplingParams::new(None, Some(0.0))
+            .with_reaction_rule(ReactionRule::new(reactant, product, rate)),
+        &[(reactant, before.clone())],
+    );
+
+    landscape.interact();
+
+    // 实际速率为规则速率乘以相似度 `1 - η`，转化量向下取整
+    let effective_rate = rate * (1.0 - reactant.hamming_ratio(&product));
+    let converted = before.mapv(|mole| (mole as f64 * effective_rate).floor() as usize);
+    assert!(converted.sum() > 0);
+    assert_eq!(moles(&landscape, &reactant).unwrap(), &before - &converted);
+    assert_eq!(moles(&landscape, &product).unwrap(), converted);
+}
+
+#[test]
+fn reaction_products_get_a_distribution_before_they_exist() {
+    let (reactant, product) = (substance("1/2"), substance("3/4"));
+    let absent = substance("2/7");
+    let mut landscape = landscape(
+        CouplingParams::new(None, Some(0.0))
+            // 速率为 0 的规则也会补充生成物
+            .with_reaction_rule(ReactionRule::new(reactant, product, 0.0))
+            // 反应物不存在时规则被忽略，但生成物仍然补充
+            .with_reaction_rule(ReactionRule::new(absent, product, 1.0)),
+        &[(reactant, Array2::from_elem((3, 3), 10))],
+    );
+    assert!(moles(&landscape, &product).is_none());
+
+    landscape.interact();
+
+    assert_eq!(landscape.subtance_distributions().len(), 2);
+    assert_eq!(
+        moles(&landscape, &product).unwrap(),
+        Array2::<usize>::zeros((3, 3))
+    );
+    assert!(moles(&landscape, &absent).is_none());
+    assert_eq!(
+        moles(&landscape, &reactant).unwrap(),
+        Array2::from_elem((3, 3), 10)
+    );
+}
+
+#[test]
+fn interact_does_nothing_without_coupling() {
+    let moles_before = Array2::from_shape_fn((3, 3), |(row, col)| row * 100 + col);
+    let mut landscape = Landscape::new(MapSize::new(Some(3), Some(3)));
+    landscape.add_resource_distribution(distribution(substance("1/2"), &moles_before));
+
+    landscape.interact();
+
+    assert_eq!(moles(&landscape, &substance("1/2")).unwrap(), moles_before);
+}
@@ -0,0 +1,24 @@
+//! 物质种类计数的测试
+//!
+//! 物质注册表是全局的，因此这些用例放在单独的测试程序中。
+
+use game::SubstanceRegistry;
+
+#[test]
+fn count_matches_the_enumeration() {
+    for max_denominator in 1..=300 {
+        assert_eq!(
+            SubstanceRegistry::count_up_to(max_denominator),
+            SubstanceRegistry::enumerate_up_to(max_denominator).count(),
+            "最大分母 {}",
+            max_denominator
+        );
+    }
+}
+
+#[test]
+fn count_at_the_highest_precision_is_immediate() {
+    // Σφ(n) (n ≤ 10^6) = 303963552392，减去 φ(10^6) = 400000
+    SubstanceRegistry::update_precision(6).unwrap();
+    assert_eq!(SubstanceRegistry::count(), 2 * 303_963_152_392);
+}
@@ -0,0 +1,162 @@
+//! 物质合成的测试：线性组合、分母限制与摩尔质量守恒
+
+use game::{
+    CouplingParams, Landscape, MapSize, Property, SubstanceDistribution, SubstanceType,
+    SynthesisParams,
+};
+use ndarray::Array2;
+
+fn substance(text: &str) -> SubstanceType {
+    text.parse().unwrap()
+}
+
+/// 两两组合、互不相同的测试物质
+const SUBSTANCES: [&str; 8] = ["0", "1/3", "1/2", "3/4", "1", "7/5", "11/6", "2"];
+
+/// 按 `x_C = (1 - η) x_A + η x_B` 精确计算组合结果，其中 η 为汉明重量与 8 位之比
+fn design_combine(a: &SubstanceType, b: &SubstanceType) -> SubstanceType {
+    let weight = (a.hamming_ratio(b) * 8.0).round() as usize;
+    let denominator = 8 * a.denom() * b.denom();
+    let numerator = (8 - weight) * a.numer() * b.denom() + weight * b.numer() * a.denom();
+    SubstanceType::try_new(numerator, denominator).unwrap()
+}
+
+#[test]
+fn combine_mixes_by_the_hamming_ratio() {
+    for a in SUBSTANCES.map(substance) {
+        // 与自身组合时 η = 0，结果不变
+        assert_eq!(a.hamming_ratio(&a), 0.0);
+        assert_eq!(a.combine(&a), a);
+
+        for b in SUBSTANCES.map(substance) {
+            let combined = a.combine(&b);
+            assert_eq!(combined, design_combine(&a, &b), "{:?} 与 {:?}", a, b);
+
+            // 组合系数之和为 1，结果位于两者之间
+            let (low, high) = if a <= b { (a, b) } else { (b, a) };
+            assert!(low <= combined && combined <= high);
+        }
+    }
+}
+
+#[test]
+fn combine_all_folds_in_order() {
+    assert_eq!(SubstanceType::combine_all(&[]), None);
+
+    let [a, b, c] = ["1/3", "7/5", "3/4"].map(substance);
+    assert_eq!(SubstanceType::combine_all(&[a]), Some(a));
+    assert_eq!(
+        SubstanceType::combine_all(&[a, b, c]),
+        Some(a.combine(&b).combine(&c))
+    );
+}
+
+#[test]
+fn limit_denominator_returns_the_closest_fraction_within_bounds() {
+    let value = |x: &SubstanceType| x.numer() as f64 / x.denom() as f64;
+    let distance = |x: &SubstanceType, y: &SubstanceType| (value(x) - value(y)).abs();
+
+    for text in ["213/113", "1/97", "193/97", "2", "0", "5/8", "123/100"] {
+        let original = substance(text);
+        for max_denominator in [0, 1, 2, 3, 7, 10, 16, 50, 200] {
+            let limited = original.limit_denominator(max_denominator);
+            assert!(limited.denom() <= max_denominator.max(1));
+            assert!(substance("0") <= limited && limited <= substance("2"));
+
+            // 分母不超过上限的分数中没有更接近的
+            for denominator in 1..=max_denominator.max(1) {
+                for numerator in 0..=2 * denominator {
+                    let candidate = SubstanceType::try_new(numerator, denominator).unwrap();
+                    assert!(
+                        distance(&limited, &original) <= distance(&candidate, &original) + 1e-15,
+                        "{} 限制到 {} 得到 {:?}，但 {:?} 更接近",
+                        text,
+                        max_denominator,
+                        limited,
+                        candidate
+                    );
+                }
+            }
+        }
+        // 分母已经满足上限时不变
+        assert_eq!(original.limit_denominator(original.denom()), original);
+    }
+}
+
+/// 物质分布的总质量加上合成余量的质量
+fn total_molar_mass(landscape: &Landscape) -> f64 {
+    let distributions: f64 = landscape
+        .subtance_distributions()
+        .iter()
+        .map(SubstanceDistribution::total_mass)
+        .sum();
+    let remainders: f64 = landscape
+        .synthesis_remainders()
+        .iter()
+        .map(|(substance_type, remainder)| {
+            remainder.sum() * Property::calculate_property(Property::MolarMass, substance_type)
+        })
+        .sum();
+    distributions + remainders
+}
+
+fn total_moles(landscape: &Landscape, substance_type: &SubstanceType) -> usize {
+    landscape
+        .subtance_distributions()
+        .iter()
+        .find(|distribution| distribution.substance_type() == substance_type)
+        .map_or(0, |distribution| {
+            distribution
+                .distribution()
+                .iter()
+                .map(|unit| unit.mole())
+                .sum()
+        })
+}
+
+#[test]
+fn synthesis_conserves_total_molar_mass() {
+    let reactants = ["1/3", "3/4", "7/5"].map(substance);
+    let map_size = MapSize::new(Some(5), Some(4));
+    let mut landscape = Landscape::new(map_size).with_coupling(
+        // 置换速率为 0，只有合成改变摩尔数
+        CouplingParams::new(None, Some(0.0)).with_synthesis(SynthesisParams::new(
+            Some(1.0),
+            Some(3),
+            Some(12),
+            None,
+        )),
+    );
+    for (index, substance_type) in reactants.iter().enumerate() {
+        let moles = Array2::from_shape_fn((4, 5), |(row, col)| {
+            ((row * 5 + col) * (index + 2)) % 17 * 60 + 13
+        });
+        let mut distribution = SubstanceDistribution::new(*substance_type, map_size, None);
+        for (unit, &mole) in distribution.distribution_mut().iter_mut().zip(moles.iter()) {
+            unit.set_mole(mole);
+        }
+        landscape.add_resource_distribution(distribution);
+    }
+
+    let before = total_molar_mass(&landscape);
+    let reactant_moles: usize = reactants.iter().map(|st| total_moles(&landscape, st)).sum();
+    for _ in 0..5 {
+        landscape.interact();
+        let after = total_molar_mass(&landscape);
+        assert!(
+            (after - before).abs() <= before * 1e-12,
+            "合成前 {}，合成后 {}",
+            before,
+            after
+        );
+    }
+
+    // 确实发生了合成，且余量都不足一摩尔
+    let remaining: usize = reactants.iter().map(|st| total_moles(&landscape, st)).sum();
+    assert!(remaining < reactant_moles);
+    assert!(landscape.subtance_distributions().len() > reactants.len());
+    assert!(!landscape.synthesis_remainders().is_empty());
+    for (_, remainder) in landscape.synthesis_remainders() {
+        assert!(remainder.iter().all(|&value| (0.0..1.0).contains(&value)));
+    }
+}
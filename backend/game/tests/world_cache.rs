@@ -0,0 +1,191 @@
+//! 基于进程内 Redis 替身的世界缓存测试
+
+// 与 back-core 的测试共用 Redis 替身，这里只用到其中一部分
+#[allow(dead_code)]
+#[path = "../../back-core/tests/support/mod.rs"]
+mod support;
+
+use back_core::context::redis::store::RedisStore;
+use futures::StreamExt;
+use game::game_context::GameContext;
+use game::{
+    Landscape, MapSize, Metric, MetricsRecorder, MetricsSnapshot, NoiseGeneratable, NoiseParams,
+    SubstanceDistribution, WorldCache, WorldEvent,
+};
+use std::time::Duration;
+use support::RedisStandIn;
+use uuid::Uuid;
+
+/// 含一种物质、已推进若干步的地貌
+fn landscape(ticks: u64) -> Landscape {
+    GameContext::update_game_gravity_const(9.8);
+    let map_size = MapSize::new(Some(8), Some(8));
+    let mut distribution = SubstanceDistribution::new(
+        "1/2".parse().unwrap(),
+        map_size,
+        Some(NoiseParams::new(Some(7), Some(2.0))),
+    );
+    distribution.generate_simplex_noise();
+
+    let mut landscape = Landscape::new(map_size);
+    landscape.add_resource_distribution(distribution);
+    for _ in 0..ticks {
+        landscape.update_potential_distribution();
+        landscape.diffuse();
+    }
+    landscape
+}
+
+/// 各物质分布逐格的摩尔数
+fn moles(landscape: &Landscape) -> Vec<Vec<usize>> {
+    landscape
+        .subtance_distributions()
+        .iter()
+        .map(|distribution| {
+            distribution
+                .distribution()
+                .iter()
+                .map(|unit| unit.mole())
+                .collect()
+        })
+        .collect()
+}
+
+fn statistics(landscape: &Landscape) -> MetricsSnapshot {
+    let metrics: Vec<Metric> = Metric::parse_list("total:1/2,substance_count").unwrap();
+    MetricsSnapshot::sample(landscape, &metrics)
+}
+
+async fn cache(server: &RedisStandIn) -> WorldCache {
+    WorldCache::new(RedisStore::open(server.url()).await.unwrap())
+}
+
+#[tokio::test]
+async fn publish_tick_stores_the_latest_snapshot_and_statistics() {
+    let server = RedisStandIn::start(None).await;
+    let cache = cache(&server).await;
+    let world = Uuid::new_v4();
+
+    assert!(cache.latest_landscape(world).await.unwrap().is_none());
+    assert!(cache.latest_statistics(world).await.unwrap().is_none());
+
+    for ticks in [1, 3] {
+        let landscape = landscape(ticks);
+        let statistics = statistics(&landscape);
+        // 没有订阅者时快照照常写入
+        assert_eq!(
+            cache
+                .publish_tick(world, &landscape, &statistics)
+                .await
+                .unwrap(),
+            0
+        );
+
+        let latest = cache.latest_landscape(world).await.unwrap().unwrap();
+        assert_eq!(latest.tick(), ticks);
+        assert_eq!(moles(&latest), moles(&landscape));
+        assert_eq!(
+            cache.latest_statistics(world).await.unwrap(),
+            Some(statistics)
+        );
+    }
+
+    // 其他世界不受影响
+    assert!(cache
+        .latest_landscape(Uuid::new_v4())
+        .await
+        .unwrap()
+        .is_none());
+
+    assert!(cache.remove(world).await.unwrap());
+    assert!(cache.latest_landscape(world).await.unwrap().is_none());
+    assert!(cache.latest_statistics(world).await.unwrap().is_none());
+    assert!(!cache.remove(world).await.unwrap());
+}
+
+#[tokio::test]
+async fn subscribe_filters_events_by_world() {
+    let server = RedisStandIn::start(None).await;
+    let cache = cache(&server).await;
+    let (world, other) = (Uuid::new_v4(), Uuid::new_v4());
+
+    let mut own = cache.subscribe(Some(world)).await.unwrap();
+    let mut all = cache.subscribe(None).await.unwrap();
+
+    let landscape = landscape(1);
+    let statistics = statistics(&landscape);
+    cache
+        .publish_tick(other, &landscape, &statistics)
+        .await
+        .unwrap();
+    assert_eq!(
+        cache
+            .publish_tick(world, &landscape, &statistics)
+            .await
+            .unwrap(),
+        2
+    );
+    cache.remove(world).await.unwrap();
+
+    let expected_own = [
+        WorldEvent::TickCompleted {
+            civilization_id: world,
+            tick: 1,
+        },
+        WorldEvent::Stopped {
+            civilization_id: world,
+        },
+    ];
+    for expected in &expected_own {
+        assert_eq!(own.next().await.as_ref(), Some(expected));
+    }
+
+    let first = all.next().await.unwrap();
+    assert_eq!(
+        first,
+        WorldEvent::TickCompleted {
+            civilization_id: other,
+            tick: 1,
+        }
+    );
+    for expected in &expected_own {
+        assert_eq!(all.next().await.as_ref(), Some(expected));
+    }
+}
+
+#[tokio::test]
+async fn follow_records_metrics_until_the_world_stops() {
+    let server = RedisStandIn::start(None).await;
+    let cache = cache(&server).await;
+    let world = Uuid::new_v4();
+    let recorder = MetricsRecorder::new(Metric::parse_list("total:1/2").unwrap(), Some(2)).shared();
+
+    let follower = tokio::spawn({
+        let (cache, recorder) = (cache.clone(), recorder.clone());
+        async move { cache.follow(world, recorder).await }
+    });
+    // 等待跟随任务订阅完成
+    let started = WorldEvent::Started {
+        civilization_id: world,
+    };
+    while cache.publish_event(&started).await.unwrap() == 0 {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+
+    for ticks in 1..=4 {
+        let landscape = landscape(ticks);
+        cache
+            .publish_tick(world, &landscape, &statistics(&landscape))
+            .await
+            .unwrap();
+    }
+    cache.remove(world).await.unwrap();
+
+    tokio::time::timeout(Duration::from_secs(10), follower)
+        .await
+        .expect("跟随任务应在世界停止后结束")
+        .unwrap()
+        .unwrap();
+    let recorder = recorder.read().unwrap();
+    assert_eq!(recorder.ticks(), &[2, 4]);
+}
@@ -1,57 +1,109 @@
-use dotenvy::dotenv;
-use std::{env, fs, path::Path};
-use tracing_appender::non_blocking::WorkerGuard;
+mod options;
+mod otlp;
+mod rolling;
+
+pub use options::{LogFormat, LogOptions, LogRotation};
+pub use rolling::RollingFile;
+
+use otlp::{otlp_layer, OtlpGuard};
+use tracing::Subscriber;
+use tracing_appender::non_blocking::{NonBlocking, WorkerGuard};
 use tracing_subscriber::{
-    filter::LevelFilter, fmt, layer::SubscriberExt, util::SubscriberInitExt, EnvFilter, Layer,
+    fmt, layer::SubscriberExt, registry::LookupSpan, util::SubscriberInitExt, EnvFilter, Layer,
 };
 
-/// Initializes logging with JSON file output and compact terminal output.
-pub fn init_logging() -> WorkerGuard {
-    dotenv().ok();
-    let log_dir = setup_log_dir();
+/// 保持日志的后台写入，被丢弃时写完剩余的日志
+///
+/// 须保持在作用范围内直到程序结束，否则日志可能丢失。
+pub struct LogGuard {
+    _file: WorkerGuard,
+    _otlp: Option<OtlpGuard>,
+}
+
+/// Initializes logging with rotating file output, console output and optional OTLP export.
+///
+/// 日志文件无法创建时 panic；采集器地址无效时只打印错误，其余输出照常工作。
+///
+/// ### 参数
+/// - `options`: 日志的输出配置。
+pub fn init_logging(options: &LogOptions) -> LogGuard {
+    let file = RollingFile::open(
+        &options.dir,
+        &options.file_name,
+        options.rotation,
+        options.max_file_size,
+        options.max_files,
+    )
+    .expect("日志文件创建失败！");
+    let (non_blocking, file_guard) = tracing_appender::non_blocking(file);
+
+    let mut layers = vec![file_layer(options.file_format, non_blocking)];
+    if let Some(filter) = &options.console_filter {
+        layers.push(console_layer(options.console_format, filter));
+    }
+
+    let otlp_guard = match options
+        .otlp_endpoint
+        .as_deref()
+        .map(|endpoint| otlp_layer(endpoint, &options.service_name))
+    {
+        Some(Ok((layer, guard))) => {
+            layers.push(layer.boxed());
+            Some(guard)
+        }
+        Some(Err(e)) => {
+            eprintln!("日志导出未启用: {}", e);
+            None
+        }
+        None => None,
+    };
 
-    let (non_blocking, guard) =
-        tracing_appender::non_blocking(tracing_appender::rolling::daily(&log_dir, "app.json"));
+    tracing_subscriber::registry()
+        .with(EnvFilter::try_new(&options.filter).unwrap_or_else(|_| EnvFilter::new("debug")))
+        .with(layers)
+        .init();
 
-    let file_layer = fmt::layer()
-        .json()
-        .with_writer(non_blocking)
+    LogGuard {
+        _file: file_guard,
+        _otlp: otlp_guard,
+    }
+}
+
+/// 文件输出：带线程、源码位置，JSON 格式时包含当前 span 及其全部上层 span 的字段
+fn file_layer<S>(format: LogFormat, writer: NonBlocking) -> Box<dyn Layer<S> + Send + Sync>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    let layer = fmt::layer()
+        .with_writer(writer)
+        .with_ansi(false)
         .with_target(true)
         .with_thread_ids(true)
         .with_file(true)
         .with_line_number(true);
+    match format {
+        LogFormat::Json => layer.json().boxed(),
+        LogFormat::Pretty => layer.pretty().boxed(),
+        LogFormat::Compact => layer.compact().boxed(),
+    }
+}
 
-    let console_layer = fmt::layer()
+/// 控制台输出：不带时间、带颜色，按 `filter` 单独过滤
+fn console_layer<S>(format: LogFormat, filter: &str) -> Box<dyn Layer<S> + Send + Sync>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    let filter = EnvFilter::try_new(filter).unwrap_or_else(|_| EnvFilter::new("info"));
+    let layer = fmt::layer()
         .with_target(true)
         .with_thread_ids(true)
         .with_file(true)
         .with_line_number(true)
         .without_time()
-        .with_ansi(true)
-        .compact()
-        .with_filter(LevelFilter::INFO);
-
-    tracing_subscriber::registry()
-        .with(EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("debug")))
-        .with(file_layer)
-        .with(console_layer)
-        .init();
-
-    guard
-}
-
-/// 获取项目的根目录路径。
-///
-/// 该路径通过读取环境变量 `PROJECT_ROOT` 来确定。如果环境变量未设置，
-/// 则使用默认路径 `/workspaces/EvoScoSim/backend`。
-///
-/// ### 返回值
-///
-/// 返回一个字符串，表示项目的根目录路径。
-fn setup_log_dir() -> String {
-    let root_dir =
-        env::var("PROJECT_ROOT").unwrap_or_else(|_| "/workspaces/EvoScoSim/backend".to_string());
-    let log_dir = Path::new(&root_dir).join("log");
-    fs::create_dir_all(&log_dir).expect("日志目录创建失败！");
-    log_dir.to_str().unwrap().to_string()
+        .with_ansi(true);
+    match format {
+        LogFormat::Json => layer.json().with_filter(filter).boxed(),
+        LogFormat::Pretty => layer.pretty().with_filter(filter).boxed(),
+        LogFormat::Compact => layer.compact().with_filter(filter).boxed(),
+    }
 }
@@ -0,0 +1,141 @@
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// 日志行的格式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    /// 每行一个 JSON 对象，包含所在的 span 及其字段，便于检索
+    #[default]
+    Json,
+    /// 多行、带缩进的可读格式
+    Pretty,
+    /// 单行的可读格式
+    Compact,
+}
+
+/// 日志文件按时间轮转的周期
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogRotation {
+    Minutely,
+    Hourly,
+    #[default]
+    Daily,
+    /// 不按时间轮转，只按大小轮转（若设置了大小上限）
+    Never,
+}
+
+/// 日志的输出配置
+///
+/// ```no_run
+/// use log::{init_logging, LogFormat, LogOptions, LogRotation};
+///
+/// let options = LogOptions::new(Some("log".into()), Some("info,game=debug".to_string()))
+///     .with_file_format(LogFormat::Json)
+///     .with_rotation(LogRotation::Hourly)
+///     .with_max_file_size(64 * 1024 * 1024)
+///     .with_max_files(24)
+///     .with_otlp_endpoint("http://127.0.0.1:4318");
+/// let _guard = init_logging(&options);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogOptions {
+    pub(crate) dir: PathBuf,
+    pub(crate) filter: String,
+    pub(crate) file_name: String,
+    pub(crate) file_format: LogFormat,
+    pub(crate) rotation: LogRotation,
+    pub(crate) max_file_size: Option<u64>,
+    pub(crate) max_files: Option<usize>,
+    pub(crate) console_filter: Option<String>,
+    pub(crate) console_format: LogFormat,
+    pub(crate) otlp_endpoint: Option<String>,
+    pub(crate) service_name: String,
+}
+
+impl LogOptions {
+    /// 创建日志配置：JSON 文件按天轮转且不限数量，控制台输出 INFO 及以上，不导出到采集器
+    ///
+    /// ### 参数
+    /// - `dir`: 日志文件所在的目录，不存在时自动创建；默认为 `log`。
+    /// - `filter`: `tracing` 的过滤指令，例如 `info,sqlx=warn`；默认为 `debug`，无法解析时也退回 `debug`。
+    pub fn new(dir: Option<PathBuf>, filter: Option<String>) -> Self {
+        Self {
+            dir: dir.unwrap_or_else(|| PathBuf::from("log")),
+            filter: filter.unwrap_or_else(|| "debug".to_string()),
+            file_name: "app.json".to_string(),
+            file_format: LogFormat::Json,
+            rotation: LogRotation::Daily,
+            max_file_size: None,
+            max_files: None,
+            console_filter: Some("info".to_string()),
+            console_format: LogFormat::Compact,
+            otlp_endpoint: None,
+            service_name: "re-hive".to_string(),
+        }
+    }
+
+    /// 设置当前日志文件的名称，轮转出的文件在其后追加时间（可链式调用）
+    pub fn with_file_name(mut self, file_name: impl Into<String>) -> Self {
+        self.file_name = file_name.into();
+        self
+    }
+
+    /// 设置日志文件的格式（可链式调用）
+    pub fn with_file_format(mut self, format: LogFormat) -> Self {
+        self.file_format = format;
+        self
+    }
+
+    /// 设置按时间轮转的周期（可链式调用）
+    pub fn with_rotation(mut self, rotation: LogRotation) -> Self {
+        self.rotation = rotation;
+        self
+    }
+
+    /// 日志文件超过 `bytes` 字节后轮转，与按时间轮转同时生效（可链式调用）
+    pub fn with_max_file_size(mut self, bytes: u64) -> Self {
+        self.max_file_size = Some(bytes.max(1));
+        self
+    }
+
+    /// 最多保留 `count` 个轮转出的旧文件，更早的文件会被删除（可链式调用）
+    pub fn with_max_files(mut self, count: usize) -> Self {
+        self.max_files = Some(count);
+        self
+    }
+
+    /// 设置控制台输出的过滤指令，为 `None` 时不输出到控制台（可链式调用）
+    pub fn with_console(mut self, filter: Option<String>, format: LogFormat) -> Self {
+        self.console_filter = filter;
+        self.console_format = format;
+        self
+    }
+
+    /// 以 OTLP/HTTP 协议将 span 及其中的事件导出到本地采集器，例如 `http://127.0.0.1:4318`（可链式调用）
+    pub fn with_otlp_endpoint(mut self, endpoint: impl Into<String>) -> Self {
+        self.otlp_endpoint = Some(endpoint.into());
+        self
+    }
+
+    /// 设置导出时 `service.name` 资源属性的值（可链式调用）
+    pub fn with_service_name(mut self, service_name: impl Into<String>) -> Self {
+        self.service_name = service_name.into();
+        self
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn filter(&self) -> &str {
+        &self.filter
+    }
+}
+
+impl Default for LogOptions {
+    fn default() -> Self {
+        Self::new(None, None)
+    }
+}
@@ -0,0 +1,61 @@
+use opentelemetry::trace::TracerProvider;
+use opentelemetry_otlp::{SpanExporter, WithExportConfig};
+use opentelemetry_sdk::trace::SdkTracerProvider;
+use opentelemetry_sdk::Resource;
+use tracing::Subscriber;
+use tracing_opentelemetry::OpenTelemetryLayer;
+use tracing_subscriber::registry::LookupSpan;
+
+/// 保持导出器运行，被丢弃时发送剩余的 span 并关闭导出器
+pub(crate) struct OtlpGuard {
+    provider: SdkTracerProvider,
+}
+
+impl Drop for OtlpGuard {
+    fn drop(&mut self) {
+        if let Err(e) = self.provider.shutdown() {
+            eprintln!("关闭日志导出失败: {}", e);
+        }
+    }
+}
+
+/// 创建以 OTLP/HTTP 协议导出 span 的层
+///
+/// span 及其字段（例如 `civilization_id` 与 `tick`）作为 trace 导出，
+/// span 内的事件作为该 span 的事件导出。导出在后台线程中分批进行，
+/// 采集器跟不上时丢弃新的 span，而不是阻塞模拟。
+///
+/// ### 参数
+/// - `endpoint`: 采集器的地址，例如 `http://127.0.0.1:4318`，span 发往其下的 `/v1/traces`。
+/// - `service_name`: `service.name` 资源属性的值。
+pub(crate) fn otlp_layer<S>(
+    endpoint: &str,
+    service_name: &str,
+) -> Result<
+    (
+        OpenTelemetryLayer<S, opentelemetry_sdk::trace::Tracer>,
+        OtlpGuard,
+    ),
+    String,
+>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    let exporter = SpanExporter::builder()
+        .with_http()
+        .with_endpoint(format!("{}/v1/traces", endpoint.trim_end_matches('/')))
+        .build()
+        .map_err(|e| format!("采集器地址 {} 无效: {}", endpoint, e))?;
+    let provider = SdkTracerProvider::builder()
+        .with_batch_exporter(exporter)
+        .with_resource(
+            Resource::builder()
+                .with_service_name(service_name.to_string())
+                .build(),
+        )
+        .build();
+
+    let layer =
+        tracing_opentelemetry::layer().with_tracer(provider.tracer(service_name.to_string()));
+    Ok((layer, OtlpGuard { provider }))
+}
@@ -0,0 +1,182 @@
+use crate::options::LogRotation;
+use chrono::{DateTime, DurationRound, TimeDelta, Utc};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+/// 按时间和大小轮转的日志文件
+///
+/// 当前的日志总是写入 `dir/file_name`；轮转时它被重命名为 `file_name.<时间>`，
+/// 时间为该文件所属周期的起点（按大小轮转时为轮转的时刻），重名时再追加序号。
+/// 设置了保留数量时，最旧的文件会被删除。
+///
+/// `init_logging` 用它写日志文件，也可以单独作为任意 `Write` 的目标使用：
+///
+/// ```no_run
+/// use log::{LogRotation, RollingFile};
+/// use std::io::Write;
+/// use std::path::Path;
+///
+/// // 每小时、或超过 1 MiB 时轮转，最多保留 24 个旧文件
+/// let mut file = RollingFile::open(
+///     Path::new("log"),
+///     "trades.csv",
+///     LogRotation::Hourly,
+///     Some(1024 * 1024),
+///     Some(24),
+/// )
+/// .unwrap();
+/// writeln!(file, "tick,agent,amount").unwrap();
+/// ```
+pub struct RollingFile {
+    dir: PathBuf,
+    file_name: String,
+    rotation: LogRotation,
+    max_size: Option<u64>,
+    max_files: Option<usize>,
+    file: File,
+    size: u64,
+    /// 当前文件所属周期的起点，不按时间轮转时为 `None`
+    period_start: Option<DateTime<Utc>>,
+}
+
+impl RollingFile {
+    /// 打开 `dir/file_name` 并续写，目录不存在时自动创建；打开时即按保留数量清理旧文件
+    ///
+    /// ### 参数
+    /// - `rotation`: 按时间轮转的周期。
+    /// - `max_size`: 文件超过该字节数后轮转，为 `None` 时不按大小轮转。
+    /// - `max_files`: 最多保留的旧文件数，为 `None` 时全部保留。
+    pub fn open(
+        dir: &Path,
+        file_name: &str,
+        rotation: LogRotation,
+        max_size: Option<u64>,
+        max_files: Option<usize>,
+    ) -> io::Result<Self> {
+        fs::create_dir_all(dir)?;
+        let path = dir.join(file_name);
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let metadata = file.metadata()?;
+
+        // 续写已有的文件时，以它最后修改的时间判断它属于哪个周期
+        let modified = metadata
+            .modified()
+            .map(DateTime::<Utc>::from)
+            .unwrap_or_else(|_| Utc::now());
+        let rolling = Self {
+            dir: dir.to_path_buf(),
+            file_name: file_name.to_string(),
+            rotation,
+            max_size,
+            max_files,
+            file,
+            size: metadata.len(),
+            period_start: period_start(rotation, modified),
+        };
+        rolling.prune()?;
+        Ok(rolling)
+    }
+
+    /// 是否需要在写入 `incoming` 字节前轮转
+    fn should_rotate(&self, now: DateTime<Utc>, incoming: usize) -> bool {
+        let period_ended = self.period_start != period_start(self.rotation, now);
+        let oversized = self
+            .max_size
+            .is_some_and(|max_size| self.size > 0 && self.size + incoming as u64 > max_size);
+        period_ended || oversized
+    }
+
+    fn rotate(&mut self, now: DateTime<Utc>) -> io::Result<()> {
+        self.file.flush()?;
+
+        let period_ended = self.period_start != period_start(self.rotation, now);
+        let stamp = match self.period_start {
+            Some(start) if period_ended => start.format(period_format(self.rotation)).to_string(),
+            _ => now.format("%Y-%m-%d-%H-%M-%S").to_string(),
+        };
+        let active = self.dir.join(&self.file_name);
+        fs::rename(&active, self.archive_path(&stamp))?;
+
+        self.file = OpenOptions::new().create(true).append(true).open(&active)?;
+        self.size = 0;
+        self.period_start = period_start(self.rotation, now);
+        self.prune()
+    }
+
+    /// 轮转出的文件的路径，重名时追加序号
+    fn archive_path(&self, stamp: &str) -> PathBuf {
+        let base = format!("{}.{}", self.file_name, stamp);
+        let mut path = self.dir.join(&base);
+        let mut sequence = 1;
+        while path.exists() {
+            path = self.dir.join(format!("{}.{}", base, sequence));
+            sequence += 1;
+        }
+        path
+    }
+
+    /// 按保留数量删除最旧的文件
+    fn prune(&self) -> io::Result<()> {
+        let Some(max_files) = self.max_files else {
+            return Ok(());
+        };
+
+        let prefix = format!("{}.", self.file_name);
+        let mut archives = Vec::new();
+        for entry in fs::read_dir(&self.dir)? {
+            let entry = entry?;
+            let name = entry.file_name().to_string_lossy().into_owned();
+            if name.starts_with(&prefix) && entry.file_type()?.is_file() {
+                let modified = entry.metadata()?.modified()?;
+                archives.push((modified, name, entry.path()));
+            }
+        }
+        if archives.len() <= max_files {
+            return Ok(());
+        }
+
+        archives.sort();
+        let expired = archives.len() - max_files;
+        for (_, _, path) in archives.into_iter().take(expired) {
+            fs::remove_file(path)?;
+        }
+        Ok(())
+    }
+}
+
+impl Write for RollingFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let now = Utc::now();
+        if self.should_rotate(now, buf.len()) {
+            self.rotate(now)?;
+        }
+        let written = self.file.write(buf)?;
+        self.size += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+/// `time` 所在周期的起点，不按时间轮转时为 `None`
+fn period_start(rotation: LogRotation, time: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    let period = match rotation {
+        LogRotation::Minutely => TimeDelta::minutes(1),
+        LogRotation::Hourly => TimeDelta::hours(1),
+        LogRotation::Daily => TimeDelta::days(1),
+        LogRotation::Never => return None,
+    };
+    time.duration_trunc(period).ok()
+}
+
+/// 轮转出的文件名中周期起点的格式，与 `tracing_appender::rolling` 一致
+fn period_format(rotation: LogRotation) -> &'static str {
+    match rotation {
+        LogRotation::Minutely => "%Y-%m-%d-%H-%M",
+        LogRotation::Hourly => "%Y-%m-%d-%H",
+        LogRotation::Daily | LogRotation::Never => "%Y-%m-%d",
+    }
+}
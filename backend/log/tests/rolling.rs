@@ -0,0 +1,182 @@
+//! 日志文件按大小、按时间轮转与按数量保留的测试
+
+use chrono::{DateTime, TimeDelta, Utc};
+use log::{LogRotation, RollingFile};
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// 测试用的临时目录，结束（包括失败）时删除
+struct TempDir(PathBuf);
+
+impl TempDir {
+    fn new(name: &str) -> Self {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let path = std::env::temp_dir().join(format!(
+            "re-hive-log-{}-{}-{}",
+            name,
+            std::process::id(),
+            nanos
+        ));
+        fs::create_dir_all(&path).unwrap();
+        Self(path)
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+/// 目录中的文件名及其内容，按文件名排序
+fn files(dir: &Path) -> Vec<(String, String)> {
+    let mut files: Vec<_> = fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| {
+            let entry = entry.unwrap();
+            (
+                entry.file_name().to_string_lossy().into_owned(),
+                fs::read_to_string(entry.path()).unwrap(),
+            )
+        })
+        .collect();
+    files.sort();
+    files
+}
+
+/// 创建内容为 `content`、最后修改时间为 `modified` 的文件
+fn create_file(path: &Path, content: &str, modified: DateTime<Utc>) {
+    let mut file = File::create(path).unwrap();
+    file.write_all(content.as_bytes()).unwrap();
+    file.set_modified(modified.into()).unwrap();
+}
+
+#[test]
+fn rotates_when_the_next_write_would_exceed_the_size_limit() {
+    let dir = TempDir::new("size");
+    let mut file =
+        RollingFile::open(&dir.0, "app.log", LogRotation::Never, Some(10), None).unwrap();
+
+    // 每行 5 字节：前两行共 10 字节，恰好不超过上限
+    for line in ["one.\n", "two.\n", "tri.\n", "for.\n", "fiv.\n"] {
+        file.write_all(line.as_bytes()).unwrap();
+    }
+    file.flush().unwrap();
+
+    let files = files(&dir.0);
+    assert_eq!(files.len(), 3);
+    assert_eq!(files[0], ("app.log".to_string(), "fiv.\n".to_string()));
+    // 轮转出的文件以轮转的时刻命名，同一秒内重名时追加序号
+    let archives: Vec<_> = files[1..]
+        .iter()
+        .map(|(_, content)| content.as_str())
+        .collect();
+    assert_eq!(archives.len(), 2);
+    assert!(archives.contains(&"one.\ntwo.\n"));
+    assert!(archives.contains(&"tri.\nfor.\n"));
+    assert!(files[1..]
+        .iter()
+        .all(|(name, _)| name.starts_with("app.log.")));
+}
+
+#[test]
+fn a_single_oversized_write_goes_to_an_empty_file() {
+    let dir = TempDir::new("oversized");
+    let mut file = RollingFile::open(&dir.0, "app.log", LogRotation::Never, Some(4), None).unwrap();
+
+    // 空文件不轮转，超过上限的一行也完整写入
+    file.write_all(b"longer than four\n").unwrap();
+    file.flush().unwrap();
+    assert_eq!(
+        files(&dir.0),
+        vec![("app.log".to_string(), "longer than four\n".to_string())]
+    );
+}
+
+#[test]
+fn keeps_only_the_newest_archives_when_rotating() {
+    let dir = TempDir::new("retention");
+    let mut file =
+        RollingFile::open(&dir.0, "app.log", LogRotation::Never, Some(5), Some(2)).unwrap();
+
+    for line in ["1111\n", "2222\n", "3333\n", "4444\n", "5555\n"] {
+        file.write_all(line.as_bytes()).unwrap();
+    }
+    file.flush().unwrap();
+
+    let mut contents: Vec<_> = files(&dir.0)
+        .into_iter()
+        .map(|(_, content)| content)
+        .collect();
+    contents.sort();
+    // 当前文件加上最新的两个旧文件
+    assert_eq!(contents, vec!["3333\n", "4444\n", "5555\n"]);
+}
+
+#[test]
+fn prunes_existing_archives_on_open() {
+    let dir = TempDir::new("open");
+    let now = Utc::now();
+    create_file(
+        &dir.0.join("app.log.a"),
+        "oldest",
+        now - TimeDelta::hours(3),
+    );
+    create_file(&dir.0.join("app.log.b"), "older", now - TimeDelta::hours(2));
+    create_file(&dir.0.join("app.log.c"), "newer", now - TimeDelta::hours(1));
+    // 不属于该日志的文件不受影响
+    create_file(
+        &dir.0.join("other.log.a"),
+        "other",
+        now - TimeDelta::hours(4),
+    );
+
+    RollingFile::open(&dir.0, "app.log", LogRotation::Never, None, Some(2)).unwrap();
+
+    let names: Vec<_> = files(&dir.0).into_iter().map(|(name, _)| name).collect();
+    assert_eq!(
+        names,
+        vec!["app.log", "app.log.b", "app.log.c", "other.log.a"]
+    );
+}
+
+#[test]
+fn continues_a_file_from_an_earlier_period_and_rotates_it_under_that_period() {
+    let dir = TempDir::new("period");
+    let modified = Utc::now() - TimeDelta::hours(2);
+    create_file(&dir.0.join("app.log"), "earlier\n", modified);
+
+    let mut file = RollingFile::open(&dir.0, "app.log", LogRotation::Hourly, None, None).unwrap();
+    file.write_all(b"now\n").unwrap();
+    file.flush().unwrap();
+
+    let archive = format!("app.log.{}", modified.format("%Y-%m-%d-%H"));
+    assert_eq!(
+        files(&dir.0),
+        vec![
+            ("app.log".to_string(), "now\n".to_string()),
+            (archive, "earlier\n".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn appends_to_a_file_from_the_current_period() {
+    let dir = TempDir::new("append");
+    create_file(&dir.0.join("app.log"), "earlier\n", Utc::now());
+
+    let mut file =
+        RollingFile::open(&dir.0, "app.log", LogRotation::Daily, Some(1024), None).unwrap();
+    file.write_all(b"now\n").unwrap();
+    file.flush().unwrap();
+
+    assert_eq!(
+        files(&dir.0),
+        vec![("app.log".to_string(), "earlier\nnow\n".to_string())]
+    );
+}
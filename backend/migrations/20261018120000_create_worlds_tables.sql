@@ -0,0 +1,36 @@
+-- Add migration script here
+
+CREATE TABLE IF NOT EXISTS worlds (
+    world_id UUID PRIMARY KEY,
+    seed BIGINT NOT NULL,
+    map_width INTEGER NOT NULL CHECK (map_width > 0),
+    map_height INTEGER NOT NULL CHECK (map_height > 0),
+    gravity DOUBLE PRECISION NOT NULL CHECK (gravity > 0),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE TABLE IF NOT EXISTS runs (
+    run_id UUID PRIMARY KEY,
+    world_id UUID NOT NULL REFERENCES worlds (world_id) ON DELETE CASCADE,
+    status TEXT NOT NULL CHECK (status IN ('running', 'completed', 'failed')),
+    start_tick BIGINT NOT NULL CHECK (start_tick >= 0),
+    last_tick BIGINT NOT NULL CHECK (last_tick >= start_tick),
+    checkpoint_interval INTEGER NOT NULL CHECK (checkpoint_interval > 0),
+    started_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    finished_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS runs_world_id_idx ON runs (world_id, started_at DESC);
+
+CREATE TABLE IF NOT EXISTS checkpoints (
+    checkpoint_id BIGSERIAL PRIMARY KEY,
+    run_id UUID NOT NULL REFERENCES runs (run_id) ON DELETE CASCADE,
+    world_id UUID NOT NULL REFERENCES worlds (world_id) ON DELETE CASCADE,
+    tick BIGINT NOT NULL CHECK (tick >= 0),
+    snapshot BYTEA NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+
+    UNIQUE (run_id, tick)
+);
+
+CREATE INDEX IF NOT EXISTS checkpoints_world_id_idx ON checkpoints (world_id, tick DESC);
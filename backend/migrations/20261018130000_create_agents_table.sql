@@ -0,0 +1,9 @@
+-- Add migration script here
+
+CREATE TABLE IF NOT EXISTS agents (
+    agent_id UUID PRIMARY KEY,
+    world_id UUID NOT NULL REFERENCES worlds (world_id) ON DELETE CASCADE,
+    born_tick BIGINT NOT NULL CHECK (born_tick >= 0)
+);
+
+CREATE INDEX IF NOT EXISTS agents_world_id_idx ON agents (world_id);
@@ -15,17 +15,19 @@ use syn::{parse_macro_input, Data, DeriveInput, Ident};
 ///
 /// ### 示例
 /// ```rust
+/// # use my_proc_macro::Literal;
 /// #[derive(Literal)]
 /// pub struct Example {
-///     pub field1: i32,
+///     pub map_size: i32,
 ///     #[notLiteral]
-///     pub field2: String, // 此字段不会生成常量
+///     pub label: String, // 此字段不会生成常量
 /// }
 ///
-/// // 自动生成代码
-/// impl Example {
-///     pub const FIELD1: &'static str = "field1";
-/// }
+/// // 自动生成代码：
+/// // impl Example {
+/// //     pub const MAP_SIZE: &'static str = "map_size";
+/// // }
+/// assert_eq!(Example::MAP_SIZE, "map_size");
 /// ```
 #[proc_macro_derive(Literal, attributes(notLiteral))]
 pub fn derive_literal(input: TokenStream) -> TokenStream {
@@ -6,7 +6,7 @@
 ///
 /// 宏的使用格式为：
 ///
-/// ```
+/// ```text
 /// enum_map! {
 ///     #[derive(Debug, PartialEq, Eq, Hash)] // 可以在这里添加需要的额外属性
 ///     pub(crate) EnumName => ValueType {
@@ -24,11 +24,13 @@
 /// - `value_fn1`, `value_fn2`, `value_fn3` 是与各个枚举变体对应的函数，它们的返回类型应当是 `ValueType`。
 ///
 /// 该宏生成的 `to_map` 方法可以返回一个静态的 `HashMap`，
-/// 其中包含枚举变体到 `ValueType` 的映射。
+/// 其中包含枚举变体到 `ValueType` 的映射；
+/// 生成的 `VARIANTS` 常量则按声明顺序列出全部变体，`name` 方法返回变体名称。
 ///
 /// ### 示例
 ///
 /// ```rust
+/// # use share_and_commute::enum_map;
 /// enum_map! {
 ///     pub(crate) ExampleEnum => i32 {
 ///         A => || 1,
@@ -64,6 +66,16 @@ macro_rules! enum_map {
         });
 
         impl $name {
+            // 按声明顺序排列的全部变体，便于需要稳定顺序的遍历
+            $vis const VARIANTS: &'static [$name] = &[$($name::$key),*];
+
+            // 获取变体名称的字符串形式
+            $vis fn name(&self) -> &'static str {
+                match self {
+                    $($name::$key => stringify!($key)),*
+                }
+            }
+
             // 提供一个方法来访问静态的 HashMap
             $vis fn to_map() -> &'static HashMap<$name, $value_type> {
                 &MAP
@@ -0,0 +1,67 @@
+-- SQLite 版本的表结构，与 migrations 目录中的 Postgres 表一一对应
+-- UUID 以 BLOB 保存，时间由应用写入 TEXT，保证比较顺序与写入格式一致
+
+CREATE TABLE IF NOT EXISTS worlds (
+    world_id BLOB PRIMARY KEY,
+    seed INTEGER NOT NULL,
+    map_width INTEGER NOT NULL CHECK (map_width > 0),
+    map_height INTEGER NOT NULL CHECK (map_height > 0),
+    gravity REAL NOT NULL CHECK (gravity > 0),
+    created_at TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS runs (
+    run_id BLOB PRIMARY KEY,
+    world_id BLOB NOT NULL REFERENCES worlds (world_id) ON DELETE CASCADE,
+    status TEXT NOT NULL CHECK (status IN ('running', 'completed', 'failed')),
+    start_tick INTEGER NOT NULL CHECK (start_tick >= 0),
+    last_tick INTEGER NOT NULL CHECK (last_tick >= start_tick),
+    checkpoint_interval INTEGER NOT NULL CHECK (checkpoint_interval > 0),
+    started_at TEXT NOT NULL,
+    finished_at TEXT
+);
+
+CREATE INDEX IF NOT EXISTS runs_world_id_idx ON runs (world_id, started_at DESC);
+
+CREATE TABLE IF NOT EXISTS checkpoints (
+    checkpoint_id INTEGER PRIMARY KEY AUTOINCREMENT,
+    run_id BLOB NOT NULL REFERENCES runs (run_id) ON DELETE CASCADE,
+    world_id BLOB NOT NULL REFERENCES worlds (world_id) ON DELETE CASCADE,
+    tick INTEGER NOT NULL CHECK (tick >= 0),
+    snapshot BLOB NOT NULL,
+    created_at TEXT NOT NULL,
+
+    UNIQUE (run_id, tick)
+);
+
+CREATE INDEX IF NOT EXISTS checkpoints_world_id_idx ON checkpoints (world_id, tick DESC);
+
+CREATE TABLE IF NOT EXISTS agents (
+    agent_id BLOB PRIMARY KEY,
+    world_id BLOB NOT NULL REFERENCES worlds (world_id) ON DELETE CASCADE,
+    born_tick INTEGER NOT NULL CHECK (born_tick >= 0)
+);
+
+CREATE INDEX IF NOT EXISTS agents_world_id_idx ON agents (world_id);
+
+CREATE TABLE IF NOT EXISTS resources (
+    record_id INTEGER PRIMARY KEY AUTOINCREMENT,
+    agent_id BLOB NOT NULL,
+    subtance_numerator INTEGER NOT NULL CHECK (subtance_numerator > 0),
+    subtance_denominator INTEGER NOT NULL CHECK (subtance_denominator > 0),
+    allocatable INTEGER NOT NULL CHECK (allocatable >= 0),
+    investment INTEGER NOT NULL CHECK (investment >= 0),
+    debt INTEGER NOT NULL CHECK (debt >= 0),
+
+    UNIQUE (agent_id, subtance_numerator, subtance_denominator)
+);
+
+CREATE TABLE IF NOT EXISTS preferences (
+    record_id INTEGER PRIMARY KEY AUTOINCREMENT,
+    agent_id BLOB NOT NULL,
+    subtance_numerator INTEGER NOT NULL CHECK (subtance_numerator > 0),
+    subtance_denominator INTEGER NOT NULL CHECK (subtance_denominator > 0),
+    preference REAL NOT NULL CHECK (preference >= 0 AND preference <= 1),
+
+    UNIQUE (agent_id, subtance_numerator, subtance_denominator)
+);
@@ -0,0 +1,15 @@
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use back_core::context::core_context::AppContext;
+
+/// `GET /health`: 各外部服务的状态，降级运行时返回 503
+pub async fn health() -> Response {
+    let report = AppContext::health();
+    let status = if report.is_degraded() {
+        StatusCode::SERVICE_UNAVAILABLE
+    } else {
+        StatusCode::OK
+    };
+    (status, Json(report)).into_response()
+}
@@ -0,0 +1,59 @@
+use axum::extract::{Query, State};
+use axum::http::{header, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::{Json, Router};
+use game::{SeriesQuery, SharedMetricsRecorder};
+
+/// 指标查询接口
+///
+/// - `GET /metrics`: 已记录的指标名称。
+/// - `GET /metrics/series?metrics=mean:1/2,gini:potential&from=0&to=1000&max_points=200`:
+///   按步数区间截取并降采样后的时间序列。
+/// - `GET /metrics/csv`: 全部采样的 CSV。
+pub fn router(metrics: SharedMetricsRecorder) -> Router {
+    Router::new()
+        .route("/", get(list_metrics))
+        .route("/series", get(query_series))
+        .route("/csv", get(export_csv))
+        .with_state(metrics)
+}
+
+async fn list_metrics(State(metrics): State<SharedMetricsRecorder>) -> Response {
+    match metrics.read() {
+        Ok(recorder) => Json(
+            recorder
+                .metrics()
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+        )
+        .into_response(),
+        Err(e) => lock_poisoned(e),
+    }
+}
+
+async fn query_series(
+    State(metrics): State<SharedMetricsRecorder>,
+    Query(query): Query<SeriesQuery>,
+) -> Response {
+    match metrics.read() {
+        Ok(recorder) => match recorder.query(&query) {
+            Ok(series) => Json(series).into_response(),
+            Err(e) => (StatusCode::BAD_REQUEST, e.to_string()).into_response(),
+        },
+        Err(e) => lock_poisoned(e),
+    }
+}
+
+async fn export_csv(State(metrics): State<SharedMetricsRecorder>) -> Response {
+    match metrics.read() {
+        Ok(recorder) => ([(header::CONTENT_TYPE, "text/csv")], recorder.to_csv()).into_response(),
+        Err(e) => lock_poisoned(e),
+    }
+}
+
+fn lock_poisoned(e: impl std::fmt::Display) -> Response {
+    tracing::error!("无法获取指标记录器的读锁: {}", e);
+    (StatusCode::INTERNAL_SERVER_ERROR, "指标记录器不可用").into_response()
+}
@@ -0,0 +1,15 @@
+pub mod health;
+pub mod metrics;
+pub mod worlds;
+
+use axum::routing::get;
+use axum::Router;
+use game::{SharedMetricsRecorder, WorldCache};
+
+/// 汇总全部 HTTP 路由
+pub fn router(metrics: SharedMetricsRecorder, worlds: Option<WorldCache>) -> Router {
+    Router::new()
+        .route("/health", get(health::health))
+        .nest("/metrics", metrics::router(metrics))
+        .nest("/worlds", worlds::router(worlds))
+}
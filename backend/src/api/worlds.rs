@@ -0,0 +1,60 @@
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::{Json, Router};
+use game::{WorldCache, WorldSyncError};
+use serde::Serialize;
+use uuid::Uuid;
+
+/// 世界状态查询接口，数据来自 Redis 缓存，可以读取由其他实例模拟的世界
+///
+/// - `GET /worlds/{civilization_id}/landscape`: 最新的地貌快照。
+/// - `GET /worlds/{civilization_id}/statistics`: 最新的统计。
+///
+/// 缓存中没有该世界时返回 404；Redis 未连接时返回 503。
+pub fn router(worlds: Option<WorldCache>) -> Router {
+    Router::new()
+        .route("/:civilization_id/landscape", get(latest_landscape))
+        .route("/:civilization_id/statistics", get(latest_statistics))
+        .with_state(worlds)
+}
+
+async fn latest_landscape(
+    State(worlds): State<Option<WorldCache>>,
+    Path(civilization_id): Path<Uuid>,
+) -> Response {
+    let Some(worlds) = worlds else {
+        return cache_unavailable();
+    };
+    respond(worlds.latest_landscape(civilization_id).await)
+}
+
+async fn latest_statistics(
+    State(worlds): State<Option<WorldCache>>,
+    Path(civilization_id): Path<Uuid>,
+) -> Response {
+    let Some(worlds) = worlds else {
+        return cache_unavailable();
+    };
+    respond(worlds.latest_statistics(civilization_id).await)
+}
+
+fn respond<T: Serialize>(result: Result<Option<T>, WorldSyncError>) -> Response {
+    match result {
+        Ok(Some(value)) => Json(value).into_response(),
+        Ok(None) => (StatusCode::NOT_FOUND, "缓存中没有该世界").into_response(),
+        Err(e) => {
+            tracing::error!("读取世界缓存失败: {}", e);
+            (StatusCode::BAD_GATEWAY, "读取世界缓存失败").into_response()
+        }
+    }
+}
+
+fn cache_unavailable() -> Response {
+    (
+        StatusCode::SERVICE_UNAVAILABLE,
+        "Redis 未连接，无法读取世界缓存",
+    )
+        .into_response()
+}
@@ -0,0 +1,65 @@
+//! 服务端的命令行参数
+//!
+//! 除下列参数外，每个配置项都可以用 `--<键> <值>` 覆盖，例如 `--database.pool_size 20`。
+//! 参数有误时由 clap 打印错误与用法，并以退出码 2 退出。
+
+use back_core::config::loader::ConfigLoader;
+use clap::{Arg, CommandFactory, FromArgMatches, Parser};
+use std::path::PathBuf;
+
+/// re-hive 服务端：启动 HTTP 服务，或只执行数据库迁移、重放事件日志后退出
+#[derive(Debug, Parser)]
+#[command(name = "re-hive", version)]
+pub(crate) struct ServerArgs {
+    /// 配置文件，默认读取环境变量 `RE_HIVE_CONFIG` 指定的文件或当前目录下的 `config.toml`
+    #[arg(long, value_name = "配置.toml")]
+    pub(crate) config: Option<PathBuf>,
+    /// 只执行数据库迁移（SQLite 后端时为 SQLite 的迁移），完成后退出
+    #[arg(long, conflicts_with = "replay")]
+    pub(crate) migrate_only: bool,
+    /// 从事件日志重建世界并校验状态哈希，完成后退出
+    #[arg(long, value_name = "事件日志.jsonl")]
+    pub(crate) replay: Option<PathBuf>,
+    /// 只重放到这一步
+    #[arg(long, value_name = "步数", requires = "replay")]
+    pub(crate) replay_until: Option<u64>,
+    /// 命令行参数中的 `(配置项的键, 值)`
+    #[arg(skip)]
+    pub(crate) overrides: Vec<(String, String)>,
+}
+
+impl ServerArgs {
+    /// 解析进程的命令行参数，有误时打印错误与用法后退出
+    pub(crate) fn parse_with_overrides() -> Self {
+        let keys = ConfigLoader::keys();
+        let command = keys.iter().fold(Self::command(), |command, key| {
+            command.arg(
+                Arg::new(key.clone())
+                    .long(key.clone())
+                    .value_name("值")
+                    .help(format!("覆盖配置项 {}", key)),
+            )
+        });
+        let matches = command.get_matches();
+        let mut args = Self::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+        args.overrides = keys
+            .into_iter()
+            .filter_map(|key| {
+                let value = matches.get_one::<String>(&key)?.clone();
+                Some((key, value))
+            })
+            .collect();
+        args
+    }
+
+    /// 按 默认值 → 配置文件 → 环境变量 → 命令行参数 的顺序加载配置的加载器
+    pub(crate) fn config_loader(&self) -> ConfigLoader {
+        let loader = match &self.config {
+            Some(path) => ConfigLoader::new().with_file(path),
+            None => ConfigLoader::new(),
+        };
+        self.overrides.iter().fold(loader, |loader, (key, value)| {
+            loader.with_override(key.clone(), value.clone())
+        })
+    }
+}
@@ -1,16 +1,192 @@
+mod api;
+mod args;
+
+use args::ServerArgs;
+use back_core::config::app_config::{DatabaseConfig, StorageBackend};
 use back_core::context::core_context::AppContext;
+use back_core::repository::property::PropertyRepository;
+use game::game_context::GameContext;
+use game::{
+    open_event_log, MapSize, Metric, MetricsRecorder, PropertyCache, PropertyRegistry, Replayer,
+    SharedMetricsRecorder, WorldCache,
+};
 use log::init_logging;
-use tracing;
+use std::path::Path;
 
 #[tokio::main]
 async fn main() {
+    // 按 默认值 → config.toml → 环境变量 → 命令行参数 的顺序加载配置，有误时直接退出
+    let args = ServerArgs::parse_with_overrides();
+    let config = match args.config_loader().load() {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("配置有误: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    // 初始化日志，并确保 guard 保持作用范围，防止程序结束时日志未写入
+    let guard = init_logging(&config.log.to_options());
     tracing::info!("re-hive启动！");
+    tracing::debug!("配置: {:?}", config);
+
+    // 加载 `simulation.property_catalogue` 指定的属性目录，目录有误时直接退出
+    if let Err(e) = PropertyRegistry::init(config.simulation.property_catalogue()) {
+        tracing::error!("属性目录加载失败: {}", e);
+        drop(guard);
+        std::process::exit(1);
+    }
+
+    // 只有 Postgres 后端需要数据库服务，其余后端可以离线运行
+    let uses_postgres = config.storage.backend == StorageBackend::Postgres;
+
+    // 按配置挂载到模拟的世界上，之后即可通过 /metrics 查询其时间序列
+    let metrics = match Metric::parse_list(&config.simulation.metrics) {
+        Ok(list) => MetricsRecorder::new(list, Some(config.simulation.metrics_interval)).shared(),
+        Err(e) => {
+            tracing::error!("simulation.metrics 有误: {}", e);
+            drop(guard);
+            std::process::exit(1);
+        }
+    };
+
+    if let Some(path) = &args.replay {
+        let succeeded = replay(path, args.replay_until, &metrics);
+        drop(guard);
+        std::process::exit(if succeeded { 0 } else { 1 });
+    }
+
+    if args.migrate_only {
+        let result = if uses_postgres {
+            let database = DatabaseConfig {
+                run_migrations: true,
+                ..config.database.clone()
+            };
+            AppContext::new().try_with_db_pool(&database).await
+        } else {
+            AppContext::new().try_with_storage(&config.storage).await
+        };
+        if let Err(e) = result {
+            tracing::error!("数据库迁移未完成: {}", e);
+            drop(guard);
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    // 模拟的默认参数
+    GameContext::update_game_map_size(MapSize::new(
+        Some(config.simulation.map_width),
+        Some(config.simulation.map_height),
+    ));
+    GameContext::update_game_gravity_const(config.simulation.gravity);
+
+    // 初始化全局的 AppContext 实例，外部服务不可用时以降级模式继续运行
+    let mut context = AppContext::new();
+    if uses_postgres {
+        context = context.with_db_pool(&config.database).await;
+    }
+    context
+        .with_storage(&config.storage)
+        .await
+        .with_redis(&config.redis)
+        .await
+        .install();
+    if AppContext::health().is_degraded() {
+        tracing::warn!("部分外部服务不可用，以降级模式运行，详见 /health");
+    }
+
+    // 连接了数据库时，用 properties 表预热属性缓存，退出前再写回
+    let properties = AppContext::try_get_db_pool()
+        .ok()
+        .map(PropertyRepository::new);
+    if let Some(repository) = &properties {
+        if let Err(e) = PropertyCache::warm_from_db(repository).await {
+            tracing::warn!("属性缓存预热失败: {}", e);
+        }
+    }
+
+    // 连接了 Redis 时，可以读取任意实例写入缓存的世界
+    let worlds = AppContext::try_get_redis().ok().map(WorldCache::new);
+
+    // 跟随配置中的世界，每步完成后记录指标
+    if let Ok(civilization_id) = config.simulation.metrics_world.parse::<uuid::Uuid>() {
+        match &worlds {
+            Some(cache) => {
+                let (cache, metrics) = (cache.clone(), metrics.clone());
+                tokio::spawn(async move {
+                    tracing::info!("开始跟随世界 {} 记录指标", civilization_id);
+                    match cache.follow(civilization_id, metrics).await {
+                        Ok(()) => tracing::info!("世界 {} 已停止，不再记录指标", civilization_id),
+                        Err(e) => tracing::warn!("跟随世界 {} 失败: {}", civilization_id, e),
+                    }
+                });
+            }
+            None => tracing::warn!("未连接 Redis，无法跟随世界 {} 记录指标", civilization_id),
+        }
+    }
+
+    let listener = tokio::net::TcpListener::bind(config.server.listen_addr)
+        .await
+        .expect("无法绑定 HTTP 监听地址");
+    tracing::info!("HTTP 服务监听于 {}", config.server.listen_addr);
+    axum::serve(listener, api::router(metrics, worlds))
+        .with_graceful_shutdown(shutdown_signal())
+        .await
+        .expect("HTTP 服务异常退出");
+
+    if let Some(repository) = &properties {
+        if let Err(e) = PropertyCache::persist_to_db(repository).await {
+            tracing::warn!("属性缓存写回失败: {}", e);
+        }
+    }
+    tracing::info!("re-hive已退出");
+}
+
+/// 等待 Ctrl+C，收到后 HTTP 服务停止接受新连接并处理完已有请求
+async fn shutdown_signal() {
+    if let Err(e) = tokio::signal::ctrl_c().await {
+        tracing::warn!("无法监听退出信号: {}", e);
+        std::future::pending::<()>().await;
+    }
+    tracing::info!("收到退出信号，正在关闭");
+}
 
-    // 初始化日志，并确保 _guard 保持作用范围，防止程序结束时日志未写入
-    let _guard = init_logging();
+/// 重放事件日志并打印校验结果，状态哈希不一致或日志有误时失败
+///
+/// 重放的世界挂载配置的指标记录器，配置了指标时结束后打印其 CSV。
+fn replay(path: &Path, until_tick: Option<u64>, metrics: &SharedMetricsRecorder) -> bool {
+    let report = match open_event_log(path).and_then(|entries| {
+        Replayer::new(entries)
+            .with_metrics(metrics.clone())
+            .replay(until_tick)
+    }) {
+        Ok(report) => report,
+        Err(e) => {
+            eprintln!("重放 {} 失败: {}", path.display(), e);
+            return false;
+        }
+    };
 
-    // 初始化全局的 AppContext 实例
-    AppContext::new()
-        .with_db_pool(Some(30), Some(10), Some(5))
-        .await;
+    let state = report.state();
+    println!(
+        "重放到第 {} 步，校验通过 {} 个状态哈希，当前状态哈希为 {:016x}",
+        state.tick(),
+        report.verified_hashes(),
+        state.state_hash()
+    );
+    let metrics = metrics.read().expect("未能获取读锁，导出指标失败");
+    if !metrics.metrics().is_empty() {
+        print!("{}", metrics.to_csv());
+    }
+    match report.divergence() {
+        Some(divergence) => {
+            println!(
+                "第 {} 步（第 {} 号事件）状态哈希不一致: 记录为 {:016x}，重放为 {:016x}",
+                divergence.tick, divergence.sequence, divergence.expected, divergence.actual
+            );
+            false
+        }
+        None => true,
+    }
 }